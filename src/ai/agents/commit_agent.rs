@@ -15,7 +15,11 @@ use tracing::{debug, info, instrument, warn};
 use crate::{
     ai::{Agent, AgentCapability, AgentResult, AgentTask, HealthStatus, AgentMetrics},
     error::AppResult,
-    git::{FileStatus, GitService},
+    git::{
+        commit_lint::{fix_message, lint_message},
+        trailers::{self, Trailer},
+        ConventionalCommit, FileStatus, GitService, LintReport, LintRules,
+    },
 };
 
 /// Commit message generation request
@@ -57,8 +61,11 @@ impl Default for CommitMessageStyle {
 pub struct GeneratedCommitMessage {
     /// Primary commit message (subject line)
     pub subject: String,
-    /// Detailed description (body)
+    /// Detailed description (body), without trailers
     pub description: Option<String>,
+    /// Trailers such as `Co-authored-by`, kept apart from the body
+    #[serde(default)]
+    pub trailers: Vec<Trailer>,
     /// Confidence score (0.0 to 1.0)
     pub confidence: f64,
     /// Suggested commit type (feat, fix, docs, etc.)
@@ -208,7 +215,7 @@ impl CommitAgent {
         let analysis = self.analyze_staged_changes(&request.staged_files)?;
 
         // Generate commit message based on style
-        let commit_message = match request.style {
+        let mut commit_message = match request.style {
             CommitMessageStyle::Conventional => {
                 self.generate_conventional_commit(&analysis, &request).await?
             }
@@ -223,6 +230,7 @@ impl CommitAgent {
             }
         };
 
+        Self::separate_trailers(&mut commit_message);
         let generation_time = start_time.elapsed();
 
         // Update metrics
@@ -240,6 +248,81 @@ impl CommitAgent {
         Ok(commit_message)
     }
 
+    /// Generate a commit message that passes the repository lint rules
+    ///
+    /// Every attempt is run through [`fix_message`]; if errors remain the
+    /// request is tightened (conventional header, no body when body rules
+    /// failed) and generation is retried. When no attempt passes, the last
+    /// message is returned with its report so the editor can show what is
+    /// left to fix.
+    pub async fn generate_linted_commit_message(
+        &mut self,
+        mut request: CommitGenerationRequest,
+        rules: &LintRules,
+        max_attempts: usize,
+    ) -> AppResult<(String, LintReport)> {
+        let mut result = (String::new(), LintReport::default());
+        for attempt in 1..=max_attempts.max(1) {
+            let generated = self.generate_commit_message(request.clone()).await?;
+            let message = fix_message(&self.conform_to_rules(&generated, rules), rules);
+            let report = lint_message(&message, rules);
+            if !report.has_errors() {
+                return Ok((message, report));
+            }
+
+            warn!("Generated commit message failed lint (attempt {}):\n{}", attempt, report.summary());
+            request.style = CommitMessageStyle::Conventional;
+            if report.violations.iter().any(|v| v.rule.starts_with("body-")) {
+                request.include_description = false;
+            }
+            result = (message, report);
+        }
+        Ok(result)
+    }
+
+    /// Compose the full message, replacing a type or scope the rules do not allow
+    fn conform_to_rules(&self, generated: &GeneratedCommitMessage, rules: &LintRules) -> String {
+        let commit_type = if rules.types.is_empty() || rules.types.contains(&generated.commit_type) {
+            generated.commit_type.clone()
+        } else if rules.types.iter().any(|t| t == "chore") {
+            "chore".to_string()
+        } else {
+            rules.types[0].clone()
+        };
+        let scope = generated
+            .scope
+            .clone()
+            .filter(|scope| rules.scopes.is_empty() || rules.scopes.contains(scope))
+            .or_else(|| rules.scope_required.then(|| rules.scopes.first().cloned()).flatten());
+        // Only a conventional header is replaced; a plain subject is kept whole
+        let header = ConventionalCommit::parse(&generated.subject);
+        let subject = header.as_ref().map_or(generated.subject.as_str(), |header| header.description.as_str());
+        let bang = if header.as_ref().is_some_and(|header| header.breaking) { "!" } else { "" };
+
+        let mut message = match scope {
+            Some(scope) => format!("{}({}){}: {}", commit_type, scope, bang, subject),
+            None => format!("{}{}: {}", commit_type, bang, subject),
+        };
+        if let Some(description) = generated.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            message.push_str("\n\n");
+            message.push_str(description.trim());
+        }
+        trailers::format_message(&message, &generated.trailers)
+    }
+
+    /// Move a trailer block at the end of the description into `trailers`
+    fn separate_trailers(message: &mut GeneratedCommitMessage) {
+        let Some(description) = message.description.take() else {
+            return;
+        };
+        let (text, trailers) = trailers::split_trailers(&format!("{}\n\n{}", message.subject, description));
+        message.description = text
+            .split_once("\n\n")
+            .map(|(_, body)| body.trim().to_string())
+            .filter(|body| !body.is_empty());
+        message.trailers.extend(trailers);
+    }
+
     /// Analyze staged changes to understand the nature of commits
     fn analyze_staged_changes(&self, files: &[FileStatus]) -> AppResult<ChangeAnalysis> {
         let mut analysis = ChangeAnalysis::default();
@@ -310,6 +393,7 @@ impl CommitAgent {
         Ok(GeneratedCommitMessage {
             subject: self.truncate_subject(&subject),
             description,
+            trailers: Vec::new(),
             confidence: self.calculate_confidence(analysis),
             commit_type: commit_type.clone(),
             scope,
@@ -334,6 +418,7 @@ impl CommitAgent {
         Ok(GeneratedCommitMessage {
             subject: self.truncate_subject(&subject),
             description: None,
+            trailers: Vec::new(),
             confidence: self.calculate_confidence(analysis),
             commit_type: analysis.primary_type.clone(),
             scope: None,
@@ -363,6 +448,7 @@ impl CommitAgent {
         Ok(GeneratedCommitMessage {
             subject: self.truncate_subject(&subject),
             description: Some(description),
+            trailers: Vec::new(),
             confidence: self.calculate_confidence(analysis),
             commit_type: analysis.primary_type.clone(),
             scope: None,
@@ -395,6 +481,7 @@ impl CommitAgent {
         Ok(GeneratedCommitMessage {
             subject: self.truncate_subject(&subject),
             description: None,
+            trailers: Vec::new(),
             confidence: 0.70, // Lower confidence for custom templates
             commit_type: analysis.primary_type.clone(),
             scope: None,
@@ -714,4 +801,40 @@ impl CommitAgent {
     pub fn get_config(&self) -> &CommitAgentConfig {
         &self.config
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generated(subject: &str) -> GeneratedCommitMessage {
+        GeneratedCommitMessage {
+            subject: subject.to_string(),
+            description: None,
+            trailers: Vec::new(),
+            confidence: 1.0,
+            commit_type: "feat".to_string(),
+            scope: Some("ui".to_string()),
+            alternatives: Vec::new(),
+            metadata: CommitMetadata {
+                files_count: 1,
+                generation_time_ms: 0,
+                model_used: None,
+                analysis_confidence: 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_conform_to_rules_keeps_subject_and_breaking_marker() {
+        let agent = CommitAgent::new(CommitAgentConfig::default(), None);
+        let rules = LintRules::default();
+
+        assert_eq!(
+            agent.conform_to_rules(&generated("Update README: fix typo"), &rules),
+            "feat(ui): Update README: fix typo"
+        );
+        assert_eq!(agent.conform_to_rules(&generated("feat!: drop v1 API"), &rules), "feat(ui)!: drop v1 API");
+        assert_eq!(agent.conform_to_rules(&generated("fix(core): handle empty diff"), &rules), "feat(ui): handle empty diff");
+    }
+}
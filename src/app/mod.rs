@@ -22,7 +22,7 @@ use tracing::{debug, info, warn};
 use crate::{
     config::Config,
     error::{AppError, AppResult},
-    git::GitService,
+    git::{GitService, Workspace},
     ui::UI,
 };
 use events::{AppEvent, EventHandler};
@@ -85,7 +85,28 @@ impl App {
 
         // Initialize UI - target: < 100ms
         let ui_start = Instant::now();
-        let ui = UI::new(&config.ui)?;
+        // Parsed hunks of streamed diffs stay well under the memory warning threshold
+        let mut ui_config = config.ui.clone();
+        ui_config.diff.stream_cache_mb = ui_config
+            .diff
+            .stream_cache_mb
+            .min((config.performance.memory_warning_mb / 4).max(1) as usize);
+        let mut ui = UI::new(&ui_config)?;
+        if config.workspace.is_configured() {
+            let base_dir = std::env::current_dir()?;
+            let mut workspace = Workspace::new(&config.workspace, &config.git, &base_dir, Some(&git_service));
+
+            // Started outside of a repository: open the first workspace repository instead
+            if workspace.initial_repository().is_none() {
+                if let Some(first) = workspace.repositories().first().cloned() {
+                    match workspace.service(&first) {
+                        Ok(service) => state.set_git_service(service),
+                        Err(e) => warn!("Failed to open workspace repository {}: {}", first.display(), e),
+                    }
+                }
+            }
+            ui.set_workspace(workspace);
+        }
         debug!("UI initialized in {:?}", ui_start.elapsed());
 
         let startup_time = init_start.elapsed();
@@ -198,6 +219,10 @@ impl App {
         }
 
         match event::read()? {
+            Event::Key(key) if self.ui.captures_text_input(&self.state) => {
+                // Text input: q/Esc/r belong to the editor, not the app
+                self.ui.handle_key_event(key, &mut self.state)?;
+            }
             Event::Key(key) => {
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Esc => {
@@ -214,6 +239,9 @@ impl App {
                     }
                 }
             }
+            Event::Mouse(mouse) => {
+                self.ui.handle_mouse_event(mouse, &mut self.state)?;
+            }
             Event::Resize(width, height) => {
                 debug!("Terminal resized to {}x{}", width, height);
                 self.ui.handle_resize(width, height);
@@ -291,8 +319,10 @@ impl App {
 
         info!("Refreshing Git repository status");
 
-        // Get fresh status from Git service
-        let status = self.git_service.get_status().await?;
+        // Get fresh status from the active Git service (workspace mode may have switched it)
+        let git_service = self.state.git_service.as_ref().unwrap_or(&self.git_service);
+        let status = git_service.get_status().await?;
+        let sparse = git_service.sparse_checkout().await.ok().filter(|sparse| sparse.enabled);
 
         let refresh_time = refresh_start.elapsed();
 
@@ -308,6 +338,7 @@ impl App {
 
         // Update application state
         self.state.update_git_status(status);
+        self.state.git_state.sparse_checkout = sparse;
 
         Ok(())
     }
@@ -10,7 +10,7 @@ use uuid::Uuid;
 
 use crate::{
     ai::{AgentResult, AgentStatus, AgentType},
-    git::{BranchInfo, CommitInfo, FileStatus, GitService},
+    git::{BranchInfo, CommitInfo, FileStatus, GitService, SparseCheckout},
     ui::selection::SelectionManager,
 };
 
@@ -86,6 +86,26 @@ impl AppState {
         self.update_current_branch(default_branch, commits);
     }
 
+    /// Switch to another repository's Git service
+    ///
+    /// Drops the state of the previous repository; `status` and `branch`
+    /// describe the new one.
+    pub fn switch_git_service(&mut self, git_service: GitService, status: Vec<FileStatus>, branch: Option<BranchInfo>) {
+        self.git_state.repository_path = Some(git_service.repo_path().display().to_string());
+        self.git_state.commits_by_branch.clear();
+        self.git_state.current_branch_commits.clear();
+        self.git_state.current_branch = None;
+        self.git_state.sparse_checkout = None;
+        self.git_service = Some(git_service);
+        self.git_state.is_repository = true;
+        self.ui_state.sidebar_selected_index = 0;
+
+        if let Some(branch) = branch {
+            self.update_current_branch(branch, Vec::new());
+        }
+        self.update_git_status(status);
+    }
+
     /// Check if the application should quit
     pub fn should_quit(&self) -> bool {
         matches!(self.app_state.lifecycle, LifecyclePhase::Quitting)
@@ -182,6 +202,8 @@ impl AppState {
                     author_email: "main@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::hours(1),
                     parents: vec!["def5678".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "def5678901234".to_string(),
@@ -191,6 +213,8 @@ impl AppState {
                     author_email: "main@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::hours(3),
                     parents: vec!["ghi9012".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "ghi9012345678".to_string(),
@@ -200,6 +224,8 @@ impl AppState {
                     author_email: "main@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::hours(6),
                     parents: vec!["jkl3456".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
             ],
             "develop" => vec![
@@ -211,6 +237,8 @@ impl AppState {
                     author_email: "dev@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::minutes(30),
                     parents: vec!["dev5678".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "dev5678901234".to_string(),
@@ -220,6 +248,8 @@ impl AppState {
                     author_email: "dev@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::hours(2),
                     parents: vec!["dev9012".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "dev9012345678".to_string(),
@@ -229,6 +259,8 @@ impl AppState {
                     author_email: "dev@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::hours(4),
                     parents: vec!["dev3456".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
             ],
             _ => vec![
@@ -240,6 +272,8 @@ impl AppState {
                     author_email: "feature@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::minutes(15),
                     parents: vec!["feat567".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "feat567890123".to_string(),
@@ -249,6 +283,8 @@ impl AppState {
                     author_email: "feature@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::hours(1),
                     parents: vec!["feat890".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
             ]
         }
@@ -301,6 +337,8 @@ pub struct GitState {
     pub commits_by_branch: HashMap<String, Vec<CommitInfo>>,
     /// Last commits update time
     pub last_commits_update: DateTime<Utc>,
+    /// Sparse-checkout cone, when the repository uses one
+    pub sparse_checkout: Option<SparseCheckout>,
 }
 
 impl Default for GitState {
@@ -314,6 +352,7 @@ impl Default for GitState {
             current_branch_commits: Vec::new(),
             commits_by_branch: HashMap::new(),
             last_commits_update: Utc::now(),
+            sparse_checkout: None,
         }
     }
 }
@@ -369,6 +408,7 @@ pub enum TabType {
     Remotes,
     History,
     GitFlow,
+    Insights,
 }
 
 impl TabType {
@@ -382,6 +422,7 @@ impl TabType {
             TabType::Remotes,
             TabType::History,
             TabType::GitFlow,
+            TabType::Insights,
         ]
     }
 
@@ -395,6 +436,7 @@ impl TabType {
             TabType::Remotes => "Remotes",
             TabType::History => "History",
             TabType::GitFlow => "Git工作流",
+            TabType::Insights => "Insights",
         }
     }
 }
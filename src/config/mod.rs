@@ -7,11 +7,17 @@
 //! - Validation and migration
 
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 use tokio::fs;
 use tracing::{debug, info};
 
-use crate::error::{AppError, AppResult};
+use crate::{
+    error::{AppError, AppResult},
+    ui::diff::{DiffAlgorithm, IntralineGranularity, WhitespaceMode},
+};
 
 /// Main application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +34,9 @@ pub struct Config {
     pub mcp: McpConfig,
     /// Performance monitoring configuration
     pub performance: PerformanceConfig,
+    /// Multi-repository workspace configuration
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
 }
 
 impl Config {
@@ -116,6 +125,20 @@ impl Config {
             ));
         }
 
+        // Validate Git settings
+        if self.git.rename_similarity > 100 || self.git.copy_similarity > 100 {
+            return Err(AppError::application(
+                "rename_similarity and copy_similarity must be between 0 and 100"
+            ));
+        }
+
+        // Validate workspace settings
+        if self.workspace.scan_depth > 5 {
+            return Err(AppError::application(
+                "workspace scan_depth must be between 0 and 5"
+            ));
+        }
+
         // Validate UI settings
         if self.ui.sidebar_width < 10 || self.ui.sidebar_width > 100 {
             return Err(AppError::application(
@@ -146,6 +169,7 @@ impl Default for Config {
             agents: AgentConfig::default(),
             mcp: McpConfig::default(),
             performance: PerformanceConfig::default(),
+            workspace: WorkspaceConfig::default(),
         }
     }
 }
@@ -184,6 +208,7 @@ impl Default for AppConfig {
 
 /// Git-related configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GitConfig {
     /// Default branch name for new repositories
     pub default_branch: String,
@@ -197,6 +222,18 @@ pub struct GitConfig {
     pub enable_submodules: bool,
     /// Enable LFS support
     pub enable_lfs: bool,
+    /// Minimum similarity (0-100) for a rename when following file history
+    pub rename_similarity: u16,
+    /// Minimum similarity (0-100) for a copy when following file history
+    pub copy_similarity: u16,
+    /// Follow copies as well as renames in file history
+    pub follow_copies: bool,
+    /// Add a `Signed-off-by` trailer for the committer to new commit messages
+    pub auto_signoff: bool,
+    /// Notes refs shown with commits; the first one is edited by default
+    pub notes_refs: Vec<String>,
+    /// Seconds a `diff.<driver>.textconv` command may run before it is killed
+    pub textconv_timeout_secs: u64,
 }
 
 impl Default for GitConfig {
@@ -208,6 +245,12 @@ impl Default for GitConfig {
             max_files: 10000,
             enable_submodules: true,
             enable_lfs: true,
+            rename_similarity: 50,
+            copy_similarity: 50,
+            follow_copies: true,
+            auto_signoff: false,
+            notes_refs: vec!["refs/notes/commits".to_string(), "refs/notes/review".to_string()],
+            textconv_timeout_secs: 10,
         }
     }
 }
@@ -229,6 +272,9 @@ pub struct UIConfig {
     pub refresh_rate_ms: u64,
     /// Enable syntax highlighting
     pub enable_syntax_highlighting: bool,
+    /// Diff viewer settings
+    #[serde(default)]
+    pub diff: DiffConfig,
 }
 
 impl Default for UIConfig {
@@ -241,6 +287,53 @@ impl Default for UIConfig {
             enable_mouse: true,
             refresh_rate_ms: 100,
             enable_syntax_highlighting: true,
+            diff: DiffConfig::default(),
+        }
+    }
+}
+
+/// Diff computation settings (`[ui.diff]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// Default algorithm: myers, minimal, patience or histogram
+    pub algorithm: DiffAlgorithm,
+    /// Algorithm per file type, keyed by extension (`rs`, `*.lock`) or file name (`Cargo.lock`)
+    pub file_algorithms: HashMap<String, DiffAlgorithm>,
+    /// exact, ignore-eol, ignore-change or ignore-all
+    pub whitespace: WhitespaceMode,
+    /// Hide changes that only add or remove blank lines
+    pub ignore_blank_lines: bool,
+    /// Context lines around each change
+    pub context_lines: usize,
+    /// Highlight the changed words or characters inside modified lines
+    pub intraline_highlight: bool,
+    /// word or char
+    pub intraline_granularity: IntralineGranularity,
+    /// Highlight source code in diffs; also off when `enable_syntax_highlighting` is off
+    pub syntax_highlighting: bool,
+    /// Syntax palette: `theme` follows the UI theme, or a built-in name such as `base16-ocean.dark`
+    pub syntax_theme: String,
+    /// Files larger than this (KB) are diffed by `git diff` and streamed, loading only the hunks on screen
+    pub stream_threshold_kb: usize,
+    /// Memory for parsed hunks of a streamed diff (MB); capped at a quarter of `performance.memory_warning_mb`
+    pub stream_cache_mb: usize,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: DiffAlgorithm::Myers,
+            file_algorithms: HashMap::new(),
+            whitespace: WhitespaceMode::Exact,
+            ignore_blank_lines: false,
+            context_lines: 3,
+            intraline_highlight: true,
+            intraline_granularity: IntralineGranularity::Word,
+            syntax_highlighting: true,
+            syntax_theme: "theme".to_string(),
+            stream_threshold_kb: 1024,
+            stream_cache_mb: 32,
         }
     }
 }
@@ -357,3 +450,26 @@ impl Default for PerformanceConfig {
         }
     }
 }
+
+/// Multi-repository workspace configuration
+///
+/// Workspace mode is enabled as soon as a repository or scan directory is
+/// configured. Relative paths are resolved against the directory the TUI
+/// starts in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    /// Repositories that belong to the workspace
+    pub repositories: Vec<PathBuf>,
+    /// Directories scanned for repositories
+    pub scan_dirs: Vec<PathBuf>,
+    /// How many directory levels below each scan dir are searched (0 = direct children only)
+    pub scan_depth: usize,
+}
+
+impl WorkspaceConfig {
+    /// Whether any repositories or scan directories are configured
+    pub fn is_configured(&self) -> bool {
+        !self.repositories.is_empty() || !self.scan_dirs.is_empty()
+    }
+}
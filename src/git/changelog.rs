@@ -0,0 +1,447 @@
+//! Changelog generation
+//!
+//! Walks the commits between two revisions, parses them as conventional
+//! commits and groups them by type and scope into a Keep-a-Changelog style
+//! release section. Merge commits are skipped.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use git2::{DescribeFormatOptions, DescribeOptions, Oid, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::AppResult,
+    git::conventional::{self, ConventionalCommit},
+};
+
+/// Title used for commits that are not conventional
+const OTHER_SECTION: &str = "Other";
+
+const CHANGELOG_HEADER: &str = "# Changelog\n\nAll notable changes to this project will be documented in this file.\n\nThe format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/).\n";
+
+/// Output format of a generated changelog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangelogFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+impl ChangelogFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "Markdown",
+            ChangelogFormat::Json => "JSON",
+        }
+    }
+
+    /// File the rendered changelog is written to
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "CHANGELOG.md",
+            ChangelogFormat::Json => "CHANGELOG.json",
+        }
+    }
+}
+
+/// Range and labelling of a changelog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogOptions {
+    /// Exclusive start revision; `None` uses the last tag before `to`
+    pub from: Option<String>,
+    /// Inclusive end revision
+    pub to: String,
+    /// Release version for the heading; `None` renders `[Unreleased]`
+    pub version: Option<String>,
+    /// Keep non-conventional commits in an "Other" section
+    pub include_other: bool,
+}
+
+impl Default for ChangelogOptions {
+    fn default() -> Self {
+        Self {
+            from: None,
+            to: "HEAD".to_string(),
+            version: None,
+            include_other: false,
+        }
+    }
+}
+
+/// One changelog line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub description: String,
+    /// Abbreviated commit hash
+    pub hash: String,
+    pub breaking: bool,
+}
+
+/// Entries of one commit type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangelogSection {
+    pub title: String,
+    /// Entries ordered by scope, unscoped first
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// A generated release section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Changelog {
+    pub version: Option<String>,
+    pub date: NaiveDate,
+    /// Resolved start revision, `None` when the range starts at the root
+    pub from: Option<String>,
+    pub to: String,
+    /// Breaking changes, described by their `BREAKING CHANGE` note if any
+    pub breaking: Vec<ChangelogEntry>,
+    pub sections: Vec<ChangelogSection>,
+    pub commit_count: usize,
+}
+
+impl Changelog {
+    pub fn is_empty(&self) -> bool {
+        self.breaking.is_empty() && self.sections.is_empty()
+    }
+
+    /// Markdown heading of the release section
+    pub fn heading(&self) -> String {
+        match &self.version {
+            Some(version) => format!("## [{}] - {}", version, self.date.format("%Y-%m-%d")),
+            None => "## [Unreleased]".to_string(),
+        }
+    }
+
+    /// Render as a Keep-a-Changelog release section
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("{}\n", self.heading());
+
+        if !self.breaking.is_empty() {
+            out.push_str("\n### ⚠ BREAKING CHANGES\n\n");
+            for entry in &self.breaking {
+                out.push_str(&markdown_entry(entry));
+            }
+        }
+
+        for section in &self.sections {
+            out.push_str(&format!("\n### {}\n\n", section.title));
+            for entry in &section.entries {
+                out.push_str(&markdown_entry(entry));
+            }
+        }
+
+        if self.is_empty() {
+            out.push_str("\nNo notable changes.\n");
+        }
+
+        out
+    }
+
+    pub fn to_json(&self) -> AppResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn render(&self, format: ChangelogFormat) -> AppResult<String> {
+        match format {
+            ChangelogFormat::Markdown => Ok(self.to_markdown()),
+            ChangelogFormat::Json => self.to_json(),
+        }
+    }
+}
+
+fn markdown_entry(entry: &ChangelogEntry) -> String {
+    let description = entry.description.replace('\n', "\n  ");
+    match &entry.scope {
+        Some(scope) => format!("- **{}:** {} ({})\n", scope, description, entry.hash),
+        None => format!("- {} ({})\n", description, entry.hash),
+    }
+}
+
+/// Generate the changelog for `options.from..options.to`
+pub fn generate_changelog(repo: &Repository, options: &ChangelogOptions) -> AppResult<Changelog> {
+    let to = repo.revparse_single(&options.to)?.peel_to_commit()?;
+    let from = match &options.from {
+        Some(from) => Some(from.clone()),
+        None => previous_tag(repo, &to),
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(to.id())?;
+    if let Some(from) = &from {
+        revwalk.hide(repo.revparse_single(from)?.peel_to_commit()?.id())?;
+    }
+
+    let mut breaking = Vec::new();
+    let mut by_type: Vec<(String, Vec<ChangelogEntry>)> = Vec::new();
+    let mut commit_count = 0;
+
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        commit_count += 1;
+
+        let message = commit.message().unwrap_or_default();
+        let hash = short_hash(commit.id());
+        let (title, entry) = match ConventionalCommit::parse(message) {
+            Some(parsed) => {
+                if parsed.breaking {
+                    breaking.push(ChangelogEntry {
+                        commit_type: Some(parsed.commit_type.clone()),
+                        scope: parsed.scope.clone(),
+                        description: parsed.breaking_note().unwrap_or(&parsed.description).to_string(),
+                        hash: hash.clone(),
+                        breaking: true,
+                    });
+                }
+                let title = conventional::section_title(&parsed.commit_type);
+                if title.is_none() && !options.include_other {
+                    continue;
+                }
+                (
+                    title.unwrap_or(OTHER_SECTION),
+                    ChangelogEntry {
+                        commit_type: Some(parsed.commit_type),
+                        scope: parsed.scope,
+                        description: parsed.description,
+                        hash,
+                        breaking: parsed.breaking,
+                    },
+                )
+            }
+            None if options.include_other => (
+                OTHER_SECTION,
+                ChangelogEntry {
+                    commit_type: None,
+                    scope: None,
+                    description: commit.summary().unwrap_or_default().to_string(),
+                    hash,
+                    breaking: false,
+                },
+            ),
+            None => continue,
+        };
+
+        match by_type.iter_mut().find(|(name, _)| name == title) {
+            Some((_, entries)) => entries.push(entry),
+            None => by_type.push((title.to_string(), vec![entry])),
+        }
+    }
+
+    // Section order follows COMMIT_TYPES, with "Other" last
+    let rank = |title: &str| {
+        conventional::COMMIT_TYPES
+            .iter()
+            .position(|(_, section)| *section == title)
+            .unwrap_or(usize::MAX)
+    };
+    by_type.sort_by_key(|(title, _)| rank(title));
+
+    let sections = by_type
+        .into_iter()
+        .map(|(title, mut entries)| {
+            entries.sort_by(|a, b| a.scope.cmp(&b.scope));
+            ChangelogSection { title, entries }
+        })
+        .collect();
+
+    let date = DateTime::<Utc>::from_timestamp(to.time().seconds(), 0)
+        .map(|time| time.date_naive())
+        .unwrap_or_else(|| Utc::now().date_naive());
+
+    Ok(Changelog {
+        version: options.version.clone(),
+        date,
+        from,
+        to: options.to.clone(),
+        breaking,
+        sections,
+        commit_count,
+    })
+}
+
+/// Nearest tag reachable from `commit`, excluding a tag on `commit` itself
+fn previous_tag(repo: &Repository, commit: &git2::Commit) -> Option<String> {
+    let start = if is_tagged(repo, commit.id()) {
+        commit.parent(0).ok()?
+    } else {
+        commit.clone()
+    };
+
+    let mut describe_options = DescribeOptions::new();
+    describe_options.describe_tags();
+    let describe = start.as_object().describe(&describe_options).ok()?;
+
+    let mut format_options = DescribeFormatOptions::new();
+    format_options.abbreviated_size(0);
+    describe.format(Some(&format_options)).ok()
+}
+
+fn is_tagged(repo: &Repository, oid: Oid) -> bool {
+    repo.tag_names(None).is_ok_and(|names| {
+        names.iter().flatten().any(|name| {
+            repo.revparse_single(&format!("refs/tags/{}", name))
+                .and_then(|object| object.peel_to_commit())
+                .is_ok_and(|target| target.id() == oid)
+        })
+    })
+}
+
+fn short_hash(oid: Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+/// Version label for a tag name: `v1.2.0` → `1.2.0`
+pub fn version_from_tag(tag: &str) -> String {
+    let trimmed = tag.rsplit('/').next().unwrap_or(tag);
+    match trimmed.strip_prefix('v') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest.to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+/// Write rendered changelog content into the working tree
+///
+/// Markdown is merged into `CHANGELOG.md`: a section with the same heading
+/// is replaced, otherwise the new section goes above the newest release.
+/// JSON overwrites `CHANGELOG.json`. Returns the written path.
+pub fn write_changelog(workdir: &Path, content: &str, format: ChangelogFormat) -> AppResult<PathBuf> {
+    let path = workdir.join(format.file_name());
+    let content = match format {
+        ChangelogFormat::Markdown => {
+            let existing = std::fs::read_to_string(&path).unwrap_or_default();
+            merge_markdown(&existing, content)
+        }
+        ChangelogFormat::Json => format!("{}\n", content.trim_end()),
+    };
+
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Insert or replace a release section in an existing CHANGELOG.md
+fn merge_markdown(existing: &str, section: &str) -> String {
+    let section = format!("{}\n", section.trim_end());
+    if existing.trim().is_empty() {
+        return format!("{}\n{}", CHANGELOG_HEADER, section);
+    }
+
+    let heading = section.lines().next().unwrap_or_default();
+    let lines: Vec<&str> = existing.lines().collect();
+    let is_release = |line: &&str| line.starts_with("## ");
+
+    let (start, end) = match lines.iter().position(|line| *line == heading) {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(is_release)
+                .map_or(lines.len(), |offset| start + 1 + offset);
+            (start, end)
+        }
+        None => {
+            let at = lines.iter().position(is_release).unwrap_or(lines.len());
+            (at, at)
+        }
+    };
+
+    let mut out = String::new();
+    for line in &lines[..start] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+    out.push_str(&section);
+    if end < lines.len() {
+        out.push('\n');
+        for line in &lines[end..] {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generate_changelog_since_last_tag() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        for message in ["feat: initial", "fix: old bug"] {
+            git(dir.path(), &["commit", "-q", "--allow-empty", "-m", message]);
+        }
+        git(dir.path(), &["tag", "v1.0.0"]);
+        for message in [
+            "feat(ui): dark mode",
+            "fix: crash on start",
+            "feat!: new config format\n\nBREAKING CHANGE: config.toml moved",
+            "feat: search",
+            "Update readme",
+        ] {
+            git(dir.path(), &["commit", "-q", "--allow-empty", "-m", message]);
+        }
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let options = ChangelogOptions { version: Some("1.1.0".to_string()), ..Default::default() };
+        let changelog = generate_changelog(&repo, &options).unwrap();
+
+        assert_eq!(changelog.from.as_deref(), Some("v1.0.0"));
+        assert_eq!(changelog.commit_count, 5);
+        assert_eq!(changelog.breaking.len(), 1);
+        assert_eq!(changelog.breaking[0].description, "config.toml moved");
+        assert_eq!(changelog.sections.len(), 2);
+        assert_eq!(changelog.sections[0].title, "Added");
+        let added: Vec<_> = changelog.sections[0].entries.iter().map(|e| e.description.as_str()).collect();
+        assert_eq!(added, ["search", "new config format", "dark mode"]);
+
+        let markdown = changelog.to_markdown();
+        assert!(markdown.starts_with("## [1.1.0] - "));
+        assert!(markdown.contains("### ⚠ BREAKING CHANGES"));
+        assert!(markdown.contains("- **ui:** dark mode ("));
+        assert!(!markdown.contains("readme"));
+
+        // Generating for the tag itself goes back to the previous tag
+        git(dir.path(), &["tag", "v1.1.0"]);
+        let options = ChangelogOptions { to: "v1.1.0".to_string(), include_other: true, ..Default::default() };
+        let changelog = generate_changelog(&repo, &options).unwrap();
+        assert_eq!(changelog.from.as_deref(), Some("v1.0.0"));
+        assert_eq!(changelog.sections.last().unwrap().title, "Other");
+
+        let options = ChangelogOptions { to: "v1.0.0".to_string(), ..Default::default() };
+        let changelog = generate_changelog(&repo, &options).unwrap();
+        assert_eq!(changelog.from, None);
+        assert_eq!(changelog.commit_count, 2);
+    }
+
+    #[test]
+    fn test_merge_markdown() {
+        let created = merge_markdown("", "## [1.0.0] - 2024-01-01\n\n### Added\n\n- a (1234567)\n");
+        assert!(created.starts_with("# Changelog\n"));
+        assert!(created.ends_with("- a (1234567)\n"));
+
+        let merged = merge_markdown(&created, "## [1.1.0] - 2024-02-01\n\n### Fixed\n\n- b (89abcde)");
+        let newer = merged.find("## [1.1.0]").unwrap();
+        let older = merged.find("## [1.0.0]").unwrap();
+        assert!(newer < older);
+        assert!(merged.contains("- b (89abcde)\n\n## [1.0.0]"));
+
+        let replaced = merge_markdown(&merged, "## [1.1.0] - 2024-02-01\n\n### Fixed\n\n- c (fedcba9)");
+        assert!(!replaced.contains("- b (89abcde)"));
+        assert!(replaced.contains("- c (fedcba9)"));
+        assert_eq!(replaced.matches("## [1.1.0]").count(), 1);
+
+        assert_eq!(version_from_tag("v2.0.1"), "2.0.1");
+        assert_eq!(version_from_tag("release/v3"), "3");
+        assert_eq!(version_from_tag("vendor-drop"), "vendor-drop");
+    }
+}
@@ -0,0 +1,475 @@
+//! Working tree cleaning with a recoverable trash
+//!
+//! Mirrors the selection rules of `git clean` (`-x`, `-X`, `-d`), but instead
+//! of deleting, files are moved into `.git/ai-c-trash/<batch>/` together with
+//! a manifest so a clean can be undone.
+
+use chrono::{DateTime, Utc};
+use git2::{Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, info, warn};
+
+use crate::error::{AppError, AppResult};
+
+/// Name of the trash directory inside `.git`
+pub const TRASH_DIR: &str = "ai-c-trash";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Which ignored files a clean selects
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CleanIgnored {
+    /// Untracked files only (`git clean`)
+    #[default]
+    Exclude,
+    /// Untracked and ignored files (`git clean -x`)
+    Include,
+    /// Ignored files only (`git clean -X`)
+    Only,
+}
+
+impl CleanIgnored {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CleanIgnored::Exclude => "untracked only",
+            CleanIgnored::Include => "untracked + ignored",
+            CleanIgnored::Only => "ignored only",
+        }
+    }
+}
+
+/// Options for cleaning the working tree
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanOptions {
+    pub ignored: CleanIgnored,
+    /// Also remove untracked directories (`git clean -d`)
+    pub directories: bool,
+}
+
+/// A file or directory selected for cleaning
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanCandidate {
+    /// Path relative to the working tree; directories end with `/`
+    pub path: String,
+    pub is_dir: bool,
+    pub is_ignored: bool,
+    /// Size in bytes (recursive for directories)
+    pub size: u64,
+}
+
+/// Everything a clean would remove
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanPreview {
+    pub candidates: Vec<CleanCandidate>,
+    pub total_size: u64,
+}
+
+/// One clean operation stored in the trash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashBatch {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub entries: Vec<CleanCandidate>,
+    pub total_size: u64,
+}
+
+/// Result of restoring a trash batch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub restored: Vec<String>,
+    /// Entries left in the trash because the path exists again
+    pub skipped: Vec<String>,
+}
+
+/// List what a clean with `options` would remove
+pub fn preview_clean(repo: &Repository, options: &CleanOptions) -> AppResult<CleanPreview> {
+    let workdir = workdir(repo)?;
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(options.ignored != CleanIgnored::Only)
+        .include_ignored(options.ignored != CleanIgnored::Exclude)
+        .recurse_untracked_dirs(false)
+        .recurse_ignored_dirs(false)
+        .exclude_submodules(true);
+
+    let mut preview = CleanPreview::default();
+    for entry in repo.statuses(Some(&mut status_options))?.iter() {
+        let status = entry.status();
+        let is_ignored = status.is_ignored();
+        if !is_ignored && !status.is_wt_new() {
+            continue;
+        }
+
+        let Some(path) = entry.path() else {
+            warn!("Skipping non UTF-8 path while cleaning");
+            continue;
+        };
+        let is_dir = path.ends_with('/');
+        let full_path = workdir.join(path);
+
+        // Like git clean without -ff: never touch nested repositories
+        if is_dir && (!options.directories || full_path.join(".git").exists()) {
+            continue;
+        }
+
+        // Without -x, ignored files inside an untracked directory stay
+        if is_dir && options.ignored == CleanIgnored::Exclude {
+            collect_untracked(repo, &workdir, path, &mut preview.candidates)?;
+        } else {
+            preview.candidates.push(candidate(&workdir, path, is_dir, is_ignored));
+        }
+    }
+
+    preview.candidates.sort_by(|a, b| a.path.cmp(&b.path));
+    preview.total_size = preview.candidates.iter().map(|c| c.size).sum();
+    debug!("Clean preview: {} entries, {} bytes", preview.candidates.len(), preview.total_size);
+    Ok(preview)
+}
+
+fn candidate(workdir: &Path, path: &str, is_dir: bool, is_ignored: bool) -> CleanCandidate {
+    CleanCandidate {
+        path: path.to_string(),
+        is_dir,
+        is_ignored,
+        size: path_size(&workdir.join(path)),
+    }
+}
+
+/// Select the untracked directory `dir` (ending with `/`) as a whole, or
+/// only its untracked entries when it holds ignored files
+fn collect_untracked(repo: &Repository, workdir: &Path, dir: &str, candidates: &mut Vec<CleanCandidate>) -> AppResult<()> {
+    if !contains_ignored(repo, workdir, dir)? {
+        candidates.push(candidate(workdir, dir, true, false));
+        return Ok(());
+    }
+
+    for (path, is_dir) in dir_entries(workdir, dir)? {
+        if repo.is_path_ignored(&path)? {
+            continue;
+        }
+        if is_dir {
+            collect_untracked(repo, workdir, &path, candidates)?;
+        } else {
+            candidates.push(candidate(workdir, &path, false, false));
+        }
+    }
+    Ok(())
+}
+
+fn contains_ignored(repo: &Repository, workdir: &Path, dir: &str) -> AppResult<bool> {
+    for (path, is_dir) in dir_entries(workdir, dir)? {
+        if repo.is_path_ignored(&path)? || (is_dir && contains_ignored(repo, workdir, &path)?) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Entries of `dir` relative to the working tree, skipping nested repositories
+fn dir_entries(workdir: &Path, dir: &str) -> AppResult<Vec<(String, bool)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(workdir.join(dir))? {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            warn!("Skipping non UTF-8 path while cleaning");
+            continue;
+        };
+        if entry.file_type()?.is_dir() {
+            if entry.path().join(".git").exists() {
+                continue;
+            }
+            entries.push((format!("{}{}/", dir, name), true));
+        } else {
+            entries.push((format!("{}{}", dir, name), false));
+        }
+    }
+    Ok(entries)
+}
+
+/// Move everything selected by `options` into a new trash batch
+///
+/// Returns `None` when there was nothing to clean.
+pub fn clean(repo: &Repository, options: &CleanOptions) -> AppResult<Option<TrashBatch>> {
+    let preview = preview_clean(repo, options)?;
+    if preview.candidates.is_empty() {
+        return Ok(None);
+    }
+
+    let workdir = workdir(repo)?;
+    let id = Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+    let batch_dir = trash_dir(repo).join(&id);
+    fs::create_dir_all(&batch_dir)?;
+
+    let mut batch = TrashBatch {
+        id,
+        created_at: Utc::now(),
+        entries: Vec::new(),
+        total_size: 0,
+    };
+
+    for candidate in preview.candidates {
+        let relative = candidate.path.trim_end_matches('/');
+        let target = batch_dir.join("files").join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Record what was moved even if a later entry fails
+        if let Err(e) = move_path(&workdir.join(relative), &target) {
+            write_manifest(&batch_dir, &batch)?;
+            return Err(e);
+        }
+        batch.total_size += candidate.size;
+        batch.entries.push(candidate);
+    }
+
+    write_manifest(&batch_dir, &batch)?;
+    info!("Moved {} entries to trash batch {}", batch.entries.len(), batch.id);
+    Ok(Some(batch))
+}
+
+/// Trash batches, newest first
+pub fn list_trash(repo: &Repository) -> AppResult<Vec<TrashBatch>> {
+    let dir = trash_dir(repo);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut batches = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let manifest = entry?.path().join(MANIFEST_FILE);
+        match fs::read_to_string(&manifest).map_err(AppError::from).and_then(|content| {
+            serde_json::from_str::<TrashBatch>(&content).map_err(AppError::from)
+        }) {
+            Ok(batch) => batches.push(batch),
+            Err(e) => warn!("Ignoring unreadable trash manifest {}: {}", manifest.display(), e),
+        }
+    }
+
+    batches.sort_by_key(|batch| std::cmp::Reverse(batch.created_at));
+    Ok(batches)
+}
+
+/// Move the entries of a trash batch back into the working tree
+///
+/// Paths that exist again are not overwritten; they stay in the batch.
+pub fn restore_trash(repo: &Repository, batch_id: &str) -> AppResult<RestoreReport> {
+    let workdir = workdir(repo)?;
+    let batch_dir = trash_dir(repo).join(batch_id);
+    let manifest = batch_dir.join(MANIFEST_FILE);
+    if batch_id.contains(['/', '\\']) || !manifest.exists() {
+        return Err(AppError::InvalidOperation(format!("Trash batch '{}' not found", batch_id)));
+    }
+
+    let mut batch: TrashBatch = serde_json::from_str(&fs::read_to_string(&manifest)?)?;
+    let mut report = RestoreReport::default();
+    let mut remaining = Vec::new();
+
+    for entry in batch.entries {
+        let relative = entry.path.trim_end_matches('/');
+        let target = workdir.join(relative);
+        if target.symlink_metadata().is_ok() {
+            report.skipped.push(entry.path.clone());
+            remaining.push(entry);
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_path(&batch_dir.join("files").join(relative), &target)?;
+        report.restored.push(entry.path);
+    }
+
+    if remaining.is_empty() {
+        fs::remove_dir_all(&batch_dir)?;
+    } else {
+        batch.total_size = remaining.iter().map(|e| e.size).sum();
+        batch.entries = remaining;
+        write_manifest(&batch_dir, &batch)?;
+    }
+
+    info!(
+        "Restored {} entries from trash batch {} ({} skipped)",
+        report.restored.len(),
+        batch_id,
+        report.skipped.len()
+    );
+    Ok(report)
+}
+
+fn workdir(repo: &Repository) -> AppResult<PathBuf> {
+    repo.workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| AppError::InvalidOperation("Cannot clean a bare repository".to_string()))
+}
+
+fn trash_dir(repo: &Repository) -> PathBuf {
+    repo.path().join(TRASH_DIR)
+}
+
+fn write_manifest(batch_dir: &Path, batch: &TrashBatch) -> AppResult<()> {
+    fs::write(batch_dir.join(MANIFEST_FILE), serde_json::to_string_pretty(batch)?)?;
+    Ok(())
+}
+
+/// Rename, falling back to copy + delete across file systems
+fn move_path(from: &Path, to: &Path) -> AppResult<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    copy_recursive(from, to)?;
+    if from.is_dir() {
+        fs::remove_dir_all(from)?;
+    } else {
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> AppResult<()> {
+    let metadata = from.symlink_metadata()?;
+    if metadata.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else if metadata.file_type().is_symlink() {
+        let link = fs::read_link(from)?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(link, to)?;
+        #[cfg(not(unix))]
+        fs::copy(from.parent().unwrap_or(Path::new(".")).join(link), to)?;
+    } else {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+/// Size of a file, or of everything below a directory (symlinks not followed)
+fn path_size(path: &Path) -> u64 {
+    let Ok(metadata) = path.symlink_metadata() else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| path_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let root = dir.path();
+        fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::write(root.join("tracked.rs"), "fn main() {}\n").unwrap();
+        git(root, &["add", "."]);
+
+        fs::write(root.join("scratch.txt"), "12345").unwrap();
+        fs::write(root.join("debug.log"), "log").unwrap();
+        fs::create_dir_all(root.join("target/debug")).unwrap();
+        fs::write(root.join("target/debug/app"), "0123456789").unwrap();
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join("notes/todo.md"), "todo").unwrap();
+
+        let repo = Repository::open(root).unwrap();
+        (dir, repo)
+    }
+
+    fn paths(preview: &CleanPreview) -> Vec<&str> {
+        preview.candidates.iter().map(|c| c.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_preview_matches_git_clean_modes() {
+        let (_dir, repo) = setup();
+
+        let untracked = preview_clean(&repo, &CleanOptions::default()).unwrap();
+        assert_eq!(paths(&untracked), ["scratch.txt"]);
+        assert_eq!(untracked.total_size, 5);
+
+        let with_dirs = CleanOptions { directories: true, ..Default::default() };
+        assert_eq!(paths(&preview_clean(&repo, &with_dirs).unwrap()), ["notes/", "scratch.txt"]);
+
+        let everything = CleanOptions { ignored: CleanIgnored::Include, directories: true };
+        let preview = preview_clean(&repo, &everything).unwrap();
+        assert_eq!(paths(&preview), ["debug.log", "notes/", "scratch.txt", "target/"]);
+        assert_eq!(preview.candidates[3].size, 10);
+
+        let ignored = CleanOptions { ignored: CleanIgnored::Only, directories: true };
+        assert_eq!(paths(&preview_clean(&repo, &ignored).unwrap()), ["debug.log", "target/"]);
+    }
+
+    #[test]
+    fn test_untracked_directory_keeps_ignored_files() {
+        let (dir, repo) = setup();
+        let root = dir.path();
+        fs::write(root.join("notes/draft.log"), "ignored").unwrap();
+        fs::create_dir_all(root.join("notes/archive")).unwrap();
+        fs::write(root.join("notes/archive/old.md"), "old").unwrap();
+        fs::create_dir_all(root.join("notes/target")).unwrap();
+        fs::write(root.join("notes/target/out"), "build").unwrap();
+
+        let with_dirs = CleanOptions { directories: true, ..Default::default() };
+        let preview = preview_clean(&repo, &with_dirs).unwrap();
+        assert_eq!(paths(&preview), ["notes/archive/", "notes/todo.md", "scratch.txt"]);
+        assert_eq!(preview.total_size, 3 + 4 + 5);
+
+        clean(&repo, &with_dirs).unwrap().unwrap();
+        assert!(root.join("notes/draft.log").exists());
+        assert!(root.join("notes/target/out").exists());
+        assert!(!root.join("notes/todo.md").exists());
+        assert!(!root.join("notes/archive").exists());
+
+        // With -x the directory goes as a whole
+        let everything = CleanOptions { ignored: CleanIgnored::Include, directories: true };
+        assert_eq!(paths(&preview_clean(&repo, &everything).unwrap()), ["debug.log", "notes/", "target/"]);
+    }
+
+    #[test]
+    fn test_clean_to_trash_and_restore() {
+        let (dir, repo) = setup();
+        let root = dir.path();
+        let options = CleanOptions { ignored: CleanIgnored::Include, directories: true };
+
+        let batch = clean(&repo, &options).unwrap().unwrap();
+        assert_eq!(batch.entries.len(), 4);
+        assert!(!root.join("target").exists());
+        assert!(!root.join("scratch.txt").exists());
+        assert!(root.join("tracked.rs").exists());
+        assert!(clean(&repo, &options).unwrap().is_none());
+
+        let trash = list_trash(&repo).unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id, batch.id);
+
+        // An existing path is never overwritten
+        fs::write(root.join("scratch.txt"), "new").unwrap();
+        let report = restore_trash(&repo, &batch.id).unwrap();
+        assert_eq!(report.restored.len(), 3);
+        assert_eq!(report.skipped, ["scratch.txt"]);
+        assert_eq!(fs::read_to_string(root.join("target/debug/app")).unwrap(), "0123456789");
+        assert_eq!(fs::read_to_string(root.join("scratch.txt")).unwrap(), "new");
+        assert_eq!(list_trash(&repo).unwrap()[0].entries.len(), 1);
+
+        fs::remove_file(root.join("scratch.txt")).unwrap();
+        restore_trash(&repo, &batch.id).unwrap();
+        assert_eq!(fs::read_to_string(root.join("scratch.txt")).unwrap(), "12345");
+        assert!(list_trash(&repo).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,465 @@
+//! Commit message linting
+//!
+//! A small subset of commitlint: allowed types and scopes, header length,
+//! subject case and trailing period, blank line before the body, body line
+//! wrapping and required trailers. Rules come from `.ai-c/commitlint.toml`
+//! in the repository root, or from the supported rules of
+//! `.commitlintrc.json`, on top of the conventional defaults.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::{
+    error::{AppError, AppResult},
+    git::{
+        conventional::{self, ConventionalCommit},
+        trailers,
+    },
+};
+
+/// Repository lint configuration, relative to the working directory
+pub const LINT_CONFIG_FILE: &str = ".ai-c/commitlint.toml";
+/// commitlint configuration read when [`LINT_CONFIG_FILE`] is absent
+pub const COMMITLINT_FILE: &str = ".commitlintrc.json";
+
+/// Severity of a rule, as in commitlint (`0` off, `1` warning, `2` error)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Off,
+    Warning,
+    #[default]
+    Error,
+}
+
+/// Required case of the first letter of the subject
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubjectCase {
+    Any,
+    #[default]
+    LowerFirst,
+    UpperFirst,
+}
+
+/// Lint rules; every field has a conventional default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintRules {
+    /// Severity of violations; `Off` disables linting
+    pub severity: LintSeverity,
+    /// Rules reported as warnings only, by commitlint name
+    pub warnings: Vec<String>,
+    /// Allowed commit types
+    pub types: Vec<String>,
+    /// Allowed scopes, empty allows any
+    pub scopes: Vec<String>,
+    pub scope_required: bool,
+    pub header_max_length: usize,
+    pub subject_case: SubjectCase,
+    pub subject_allow_full_stop: bool,
+    pub body_leading_blank: bool,
+    pub body_max_line_length: usize,
+    /// Trailer tokens that must be present, e.g. `Signed-off-by`
+    pub required_trailers: Vec<String>,
+}
+
+impl Default for LintRules {
+    fn default() -> Self {
+        Self {
+            severity: LintSeverity::Error,
+            warnings: Vec::new(),
+            types: conventional::COMMIT_TYPES.iter().map(|(name, _)| name.to_string()).collect(),
+            scopes: Vec::new(),
+            scope_required: false,
+            header_max_length: 100,
+            subject_case: SubjectCase::LowerFirst,
+            subject_allow_full_stop: false,
+            body_leading_blank: true,
+            body_max_line_length: 100,
+            required_trailers: Vec::new(),
+        }
+    }
+}
+
+impl LintRules {
+    /// Load the repository rules from `workdir`, falling back to the defaults
+    pub fn load(workdir: &Path) -> AppResult<Self> {
+        let path = workdir.join(LINT_CONFIG_FILE);
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            return toml::from_str(&content)
+                .map_err(|e| AppError::application(format!("Invalid {}: {}", LINT_CONFIG_FILE, e)));
+        }
+
+        let path = workdir.join(COMMITLINT_FILE);
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            return Self::from_commitlint(&serde_json::from_str(&content)?);
+        }
+
+        Ok(Self::default())
+    }
+
+    /// Map the supported commitlint rules onto the defaults
+    ///
+    /// Rules are `[level, "always" | "never", value]`; unknown rules are
+    /// ignored and level `1` rules are reported as warnings.
+    pub fn from_commitlint(config: &Value) -> AppResult<Self> {
+        let mut rules = Self::default();
+        let Some(entries) = config.get("rules").and_then(Value::as_object) else {
+            return Ok(rules);
+        };
+
+        for (name, rule) in entries {
+            let level = match rule.get(0).and_then(Value::as_u64) {
+                Some(1) => LintSeverity::Warning,
+                Some(2) => LintSeverity::Error,
+                _ => LintSeverity::Off,
+            };
+            let always = rule.get(1).and_then(Value::as_str) != Some("never");
+            let value = rule.get(2);
+            let enabled = level != LintSeverity::Off;
+            let strings = || -> Vec<String> {
+                match value {
+                    Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+                    Some(Value::String(item)) => vec![item.clone()],
+                    _ => Vec::new(),
+                }
+            };
+            let number = || value.and_then(Value::as_u64).map(|n| n as usize);
+
+            match name.as_str() {
+                "type-enum" if enabled => rules.types = strings(),
+                "type-enum" => rules.types.clear(),
+                "scope-enum" if enabled => rules.scopes = strings(),
+                "scope-empty" => rules.scope_required = enabled && !always,
+                "header-max-length" => rules.header_max_length = number().filter(|_| enabled).unwrap_or(usize::MAX),
+                "body-max-line-length" => {
+                    rules.body_max_line_length = number().filter(|_| enabled).unwrap_or(usize::MAX)
+                }
+                "subject-full-stop" => rules.subject_allow_full_stop = !enabled || always,
+                "body-leading-blank" => rules.body_leading_blank = enabled && always,
+                "subject-case" => {
+                    let cases = strings();
+                    let forbids_upper = cases.iter().any(|case| case == "sentence-case" || case == "upper-case");
+                    rules.subject_case = match (enabled, always) {
+                        (false, _) => SubjectCase::Any,
+                        (true, false) if forbids_upper => SubjectCase::LowerFirst,
+                        (true, true) if cases.iter().any(|case| case == "sentence-case") => SubjectCase::UpperFirst,
+                        (true, true) if cases.iter().any(|case| case == "lower-case") => SubjectCase::LowerFirst,
+                        _ => SubjectCase::Any,
+                    };
+                }
+                "trailer-exists" if enabled && always => {
+                    rules
+                        .required_trailers
+                        .extend(strings().iter().map(|t| t.trim_end_matches(':').to_string()));
+                }
+                _ => continue,
+            }
+            if level == LintSeverity::Warning {
+                rules.warnings.push(name.clone());
+            }
+        }
+
+        Ok(rules)
+    }
+}
+
+/// A broken rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintViolation {
+    /// commitlint rule name
+    pub rule: String,
+    pub severity: LintSeverity,
+    /// Zero-based line of the message the violation refers to
+    pub line: usize,
+    pub message: String,
+}
+
+/// Result of linting one message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintReport {
+    pub violations: Vec<LintViolation>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.violations.iter().any(|v| v.severity == LintSeverity::Error)
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Violations joined into one line each, for prompts and logs
+    pub fn summary(&self) -> String {
+        self.violations
+            .iter()
+            .map(|v| format!("line {}: {} ({})", v.line + 1, v.message, v.rule))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Lint a commit message; `#` comment lines are ignored like git does
+pub fn lint_message(message: &str, rules: &LintRules) -> LintReport {
+    let mut report = LintReport::default();
+    if rules.severity == LintSeverity::Off {
+        return report;
+    }
+    let mut push = |rule: &str, line: usize, message: String| {
+        let is_warning = rules.warnings.iter().any(|name| name == rule);
+        report.violations.push(LintViolation {
+            rule: rule.to_string(),
+            severity: if is_warning { LintSeverity::Warning } else { rules.severity },
+            line,
+            message,
+        });
+    };
+
+    let lines: Vec<&str> = message.lines().filter(|line| !line.starts_with('#')).collect();
+    let header = lines.first().copied().unwrap_or_default();
+    if header.trim().is_empty() {
+        push("subject-empty", 0, "Subject may not be empty".to_string());
+        return report;
+    }
+
+    if header.chars().count() > rules.header_max_length {
+        push(
+            "header-max-length",
+            0,
+            format!("Header is {} characters, maximum is {}", header.chars().count(), rules.header_max_length),
+        );
+    }
+
+    let cleaned = lines.join("\n");
+    let parsed = ConventionalCommit::parse(&cleaned);
+    match &parsed {
+        None if !rules.types.is_empty() => {
+            push("type-empty", 0, "Header must look like 'type(scope): subject'".to_string());
+        }
+        None => {}
+        Some(commit) => {
+            if !rules.types.is_empty() && !rules.types.contains(&commit.commit_type) {
+                push(
+                    "type-enum",
+                    0,
+                    format!("Type '{}' is not one of: {}", commit.commit_type, rules.types.join(", ")),
+                );
+            }
+            match &commit.scope {
+                Some(scope) if !rules.scopes.is_empty() && !rules.scopes.contains(scope) => push(
+                    "scope-enum",
+                    0,
+                    format!("Scope '{}' is not one of: {}", scope, rules.scopes.join(", ")),
+                ),
+                None if rules.scope_required => push("scope-empty", 0, "Scope may not be empty".to_string()),
+                _ => {}
+            }
+
+            let first = commit.description.chars().next().unwrap_or_default();
+            match rules.subject_case {
+                SubjectCase::LowerFirst if first.is_uppercase() => {
+                    push("subject-case", 0, "Subject must start with a lower-case letter".to_string())
+                }
+                SubjectCase::UpperFirst if first.is_lowercase() => {
+                    push("subject-case", 0, "Subject must start with an upper-case letter".to_string())
+                }
+                _ => {}
+            }
+            if !rules.subject_allow_full_stop && commit.description.ends_with('.') {
+                push("subject-full-stop", 0, "Subject may not end with a period".to_string());
+            }
+
+            for trailer in &rules.required_trailers {
+                if commit.footer_values(trailer).next().is_none() {
+                    push(
+                        "trailer-exists",
+                        lines.len().saturating_sub(1),
+                        format!("Missing required trailer '{}:'", trailer),
+                    );
+                }
+            }
+        }
+    }
+
+    if rules.body_leading_blank && lines.get(1).is_some_and(|line| !line.trim().is_empty()) {
+        push("body-leading-blank", 1, "Body must be separated from the header by a blank line".to_string());
+    }
+    // Trailers are not body text and may be longer
+    let body_end = match &parsed {
+        Some(commit) if !commit.footers.is_empty() => {
+            lines.iter().rposition(|line| line.trim().is_empty()).unwrap_or(lines.len())
+        }
+        _ => lines.len(),
+    };
+    for (index, line) in lines.iter().enumerate().take(body_end).skip(1) {
+        let length = line.chars().count();
+        if length > rules.body_max_line_length && !line.contains("://") {
+            push(
+                "body-max-line-length",
+                index,
+                format!("Line is {} characters, maximum is {}", length, rules.body_max_line_length),
+            );
+        }
+    }
+
+    report
+}
+
+/// Mechanically fix what can be fixed without understanding the change:
+/// subject case and period, the blank line after the header, body wrapping
+/// and an over-long header. The trailer block is left untouched.
+pub fn fix_message(message: &str, rules: &LintRules) -> String {
+    // Trailers are kept verbatim after the fixed text
+    let (message, trailer_block) = trailers::split_trailers(message);
+    let mut lines: Vec<String> = message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.trim_end().to_string())
+        .collect();
+    let Some(header) = lines.first_mut() else {
+        return String::new();
+    };
+
+    if let Some((prefix, subject)) = header.split_once(": ") {
+        let mut subject = subject.trim().to_string();
+        if !rules.subject_allow_full_stop {
+            while subject.ends_with('.') {
+                subject.pop();
+            }
+        }
+        let mut chars = subject.chars();
+        if let Some(first) = chars.next() {
+            let first: String = match rules.subject_case {
+                SubjectCase::LowerFirst => first.to_lowercase().collect(),
+                SubjectCase::UpperFirst => first.to_uppercase().collect(),
+                SubjectCase::Any => first.to_string(),
+            };
+            subject = first + chars.as_str();
+        }
+        *header = format!("{}: {}", prefix, subject);
+    }
+    if header.chars().count() > rules.header_max_length {
+        let truncated: String = header.chars().take(rules.header_max_length).collect();
+        *header = match truncated.rfind(' ') {
+            Some(space) if space > truncated.len() / 2 => truncated[..space].to_string(),
+            _ => truncated,
+        };
+    }
+
+    if rules.body_leading_blank && lines.get(1).is_some_and(|line| !line.is_empty()) {
+        lines.insert(1, String::new());
+    }
+
+    let mut fixed = vec![lines[0].clone()];
+    for line in &lines[1..] {
+        if line.chars().count() > rules.body_max_line_length && !line.contains("://") {
+            fixed.extend(wrap(line, rules.body_max_line_length));
+        } else {
+            fixed.push(line.clone());
+        }
+    }
+    trailers::format_message(&fixed.join("\n"), &trailer_block)
+}
+
+/// Greedy word wrap that keeps list-item indentation on continuation lines
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+    let continuation = if line.trim_start().starts_with("- ") || line.trim_start().starts_with("* ") {
+        format!("{}  ", indent)
+    } else {
+        indent.clone()
+    };
+
+    let mut wrapped = Vec::new();
+    let mut current = indent;
+    for word in line.split_whitespace() {
+        let is_start = current.trim().is_empty();
+        if !is_start && current.chars().count() + 1 + word.chars().count() > width {
+            wrapped.push(std::mem::replace(&mut current, continuation.clone()));
+        }
+        if !current.trim().is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    wrapped.push(current);
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules_of(report: &LintReport) -> Vec<&str> {
+        report.violations.iter().map(|v| v.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_message() {
+        let rules = LintRules {
+            scopes: vec!["ui".to_string(), "git".to_string()],
+            body_max_line_length: 20,
+            required_trailers: vec!["Signed-off-by".to_string()],
+            ..Default::default()
+        };
+
+        let report = lint_message("feat(ui): add panel\n\nShort body.\n\nSigned-off-by: A <a@example.com>", &rules);
+        assert!(report.is_clean(), "{}", report.summary());
+
+        let report = lint_message("Feature(core): Add panel.\nbody line that is far too long", &rules);
+        assert_eq!(
+            rules_of(&report),
+            ["type-enum", "scope-enum", "subject-case", "subject-full-stop", "trailer-exists", "body-leading-blank", "body-max-line-length"]
+        );
+        assert!(report.has_errors());
+        assert_eq!(report.violations.last().unwrap().line, 1);
+
+        let report = lint_message("update things\n# comment lines are ignored", &LintRules::default());
+        assert_eq!(rules_of(&report), ["type-empty"]);
+    }
+
+    #[test]
+    fn test_fix_message() {
+        let rules = LintRules { body_max_line_length: 24, ..Default::default() };
+        let fixed = fix_message("fix(api): Reject empty ids.\n- the handler used to panic on empty input", &rules);
+        assert_eq!(
+            fixed,
+            "fix(api): reject empty ids\n\n- the handler used to\n  panic on empty input"
+        );
+        assert!(lint_message(&fixed, &rules).is_clean());
+    }
+
+    #[test]
+    fn test_from_commitlint() {
+        let config: Value = serde_json::from_str(
+            r#"{
+                "extends": ["@commitlint/config-conventional"],
+                "rules": {
+                    "type-enum": [2, "always", ["feat", "fix", "release"]],
+                    "scope-empty": [1, "never"],
+                    "header-max-length": [2, "always", 72],
+                    "subject-case": [2, "never", ["sentence-case", "start-case", "pascal-case", "upper-case"]],
+                    "body-max-line-length": [0, "always", 100],
+                    "trailer-exists": [2, "always", "Signed-off-by:"],
+                    "unknown-rule": [2, "always"]
+                }
+            }"#,
+        )
+        .unwrap();
+        let rules = LintRules::from_commitlint(&config).unwrap();
+
+        assert_eq!(rules.types, ["feat", "fix", "release"]);
+        assert!(rules.scope_required);
+        assert_eq!(rules.header_max_length, 72);
+        assert_eq!(rules.subject_case, SubjectCase::LowerFirst);
+        assert_eq!(rules.body_max_line_length, usize::MAX);
+        assert_eq!(rules.required_trailers, ["Signed-off-by"]);
+        assert_eq!(rules.warnings, ["scope-empty"]);
+        assert!(!rules.subject_allow_full_stop);
+    }
+}
@@ -0,0 +1,583 @@
+//! Blob content classification for diffs
+//!
+//! Before two sides of a file are diffed line by line, each side is turned
+//! into a [`BlobContent`]: binary detection follows `.gitattributes`
+//! (`binary`, `-diff`, `diff`) and falls back to git's NUL-byte rule, image
+//! headers are parsed for dimensions, and text is decoded using
+//! `working-tree-encoding`, a byte order mark or detection (UTF-8, UTF-16,
+//! GBK, otherwise Latin-1). [`describe_change`] summarizes what the line
+//! diff cannot show: binary sizes and hashes, image metadata, encodings and
+//! line-ending conversions.
+
+use encoding_rs::{Encoding, GBK, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use git2::{AttrCheckFlags, AttrValue, ObjectType, Oid, Repository};
+use std::path::Path;
+
+use super::diff_driver::DiffDriver;
+
+/// Git only looks at the first 8000 bytes for NUL when guessing binary content
+const BINARY_PROBE_LEN: usize = 8000;
+/// Bytes sampled when guessing UTF-16 without a BOM
+const UTF16_PROBE_LEN: usize = 1024;
+
+/// Attributes that affect how a path is diffed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentAttributes {
+    /// `Some(true)` for `binary`/`-diff`, `Some(false)` for `diff`/`text`
+    pub binary: Option<bool>,
+    /// `working-tree-encoding`
+    pub encoding: Option<String>,
+    /// Driver named by `diff=<driver>`
+    pub driver: Option<DiffDriver>,
+}
+
+/// Read the diff-related attributes of `path`
+pub fn attributes(repo: &Repository, path: &str) -> ContentAttributes {
+    let flags = AttrCheckFlags::FILE_THEN_INDEX;
+    let path = Path::new(path);
+    // libgit2 marks set/unset attributes by pointer, so classify before copying
+    let state = |name: &str| repo.get_attr(path, name, flags).ok().map(AttrValue::from_string);
+
+    let (binary, driver) = match (state("diff"), state("text")) {
+        (Some(AttrValue::False), _) => (Some(true), None),
+        (Some(AttrValue::String(name)), _) => {
+            // A driver decides binary-ness itself, or leaves it to detection
+            let driver = DiffDriver::load(repo, name);
+            (driver.binary, Some(driver))
+        }
+        (Some(AttrValue::True), _) | (_, Some(AttrValue::True)) => (Some(false), None),
+        _ => (None, None),
+    };
+    let encoding = match state("working-tree-encoding") {
+        Some(AttrValue::String(value)) if !value.is_empty() => Some(value.to_string()),
+        _ => None,
+    };
+
+    ContentAttributes { binary, encoding, driver }
+}
+
+/// How the encoding of a text blob was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingSource {
+    /// Plain UTF-8 (the default)
+    Utf8,
+    ByteOrderMark,
+    /// `working-tree-encoding` in `.gitattributes`
+    Attribute,
+    Detected,
+}
+
+/// Result of running a driver's textconv command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextconvOutcome {
+    /// Converted by the named driver
+    Converted(String),
+    Failed(String),
+}
+
+/// Counts of line terminators
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineEndings {
+    pub lf: usize,
+    pub crlf: usize,
+}
+
+impl LineEndings {
+    pub fn of(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        Self { lf: text.matches('\n').count() - crlf, crlf }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match (self.lf, self.crlf) {
+            (0, 0) => "none",
+            (_, 0) => "LF",
+            (0, _) => "CRLF",
+            _ => "mixed",
+        }
+    }
+}
+
+/// Dimensions and format details read from an image header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub format: &'static str,
+    pub width: u32,
+    pub height: u32,
+    /// Color type, bit depth and similar details when the header has them
+    pub detail: Option<String>,
+}
+
+impl ImageInfo {
+    fn describe(&self) -> String {
+        match &self.detail {
+            Some(detail) => format!("{} {}×{} ({})", self.format, self.width, self.height, detail),
+            None => format!("{} {}×{}", self.format, self.width, self.height),
+        }
+    }
+}
+
+/// What a blob holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentKind {
+    Text,
+    Binary,
+    Image(ImageInfo),
+}
+
+/// One side of a file diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobContent {
+    /// `false` when the path does not exist on this side
+    pub exists: bool,
+    pub size: usize,
+    /// Blob id the content would have in git
+    pub id: Option<Oid>,
+    pub kind: ContentKind,
+    /// Decoded text; empty for binary content
+    pub text: String,
+    pub encoding: &'static str,
+    pub encoding_source: EncodingSource,
+    /// Some bytes were replaced while decoding
+    pub decode_errors: bool,
+    pub line_endings: LineEndings,
+    /// Diff driver of the path (`diff=<driver>`)
+    pub driver: Option<DiffDriver>,
+    /// Set when a textconv driver applies to this content
+    pub textconv: Option<TextconvOutcome>,
+}
+
+impl BlobContent {
+    pub fn missing() -> Self {
+        Self {
+            exists: false,
+            size: 0,
+            id: None,
+            kind: ContentKind::Text,
+            text: String::new(),
+            encoding: UTF_8.name(),
+            encoding_source: EncodingSource::Utf8,
+            decode_errors: false,
+            line_endings: LineEndings::default(),
+            driver: None,
+            textconv: None,
+        }
+    }
+
+    /// Classify and decode raw bytes
+    pub fn from_bytes(bytes: &[u8], attributes: &ContentAttributes) -> Self {
+        let id = Oid::hash_object(ObjectType::Blob, bytes).ok();
+        let mut content = Self {
+            exists: true,
+            size: bytes.len(),
+            id,
+            driver: attributes.driver.clone(),
+            ..Self::missing()
+        };
+
+        if let Some(image) = image_info(bytes) {
+            if attributes.binary != Some(false) {
+                content.kind = ContentKind::Image(image);
+                return content;
+            }
+        }
+
+        let (encoding, source) = choose_encoding(bytes, attributes);
+        let utf16 = encoding == UTF_16LE || encoding == UTF_16BE;
+        let binary = match attributes.binary {
+            Some(binary) => binary,
+            None => !utf16 && bytes[..bytes.len().min(BINARY_PROBE_LEN)].contains(&0),
+        };
+        if binary {
+            content.kind = ContentKind::Binary;
+            return content;
+        }
+
+        let (text, errors) = match source {
+            EncodingSource::ByteOrderMark => {
+                let (text, _, errors) = encoding.decode(bytes);
+                (text, errors)
+            }
+            _ => encoding.decode_without_bom_handling(bytes),
+        };
+        content.text = text.into_owned();
+        content.encoding = encoding.name();
+        content.encoding_source = source;
+        content.decode_errors = errors;
+        content.line_endings = LineEndings::of(&content.text);
+        content
+    }
+
+    /// Replace the content with the output of `driver`'s textconv command
+    pub fn set_converted_text(&mut self, driver: &str, text: &str) {
+        self.kind = ContentKind::Text;
+        self.text = text.to_string();
+        self.encoding = UTF_8.name();
+        self.encoding_source = EncodingSource::Utf8;
+        self.decode_errors = false;
+        self.line_endings = LineEndings::of(text);
+        self.textconv = Some(TextconvOutcome::Converted(driver.to_string()));
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.kind == ContentKind::Text
+    }
+}
+
+fn choose_encoding(bytes: &[u8], attributes: &ContentAttributes) -> (&'static Encoding, EncodingSource) {
+    if let Some(encoding) = attributes.encoding.as_deref().and_then(|label| Encoding::for_label(label.as_bytes())) {
+        return (encoding, EncodingSource::Attribute);
+    }
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return (encoding, EncodingSource::ByteOrderMark);
+    }
+    // NUL is valid UTF-8, so UTF-16 has to be ruled out first
+    if let Some(encoding) = detect_utf16(bytes) {
+        return (encoding, EncodingSource::Detected);
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return (UTF_8, EncodingSource::Utf8);
+    }
+    (legacy_encoding(bytes), EncodingSource::Detected)
+}
+
+/// UTF-16 without a BOM: ASCII text leaves every other byte zero
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let probe = &bytes[..bytes.len().min(UTF16_PROBE_LEN)];
+    let pairs = probe.len() / 2;
+    // A complete odd-length blob cannot be UTF-16
+    if pairs == 0 || !probe.len().is_multiple_of(2) && probe.len() == bytes.len() {
+        return None;
+    }
+    let even_zero = probe.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_zero = probe.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    if odd_zero * 2 > pairs && even_zero * 10 < pairs {
+        Some(UTF_16LE)
+    } else if even_zero * 2 > pairs && odd_zero * 10 < pairs {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Guess the encoding of bytes that are not valid UTF-8
+pub fn legacy_encoding(bytes: &[u8]) -> &'static Encoding {
+    // GBK when it decodes cleanly and mostly into CJK characters
+    if let Some(text) = GBK.decode_without_bom_handling_and_without_replacement(bytes) {
+        let non_ascii = text.chars().filter(|c| !c.is_ascii()).count();
+        let cjk = text.chars().filter(|&c| ('\u{4e00}'..='\u{9fff}').contains(&c) || ('\u{3000}'..='\u{303f}').contains(&c) || ('\u{ff00}'..='\u{ffef}').contains(&c)).count();
+        if non_ascii > 0 && cjk * 10 >= non_ascii * 8 {
+            return GBK;
+        }
+    }
+    WINDOWS_1252
+}
+
+/// Parse the header of PNG, GIF, JPEG, BMP and WebP images
+pub fn image_info(bytes: &[u8]) -> Option<ImageInfo> {
+    let be32 = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+    let le16 = |at: usize| bytes.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as u32);
+    let le32 = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") && bytes.get(12..16) == Some(b"IHDR") {
+        let color = match bytes.get(25)? {
+            0 => "grayscale",
+            2 => "RGB",
+            3 => "indexed",
+            4 => "grayscale+alpha",
+            6 => "RGBA",
+            _ => "unknown color",
+        };
+        return Some(ImageInfo {
+            format: "PNG",
+            width: be32(16)?,
+            height: be32(20)?,
+            detail: Some(format!("{}-bit {}", bytes.get(24)?, color)),
+        });
+    }
+
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(ImageInfo {
+            format: "GIF",
+            width: le16(6)?,
+            height: le16(8)?,
+            detail: Some(String::from_utf8_lossy(&bytes[3..6]).to_string()),
+        });
+    }
+
+    if bytes.starts_with(b"BM") && bytes.len() >= 30 {
+        let height = le32(22)? as i32;
+        return Some(ImageInfo {
+            format: "BMP",
+            width: le32(18)?,
+            height: height.unsigned_abs(),
+            detail: Some(format!("{}-bit", le16(28)?)),
+        });
+    }
+
+    if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        let chunk = bytes.get(12..16)?;
+        let (width, height, detail) = match chunk {
+            b"VP8 " => (le16(26)? & 0x3fff, le16(28)? & 0x3fff, "lossy"),
+            b"VP8L" => {
+                let bits = le32(21)?;
+                ((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1, "lossless")
+            }
+            b"VP8X" => {
+                let le24 = |at: usize| bytes.get(at..at + 3).map(|b| u32::from_le_bytes([b[0], b[1], b[2], 0]));
+                (le24(24)? + 1, le24(27)? + 1, "extended")
+            }
+            _ => return None,
+        };
+        return Some(ImageInfo { format: "WebP", width, height, detail: Some(detail.to_string()) });
+    }
+
+    if bytes.starts_with(&[0xff, 0xd8]) {
+        // Walk the segments up to the first start-of-frame marker
+        let mut pos = 2;
+        while pos + 9 < bytes.len() {
+            if bytes[pos] != 0xff {
+                return None;
+            }
+            let marker = bytes[pos + 1];
+            let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+            let is_frame = (0xc0..=0xcf).contains(&marker) && !matches!(marker, 0xc4 | 0xc8 | 0xcc);
+            if is_frame {
+                let height = u16::from_be_bytes([bytes[pos + 5], bytes[pos + 6]]) as u32;
+                let width = u16::from_be_bytes([bytes[pos + 7], bytes[pos + 8]]) as u32;
+                let kind = if marker == 0xc2 { "progressive" } else { "baseline" };
+                let components = bytes[pos + 9];
+                return Some(ImageInfo {
+                    format: "JPEG",
+                    width,
+                    height,
+                    detail: Some(format!("{}, {} components", kind, components)),
+                });
+            }
+            pos += 2 + length;
+        }
+    }
+
+    None
+}
+
+/// Human-readable byte size
+pub fn format_size(size: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", size)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn describe_side(label: &str, side: &BlobContent) -> String {
+    if !side.exists {
+        return format!("{}: (none)", label);
+    }
+    let id = side.id.map(|id| id.to_string()[..8].to_string()).unwrap_or_default();
+    format!("{}: {} ({} bytes)  blob {}", label, format_size(side.size), side.size, id)
+}
+
+/// Summary lines for what a line diff does not show
+pub fn describe_change(old: &BlobContent, new: &BlobContent) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let mut drivers = Vec::new();
+    for side in [old, new] {
+        match &side.textconv {
+            Some(TextconvOutcome::Converted(driver)) if !drivers.contains(&driver) => drivers.push(driver),
+            Some(TextconvOutcome::Failed(error)) => lines.push(format!("Textconv: {}", error)),
+            _ => {}
+        }
+    }
+    for driver in drivers {
+        lines.push(format!("Converted to text by the '{}' diff driver (textconv)", driver));
+    }
+
+    if !old.is_text() || !new.is_text() {
+        match (&old.kind, &new.kind) {
+            (ContentKind::Image(before), ContentKind::Image(after)) => {
+                lines.push(format!("Image: {} → {}", before.describe(), after.describe()));
+                if (before.width, before.height) != (after.width, after.height) {
+                    lines.push(format!(
+                        "Dimensions: {}×{} → {}×{} ({:+}×{:+})",
+                        before.width,
+                        before.height,
+                        after.width,
+                        after.height,
+                        after.width as i64 - before.width as i64,
+                        after.height as i64 - before.height as i64
+                    ));
+                }
+            }
+            (ContentKind::Image(image), _) if !new.exists => lines.push(format!("Image removed: {}", image.describe())),
+            (_, ContentKind::Image(image)) if !old.exists => lines.push(format!("Image added: {}", image.describe())),
+            (before, after) => {
+                for (label, kind) in [("old", before), ("new", after)] {
+                    if let ContentKind::Image(image) = kind {
+                        lines.push(format!("{} image: {}", label, image.describe()));
+                    }
+                }
+            }
+        }
+
+        lines.push(if old.id == new.id { "Binary files are identical" } else { "Binary files differ" }.to_string());
+        lines.push(describe_side("old", old));
+        lines.push(describe_side("new", new));
+        if old.exists && new.exists && old.size != new.size {
+            let delta = new.size as i64 - old.size as i64;
+            let sign = if delta < 0 { "-" } else { "+" };
+            lines.push(format!("Size change: {}{}", sign, format_size(delta.unsigned_abs() as usize)));
+        }
+        return lines;
+    }
+
+    // Encodings
+    let describe_encoding = |side: &BlobContent| match side.encoding_source {
+        EncodingSource::Attribute => format!("{} (working-tree-encoding)", side.encoding),
+        EncodingSource::ByteOrderMark => format!("{} (BOM)", side.encoding),
+        EncodingSource::Detected => format!("{} (detected)", side.encoding),
+        EncodingSource::Utf8 => side.encoding.to_string(),
+    };
+    let sides: Vec<&BlobContent> = [old, new].into_iter().filter(|side| side.exists).collect();
+    if sides.iter().any(|side| side.encoding_source != EncodingSource::Utf8) {
+        if old.exists && new.exists && (old.encoding, old.encoding_source) != (new.encoding, new.encoding_source) {
+            lines.push(format!("Encoding: {} → {}", describe_encoding(old), describe_encoding(new)));
+        } else if let Some(side) = sides.first() {
+            lines.push(format!("Encoding: {}", describe_encoding(side)));
+        }
+    }
+    for side in &sides {
+        if side.decode_errors {
+            lines.push(format!("Some bytes are not valid {} and are shown as �", side.encoding));
+        }
+    }
+
+    // Line endings
+    if old.exists && new.exists && old.line_endings.label() != new.line_endings.label() {
+        let normalized_equal = old.text.replace("\r\n", "\n") == new.text.replace("\r\n", "\n");
+        lines.push(format!(
+            "Line endings: {} → {}{}",
+            old.line_endings.label(),
+            new.line_endings.label(),
+            if normalized_equal { " (no other changes)" } else { "" }
+        ));
+    } else if old.exists && new.exists && old.line_endings != new.line_endings {
+        lines.push(format!(
+            "Line endings: {} CRLF / {} LF → {} CRLF / {} LF",
+            old.line_endings.crlf, old.line_endings.lf, new.line_endings.crlf, new.line_endings.lf
+        ));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_detection_and_attributes() {
+        let plain = ContentAttributes::default();
+        assert_eq!(BlobContent::from_bytes(b"hello\n", &plain).kind, ContentKind::Text);
+        assert_eq!(BlobContent::from_bytes(b"he\0llo", &plain).kind, ContentKind::Binary);
+
+        let forced_binary = ContentAttributes { binary: Some(true), ..Default::default() };
+        assert_eq!(BlobContent::from_bytes(b"hello\n", &forced_binary).kind, ContentKind::Binary);
+        let forced_text = ContentAttributes { binary: Some(false), ..Default::default() };
+        assert_eq!(BlobContent::from_bytes(b"he\0llo", &forced_text).kind, ContentKind::Text);
+
+        let old = BlobContent::from_bytes(b"\0\x01\x02", &plain);
+        let new = BlobContent::from_bytes(b"\0\x01\x02\x03", &plain);
+        let summary = describe_change(&old, &new);
+        assert_eq!(summary[0], "Binary files differ");
+        assert!(summary[1].starts_with("old: 3 B (3 bytes)  blob "));
+        assert_eq!(summary[3], "Size change: +1 B");
+    }
+
+    #[test]
+    fn test_gitattributes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(
+            dir.path().join(".gitattributes"),
+            "*.dat binary\n*.lock -diff\n*.txt working-tree-encoding=UTF-16LE\n*.bin diff\n",
+        )
+        .unwrap();
+
+        assert_eq!(attributes(&repo, "a.dat").binary, Some(true));
+        assert_eq!(attributes(&repo, "Cargo.lock").binary, Some(true));
+        assert_eq!(attributes(&repo, "a.bin").binary, Some(false));
+        assert_eq!(attributes(&repo, "a.txt").encoding.as_deref(), Some("UTF-16LE"));
+        assert_eq!(attributes(&repo, "a.rs"), ContentAttributes::default());
+    }
+
+    #[test]
+    fn test_encodings() {
+        let plain = ContentAttributes::default();
+
+        // UTF-16LE with and without BOM
+        let utf16: Vec<u8> = "hi\r\n".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let with_bom = [vec![0xff, 0xfe], utf16.clone()].concat();
+        let content = BlobContent::from_bytes(&with_bom, &plain);
+        assert_eq!((content.text.as_str(), content.encoding), ("hi\r\n", "UTF-16LE"));
+        let content = BlobContent::from_bytes(&utf16, &plain);
+        assert_eq!((content.text.as_str(), content.encoding_source), ("hi\r\n", EncodingSource::Detected));
+
+        // GBK "中文" and Latin-1 "café"
+        let content = BlobContent::from_bytes(&[0xd6, 0xd0, 0xce, 0xc4, b'\n'], &plain);
+        assert_eq!((content.text.as_str(), content.encoding), ("中文\n", "GBK"));
+        let content = BlobContent::from_bytes(b"caf\xe9\n", &plain);
+        assert_eq!((content.text.as_str(), content.encoding), ("café\n", "windows-1252"));
+
+        // working-tree-encoding wins over detection
+        let attributes = ContentAttributes { encoding: Some("ISO-8859-1".to_string()), ..Default::default() };
+        let content = BlobContent::from_bytes(&[0xd6, 0xd0], &attributes);
+        assert_eq!((content.text.as_str(), content.encoding_source), ("ÖÐ", EncodingSource::Attribute));
+    }
+
+    #[test]
+    fn test_line_ending_summary() {
+        let plain = ContentAttributes::default();
+        let old = BlobContent::from_bytes(b"a\r\nb\r\n", &plain);
+        let new = BlobContent::from_bytes(b"a\nb\n", &plain);
+        assert_eq!(describe_change(&old, &new), vec!["Line endings: CRLF → LF (no other changes)"]);
+
+        let new = BlobContent::from_bytes(b"a\r\nb\n", &plain);
+        assert_eq!(describe_change(&old, &new), vec!["Line endings: CRLF → mixed (no other changes)"]);
+
+        let new = BlobContent::from_bytes(b"a\r\nc\n", &plain);
+        assert_eq!(describe_change(&old, &new), vec!["Line endings: CRLF → mixed"]);
+    }
+
+    #[test]
+    fn test_image_headers() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        png.extend_from_slice(&[8, 6, 0, 0, 0]);
+        let mut bigger = png.clone();
+        bigger[16..20].copy_from_slice(&800u32.to_be_bytes());
+
+        let plain = ContentAttributes::default();
+        let old = BlobContent::from_bytes(&png, &plain);
+        assert_eq!(
+            old.kind,
+            ContentKind::Image(ImageInfo { format: "PNG", width: 640, height: 480, detail: Some("8-bit RGBA".to_string()) })
+        );
+        let summary = describe_change(&old, &BlobContent::from_bytes(&bigger, &plain));
+        assert_eq!(summary[0], "Image: PNG 640×480 (8-bit RGBA) → PNG 800×480 (8-bit RGBA)");
+        assert_eq!(summary[1], "Dimensions: 640×480 → 800×480 (+160×+0)");
+
+        let gif = b"GIF89a\x10\x00\x20\x00";
+        let info = image_info(gif).unwrap();
+        assert_eq!((info.format, info.width, info.height), ("GIF", 16, 32));
+    }
+}
@@ -0,0 +1,223 @@
+//! Conventional Commits parsing
+//!
+//! Parses `type(scope)!: description` headers together with the body and
+//! the trailing footer block (`Token: value` / `Token #value`), following
+//! the Conventional Commits 1.0 specification.
+
+use serde::{Deserialize, Serialize};
+
+/// Known commit types and the changelog section each one maps to, in
+/// changelog order
+pub const COMMIT_TYPES: &[(&str, &str)] = &[
+    ("feat", "Added"),
+    ("fix", "Fixed"),
+    ("perf", "Performance"),
+    ("refactor", "Changed"),
+    ("revert", "Reverted"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build"),
+    ("ci", "CI"),
+    ("style", "Style"),
+    ("chore", "Chores"),
+];
+
+/// Changelog section title for a commit type, `None` for unknown types
+pub fn section_title(commit_type: &str) -> Option<&'static str> {
+    COMMIT_TYPES
+        .iter()
+        .find(|(name, _)| *name == commit_type)
+        .map(|(_, title)| *title)
+}
+
+/// A parsed conventional commit message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConventionalCommit {
+    /// Lower-cased commit type (`feat`, `fix`, ...)
+    pub commit_type: String,
+    pub scope: Option<String>,
+    /// `!` in the header or a `BREAKING CHANGE` footer
+    pub breaking: bool,
+    pub description: String,
+    pub body: Option<String>,
+    /// Footers in message order, e.g. `("Refs", "#42")`
+    pub footers: Vec<(String, String)>,
+}
+
+impl ConventionalCommit {
+    /// Parse a full commit message, `None` if the header is not conventional
+    pub fn parse(message: &str) -> Option<Self> {
+        let mut lines = message.lines();
+        let header = lines.next()?.trim_end();
+        let (commit_type, scope, bang, description) = parse_header(header)?;
+
+        let rest: Vec<&str> = lines.collect();
+        let paragraphs = split_paragraphs(&rest);
+        let footer_block = paragraphs
+            .split_last()
+            .and_then(|(last, body)| Some((body.to_vec(), parse_footers(last)?)));
+        let (body_paragraphs, footers) = footer_block.unwrap_or((paragraphs, Vec::new()));
+
+        let body = (!body_paragraphs.is_empty()).then(|| body_paragraphs.join("\n\n"));
+        let breaking = bang || footers.iter().any(|(token, _)| is_breaking_token(token));
+
+        Some(Self {
+            commit_type,
+            scope,
+            breaking,
+            description,
+            body,
+            footers,
+        })
+    }
+
+    /// Text of the `BREAKING CHANGE` footer, if any
+    pub fn breaking_note(&self) -> Option<&str> {
+        self.footers
+            .iter()
+            .find(|(token, _)| is_breaking_token(token))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Values of all footers with the given token (case-insensitive)
+    pub fn footer_values<'a>(&'a self, token: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.footers
+            .iter()
+            .filter(move |(name, _)| name.eq_ignore_ascii_case(token))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Split `type(scope)!: description` into its parts
+fn parse_header(header: &str) -> Option<(String, Option<String>, bool, String)> {
+    let (prefix, description) = header.split_once(':')?;
+    let description = description.strip_prefix(' ')?.trim();
+    if description.is_empty() {
+        return None;
+    }
+
+    let (prefix, bang) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+
+    let (commit_type, scope) = match prefix.split_once('(') {
+        Some((commit_type, scope)) => {
+            let scope = scope.strip_suffix(')')?.trim();
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            (commit_type, Some(scope.to_string()))
+        }
+        None => (prefix, None),
+    };
+
+    if commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some((commit_type.to_ascii_lowercase(), scope, bang, description.to_string()))
+}
+
+/// Blank-line separated paragraphs, with surrounding blank lines dropped
+fn split_paragraphs(lines: &[&str]) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in lines {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join("\n"));
+    }
+    paragraphs
+}
+
+fn is_breaking_token(token: &str) -> bool {
+    token == "BREAKING CHANGE" || token == "BREAKING-CHANGE"
+}
+
+/// Split a footer line into token and value
+fn footer_line(line: &str) -> Option<(String, String)> {
+    if let Some(value) = ["BREAKING CHANGE: ", "BREAKING-CHANGE: "]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+    {
+        let token = if line.starts_with("BREAKING CHANGE") { "BREAKING CHANGE" } else { "BREAKING-CHANGE" };
+        return Some((token.to_string(), value.trim().to_string()));
+    }
+
+    let (token, value) = line
+        .split_once(": ")
+        .or_else(|| line.split_once(" #"))?;
+    let is_token = !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !is_token {
+        return None;
+    }
+    let value = if line[token.len()..].starts_with(" #") { format!("#{}", value) } else { value.to_string() };
+    Some((token.to_string(), value.trim().to_string()))
+}
+
+/// Parse the last paragraph as footers; continuation lines extend the
+/// previous footer's value. `None` if the paragraph does not start with one.
+fn parse_footers(paragraph: &str) -> Option<Vec<(String, String)>> {
+    let mut footers: Vec<(String, String)> = Vec::new();
+    for line in paragraph.lines() {
+        match footer_line(line) {
+            Some(footer) => footers.push(footer),
+            None => {
+                let (_, value) = footers.last_mut()?;
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+        }
+    }
+    Some(footers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        let commit = ConventionalCommit::parse("feat(parser)!: support arrays").unwrap();
+        assert_eq!(commit.commit_type, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("parser"));
+        assert!(commit.breaking);
+        assert_eq!(commit.description, "support arrays");
+        assert_eq!(commit.body, None);
+
+        let commit = ConventionalCommit::parse("Fix: typo").unwrap();
+        assert_eq!(commit.commit_type, "fix");
+        assert_eq!(commit.scope, None);
+        assert!(!commit.breaking);
+
+        assert!(ConventionalCommit::parse("Update README").is_none());
+        assert!(ConventionalCommit::parse("feat:missing space").is_none());
+        assert!(ConventionalCommit::parse("feat(): empty scope").is_none());
+        assert!(ConventionalCommit::parse("feat two: words").is_none());
+    }
+
+    #[test]
+    fn test_parse_body_and_footers() {
+        let message = "fix(api): reject empty ids\n\nThe handler used to panic.\n\nIt now returns 400.\n\nBREAKING CHANGE: ids are validated\n  before lookup\nRefs #42\nReviewed-by: Jane <jane@example.com>\n";
+        let commit = ConventionalCommit::parse(message).unwrap();
+
+        assert!(commit.breaking);
+        assert_eq!(commit.body.as_deref(), Some("The handler used to panic.\n\nIt now returns 400."));
+        assert_eq!(commit.breaking_note(), Some("ids are validated\nbefore lookup"));
+        assert_eq!(commit.footer_values("refs").collect::<Vec<_>>(), ["#42"]);
+        assert_eq!(commit.footers.len(), 3);
+
+        let commit = ConventionalCommit::parse("docs: readme\n\nJust a body: with a colon in it.").unwrap();
+        assert_eq!(commit.body.as_deref(), Some("Just a body: with a colon in it."));
+        assert!(commit.footers.is_empty());
+    }
+}
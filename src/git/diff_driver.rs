@@ -0,0 +1,374 @@
+//! Diff drivers from `.gitattributes`
+//!
+//! A path with `diff=<driver>` picks up `diff.<driver>.*` from git config:
+//! `textconv` turns a blob into text before it is diffed, `binary` forces
+//! binary handling, and `xfuncname`/`funcname` choose the line shown after
+//! each hunk header. Textconv runs the way git runs it — the command with a
+//! temporary copy of the blob as its argument, through the shell — but with
+//! a timeout, and its output is cached by blob id. The copy is created
+//! exclusively under `.git/ai-c-tmp/`, and on timeout the converter's whole
+//! process group is killed, not just the shell.
+
+use git2::{Oid, Repository};
+use regex::Regex;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use super::content::{self, BlobContent, TextconvOutcome};
+use crate::error::{AppError, AppResult};
+
+/// Converted blobs kept in memory
+const TEXTCONV_CACHE_CAPACITY: usize = 64;
+/// Git truncates hunk header context to this many bytes
+const FUNCNAME_MAX_LEN: usize = 80;
+/// Directory under `.git/` for textconv inputs and diff spools
+const PRIVATE_TMP_DIR: &str = "ai-c-tmp";
+
+/// `diff.<name>.*` settings of one driver
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffDriver {
+    pub name: String,
+    /// `diff.<name>.textconv`
+    pub textconv: Option<String>,
+    /// `diff.<name>.binary`
+    pub binary: Option<bool>,
+    /// `diff.<name>.xfuncname`, or `funcname` when only that is set
+    pub funcname: Option<String>,
+}
+
+impl DiffDriver {
+    /// Read the driver's settings from git config
+    pub fn load(repo: &Repository, name: &str) -> Self {
+        let config = repo.config().ok().and_then(|mut config| config.snapshot().ok());
+        let string = |key: &str| {
+            config
+                .as_ref()
+                .and_then(|config| config.get_string(&format!("diff.{}.{}", name, key)).ok())
+                .filter(|value| !value.is_empty())
+        };
+        let binary = config
+            .as_ref()
+            .and_then(|config| config.get_bool(&format!("diff.{}.binary", name)).ok());
+
+        Self {
+            name: name.to_string(),
+            textconv: string("textconv"),
+            binary,
+            funcname: string("xfuncname").or_else(|| string("funcname")),
+        }
+    }
+}
+
+/// Hunk header context patterns, one regex per line; `!` lines exclude matches
+#[derive(Debug, Clone)]
+pub struct FuncnamePattern {
+    source: String,
+    rules: Vec<(bool, Regex)>,
+}
+
+impl FuncnamePattern {
+    pub fn parse(pattern: &str) -> AppResult<Self> {
+        let rules = pattern
+            .split('\n')
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| {
+                let (negated, rule) = match rule.strip_prefix('!') {
+                    Some(rule) => (true, rule),
+                    None => (false, rule),
+                };
+                Regex::new(rule)
+                    .map(|regex| (negated, regex))
+                    .map_err(|e| AppError::InvalidOperation(format!("Invalid funcname pattern {:?}: {}", rule, e)))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self {
+            source: pattern.to_string(),
+            rules,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Header context for `line`; the first capture group when the rule has one
+    pub fn matches(&self, line: &str) -> Option<String> {
+        for (negated, regex) in &self.rules {
+            let Some(captures) = regex.captures(line) else { continue };
+            if *negated {
+                return None;
+            }
+            let matched = captures.get(1).or_else(|| captures.get(0))?.as_str().trim_end();
+            let mut end = matched.len().min(FUNCNAME_MAX_LEN);
+            while !matched.is_char_boundary(end) {
+                end -= 1;
+            }
+            return Some(matched[..end].to_string());
+        }
+        None
+    }
+
+    /// Context for a hunk: the nearest matching line before `before` (0-based, exclusive)
+    pub fn find(&self, lines: &[&str], before: usize) -> Option<String> {
+        lines[..before.min(lines.len())].iter().rev().find_map(|line| self.matches(line))
+    }
+}
+
+/// Runs textconv commands and caches their output by command and blob id
+#[derive(Debug)]
+pub struct TextconvRunner {
+    timeout: Duration,
+    cache: Mutex<TextconvCache>,
+}
+
+#[derive(Debug, Default)]
+struct TextconvCache {
+    entries: HashMap<(String, Oid), Arc<str>>,
+    order: VecDeque<(String, Oid)>,
+}
+
+impl TextconvRunner {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            cache: Mutex::new(TextconvCache::default()),
+        }
+    }
+
+    /// Convert `bytes` (the content of `path`) with `command`
+    pub fn convert(&self, repo: &Repository, command: &str, path: &str, bytes: &[u8]) -> AppResult<Arc<str>> {
+        let id = Oid::hash_object(git2::ObjectType::Blob, bytes)?;
+        let key = (command.to_string(), id);
+        if let Some(text) = self.cache.lock().ok().and_then(|cache| cache.entries.get(&key).cloned()) {
+            return Ok(text);
+        }
+
+        let text: Arc<str> = Arc::from(self.run(repo, command, path, id, bytes)?);
+        if let Ok(mut cache) = self.cache.lock() {
+            if cache.entries.len() >= TEXTCONV_CACHE_CAPACITY {
+                if let Some(oldest) = cache.order.pop_front() {
+                    cache.entries.remove(&oldest);
+                }
+            }
+            cache.order.push_back(key.clone());
+            cache.entries.insert(key, text.clone());
+        }
+        Ok(text)
+    }
+
+    fn run(&self, repo: &Repository, command: &str, path: &str, id: Oid, bytes: &[u8]) -> AppResult<String> {
+        // Keep the extension; converters often pick a format from it
+        let extension = std::path::Path::new(path)
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        let (input, mut file) = create_private_file(repo.path(), &format!("textconv-{}", id), &extension)?;
+        let written = file.write_all(bytes).and_then(|_| file.flush());
+        drop(file);
+
+        let result = match written {
+            Ok(()) => self.run_command(repo, command, &input),
+            Err(e) => Err(e.into()),
+        };
+        let _ = std::fs::remove_file(&input);
+        result
+    }
+
+    fn run_command(&self, repo: &Repository, command: &str, input: &Path) -> AppResult<String> {
+        let mut shell = Command::new("sh");
+        shell
+            .arg("-c")
+            .arg(format!("{} \"$@\"", command))
+            .arg(command)
+            .arg(input)
+            .current_dir(repo.workdir().unwrap_or_else(|| repo.path()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Its own process group, so a timeout also stops what the shell started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+        let mut child = shell.spawn()?;
+
+        // Drain the pipes on threads so a chatty converter cannot block on a full pipe
+        let drain = |pipe: Option<Box<dyn Read + Send>>| {
+            std::thread::spawn(move || {
+                let mut buffer = Vec::new();
+                if let Some(mut pipe) = pipe {
+                    let _ = pipe.read_to_end(&mut buffer);
+                }
+                buffer
+            })
+        };
+        let stdout = drain(child.stdout.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+        let stderr = drain(child.stderr.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                kill_process_group(&mut child);
+                let _ = child.wait();
+                return Err(AppError::InvalidOperation(format!(
+                    "textconv '{}' timed out after {}s",
+                    command,
+                    self.timeout.as_secs()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+        if !status.success() {
+            return Err(AppError::InvalidOperation(format!(
+                "textconv '{}' failed: {}",
+                command,
+                String::from_utf8_lossy(&stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+}
+
+/// Kill a child spawned as a process group leader together with its descendants
+pub(crate) fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+}
+
+/// Create a new file in `git_dir`'s private temporary directory
+///
+/// The directory is only accessible to the owner and the file is created
+/// exclusively, so nothing can be planted at the path beforehand.
+pub(crate) fn create_private_file(git_dir: &Path, stem: &str, extension: &str) -> AppResult<(PathBuf, File)> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let dir = git_dir.join(PRIVATE_TMP_DIR);
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+
+    loop {
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("{}-{}-{}{}", stem, std::process::id(), count, extension));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Classify `bytes` for `path`, converting them with the path's textconv driver if it has one
+pub fn load_content(repo: &Repository, path: &str, bytes: &[u8], textconv: &TextconvRunner) -> BlobContent {
+    let attributes = content::attributes(repo, path);
+    let mut content = BlobContent::from_bytes(bytes, &attributes);
+    let Some(driver) = attributes.driver.as_ref() else { return content };
+    if let Some(command) = driver.textconv.as_deref() {
+        match textconv.convert(repo, command, path, bytes) {
+            Ok(text) => content.set_converted_text(&driver.name, &text),
+            Err(e) => {
+                tracing::warn!("textconv for {} failed: {}", path, e);
+                content.textconv = Some(TextconvOutcome::Failed(e.to_string()));
+            }
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_driver_from_attributes_and_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join(".gitattributes"), "*.db diff=sqlite\n*.min.js diff=minjs\n").unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("diff.sqlite.textconv", "tr -d '\\000' <").unwrap();
+        config.set_str("diff.sqlite.xfuncname", "^table (.*)$").unwrap();
+        config.set_bool("diff.minjs.binary", true).unwrap();
+
+        let attributes = content::attributes(&repo, "data.db");
+        let driver = attributes.driver.unwrap();
+        assert_eq!(driver.textconv.as_deref(), Some("tr -d '\\000' <"));
+        assert_eq!(driver.funcname.as_deref(), Some("^table (.*)$"));
+        assert_eq!(attributes.binary, None);
+        assert_eq!(content::attributes(&repo, "app.min.js").binary, Some(true));
+
+        // Converted once, then served from the cache
+        let runner = TextconvRunner::new(Duration::from_secs(5));
+        let content = load_content(&repo, "data.db", b"\0binary rows\n", &runner);
+        assert_eq!(content.text, "binary rows\n");
+        assert!(content.is_text());
+        assert_eq!(content.textconv, Some(TextconvOutcome::Converted("sqlite".to_string())));
+        assert_eq!(runner.cache.lock().unwrap().entries.len(), 1);
+        load_content(&repo, "data.db", b"\0binary rows\n", &runner);
+        assert_eq!(runner.cache.lock().unwrap().entries.len(), 1);
+    }
+
+    #[test]
+    fn test_textconv_timeout_and_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let runner = TextconvRunner::new(Duration::from_millis(200));
+
+        // The converter's children go down with the shell
+        let marker = dir.path().join("survived");
+        let command = format!("(sleep 1; touch {}) & wait; cat", marker.display());
+        let started = Instant::now();
+        let error = runner.convert(&repo, &command, "a.bin", b"x").unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(3));
+        std::thread::sleep(Duration::from_millis(1500));
+        assert!(!marker.exists());
+
+        // Inputs live in a private directory and are removed afterwards
+        let tmp = repo.path().join(PRIVATE_TMP_DIR);
+        assert_eq!(std::fs::read_dir(&tmp).unwrap().count(), 0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&tmp).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+
+        assert!(runner.convert(&repo, "false", "a.bin", b"x").is_err());
+    }
+
+    #[test]
+    fn test_funcname_pattern() {
+        let pattern = FuncnamePattern::parse("!^\\s*//\n^\\s*(fn [a-z_]+)\n^impl .*").unwrap();
+        let lines = ["impl Foo {", "    // fn not_this", "    fn bar() {", "        let x = 1;", "    }"];
+        assert_eq!(pattern.find(&lines, 4).as_deref(), Some("fn bar"));
+        assert_eq!(pattern.find(&lines, 2).as_deref(), Some("impl Foo {"));
+        assert_eq!(pattern.find(&lines, 0), None);
+        assert!(FuncnamePattern::parse("(unclosed").is_err());
+    }
+}
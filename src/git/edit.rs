@@ -0,0 +1,220 @@
+//! Writing edited diff sides back
+//!
+//! The diff viewer can edit the new side of a diff in place. An edit starts
+//! from an [`EditBase`]: the content of one path in the working tree or the
+//! index plus a fingerprint of what was read. [`write_edit`] refuses to
+//! overwrite a file whose fingerprint changed in the meantime unless forced,
+//! and writes index edits as a new blob without touching the working tree.
+
+use git2::{IndexEntry, IndexTime, ObjectType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::{AppError, AppResult};
+
+/// Where an edit is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EditTarget {
+    WorkTree,
+    /// The staged version; the working tree is left alone
+    Index,
+}
+
+impl EditTarget {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EditTarget::WorkTree => "working tree",
+            EditTarget::Index => "index",
+        }
+    }
+}
+
+/// Content an edit started from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditBase {
+    pub target: EditTarget,
+    pub path: String,
+    pub content: String,
+    /// Blob id of what was read; `None` when the path did not exist
+    pub fingerprint: Option<Oid>,
+}
+
+/// Result of writing an edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditOutcome {
+    /// Written; carries the new base for further edits
+    Written(EditBase),
+    /// The target changed since it was read; nothing was written
+    Conflict { current: Option<Oid> },
+}
+
+fn worktree_path(repo: &Repository, path: &str) -> AppResult<std::path::PathBuf> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| AppError::InvalidState("Bare repository has no working tree".to_string()))?;
+    Ok(workdir.join(path))
+}
+
+/// Current content and fingerprint of `path` in the target
+fn current(repo: &Repository, target: EditTarget, path: &str) -> AppResult<(Vec<u8>, Option<Oid>)> {
+    match target {
+        EditTarget::WorkTree => {
+            let full = worktree_path(repo, path)?;
+            if !full.is_file() {
+                return Ok((Vec::new(), None));
+            }
+            let bytes = std::fs::read(full)?;
+            let id = Oid::hash_object(ObjectType::Blob, &bytes)?;
+            Ok((bytes, Some(id)))
+        }
+        EditTarget::Index => {
+            let index = repo.index()?;
+            match index.get_path(Path::new(path), 0) {
+                Some(entry) => Ok((repo.find_blob(entry.id)?.content().to_vec(), Some(entry.id))),
+                None => Ok((Vec::new(), None)),
+            }
+        }
+    }
+}
+
+/// Read `path` from the working tree or the index for editing
+pub fn read_for_edit(repo: &Repository, target: EditTarget, path: &str) -> AppResult<EditBase> {
+    let (bytes, fingerprint) = current(repo, target, path)?;
+    if bytes.contains(&0) {
+        return Err(AppError::InvalidOperation(format!("{} is a binary file and cannot be edited", path)));
+    }
+    // Edits are written back as UTF-8, so other encodings would be converted silently
+    let content = String::from_utf8(bytes)
+        .map_err(|_| AppError::InvalidOperation(format!("{} is not UTF-8 and cannot be edited", path)))?;
+    Ok(EditBase {
+        target,
+        path: path.to_string(),
+        content,
+        fingerprint,
+    })
+}
+
+/// Give edited text (joined with `\n`, no final newline) the line endings of the original
+pub fn match_line_endings(original: &str, edited: &str) -> String {
+    let mut text = if original.contains("\r\n") {
+        edited.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        edited.to_string()
+    };
+    let newline = if original.contains("\r\n") { "\r\n" } else { "\n" };
+    if original.ends_with('\n') && !text.is_empty() && !text.ends_with('\n') {
+        text.push_str(newline);
+    }
+    text
+}
+
+/// Write `content` to the edit's target; `force` overwrites concurrent changes
+pub fn write_edit(repo: &Repository, base: &EditBase, content: &str, force: bool) -> AppResult<EditOutcome> {
+    let (_, fingerprint) = current(repo, base.target, &base.path)?;
+    if fingerprint != base.fingerprint && !force {
+        return Ok(EditOutcome::Conflict { current: fingerprint });
+    }
+
+    let bytes = content.as_bytes();
+    let id = match base.target {
+        EditTarget::WorkTree => {
+            let full = worktree_path(repo, &base.path)?;
+            if let Some(parent) = full.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&full, bytes)?;
+            Oid::hash_object(ObjectType::Blob, bytes)?
+        }
+        EditTarget::Index => {
+            let id = repo.blob(bytes)?;
+            let mut index = repo.index()?;
+            let mut entry = index.get_path(Path::new(&base.path), 0).unwrap_or_else(|| IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id,
+                flags: 0,
+                flags_extended: 0,
+                path: base.path.as_bytes().to_vec(),
+            });
+            // Clear the cached stat data so git re-reads the working tree file
+            entry.id = id;
+            entry.file_size = bytes.len() as u32;
+            entry.mtime = IndexTime::new(0, 0);
+            entry.ctime = IndexTime::new(0, 0);
+            index.add(&entry)?;
+            index.write()?;
+            id
+        }
+    };
+
+    Ok(EditOutcome::Written(EditBase {
+        target: base.target,
+        path: base.path.clone(),
+        content: content.to_string(),
+        fingerprint: Some(id),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup() -> (tempfile::TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_worktree_edit_detects_conflicts() {
+        let (dir, repo) = setup();
+        let base = read_for_edit(&repo, EditTarget::WorkTree, "a.txt").unwrap();
+        assert_eq!(base.content, "one\ntwo\n");
+
+        fs::write(dir.path().join("a.txt"), "changed elsewhere\n").unwrap();
+        let outcome = write_edit(&repo, &base, "one\n2\n", false).unwrap();
+        assert!(matches!(outcome, EditOutcome::Conflict { .. }));
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "changed elsewhere\n");
+
+        let outcome = write_edit(&repo, &base, "one\n2\n", true).unwrap();
+        let EditOutcome::Written(next) = outcome else { panic!("forced write failed") };
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "one\n2\n");
+        assert!(matches!(write_edit(&repo, &next, "three\n", false).unwrap(), EditOutcome::Written(_)));
+    }
+
+    #[test]
+    fn test_index_edit_leaves_worktree_alone() {
+        let (dir, repo) = setup();
+        let base = read_for_edit(&repo, EditTarget::Index, "a.txt").unwrap();
+        let outcome = write_edit(&repo, &base, "one\nstaged\n", false).unwrap();
+        assert!(matches!(outcome, EditOutcome::Written(_)));
+
+        assert_eq!(fs::read_to_string(dir.path().join("a.txt")).unwrap(), "one\ntwo\n");
+        let index = repo.index().unwrap();
+        let entry = index.get_path(Path::new("a.txt"), 0).unwrap();
+        assert_eq!(repo.find_blob(entry.id).unwrap().content(), b"one\nstaged\n");
+
+        // The worktree file now differs from the index
+        let statuses = repo.statuses(None).unwrap();
+        let status = statuses.iter().find(|s| s.path() == Some("a.txt")).unwrap().status();
+        assert!(status.contains(git2::Status::WT_MODIFIED));
+    }
+
+    #[test]
+    fn test_match_line_endings() {
+        assert_eq!(match_line_endings("a\nb\n", "a\nc"), "a\nc\n");
+        assert_eq!(match_line_endings("a\r\nb\r\n", "a\nc"), "a\r\nc\r\n");
+        assert_eq!(match_line_endings("a", "b"), "b");
+    }
+}
@@ -0,0 +1,311 @@
+//! Ignore rule management
+//!
+//! Builds `.gitignore` patterns for a path (the file itself, its extension or
+//! its directory), appends them to a chosen ignore file and explains which
+//! rule ignores a path, like `git check-ignore -v`.
+
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tracing::{debug, info};
+
+use crate::error::{AppError, AppResult};
+
+/// File a new ignore rule is written to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IgnoreTarget {
+    /// `.gitignore` inside the given directory (relative to the repository root, empty for the root)
+    Gitignore(PathBuf),
+    /// `.git/info/exclude`, private to this clone
+    InfoExclude,
+}
+
+impl IgnoreTarget {
+    /// Absolute path of the ignore file
+    pub fn file_path(&self, repo: &Repository) -> AppResult<PathBuf> {
+        match self {
+            IgnoreTarget::Gitignore(dir) => Ok(workdir(repo)?.join(dir).join(".gitignore")),
+            IgnoreTarget::InfoExclude => Ok(common_dir(repo).join("info").join("exclude")),
+        }
+    }
+
+    /// Repository-relative name shown in menus
+    pub fn label(&self) -> String {
+        match self {
+            IgnoreTarget::Gitignore(dir) if dir.as_os_str().is_empty() => ".gitignore".to_string(),
+            IgnoreTarget::Gitignore(dir) => format!("{}/.gitignore", to_slash(dir)),
+            IgnoreTarget::InfoExclude => ".git/info/exclude".to_string(),
+        }
+    }
+
+    /// Directory patterns in this file are relative to
+    fn base_dir(&self) -> &Path {
+        match self {
+            IgnoreTarget::Gitignore(dir) => dir,
+            IgnoreTarget::InfoExclude => Path::new(""),
+        }
+    }
+}
+
+/// What a new ignore rule should match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IgnoreRuleKind {
+    /// Exactly this file (or untracked directory)
+    File,
+    /// Every file with the same extension
+    Extension,
+    /// The directory containing the file
+    Directory,
+}
+
+/// Rule that decides whether a path is ignored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IgnoreMatch {
+    /// Ignore file the rule comes from, as reported by git
+    pub source: String,
+    /// 1-based line number in `source`
+    pub line: usize,
+    /// Pattern as written in the file
+    pub pattern: String,
+}
+
+impl IgnoreMatch {
+    /// A matching `!pattern` re-includes the path
+    pub fn is_negated(&self) -> bool {
+        self.pattern.starts_with('!')
+    }
+
+    /// `source:line: pattern`, the way `git check-ignore -v` prints it
+    pub fn describe(&self) -> String {
+        format!("{}:{}: {}", self.source, self.line, self.pattern)
+    }
+}
+
+/// Ignore files a rule for `path` can go into: the root `.gitignore`, one per
+/// ancestor directory of `path`, and `.git/info/exclude`
+pub fn ignore_targets(path: &str) -> Vec<IgnoreTarget> {
+    let path = Path::new(path.trim_end_matches('/'));
+    let mut dirs: Vec<PathBuf> = path.ancestors().skip(1).map(Path::to_path_buf).collect();
+    dirs.reverse();
+
+    dirs.into_iter()
+        .map(IgnoreTarget::Gitignore)
+        .chain(std::iter::once(IgnoreTarget::InfoExclude))
+        .collect()
+}
+
+/// Build the pattern for ignoring `path` with a rule of `kind` written to `target`
+///
+/// `path` is relative to the repository root; untracked directories end with
+/// `/`. File and directory rules are anchored to the directory of the ignore
+/// file. Returns `None` if the rule cannot be expressed from that file, e.g.
+/// a file without extension or a directory outside the target directory.
+pub fn ignore_pattern(path: &str, kind: IgnoreRuleKind, target: &IgnoreTarget) -> Option<String> {
+    let is_dir = path.ends_with('/');
+    let path = Path::new(path.trim_end_matches('/'));
+    let relative = path.strip_prefix(target.base_dir()).ok()?;
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+
+    match kind {
+        IgnoreRuleKind::File => Some(format!(
+            "/{}{}",
+            escape_pattern(&to_slash(relative)),
+            if is_dir { "/" } else { "" }
+        )),
+        IgnoreRuleKind::Extension if !is_dir => {
+            let extension = relative.extension()?.to_str()?;
+            Some(format!("*.{}", escape_pattern(extension)))
+        }
+        IgnoreRuleKind::Extension => None,
+        IgnoreRuleKind::Directory => {
+            let parent = relative.parent().filter(|parent| !parent.as_os_str().is_empty())?;
+            Some(format!("/{}/", escape_pattern(&to_slash(parent))))
+        }
+    }
+}
+
+/// Append `pattern` to the ignore file of `target`
+///
+/// Returns `false` if the file already contains the exact rule.
+pub fn add_ignore_rule(repo: &Repository, target: &IgnoreTarget, pattern: &str) -> AppResult<bool> {
+    let file = target.file_path(repo)?;
+    let existing = match fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(AppError::Io(e)),
+    };
+
+    if existing.lines().any(|line| line.trim_end() == pattern) {
+        debug!("{} already contains {}", file.display(), pattern);
+        return Ok(false);
+    }
+
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(pattern);
+    content.push('\n');
+
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&file, content)?;
+
+    info!("Added ignore rule {} to {}", pattern, target.label());
+    Ok(true)
+}
+
+/// Find the rule deciding whether `path` is ignored (`git check-ignore -v`)
+///
+/// Tracked files are checked as well (`--no-index`). Returns `None` if no
+/// ignore rule matches.
+pub fn explain_ignore(repo: &Repository, path: &str) -> AppResult<Option<IgnoreMatch>> {
+    let mut child = Command::new("git")
+        .args(["check-ignore", "-v", "-n", "-z", "--no-index", "--stdin"])
+        .current_dir(workdir(repo)?)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // NUL-terminated input keeps paths with special characters unquoted
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(path.as_bytes())?;
+        stdin.write_all(b"\0")?;
+    }
+    let output = child.wait_with_output()?;
+
+    // Exit code 1 only means "not ignored"
+    if !matches!(output.status.code(), Some(0) | Some(1)) {
+        return Err(AppError::application(format!(
+            "git check-ignore failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(parse_check_ignore(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git check-ignore -v -n -z` output: `source NUL line NUL pattern NUL path NUL`
+fn parse_check_ignore(output: &str) -> Option<IgnoreMatch> {
+    let mut fields = output.split('\0');
+    let source = fields.next()?;
+    let line = fields.next()?;
+    let pattern = fields.next()?;
+
+    if source.is_empty() {
+        return None;
+    }
+
+    Some(IgnoreMatch {
+        source: source.to_string(),
+        line: line.parse().ok()?,
+        pattern: pattern.to_string(),
+    })
+}
+
+/// Escape characters with a special meaning in ignore patterns
+fn escape_pattern(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for (index, c) in path.chars().enumerate() {
+        if matches!(c, '*' | '?' | '[' | '\\') || (index == 0 && matches!(c, '#' | '!')) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    if escaped.ends_with(' ') {
+        escaped.insert(escaped.len() - 1, '\\');
+    }
+    escaped
+}
+
+fn to_slash(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Git directory shared by all worktrees (linked worktrees point to it via `commondir`)
+fn common_dir(repo: &Repository) -> PathBuf {
+    let git_dir = repo.path();
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => git_dir.join(common.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+fn workdir(repo: &Repository) -> AppResult<PathBuf> {
+    repo.workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| AppError::InvalidOperation("Bare repositories have no working tree".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::write(dir.path().join("target/debug/app.log"), "log").unwrap();
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_patterns_are_relative_to_target() {
+        let root = IgnoreTarget::Gitignore(PathBuf::new());
+        let nested = IgnoreTarget::Gitignore(PathBuf::from("target"));
+        let path = "target/debug/app.log";
+
+        assert_eq!(ignore_pattern(path, IgnoreRuleKind::File, &root).as_deref(), Some("/target/debug/app.log"));
+        assert_eq!(ignore_pattern(path, IgnoreRuleKind::File, &nested).as_deref(), Some("/debug/app.log"));
+        assert_eq!(ignore_pattern(path, IgnoreRuleKind::Extension, &nested).as_deref(), Some("*.log"));
+        assert_eq!(ignore_pattern(path, IgnoreRuleKind::Directory, &root).as_deref(), Some("/target/debug/"));
+        assert_eq!(ignore_pattern("build/", IgnoreRuleKind::File, &IgnoreTarget::InfoExclude).as_deref(), Some("/build/"));
+        assert_eq!(ignore_pattern("Makefile", IgnoreRuleKind::Extension, &root), None);
+        assert_eq!(ignore_pattern("Makefile", IgnoreRuleKind::Directory, &root), None);
+        assert_eq!(ignore_pattern("#notes[1].txt", IgnoreRuleKind::File, &root).as_deref(), Some("/\\#notes\\[1].txt"));
+
+        let labels: Vec<String> = ignore_targets(path).iter().map(IgnoreTarget::label).collect();
+        assert_eq!(labels, [".gitignore", "target/.gitignore", "target/debug/.gitignore", ".git/info/exclude"]);
+    }
+
+    #[test]
+    fn test_add_rule_and_explain() {
+        let (dir, repo) = setup();
+        let path = "target/debug/app.log";
+        assert_eq!(explain_ignore(&repo, path).unwrap(), None);
+
+        fs::write(dir.path().join(".gitignore"), "# build output\n*.tmp").unwrap();
+        let target = IgnoreTarget::Gitignore(PathBuf::new());
+        assert!(add_ignore_rule(&repo, &target, "*.log").unwrap());
+        assert!(!add_ignore_rule(&repo, &target, "*.log").unwrap());
+        assert_eq!(fs::read_to_string(dir.path().join(".gitignore")).unwrap(), "# build output\n*.tmp\n*.log\n");
+
+        let rule = explain_ignore(&repo, path).unwrap().unwrap();
+        assert_eq!((rule.source.as_str(), rule.line, rule.pattern.as_str()), (".gitignore", 3, "*.log"));
+        assert!(!rule.is_negated());
+
+        // Deeper .gitignore files take precedence
+        let nested = IgnoreTarget::Gitignore(PathBuf::from("target"));
+        assert!(add_ignore_rule(&repo, &nested, "!/debug/app.log").unwrap());
+        let rule = explain_ignore(&repo, path).unwrap().unwrap();
+        assert_eq!(rule.source, "target/.gitignore");
+        assert!(rule.is_negated());
+
+        assert!(add_ignore_rule(&repo, &IgnoreTarget::InfoExclude, "/notes.txt").unwrap());
+        let rule = explain_ignore(&repo, "notes.txt").unwrap().unwrap();
+        assert!(rule.source.ends_with("info/exclude"));
+    }
+}
@@ -0,0 +1,436 @@
+//! Repository insights
+//!
+//! Aggregates the commit history into contributor activity over time,
+//! per-file line churn, change hotspots and bus-factor warnings. Merge
+//! commits are skipped so that changes are counted once, like
+//! `git log --no-merges --numstat`.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use git2::{DiffOptions, Patch, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::{AppError, AppResult};
+
+/// Time bucket used for activity timelines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InsightsBucket {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl InsightsBucket {
+    /// First day of the bucket containing `date`
+    fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            InsightsBucket::Day => date,
+            InsightsBucket::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            InsightsBucket::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the bucket following the one starting at `start`
+    fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            InsightsBucket::Day => start + Duration::days(1),
+            InsightsBucket::Week => start + Duration::days(7),
+            InsightsBucket::Month => {
+                let (year, month) = if start.month() == 12 {
+                    (start.year() + 1, 1)
+                } else {
+                    (start.year(), start.month() + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(start + Duration::days(31))
+            }
+        }
+    }
+
+    fn label(&self, start: NaiveDate) -> String {
+        match self {
+            InsightsBucket::Day => start.format("%Y-%m-%d").to_string(),
+            InsightsBucket::Week => format!("{}-W{:02}", start.iso_week().year(), start.iso_week().week()),
+            InsightsBucket::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// Options for computing insights
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InsightsOptions {
+    /// Maximum number of non-merge commits to analyze, newest first
+    pub max_commits: usize,
+    /// Only analyze commits at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Timeline bucket size
+    pub bucket: InsightsBucket,
+    /// Number of hotspots to report
+    pub hotspot_limit: usize,
+    /// Share of a file's commits (0.0-1.0) by one author that triggers a bus-factor warning
+    pub bus_factor_threshold: f64,
+    /// Minimum commits touching a file before it is considered for bus-factor warnings
+    pub bus_factor_min_commits: usize,
+}
+
+impl Default for InsightsOptions {
+    fn default() -> Self {
+        Self {
+            max_commits: 2000,
+            since: None,
+            bucket: InsightsBucket::Week,
+            hotspot_limit: 20,
+            bus_factor_threshold: 0.8,
+            bus_factor_min_commits: 5,
+        }
+    }
+}
+
+/// Activity of one author
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthorActivity {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Commits per bucket, aligned with [`RepositoryInsights::buckets`]
+    pub timeline: Vec<u64>,
+}
+
+/// Line churn of one file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileChurn {
+    pub path: String,
+    pub commits: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Author with the most commits to this file
+    pub top_author: String,
+    /// Share of the file's commits made by `top_author` (0.0-1.0)
+    pub top_author_share: f64,
+    /// Number of distinct authors
+    pub authors: usize,
+}
+
+impl FileChurn {
+    /// Lines added plus lines removed
+    pub fn churn(&self) -> usize {
+        self.lines_added + self.lines_removed
+    }
+}
+
+/// A file that changes both often and a lot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hotspot {
+    pub path: String,
+    pub commits: usize,
+    pub churn: usize,
+    /// Normalized change frequency times normalized churn (0.0-1.0)
+    pub score: f64,
+}
+
+/// A file dominated by a single author
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusFactorWarning {
+    pub path: String,
+    pub author: String,
+    pub share: f64,
+    pub commits: usize,
+}
+
+/// Aggregated repository insights
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepositoryInsights {
+    pub generated_at: DateTime<Utc>,
+    pub commits_analyzed: usize,
+    pub bucket: InsightsBucket,
+    /// Timeline bucket labels, oldest first
+    pub buckets: Vec<String>,
+    /// Commits per bucket across all authors
+    pub commit_timeline: Vec<u64>,
+    /// Authors sorted by commit count
+    pub authors: Vec<AuthorActivity>,
+    /// Files sorted by churn
+    pub files: Vec<FileChurn>,
+    pub hotspots: Vec<Hotspot>,
+    pub bus_factor_warnings: Vec<BusFactorWarning>,
+}
+
+impl RepositoryInsights {
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> AppResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Default)]
+struct FileAccumulator {
+    commits: usize,
+    lines_added: usize,
+    lines_removed: usize,
+    /// Commits per author key, the same key `authors` uses
+    commits_by_author: HashMap<String, usize>,
+}
+
+/// Walk the history from `HEAD` and compute insights
+pub fn compute_insights(repo: &Repository, options: &InsightsOptions) -> AppResult<RepositoryInsights> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME)?;
+    revwalk.push_head()?;
+
+    let mut authors: HashMap<String, AuthorActivity> = HashMap::new();
+    let mut author_dates: HashMap<String, Vec<NaiveDate>> = HashMap::new();
+    let mut files: HashMap<String, FileAccumulator> = HashMap::new();
+    let mut commits_analyzed = 0;
+
+    for oid in revwalk {
+        if commits_analyzed >= options.max_commits {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+
+        let date = DateTime::from_timestamp(commit.author().when().seconds(), 0)
+            .ok_or_else(|| AppError::InvalidState(format!("Invalid commit time in {}", commit.id())))?;
+        if options.since.is_some_and(|since| date < since) {
+            continue;
+        }
+        commits_analyzed += 1;
+
+        let signature = commit.author();
+        let name = signature.name().unwrap_or("unknown").to_string();
+        let email = signature.email().unwrap_or("").to_string();
+        let author_key = if email.is_empty() { name.clone() } else { email.to_lowercase() };
+
+        let parent_tree = match commit.parents().next() {
+            Some(parent) => Some(parent.tree()?),
+            None => None,
+        };
+        let tree = commit.tree()?;
+        let mut diff_options = DiffOptions::new();
+        diff_options.context_lines(0);
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_options))?;
+
+        let activity = authors.entry(author_key.clone()).or_insert_with(|| AuthorActivity {
+            name: name.clone(),
+            email: email.clone(),
+            ..Default::default()
+        });
+        activity.commits += 1;
+        author_dates.entry(author_key.clone()).or_default().push(date.date_naive());
+
+        for index in 0..diff.deltas().len() {
+            let Some(delta) = diff.get_delta(index) else { continue };
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+
+            let (added, removed) = match Patch::from_diff(&diff, index)? {
+                Some(patch) => {
+                    let (_, added, removed) = patch.line_stats()?;
+                    (added, removed)
+                }
+                None => (0, 0), // binary
+            };
+
+            activity.lines_added += added;
+            activity.lines_removed += removed;
+
+            let file = files.entry(path.to_string_lossy().into_owned()).or_default();
+            file.commits += 1;
+            file.lines_added += added;
+            file.lines_removed += removed;
+            *file.commits_by_author.entry(author_key.clone()).or_default() += 1;
+        }
+    }
+
+    // Timeline buckets from the oldest to the newest analyzed commit
+    let all_dates = author_dates.values().flatten();
+    let (buckets, bucket_index) = match (all_dates.clone().min(), all_dates.max()) {
+        (Some(first), Some(last)) => {
+            let mut labels = Vec::new();
+            let mut index = BTreeMap::new();
+            let mut start = options.bucket.start_of(*first);
+            let end = options.bucket.start_of(*last);
+            while start <= end {
+                index.insert(start, labels.len());
+                labels.push(options.bucket.label(start));
+                start = options.bucket.next(start);
+            }
+            (labels, index)
+        }
+        _ => (Vec::new(), BTreeMap::new()),
+    };
+
+    let mut commit_timeline = vec![0u64; buckets.len()];
+    for (key, dates) in &author_dates {
+        let mut timeline = vec![0u64; buckets.len()];
+        for date in dates {
+            if let Some(&slot) = bucket_index.get(&options.bucket.start_of(*date)) {
+                timeline[slot] += 1;
+                commit_timeline[slot] += 1;
+            }
+        }
+        if let Some(activity) = authors.get_mut(key) {
+            activity.timeline = timeline;
+        }
+    }
+
+    // Files count commits per author key; report the author's display name
+    let author_names: HashMap<String, String> =
+        authors.iter().map(|(key, activity)| (key.clone(), activity.name.clone())).collect();
+    let mut authors: Vec<AuthorActivity> = authors.into_values().collect();
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+
+    let mut files: Vec<FileChurn> = files
+        .into_iter()
+        .map(|(path, acc)| {
+            let (top_author, top_commits) = acc
+                .commits_by_author
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(key, commits)| (author_names.get(key).cloned().unwrap_or_else(|| key.clone()), *commits))
+                .unwrap_or_default();
+
+            FileChurn {
+                path,
+                commits: acc.commits,
+                lines_added: acc.lines_added,
+                lines_removed: acc.lines_removed,
+                top_author,
+                top_author_share: if acc.commits > 0 { top_commits as f64 / acc.commits as f64 } else { 0.0 },
+                authors: acc.commits_by_author.len(),
+            }
+        })
+        .collect();
+    files.sort_by(|a, b| b.churn().cmp(&a.churn()).then_with(|| a.path.cmp(&b.path)));
+
+    let hotspots = rank_hotspots(&files, options.hotspot_limit);
+
+    let mut bus_factor_warnings: Vec<BusFactorWarning> = files
+        .iter()
+        .filter(|f| f.commits >= options.bus_factor_min_commits && f.top_author_share >= options.bus_factor_threshold)
+        .map(|f| BusFactorWarning {
+            path: f.path.clone(),
+            author: f.top_author.clone(),
+            share: f.top_author_share,
+            commits: f.commits,
+        })
+        .collect();
+    bus_factor_warnings.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.path.cmp(&b.path)));
+
+    Ok(RepositoryInsights {
+        generated_at: Utc::now(),
+        commits_analyzed,
+        bucket: options.bucket,
+        buckets,
+        commit_timeline,
+        authors,
+        files,
+        hotspots,
+        bus_factor_warnings,
+    })
+}
+
+/// Score files by normalized change frequency times normalized churn
+fn rank_hotspots(files: &[FileChurn], limit: usize) -> Vec<Hotspot> {
+    let max_commits = files.iter().map(|f| f.commits).max().unwrap_or(0).max(1) as f64;
+    let max_churn = files.iter().map(|f| f.churn()).max().unwrap_or(0).max(1) as f64;
+
+    let mut hotspots: Vec<Hotspot> = files
+        .iter()
+        .map(|f| Hotspot {
+            path: f.path.clone(),
+            commits: f.commits,
+            churn: f.churn(),
+            score: (f.commits as f64 / max_commits) * (f.churn() as f64 / max_churn),
+        })
+        .filter(|h| h.score > 0.0)
+        .collect();
+
+    hotspots.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    hotspots.truncate(limit);
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::{git, git_with_env};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn commit(dir: &Path, file: &str, content: &str, author: &str, date: &str) {
+        std::fs::write(dir.join(file), content).unwrap();
+        let env = [("GIT_AUTHOR_DATE", date), ("GIT_COMMITTER_DATE", date)];
+        git_with_env(dir, author, &["add", "."], &env);
+        git_with_env(dir, author, &["commit", "-q", "-m", file], &env);
+    }
+
+    #[test]
+    fn test_compute_insights() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+
+        commit(dir.path(), "core.rs", "a\n", "alice", "2024-01-01T10:00:00Z");
+        commit(dir.path(), "core.rs", "a\nb\n", "alice", "2024-01-09T10:00:00Z");
+        commit(dir.path(), "core.rs", "a\nc\nd\n", "alice", "2024-01-16T10:00:00Z");
+        commit(dir.path(), "notes.md", "x\n", "bob", "2024-01-17T10:00:00Z");
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let options = InsightsOptions { bus_factor_min_commits: 3, ..Default::default() };
+        let insights = compute_insights(&repo, &options).unwrap();
+
+        assert_eq!(insights.commits_analyzed, 4);
+        assert_eq!(insights.buckets, ["2024-W01", "2024-W02", "2024-W03"]);
+        assert_eq!(insights.commit_timeline, [1, 1, 2]);
+
+        assert_eq!(insights.authors[0].name, "alice");
+        assert_eq!(insights.authors[0].timeline, [1, 1, 1]);
+
+        let core = insights.files.iter().find(|f| f.path == "core.rs").unwrap();
+        assert_eq!((core.commits, core.lines_added, core.lines_removed), (3, 4, 1));
+        assert_eq!(insights.hotspots[0].path, "core.rs");
+
+        assert_eq!(insights.bus_factor_warnings.len(), 1);
+        assert_eq!(insights.bus_factor_warnings[0].author, "alice");
+
+        let json: serde_json::Value = serde_json::from_str(&insights.to_json().unwrap()).unwrap();
+        assert_eq!(json["commits_analyzed"], 4);
+    }
+
+    #[test]
+    fn test_authors_are_keyed_by_email() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let commit_as = |file: &str, name: &str, email: &str| {
+            std::fs::write(dir.path().join(file), format!("{} <{}>", name, email)).unwrap();
+            let env = [("GIT_AUTHOR_NAME", name), ("GIT_AUTHOR_EMAIL", email)];
+            git_with_env(dir.path(), "tester", &["add", "."], &env);
+            git_with_env(dir.path(), "tester", &["commit", "-q", "-m", file], &env);
+        };
+        // One person with two spellings of their name
+        commit_as("core.rs", "Alice Smith", "alice@example.com");
+        commit_as("core.rs", "alice", "Alice@Example.com");
+        commit_as("core.rs", "Alice Smith", "alice@example.com");
+        // Two people sharing a name
+        commit_as("ui.rs", "Sam", "sam.one@example.com");
+        commit_as("ui.rs", "Sam", "sam.two@example.com");
+        commit_as("ui.rs", "Sam", "sam.one@example.com");
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let options = InsightsOptions { bus_factor_min_commits: 3, bus_factor_threshold: 0.9, ..Default::default() };
+        let insights = compute_insights(&repo, &options).unwrap();
+
+        let core = insights.files.iter().find(|f| f.path == "core.rs").unwrap();
+        assert_eq!((core.authors, core.top_author_share), (1, 1.0));
+        let ui = insights.files.iter().find(|f| f.path == "ui.rs").unwrap();
+        assert_eq!(ui.authors, 2);
+        assert_eq!(insights.bus_factor_warnings.len(), 1);
+        assert_eq!(insights.bus_factor_warnings[0].path, "core.rs");
+    }
+}
@@ -0,0 +1,781 @@
+//! Filtered commit history queries
+//!
+//! A `git log`-style revision walk driven by a [`LogFilter`]: author and
+//! committer patterns, pathspecs, date ranges, message regex, `-S`/`-G`
+//! pickaxe, merge selection and revision ranges. Results are paged: a
+//! [`HistoryCursor`] keeps one walk alive on its own thread, so each page
+//! continues from where the previous page stopped.
+//!
+//! Also provides `git log --follow`-style file history that tracks a file
+//! across renames and copies.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use git2::{Commit, Delta, Diff, DiffFindOptions, DiffOptions, Oid, Repository, Revwalk, Tree};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+use tokio::sync::oneshot;
+
+use crate::{
+    config::GitConfig,
+    error::{AppError, AppResult},
+};
+
+use super::CommitInfo;
+
+/// Content pickaxe, mirroring `git log -S` and `git log -G`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogPickaxe {
+    /// Commits that change the number of occurrences of the string (`-S`)
+    String(String),
+    /// Commits whose added or removed lines match the regex (`-G`)
+    Regex(String),
+}
+
+/// Merge commit selection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeFilter {
+    #[default]
+    All,
+    /// Only commits with more than one parent (`--merges`)
+    MergesOnly,
+    /// Only commits with at most one parent (`--no-merges`)
+    NoMerges,
+}
+
+/// Filter for a commit history query
+///
+/// Every set criterion must match, as with `git log`. Author, committer and
+/// message patterns are regular expressions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogFilter {
+    /// Pattern matched against `Name <email>` of the author
+    pub author: Option<String>,
+    /// Pattern matched against `Name <email>` of the committer
+    pub committer: Option<String>,
+    /// Pathspecs (glob patterns allowed) the commit must touch
+    pub paths: Vec<String>,
+    /// Only commits committed at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only commits committed at or before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Regex matched against the full commit message
+    pub message: Option<String>,
+    /// Content pickaxe
+    pub pickaxe: Option<LogPickaxe>,
+    /// Merge commit selection
+    pub merges: MergeFilter,
+    /// Revision range such as `main..feature`, `v1.0...HEAD` or `^a b`; `HEAD` when unset
+    pub range: Option<String>,
+    /// Case-insensitive author, committer, message and `-G` matching
+    pub ignore_case: bool,
+}
+
+impl LogFilter {
+    /// Whether the filter selects every commit reachable from `HEAD`
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Short human readable description, e.g. for a header line
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(range) = &self.range {
+            parts.push(range.clone());
+        }
+        if let Some(author) = &self.author {
+            parts.push(format!("author:{}", author));
+        }
+        if let Some(committer) = &self.committer {
+            parts.push(format!("committer:{}", committer));
+        }
+        if !self.paths.is_empty() {
+            parts.push(format!("-- {}", self.paths.join(" ")));
+        }
+        if let Some(since) = &self.since {
+            parts.push(format!("since:{}", since.format("%Y-%m-%d")));
+        }
+        if let Some(until) = &self.until {
+            parts.push(format!("until:{}", until.format("%Y-%m-%d")));
+        }
+        if let Some(message) = &self.message {
+            parts.push(format!("grep:{}", message));
+        }
+        match &self.pickaxe {
+            Some(LogPickaxe::String(s)) => parts.push(format!("-S{}", s)),
+            Some(LogPickaxe::Regex(r)) => parts.push(format!("-G{}", r)),
+            None => {}
+        }
+        match self.merges {
+            MergeFilter::All => {}
+            MergeFilter::MergesOnly => parts.push("--merges".to_string()),
+            MergeFilter::NoMerges => parts.push("--no-merges".to_string()),
+        }
+
+        if parts.is_empty() {
+            "all".to_string()
+        } else {
+            parts.join(" ")
+        }
+    }
+}
+
+/// One page of a filtered history walk
+#[derive(Debug, Clone, Default)]
+pub struct HistoryPage {
+    /// Matching commits, newest first
+    pub commits: Vec<CommitInfo>,
+    /// Number of matching commits skipped before this page
+    pub offset: usize,
+    /// Whether more matching commits follow this page
+    pub has_more: bool,
+}
+
+/// Rename and copy detection used when following a file's history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FollowOptions {
+    /// Minimum similarity (0-100) for a rename
+    pub rename_threshold: u16,
+    /// Minimum similarity (0-100) for a copy
+    pub copy_threshold: u16,
+    /// Also follow files created as copies of other files
+    pub find_copies: bool,
+}
+
+impl Default for FollowOptions {
+    fn default() -> Self {
+        Self {
+            rename_threshold: 50,
+            copy_threshold: 50,
+            find_copies: true,
+        }
+    }
+}
+
+impl From<&GitConfig> for FollowOptions {
+    fn from(config: &GitConfig) -> Self {
+        Self {
+            rename_threshold: config.rename_similarity,
+            copy_threshold: config.copy_similarity,
+            find_copies: config.follow_copies,
+        }
+    }
+}
+
+/// Parse a `YYYY-MM-DD` or RFC 3339 date for `since`/`until` bounds
+///
+/// Plain dates resolve to the start of the day, or to its last second when
+/// `end_of_day` is set so that `until` includes the whole day.
+pub fn parse_filter_date(value: &str, end_of_day: bool) -> AppResult<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Ok(date.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidOperation(format!("Invalid date '{}', expected YYYY-MM-DD", value)))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+
+    time.map(|t| t.and_utc())
+        .ok_or_else(|| AppError::InvalidOperation(format!("Invalid date '{}'", value)))
+}
+
+/// Build a [`CommitInfo`] from a libgit2 commit
+pub(crate) fn commit_info(commit: &Commit) -> CommitInfo {
+    CommitInfo {
+        hash: commit.id().to_string(),
+        short_hash: format!("{:.7}", commit.id()),
+        message: commit.message().unwrap_or("").to_string(),
+        author: commit.author().name().unwrap_or("").to_string(),
+        author_email: commit.author().email().unwrap_or("").to_string(),
+        date: DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now),
+        parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+        path: None,
+        previous_path: None,
+    }
+}
+
+/// Walk the history and return the `limit` matching commits after skipping `skip` matches
+///
+/// A one-off query; use [`HistoryCursor`] to page through a history.
+pub fn query_history(repo: &Repository, filter: &LogFilter, skip: usize, limit: usize) -> AppResult<HistoryPage> {
+    let mut walk = HistoryWalk::new(repo, filter)?;
+    if skip > 0 {
+        walk.next_page(skip)?;
+    }
+    walk.next_page(limit)
+}
+
+/// Incremental walk over the commits matching a filter
+pub struct HistoryWalk<'r, 'f> {
+    repo: &'r Repository,
+    matcher: CommitMatcher<'f>,
+    revwalk: Revwalk<'r>,
+    /// Match found while looking ahead past the previous page
+    pending: Option<CommitInfo>,
+    /// Matches returned so far
+    offset: usize,
+}
+
+impl<'r, 'f> HistoryWalk<'r, 'f> {
+    pub fn new(repo: &'r Repository, filter: &'f LogFilter) -> AppResult<Self> {
+        Ok(Self {
+            repo,
+            matcher: CommitMatcher::new(filter)?,
+            revwalk: build_revwalk(repo, filter.range.as_deref())?,
+            pending: None,
+            offset: 0,
+        })
+    }
+
+    /// The next `limit` matching commits after the previous page
+    pub fn next_page(&mut self, limit: usize) -> AppResult<HistoryPage> {
+        let mut commits = Vec::new();
+        while commits.len() < limit {
+            match self.pending.take() {
+                Some(commit) => commits.push(commit),
+                None => match self.next_match()? {
+                    Some(commit) => commits.push(commit),
+                    None => break,
+                },
+            }
+        }
+
+        // Look one match ahead so the page knows whether more follow
+        if commits.len() == limit && self.pending.is_none() {
+            self.pending = self.next_match()?;
+        }
+
+        let page = HistoryPage {
+            commits,
+            offset: self.offset,
+            has_more: self.pending.is_some(),
+        };
+        self.offset += page.commits.len();
+        Ok(page)
+    }
+
+    fn next_match(&mut self) -> AppResult<Option<CommitInfo>> {
+        for oid in self.revwalk.by_ref() {
+            let commit = self.repo.find_commit(oid?)?;
+            if self.matcher.matches(self.repo, &commit)? {
+                return Ok(Some(commit_info(&commit)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+type PageRequest = (usize, oneshot::Sender<AppResult<HistoryPage>>);
+
+/// Handle to a filtered history walk running on its own thread
+///
+/// The walk keeps its position between pages, so paging through a history
+/// walks it (and runs the path and pickaxe diffs) once. The thread ends when
+/// every clone of the cursor is dropped.
+#[derive(Clone)]
+pub struct HistoryCursor {
+    requests: mpsc::Sender<PageRequest>,
+}
+
+impl HistoryCursor {
+    /// Start a walk of the repository at `repo_path`
+    pub fn open(repo_path: PathBuf, filter: LogFilter) -> Self {
+        let (sender, requests) = mpsc::channel::<PageRequest>();
+        std::thread::spawn(move || {
+            let repo = match Repository::open(&repo_path) {
+                Ok(repo) => repo,
+                Err(e) => return Self::fail(&requests, e.into()),
+            };
+            let mut walk = match HistoryWalk::new(&repo, &filter) {
+                Ok(walk) => walk,
+                Err(e) => return Self::fail(&requests, e),
+            };
+            Self::serve(&requests, |limit| walk.next_page(limit));
+        });
+        Self { requests: sender }
+    }
+
+    /// Page through a fixed list of commits, e.g. in mock mode
+    pub fn from_commits(commits: Vec<CommitInfo>) -> Self {
+        let (sender, requests) = mpsc::channel::<PageRequest>();
+        std::thread::spawn(move || {
+            let mut commits = commits.into_iter().peekable();
+            let mut offset = 0;
+            Self::serve(&requests, |limit| {
+                let page: Vec<CommitInfo> = commits.by_ref().take(limit).collect();
+                let page = HistoryPage { offset, has_more: commits.peek().is_some(), commits: page };
+                offset += page.commits.len();
+                Ok(page)
+            });
+        });
+        Self { requests: sender }
+    }
+
+    /// The next `limit` matching commits after the previous page
+    pub async fn next_page(&self, limit: usize) -> AppResult<HistoryPage> {
+        let stopped = || AppError::InvalidState("History walk has stopped".to_string());
+        let (reply, page) = oneshot::channel();
+        self.requests.send((limit, reply)).map_err(|_| stopped())?;
+        page.await.map_err(|_| stopped())?
+    }
+
+    /// Answer page requests until the cursor is dropped or the walk fails
+    fn serve(requests: &mpsc::Receiver<PageRequest>, mut next_page: impl FnMut(usize) -> AppResult<HistoryPage>) {
+        for (limit, reply) in requests {
+            let page = next_page(limit);
+            let failed = page.is_err();
+            let _ = reply.send(page);
+            if failed {
+                return;
+            }
+        }
+    }
+
+    /// Report a walk that could not start to the first request
+    fn fail(requests: &mpsc::Receiver<PageRequest>, error: AppError) {
+        if let Ok((_, reply)) = requests.recv() {
+            let _ = reply.send(Err(error));
+        }
+    }
+}
+
+/// History of a single file from `HEAD`, following renames and copies
+///
+/// Each returned commit carries the path the file had in that commit. Commits
+/// where the file is unchanged relative to a parent are skipped, as with
+/// `git log --follow`.
+pub fn follow_file_history(repo: &Repository, path: &str, options: &FollowOptions, limit: usize) -> AppResult<Vec<CommitInfo>> {
+    let revwalk = build_revwalk(repo, None)?;
+    let mut current = path.to_string();
+    let mut commits = Vec::new();
+
+    for oid in revwalk {
+        if commits.len() >= limit {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+        let Some(blob_id) = entry_id(&tree, &current) else {
+            continue;
+        };
+
+        let parent_trees = commit.parents().map(|p| p.tree()).collect::<Result<Vec<_>, _>>()?;
+        if parent_trees.iter().any(|parent| entry_id(parent, &current) == Some(blob_id)) {
+            continue;
+        }
+
+        let mut info = commit_info(&commit);
+        info.path = Some(current.clone());
+
+        // The file appeared in this commit: look for the rename or copy source
+        if let Some(parent_tree) = parent_trees.first() {
+            if entry_id(parent_tree, &current).is_none() {
+                if let Some(previous) = previous_path(repo, parent_tree, &tree, &current, options)? {
+                    info.previous_path = Some(previous.clone());
+                    current = previous;
+                }
+            }
+        }
+        commits.push(info);
+    }
+
+    Ok(commits)
+}
+
+fn entry_id(tree: &Tree, path: &str) -> Option<Oid> {
+    tree.get_path(Path::new(path)).ok().map(|entry| entry.id())
+}
+
+/// Source path of `path` if it was renamed or copied between the two trees
+fn previous_path(repo: &Repository, old_tree: &Tree, new_tree: &Tree, path: &str, options: &FollowOptions) -> AppResult<Option<String>> {
+    let mut diff_options = DiffOptions::new();
+    // Copy sources may be files that did not change in this commit
+    diff_options.include_unmodified(options.find_copies);
+    let mut diff = repo.diff_tree_to_tree(Some(old_tree), Some(new_tree), Some(&mut diff_options))?;
+
+    let mut find_options = DiffFindOptions::new();
+    find_options
+        .renames(true)
+        .rename_threshold(options.rename_threshold)
+        .copies(options.find_copies)
+        .copies_from_unmodified(options.find_copies)
+        .copy_threshold(options.copy_threshold);
+    diff.find_similar(Some(&mut find_options))?;
+
+    let previous = diff
+        .deltas()
+        .find(|delta| {
+            matches!(delta.status(), Delta::Renamed | Delta::Copied)
+                && delta.new_file().path() == Some(Path::new(path))
+        })
+        .and_then(|delta| delta.old_file().path().map(|p| p.to_string_lossy().into_owned()));
+
+    Ok(previous)
+}
+
+/// Set up the revision walk for an optional range expression
+fn build_revwalk<'r>(repo: &'r Repository, range: Option<&str>) -> AppResult<Revwalk<'r>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+
+    let range = range.map(str::trim).filter(|r| !r.is_empty());
+    let Some(range) = range else {
+        revwalk.push_head()?;
+        return Ok(revwalk);
+    };
+
+    if range.contains("..") {
+        // `a..b` hides a, `a...b` hides the merge base (symmetric difference)
+        let spec = repo.revparse(range)?;
+        let from = spec.from().map(|o| o.id());
+        let to = spec.to().map(|o| o.id());
+        let head = || -> AppResult<Oid> {
+            repo.head()?
+                .target()
+                .ok_or_else(|| AppError::InvalidState("HEAD has no target".to_string()))
+        };
+        let from = match from {
+            Some(oid) => oid,
+            None => head()?,
+        };
+        let to = match to {
+            Some(oid) => oid,
+            None => head()?,
+        };
+
+        if spec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+            revwalk.push(from)?;
+            revwalk.push(to)?;
+            if let Ok(base) = repo.merge_base(from, to) {
+                revwalk.hide(base)?;
+            }
+        } else {
+            revwalk.push(to)?;
+            revwalk.hide(from)?;
+        }
+        return Ok(revwalk);
+    }
+
+    for rev in range.split_whitespace() {
+        if let Some(excluded) = rev.strip_prefix('^') {
+            revwalk.hide(repo.revparse_single(excluded)?.peel_to_commit()?.id())?;
+        } else {
+            revwalk.push(repo.revparse_single(rev)?.peel_to_commit()?.id())?;
+        }
+    }
+
+    Ok(revwalk)
+}
+
+/// Compiled form of a [`LogFilter`]
+struct CommitMatcher<'f> {
+    filter: &'f LogFilter,
+    author: Option<Regex>,
+    committer: Option<Regex>,
+    message: Option<Regex>,
+    pickaxe_regex: Option<Regex>,
+}
+
+impl<'f> CommitMatcher<'f> {
+    fn new(filter: &'f LogFilter) -> AppResult<Self> {
+        let compile = |pattern: &Option<String>| -> AppResult<Option<Regex>> {
+            pattern
+                .as_deref()
+                .map(|p| {
+                    RegexBuilder::new(p)
+                        .case_insensitive(filter.ignore_case)
+                        .build()
+                        .map_err(|e| AppError::InvalidOperation(format!("Invalid pattern '{}': {}", p, e)))
+                })
+                .transpose()
+        };
+
+        let pickaxe_regex = match &filter.pickaxe {
+            Some(LogPickaxe::Regex(pattern)) => compile(&Some(pattern.clone()))?,
+            _ => None,
+        };
+
+        Ok(Self {
+            filter,
+            author: compile(&filter.author)?,
+            committer: compile(&filter.committer)?,
+            message: compile(&filter.message)?,
+            pickaxe_regex,
+        })
+    }
+
+    /// Cheap metadata checks first, diff-based checks last
+    fn matches(&self, repo: &Repository, commit: &Commit) -> AppResult<bool> {
+        let parent_count = commit.parent_count();
+        match self.filter.merges {
+            MergeFilter::MergesOnly if parent_count < 2 => return Ok(false),
+            MergeFilter::NoMerges if parent_count > 1 => return Ok(false),
+            _ => {}
+        }
+
+        let time = commit.time().seconds();
+        if self.filter.since.is_some_and(|since| time < since.timestamp()) {
+            return Ok(false);
+        }
+        if self.filter.until.is_some_and(|until| time > until.timestamp()) {
+            return Ok(false);
+        }
+
+        if let Some(author) = &self.author {
+            if !Self::signature_matches(author, &commit.author()) {
+                return Ok(false);
+            }
+        }
+        if let Some(committer) = &self.committer {
+            if !Self::signature_matches(committer, &commit.committer()) {
+                return Ok(false);
+            }
+        }
+        if let Some(message) = &self.message {
+            if !message.is_match(commit.message().unwrap_or("")) {
+                return Ok(false);
+            }
+        }
+
+        if self.filter.paths.is_empty() && self.filter.pickaxe.is_none() {
+            return Ok(true);
+        }
+
+        // Like git's default history simplification, a merge only counts as
+        // touching the paths when it differs from every parent.
+        let diffs = self.parent_diffs(repo, commit)?;
+        if !self.filter.paths.is_empty() && diffs.iter().any(|diff| diff.deltas().len() == 0) {
+            return Ok(false);
+        }
+
+        match &self.filter.pickaxe {
+            Some(LogPickaxe::String(needle)) => Self::pickaxe_string(repo, &diffs[0], needle),
+            Some(LogPickaxe::Regex(_)) => self.pickaxe_regex(&diffs[0]),
+            None => Ok(true),
+        }
+    }
+
+    fn signature_matches(pattern: &Regex, signature: &git2::Signature) -> bool {
+        let identity = format!(
+            "{} <{}>",
+            signature.name().unwrap_or(""),
+            signature.email().unwrap_or("")
+        );
+        pattern.is_match(&identity)
+    }
+
+    /// Tree diffs against each parent (or the empty tree for root commits), limited to the pathspecs
+    fn parent_diffs<'r>(&self, repo: &'r Repository, commit: &Commit) -> AppResult<Vec<Diff<'r>>> {
+        let tree = commit.tree()?;
+        let mut diffs = Vec::new();
+
+        let diff_against = |parent_tree: Option<&git2::Tree>| -> AppResult<Diff<'r>> {
+            let mut options = DiffOptions::new();
+            for path in &self.filter.paths {
+                options.pathspec(path);
+            }
+            Ok(repo.diff_tree_to_tree(parent_tree, Some(&tree), Some(&mut options))?)
+        };
+
+        if commit.parent_count() == 0 {
+            diffs.push(diff_against(None)?);
+        } else {
+            for parent in commit.parents() {
+                diffs.push(diff_against(Some(&parent.tree()?))?);
+            }
+        }
+
+        Ok(diffs)
+    }
+
+    /// `-S`: the number of occurrences differs between the old and new blob of some file
+    fn pickaxe_string(repo: &Repository, diff: &Diff, needle: &str) -> AppResult<bool> {
+        let count = |oid: Oid| -> AppResult<usize> {
+            if oid.is_zero() {
+                return Ok(0);
+            }
+            let blob = repo.find_blob(oid)?;
+            if blob.is_binary() {
+                return Ok(0);
+            }
+            Ok(String::from_utf8_lossy(blob.content()).matches(needle).count())
+        };
+
+        for delta in diff.deltas() {
+            if count(delta.old_file().id())? != count(delta.new_file().id())? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// `-G`: an added or removed line matches the regex
+    fn pickaxe_regex(&self, diff: &Diff) -> AppResult<bool> {
+        let Some(pattern) = &self.pickaxe_regex else {
+            return Ok(false);
+        };
+
+        let mut found = false;
+        let result = diff.foreach(
+            &mut |_, _| true,
+            None,
+            None,
+            Some(&mut |_, _, line| {
+                if matches!(line.origin(), '+' | '-') && pattern.is_match(&String::from_utf8_lossy(line.content())) {
+                    found = true;
+                    return false; // stop iterating
+                }
+                true
+            }),
+        );
+
+        // libgit2 reports the early stop as an error
+        match result {
+            Ok(()) => Ok(found),
+            Err(_) if found => Ok(true),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git_as;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn commit_file(dir: &Path, file: &str, content: &str, message: &str, author: &str) {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+        git_as(dir, author, &["add", "."]);
+        git_as(dir, author, &["commit", "-q", "-m", message]);
+    }
+
+    fn init_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        git_as(dir.path(), "Alice", &["init", "-q", "-b", "main"]);
+        commit_file(dir.path(), "src/lib.rs", "fn a() {}\n", "feat: add a", "Alice");
+        commit_file(dir.path(), "README.md", "readme\n", "docs: readme", "Bob");
+        commit_file(dir.path(), "src/lib.rs", "fn a() {}\nfn needle() {}\n", "fix: add needle", "Bob");
+        dir
+    }
+
+    fn subjects(page: &HistoryPage) -> Vec<&str> {
+        page.commits.iter().map(|c| c.message.lines().next().unwrap_or("")).collect()
+    }
+
+    #[test]
+    fn test_query_history_filters() {
+        let dir = init_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+
+        let by_author = LogFilter { author: Some("bob".to_string()), ignore_case: true, ..Default::default() };
+        assert_eq!(subjects(&query_history(&repo, &by_author, 0, 10).unwrap()), ["fix: add needle", "docs: readme"]);
+
+        let by_path = LogFilter { paths: vec!["src/*".to_string()], ..Default::default() };
+        assert_eq!(subjects(&query_history(&repo, &by_path, 0, 10).unwrap()), ["fix: add needle", "feat: add a"]);
+
+        let by_message = LogFilter { message: Some("^(feat|docs):".to_string()), ..Default::default() };
+        assert_eq!(query_history(&repo, &by_message, 0, 10).unwrap().commits.len(), 2);
+
+        let pickaxe = LogFilter { pickaxe: Some(LogPickaxe::String("needle".to_string())), ..Default::default() };
+        assert_eq!(subjects(&query_history(&repo, &pickaxe, 0, 10).unwrap()), ["fix: add needle"]);
+
+        let grep = LogFilter { pickaxe: Some(LogPickaxe::Regex(r"fn a\(".to_string())), ..Default::default() };
+        assert_eq!(subjects(&query_history(&repo, &grep, 0, 10).unwrap()), ["feat: add a"]);
+
+        let range = LogFilter { range: Some("HEAD~2..HEAD".to_string()), ..Default::default() };
+        assert_eq!(query_history(&repo, &range, 0, 10).unwrap().commits.len(), 2);
+    }
+
+    #[test]
+    fn test_query_history_paging() {
+        let dir = init_repo();
+        let repo = Repository::open(dir.path()).unwrap();
+
+        let first = query_history(&repo, &LogFilter::default(), 0, 2).unwrap();
+        assert_eq!(first.commits.len(), 2);
+        assert!(first.has_more);
+
+        let second = query_history(&repo, &LogFilter::default(), 2, 2).unwrap();
+        assert_eq!(subjects(&second), ["feat: add a"]);
+        assert!(!second.has_more);
+    }
+
+    #[tokio::test]
+    async fn test_history_cursor_continues_walk() {
+        let dir = init_repo();
+        let cursor = HistoryCursor::open(dir.path().to_path_buf(), LogFilter::default());
+
+        let first = cursor.next_page(2).await.unwrap();
+        assert_eq!(subjects(&first), ["fix: add needle", "docs: readme"]);
+        assert!(first.has_more);
+
+        let second = cursor.next_page(2).await.unwrap();
+        assert_eq!(second.offset, 2);
+        assert_eq!(subjects(&second), ["feat: add a"]);
+        assert!(!second.has_more);
+        assert!(cursor.next_page(2).await.unwrap().commits.is_empty());
+
+        // A filter error ends the walk after reporting it
+        let invalid = LogFilter { author: Some("(".to_string()), ..Default::default() };
+        let cursor = HistoryCursor::open(dir.path().to_path_buf(), invalid);
+        assert!(cursor.next_page(2).await.is_err());
+        assert!(cursor.next_page(2).await.is_err());
+    }
+
+    #[test]
+    fn test_follow_file_history_across_rename() {
+        let dir = init_repo();
+        git_as(dir.path(), "Carol", &["mv", "src/lib.rs", "src/core.rs"]);
+        git_as(dir.path(), "Carol", &["commit", "-q", "-m", "refactor: rename lib"]);
+        commit_file(dir.path(), "src/core.rs", "fn a() {}\nfn needle() {}\nfn b() {}\n", "feat: add b", "Carol");
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let history = follow_file_history(&repo, "src/core.rs", &FollowOptions::default(), 10).unwrap();
+
+        let entries: Vec<(&str, &str)> = history
+            .iter()
+            .map(|c| (c.message.lines().next().unwrap_or(""), c.path.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("feat: add b", "src/core.rs"),
+                ("refactor: rename lib", "src/core.rs"),
+                ("fix: add needle", "src/lib.rs"),
+                ("feat: add a", "src/lib.rs"),
+            ]
+        );
+        let previous: Vec<Option<&str>> = history.iter().map(|c| c.previous_path.as_deref()).collect();
+        assert_eq!(previous, [None, Some("src/lib.rs"), None, None]);
+
+        // Demanding an exact match stops at the rename
+        let strict = FollowOptions { rename_threshold: 100, copy_threshold: 100, find_copies: false };
+        let mut content = std::fs::read_to_string(dir.path().join("src/core.rs")).unwrap();
+        content.push_str("fn c() {}\n");
+        std::fs::remove_file(dir.path().join("src/core.rs")).unwrap();
+        commit_file(dir.path(), "src/moved.rs", &content, "refactor: move and edit", "Carol");
+        let history = follow_file_history(&repo, "src/moved.rs", &strict, 10).unwrap();
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_filter_date() {
+        let start = parse_filter_date("2024-03-01", false).unwrap();
+        let end = parse_filter_date("2024-03-01", true).unwrap();
+        assert_eq!(end.timestamp() - start.timestamp(), 86_399);
+        assert!(parse_filter_date("yesterday-ish", false).is_err());
+    }
+}
@@ -7,16 +7,54 @@
 //! - Memory usage: < 100MB (large repositories)
 
 pub mod cache;
+pub mod changelog;
+pub mod clean;
+pub mod commit_lint;
+pub mod content;
+pub mod conventional;
+pub mod diff_driver;
+pub mod edit;
+pub mod ignore;
+pub mod insights;
+pub mod log_filter;
+pub mod notes;
 pub mod operations;
+pub mod patch;
+pub mod patch_stream;
+pub mod revision_diff;
 pub mod service;
+pub mod sparse;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod trailers;
+pub mod version;
 pub mod workflow;
+pub mod workspace;
 
+pub use changelog::{Changelog, ChangelogEntry, ChangelogFormat, ChangelogOptions, ChangelogSection};
+pub use clean::{CleanCandidate, CleanIgnored, CleanOptions, CleanPreview, RestoreReport, TrashBatch};
+pub use commit_lint::{LintReport, LintRules, LintSeverity, LintViolation};
+pub use content::{BlobContent, ContentAttributes, ContentKind, EncodingSource, ImageInfo, LineEndings, TextconvOutcome};
+pub use conventional::ConventionalCommit;
+pub use diff_driver::{DiffDriver, FuncnamePattern, TextconvRunner};
+pub use edit::{EditBase, EditOutcome, EditTarget};
+pub use ignore::{IgnoreMatch, IgnoreRuleKind, IgnoreTarget};
+pub use insights::{InsightsBucket, InsightsOptions, RepositoryInsights};
+pub use log_filter::{FollowOptions, HistoryCursor, HistoryPage, LogFilter, LogPickaxe, MergeFilter};
+pub use notes::{CommitNote, NotesFetchResult, NotesFetchStatus};
+pub use patch::{PatchApplyOptions, PatchApplyOutcome, PatchConflict, PatchExportOptions, PatchFormat};
+pub use patch_stream::{HunkEntry, PatchIndex, PatchSource, PatchStream};
+pub use revision_diff::{ChangeStatus, DiffSide, DiffSpec, FilePatch, RevisionDiff, RevisionFileChange};
 pub use service::GitService;
+pub use sparse::{SparseCheckout, SparseDirectory, SparseInclusion};
+pub use trailers::{Identity, Trailer};
+pub use version::{BumpLevel, Version, VersionOptions, VersionSuggestion, VersionTag};
 pub use workflow::{
-    GitWorkflowManager, BranchManager, TagManager, RemoteManager,
+    GitWorkflowManager, BranchManager, TagManager, RemoteManager, DefaultRemoteManager,
     BranchInfo as WorkflowBranchInfo, TagInfo as WorkflowTagInfo, RemoteInfo as WorkflowRemoteInfo,
     GitFlowConfig, BranchProtectionRule, MergeStrategy, BranchComparison
 };
+pub use workspace::{Workspace, WorkspaceOperation, WorkspaceOperationResult, WorkspaceRepoStatus};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -243,6 +281,13 @@ pub struct CommitInfo {
     pub date: DateTime<Utc>,
     /// Parent commit hashes
     pub parents: Vec<String>,
+    /// Path of the file in this commit, set by file history queries
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Path of the file in the first parent when this commit renamed or
+    /// copied it, set by file history queries
+    #[serde(default)]
+    pub previous_path: Option<String>,
 }
 
 /// Find the Git repository root starting from a given path
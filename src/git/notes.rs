@@ -0,0 +1,326 @@
+//! Git notes
+//!
+//! Reads and edits notes under several notes refs (`refs/notes/commits`,
+//! `refs/notes/review`, ...) and moves them between repositories with
+//! explicit refspecs. Fetched notes land in `refs/notes-remote/<remote>/`
+//! first and only fast-forward the local ref; diverged notes are left for
+//! `git notes merge`.
+
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, rc::Rc};
+
+use super::DefaultRemoteManager;
+use crate::error::{AppError, AppResult};
+
+/// Notes ref git uses when none is configured
+pub const DEFAULT_NOTES_REF: &str = "refs/notes/commits";
+/// Namespace fetched notes are stored in before they are fast-forwarded
+const REMOTE_NOTES_PREFIX: &str = "refs/notes-remote";
+
+/// A note attached to a commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitNote {
+    /// Full notes ref, e.g. `refs/notes/review`
+    pub notes_ref: String,
+    /// Annotated commit hash
+    pub commit: String,
+    pub message: String,
+}
+
+impl CommitNote {
+    /// Notes ref without `refs/notes/`, as shown in the UI
+    pub fn short_ref(&self) -> &str {
+        short_notes_ref(&self.notes_ref)
+    }
+}
+
+/// What fetching one notes ref did to the local ref
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotesFetchStatus {
+    /// The remote has no such notes ref
+    Missing,
+    UpToDate,
+    /// Local ref created or fast-forwarded
+    Updated,
+    /// Local notes contain the remote ones already
+    LocalAhead,
+    /// Both sides changed; the remote notes are kept in `tracking_ref`
+    Diverged { tracking_ref: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotesFetchResult {
+    pub notes_ref: String,
+    pub status: NotesFetchStatus,
+}
+
+/// `review` / `notes/review` / `refs/notes/review` → `refs/notes/review`
+pub fn normalize_notes_ref(name: &str) -> String {
+    let name = name.trim();
+    if name.starts_with("refs/") {
+        name.to_string()
+    } else if let Some(rest) = name.strip_prefix("notes/") {
+        format!("refs/notes/{}", rest)
+    } else {
+        format!("refs/notes/{}", name)
+    }
+}
+
+pub fn short_notes_ref(notes_ref: &str) -> &str {
+    notes_ref.strip_prefix("refs/notes/").unwrap_or(notes_ref)
+}
+
+/// Refspec pushing or fetching a notes ref to the same name
+pub fn notes_refspec(notes_ref: &str) -> String {
+    format!("{0}:{0}", notes_ref)
+}
+
+/// Where notes fetched from `remote` are stored before fast-forwarding
+pub fn remote_tracking_notes_ref(remote: &str, notes_ref: &str) -> String {
+    format!("{}/{}/{}", REMOTE_NOTES_PREFIX, remote, short_notes_ref(notes_ref))
+}
+
+/// Notes attached to `commit` under each of `notes_refs`, in that order
+pub fn notes_for_commit(repo: &Repository, commit: &str, notes_refs: &[String]) -> AppResult<Vec<CommitNote>> {
+    let oid = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    let notes = notes_refs
+        .iter()
+        .filter_map(|notes_ref| {
+            let note = repo.find_note(Some(notes_ref), oid).ok()?;
+            Some(CommitNote {
+                notes_ref: notes_ref.clone(),
+                commit: oid.to_string(),
+                message: note.message()?.trim_end().to_string(),
+            })
+        })
+        .collect();
+    Ok(notes)
+}
+
+/// Every note under `notes_refs`; refs that do not exist yet are skipped
+pub fn list_notes(repo: &Repository, notes_refs: &[String]) -> AppResult<Vec<CommitNote>> {
+    let mut notes = Vec::new();
+    for notes_ref in notes_refs {
+        let Ok(iter) = repo.notes(Some(notes_ref)) else {
+            continue;
+        };
+        for entry in iter {
+            let (_, commit) = entry?;
+            if let Ok(note) = repo.find_note(Some(notes_ref), commit) {
+                notes.push(CommitNote {
+                    notes_ref: notes_ref.clone(),
+                    commit: commit.to_string(),
+                    message: note.message().unwrap_or_default().trim_end().to_string(),
+                });
+            }
+        }
+    }
+    Ok(notes)
+}
+
+/// Add the note, replacing an existing one (`git notes add -f`)
+pub fn set_note(repo: &Repository, notes_ref: &str, commit: &str, message: &str) -> AppResult<Oid> {
+    let message = message.trim_end();
+    if message.is_empty() {
+        return Err(AppError::InvalidOperation("Note is empty; remove it instead".to_string()));
+    }
+    let oid = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    let signature = repo.signature()?;
+    Ok(repo.note(&signature, &signature, Some(notes_ref), oid, &format!("{}\n", message), true)?)
+}
+
+/// Remove the note; `false` if the commit had none
+pub fn remove_note(repo: &Repository, notes_ref: &str, commit: &str) -> AppResult<bool> {
+    let oid = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    if repo.find_note(Some(notes_ref), oid).is_err() {
+        return Ok(false);
+    }
+    let signature = repo.signature()?;
+    repo.note_delete(oid, Some(notes_ref), &signature, &signature)?;
+    Ok(true)
+}
+
+/// `origin` when it exists, otherwise the first remote
+pub fn default_remote(repo: &Repository) -> AppResult<String> {
+    let remotes = repo.remotes()?;
+    let names: Vec<&str> = remotes.iter().flatten().collect();
+    names
+        .iter()
+        .find(|name| **name == "origin")
+        .or_else(|| names.first())
+        .map(|name| name.to_string())
+        .ok_or_else(|| AppError::InvalidState("Repository has no remotes".to_string()))
+}
+
+/// Push the local notes refs that exist; returns the pushed refs
+pub fn push_notes(repo: &Repository, remote_name: &str, notes_refs: &[String]) -> AppResult<Vec<String>> {
+    let refs: Vec<String> = notes_refs
+        .iter()
+        .filter(|notes_ref| repo.find_reference(notes_ref).is_ok())
+        .cloned()
+        .collect();
+    if refs.is_empty() {
+        return Ok(refs);
+    }
+
+    let rejected: Rc<RefCell<Vec<String>>> = Rc::default();
+    let mut callbacks = DefaultRemoteManager::remote_callbacks(repo);
+    let sink = rejected.clone();
+    callbacks.push_update_reference(move |name, status| {
+        if let Some(message) = status {
+            sink.borrow_mut().push(format!("{}: {}", name, message));
+        }
+        Ok(())
+    });
+
+    let mut remote = repo.find_remote(remote_name)?;
+    let mut options = git2::PushOptions::new();
+    options.remote_callbacks(callbacks);
+    let refspecs: Vec<String> = refs.iter().map(|notes_ref| notes_refspec(notes_ref)).collect();
+    remote.push(&refspecs, Some(&mut options))?;
+
+    let rejected = rejected.borrow();
+    if !rejected.is_empty() {
+        return Err(AppError::application(format!(
+            "Notes push rejected ({}); fetch and merge the notes first",
+            rejected.join(", ")
+        )));
+    }
+    Ok(refs)
+}
+
+/// Fetch `notes_refs` from the remote and fast-forward the local refs
+pub fn fetch_notes(repo: &Repository, remote_name: &str, notes_refs: &[String]) -> AppResult<Vec<NotesFetchResult>> {
+    let refspecs: Vec<String> = notes_refs
+        .iter()
+        .map(|notes_ref| format!("+{}:{}", notes_ref, remote_tracking_notes_ref(remote_name, notes_ref)))
+        .collect();
+
+    let mut remote = repo.find_remote(remote_name)?;
+    let mut options = git2::FetchOptions::new();
+    options.remote_callbacks(DefaultRemoteManager::remote_callbacks(repo));
+    remote.fetch(&refspecs, Some(&mut options), None)?;
+
+    notes_refs
+        .iter()
+        .map(|notes_ref| {
+            let tracking_ref = remote_tracking_notes_ref(remote_name, notes_ref);
+            let status = update_from_tracking(repo, notes_ref, &tracking_ref)?;
+            Ok(NotesFetchResult {
+                notes_ref: notes_ref.clone(),
+                status,
+            })
+        })
+        .collect()
+}
+
+fn update_from_tracking(repo: &Repository, notes_ref: &str, tracking_ref: &str) -> AppResult<NotesFetchStatus> {
+    let Some(fetched) = repo.find_reference(tracking_ref).ok().and_then(|r| r.target()) else {
+        return Ok(NotesFetchStatus::Missing);
+    };
+    let message = format!("notes: fast-forward from {}", tracking_ref);
+    let Some(local) = repo.find_reference(notes_ref).ok().and_then(|r| r.target()) else {
+        repo.reference(notes_ref, fetched, false, &message)?;
+        return Ok(NotesFetchStatus::Updated);
+    };
+
+    if local == fetched {
+        Ok(NotesFetchStatus::UpToDate)
+    } else if repo.graph_descendant_of(fetched, local)? {
+        repo.reference_matching(notes_ref, fetched, true, local, &message)?;
+        Ok(NotesFetchStatus::Updated)
+    } else if repo.graph_descendant_of(local, fetched)? {
+        Ok(NotesFetchStatus::LocalAhead)
+    } else {
+        Ok(NotesFetchStatus::Diverged {
+            tracking_ref: tracking_ref.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn commit_file(repo: &Repository, name: &str) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(name), name).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        let parents: Vec<_> = repo.head().ok().and_then(|h| h.peel_to_commit().ok()).into_iter().collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, name, &tree, &parents).unwrap()
+    }
+
+    fn init_repo(dir: &Path) -> Repository {
+        let repo = Repository::init(dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_normalize_notes_ref() {
+        assert_eq!(normalize_notes_ref("review"), "refs/notes/review");
+        assert_eq!(normalize_notes_ref("notes/ci"), "refs/notes/ci");
+        assert_eq!(normalize_notes_ref("refs/notes/commits"), DEFAULT_NOTES_REF);
+        assert_eq!(remote_tracking_notes_ref("origin", "refs/notes/review"), "refs/notes-remote/origin/review");
+        assert_eq!(notes_refspec("refs/notes/review"), "refs/notes/review:refs/notes/review");
+    }
+
+    #[test]
+    fn test_set_list_and_remove_notes() {
+        let dir = TempDir::new().unwrap();
+        let repo = init_repo(dir.path());
+        let commit = commit_file(&repo, "a.txt").to_string();
+        let refs = vec![DEFAULT_NOTES_REF.to_string(), "refs/notes/review".to_string()];
+
+        set_note(&repo, "refs/notes/review", &commit, "Reviewed: https://example.com/r/1").unwrap();
+        set_note(&repo, DEFAULT_NOTES_REF, &commit, "CI green").unwrap();
+        set_note(&repo, DEFAULT_NOTES_REF, &commit, "CI green\nbuild 42").unwrap();
+
+        let notes = notes_for_commit(&repo, &commit, &refs).unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].message, "CI green\nbuild 42");
+        assert_eq!(notes[1].short_ref(), "review");
+        assert_eq!(list_notes(&repo, &refs).unwrap().len(), 2);
+
+        assert!(remove_note(&repo, DEFAULT_NOTES_REF, &commit).unwrap());
+        assert!(!remove_note(&repo, DEFAULT_NOTES_REF, &commit).unwrap());
+        assert_eq!(notes_for_commit(&repo, &commit, &refs).unwrap().len(), 1);
+        assert!(set_note(&repo, DEFAULT_NOTES_REF, &commit, "  ").is_err());
+    }
+
+    #[test]
+    fn test_push_and_fetch_notes() {
+        let dir = TempDir::new().unwrap();
+        let bare = Repository::init_bare(dir.path().join("remote.git")).unwrap();
+        let origin = bare.path().to_str().unwrap().to_string();
+
+        let alice = init_repo(&dir.path().join("alice"));
+        let commit = commit_file(&alice, "a.txt").to_string();
+        alice.remote("origin", &origin).unwrap();
+        let refs = vec!["refs/notes/review".to_string()];
+        set_note(&alice, &refs[0], &commit, "LGTM").unwrap();
+        assert_eq!(default_remote(&alice).unwrap(), "origin");
+        assert_eq!(push_notes(&alice, "origin", &refs).unwrap(), refs);
+
+        let bob = init_repo(&dir.path().join("bob"));
+        bob.remote("origin", &origin).unwrap();
+        let results = fetch_notes(&bob, "origin", &refs).unwrap();
+        assert_eq!(results[0].status, NotesFetchStatus::Updated);
+        // Bob has no commits; the note is there through the notes ref alone
+        assert_eq!(list_notes(&bob, &refs).unwrap()[0].message, "LGTM");
+        assert_eq!(fetch_notes(&bob, "origin", &refs).unwrap()[0].status, NotesFetchStatus::UpToDate);
+
+        let missing = fetch_notes(&bob, "origin", &["refs/notes/ci".to_string()]).unwrap();
+        assert_eq!(missing[0].status, NotesFetchStatus::Missing);
+    }
+}
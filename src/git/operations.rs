@@ -5,12 +5,12 @@
 
 use chrono::{DateTime, Utc};
 use git2::{BranchType, ObjectType, Repository};
-use std::time::Instant;
+use std::{cell::RefCell, rc::Rc, time::Instant};
 use tracing::{debug, info, instrument, warn};
 
 use crate::{
     error::{AppError, AppResult},
-    git::{BranchInfo, RemoteInfo, StashInfo, TagInfo},
+    git::{notes, BranchInfo, DefaultRemoteManager, RemoteInfo, StashInfo, TagInfo},
 };
 
 /// Extended Git operations for advanced workflow management
@@ -90,10 +90,8 @@ impl<'repo> GitOperations<'repo> {
         let branch_ref = branch.get();
         let branch_name = format!("refs/heads/{}", name);
 
-        // Set HEAD to point to the branch
-        self.repo.set_head(&branch_name).map_err(AppError::Git)?;
-
-        // Checkout the branch
+        // Checkout the branch while HEAD still names the old one, so files
+        // only the old branch tracks are removed
         let commit = branch_ref.peel_to_commit().map_err(AppError::Git)?;
         let tree = commit.tree().map_err(AppError::Git)?;
 
@@ -104,6 +102,9 @@ impl<'repo> GitOperations<'repo> {
             .checkout_tree(tree.as_object(), Some(&mut checkout_options))
             .map_err(AppError::Git)?;
 
+        // Set HEAD to point to the branch
+        self.repo.set_head(&branch_name).map_err(AppError::Git)?;
+
         let duration = operation_start.elapsed();
         debug!("Switched to branch {} in {:?}", name, duration);
 
@@ -360,7 +361,7 @@ impl<'repo> GitOperations<'repo> {
         Ok(())
     }
 
-    /// Merge a branch
+    /// Merge a local or remote-tracking branch into the current branch
     #[instrument(skip(self))]
     pub fn merge(&self, branch_name: &str) -> AppResult<()> {
         let operation_start = Instant::now();
@@ -371,44 +372,45 @@ impl<'repo> GitOperations<'repo> {
         let branch = self
             .repo
             .find_branch(branch_name, BranchType::Local)
+            .or_else(|_| self.repo.find_branch(branch_name, BranchType::Remote))
             .map_err(AppError::Git)?;
 
         let branch_commit = branch.get().peel_to_commit().map_err(AppError::Git)?;
-
-        // Get HEAD commit
-        let head_commit = self
+        let annotated = self
             .repo
-            .head()
-            .map_err(AppError::Git)?
-            .peel_to_commit()
+            .reference_to_annotated_commit(branch.get())
             .map_err(AppError::Git)?;
 
+        // Get HEAD commit
+        let head = self.repo.head().map_err(AppError::Git)?;
+        let head_commit = head.peel_to_commit().map_err(AppError::Git)?;
+
         // Perform merge analysis
-        let analysis = self.repo.merge_analysis(&[]).map_err(AppError::Git)?;
+        let analysis = self.repo.merge_analysis(&[&annotated]).map_err(AppError::Git)?;
 
         if analysis.0.is_up_to_date() {
             info!("Already up to date");
         } else if analysis.0.is_fast_forward() {
             // Fast-forward merge
             info!("Fast-forward merge");
-            let refname = format!(
-                "refs/heads/{}",
-                self.repo.head().unwrap().shorthand().unwrap_or("main")
-            );
-
-            let mut reference = self.repo.find_reference(&refname).map_err(AppError::Git)?;
-            reference
-                .set_target(branch_commit.id(), "Fast-forward merge")
-                .map_err(AppError::Git)?;
+            let refname = head
+                .name()
+                .filter(|_| head.is_branch())
+                .ok_or_else(|| AppError::InvalidState("Cannot fast-forward a detached HEAD".to_string()))?
+                .to_string();
 
-            // Update working directory
-            self.repo.set_head(&refname).map_err(AppError::Git)?;
+            // Update working directory before moving the branch
             let tree = branch_commit.tree().map_err(AppError::Git)?;
             let mut checkout_options = git2::build::CheckoutBuilder::new();
             checkout_options.safe();
             self.repo
                 .checkout_tree(tree.as_object(), Some(&mut checkout_options))
                 .map_err(AppError::Git)?;
+
+            let mut reference = self.repo.find_reference(&refname).map_err(AppError::Git)?;
+            reference
+                .set_target(branch_commit.id(), "Fast-forward merge")
+                .map_err(AppError::Git)?;
         } else if analysis.0.is_normal() {
             // Normal merge - requires merge commit
             info!("Normal merge (merge commit required)");
@@ -420,7 +422,7 @@ impl<'repo> GitOperations<'repo> {
             checkout_options.safe();
 
             self.repo
-                .merge(&[], Some(&mut merge_options), Some(&mut checkout_options))
+                .merge(&[&annotated], Some(&mut merge_options), Some(&mut checkout_options))
                 .map_err(AppError::Git)?;
 
             // Check if merge resulted in conflicts
@@ -459,6 +461,71 @@ impl<'repo> GitOperations<'repo> {
         Ok(())
     }
 
+    /// Push a local branch to `remote_name`
+    ///
+    /// A branch without an upstream starts tracking the pushed branch.
+    #[instrument(skip(self))]
+    pub fn push_branch(&self, remote_name: &str, branch_name: &str) -> AppResult<()> {
+        let operation_start = Instant::now();
+
+        info!("Pushing branch {} to {}", branch_name, remote_name);
+
+        let mut branch = self
+            .repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(AppError::Git)?;
+
+        let rejected: Rc<RefCell<Vec<String>>> = Rc::default();
+        let mut callbacks = DefaultRemoteManager::remote_callbacks(self.repo);
+        let sink = rejected.clone();
+        callbacks.push_update_reference(move |name, status| {
+            if let Some(message) = status {
+                sink.borrow_mut().push(format!("{}: {}", name, message));
+            }
+            Ok(())
+        });
+
+        let mut remote = self.repo.find_remote(remote_name).map_err(AppError::Git)?;
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+        let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch_name);
+        remote
+            .push(&[refspec.as_str()], Some(&mut push_options))
+            .map_err(AppError::Git)?;
+
+        let rejected = rejected.borrow();
+        if !rejected.is_empty() {
+            return Err(AppError::application(format!(
+                "Push rejected ({}); pull and merge the remote changes first",
+                rejected.join(", ")
+            )));
+        }
+
+        if branch.upstream().is_err() {
+            branch
+                .set_upstream(Some(&format!("{}/{}", remote_name, branch_name)))
+                .map_err(AppError::Git)?;
+        }
+
+        let duration = operation_start.elapsed();
+        debug!("Pushed branch {} in {:?}", branch_name, duration);
+
+        Ok(())
+    }
+
+    /// Remote a local branch pushes to: its upstream's remote, else `origin` or the first remote
+    pub fn push_remote_for(&self, branch_name: &str) -> AppResult<String> {
+        let upstream_remote = self
+            .repo
+            .branch_upstream_remote(&format!("refs/heads/{}", branch_name))
+            .ok()
+            .and_then(|remote| remote.as_str().map(str::to_string));
+        match upstream_remote {
+            Some(remote) => Ok(remote),
+            None => notes::default_remote(self.repo),
+        }
+    }
+
     /// List all branches in the repository
     pub fn list_branches(&self) -> AppResult<Vec<crate::git::BranchInfo>> {
         let operation_start = Instant::now();
@@ -608,3 +675,88 @@ impl<'repo> GitOperations<'repo> {
         Ok(branches)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn commit_file(repo: &Repository, name: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(name), name).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        let parents: Vec<_> = repo.head().ok().and_then(|h| h.peel_to_commit().ok()).into_iter().collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, name, &tree, &parents).unwrap()
+    }
+
+    fn init_repo(dir: &Path) -> Repository {
+        let repo = Repository::init(dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        repo
+    }
+
+    fn head_branch(repo: &Repository) -> String {
+        repo.head().unwrap().shorthand().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_create_merge_and_delete_branch() {
+        let dir = TempDir::new().unwrap();
+        let mut repo = init_repo(dir.path());
+        commit_file(&repo, "a.txt");
+        let main = head_branch(&repo);
+
+        GitOperations::new(&mut repo).create_branch("feature", None).unwrap();
+        GitOperations::new(&mut repo).switch_branch("feature").unwrap();
+        let feature_tip = commit_file(&repo, "b.txt");
+        GitOperations::new(&mut repo).switch_branch(&main).unwrap();
+        assert!(!dir.path().join("b.txt").exists());
+
+        // Fast-forward moves the branch and the working tree
+        GitOperations::new(&mut repo).merge("feature").unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(feature_tip));
+        assert_eq!(head_branch(&repo), main);
+        assert!(dir.path().join("b.txt").exists());
+
+        // Diverged branches get a merge commit
+        GitOperations::new(&mut repo).create_branch("topic", None).unwrap();
+        commit_file(&repo, "c.txt");
+        GitOperations::new(&mut repo).switch_branch("topic").unwrap();
+        commit_file(&repo, "d.txt");
+        GitOperations::new(&mut repo).switch_branch(&main).unwrap();
+        GitOperations::new(&mut repo).merge("topic").unwrap();
+        assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().parent_count(), 2);
+        assert!(dir.path().join("d.txt").exists());
+
+        GitOperations::new(&mut repo).delete_branch("topic").unwrap();
+        assert!(repo.find_branch("topic", BranchType::Local).is_err());
+    }
+
+    #[test]
+    fn test_push_branch_sets_upstream() {
+        let dir = TempDir::new().unwrap();
+        let bare = Repository::init_bare(dir.path().join("remote.git")).unwrap();
+        let mut repo = init_repo(&dir.path().join("work"));
+        let tip = commit_file(&repo, "a.txt");
+        repo.remote("origin", bare.path().to_str().unwrap()).unwrap();
+        let main = head_branch(&repo);
+
+        {
+            let operations = GitOperations::new(&mut repo);
+            assert_eq!(operations.push_remote_for(&main).unwrap(), "origin");
+            operations.push_branch("origin", &main).unwrap();
+        }
+
+        assert_eq!(bare.find_reference(&format!("refs/heads/{}", main)).unwrap().target(), Some(tip));
+        let branch = repo.find_branch(&main, BranchType::Local).unwrap();
+        assert_eq!(branch.upstream().unwrap().name().unwrap(), Some(format!("origin/{}", main).as_str()));
+    }
+}
@@ -0,0 +1,471 @@
+//! Patch exchange operations
+//!
+//! Mailbox-style patch export and import (`git format-patch` / `git am`).
+//! libgit2 has no mailbox support, so these operations drive the git CLI
+//! inside the repository root.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+use tracing::{debug, info, instrument, warn};
+
+use crate::error::{AppError, AppResult};
+
+/// Options for exporting commits as mailbox patch files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchExportOptions {
+    /// Directory the patch files are written to
+    pub output_dir: PathBuf,
+    /// Generate a `0000-cover-letter.patch` in front of the series
+    pub cover_letter: bool,
+    /// Subject prefix used instead of `PATCH` (e.g. `PATCH v2`)
+    pub subject_prefix: Option<String>,
+    /// Force `[PATCH n/m]` numbering even for a single patch
+    pub numbered: bool,
+}
+
+impl Default for PatchExportOptions {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("patches"),
+            cover_letter: false,
+            subject_prefix: None,
+            numbered: false,
+        }
+    }
+}
+
+/// Options for applying patches onto the current branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchApplyOptions {
+    /// Fall back to a three-way merge when a patch does not apply cleanly
+    pub three_way: bool,
+    /// Use the author date as committer date as well
+    pub keep_committer_date: bool,
+    /// Add a `Signed-off-by` trailer for the current user
+    pub signoff: bool,
+}
+
+impl Default for PatchApplyOptions {
+    fn default() -> Self {
+        Self {
+            three_way: true,
+            keep_committer_date: false,
+            signoff: false,
+        }
+    }
+}
+
+/// Detected format of a patch file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatchFormat {
+    /// mbox file produced by `git format-patch` (carries author and date)
+    Mailbox,
+    /// Plain unified diff without commit metadata
+    PlainDiff,
+}
+
+/// Patch that stopped a running apply because of conflicts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchConflict {
+    /// 1-based number of the patch that failed
+    pub patch_number: usize,
+    /// Total number of patches in the series
+    pub total: usize,
+    /// Subject line of the failing patch
+    pub subject: String,
+    /// Files left with conflict markers
+    pub conflicted_files: Vec<String>,
+}
+
+/// Result of applying a patch file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatchApplyOutcome {
+    /// All patches were applied
+    Applied { count: usize },
+    /// Applying stopped at a conflicting patch; resolve, then continue, skip or abort
+    Conflict(PatchConflict),
+    /// A plain diff left conflict markers; stage the resolved files or reset them
+    DiffConflict { conflicted_files: Vec<String> },
+}
+
+/// Detect whether patch content is an mbox series or a plain diff
+pub fn detect_patch_format(content: &str) -> PatchFormat {
+    let first_line = content.lines().next().unwrap_or("");
+    let is_mbox_separator = first_line.starts_with("From ") && first_line.split_whitespace().count() > 2;
+
+    if is_mbox_separator || content.lines().take(20).any(|line| line.starts_with("Subject: ")) {
+        PatchFormat::Mailbox
+    } else {
+        PatchFormat::PlainDiff
+    }
+}
+
+/// Count the patches contained in an mbox file
+pub fn count_mailbox_patches(content: &str) -> usize {
+    content
+        .lines()
+        .filter(|line| line.starts_with("From ") && line.split_whitespace().count() > 2)
+        .count()
+        .max(1)
+}
+
+/// Patch exchange manager bound to a repository working directory
+pub struct PatchManager<'a> {
+    repo_path: &'a Path,
+}
+
+impl<'a> PatchManager<'a> {
+    /// Create a new PatchManager for the given repository root
+    pub fn new(repo_path: &'a Path) -> Self {
+        Self { repo_path }
+    }
+
+    /// Export the inclusive commit range `oldest..=newest` as mbox patch files
+    ///
+    /// Returns the paths of the written files in series order, including the
+    /// cover letter when requested.
+    #[instrument(skip(self))]
+    pub fn export(&self, oldest: &str, newest: &str, options: &PatchExportOptions) -> AppResult<Vec<PathBuf>> {
+        let output_dir = if options.output_dir.is_absolute() {
+            options.output_dir.clone()
+        } else {
+            self.repo_path.join(&options.output_dir)
+        };
+        std::fs::create_dir_all(&output_dir).map_err(AppError::Io)?;
+
+        let mut args: Vec<String> = vec![
+            "format-patch".to_string(),
+            "-o".to_string(),
+            output_dir.to_string_lossy().to_string(),
+        ];
+        if options.cover_letter {
+            args.push("--cover-letter".to_string());
+        }
+        if options.numbered {
+            args.push("--numbered".to_string());
+        }
+        if let Some(prefix) = &options.subject_prefix {
+            args.push(format!("--subject-prefix={}", prefix));
+        }
+
+        // A range starting at a root commit has no parent to exclude
+        if self.has_parent(oldest)? {
+            args.push(format!("{}^..{}", oldest, newest));
+        } else {
+            args.push("--root".to_string());
+            args.push(newest.to_string());
+        }
+
+        let output = self.run_git(&args)?;
+        let files: Vec<PathBuf> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| PathBuf::from(line.trim()))
+            .collect();
+
+        info!("Exported {} patch files to {}", files.len(), output_dir.display());
+        Ok(files)
+    }
+
+    /// Apply an mbox series or a plain diff onto the current branch
+    ///
+    /// Mailbox patches are committed with their original author and author
+    /// date. Plain diffs are applied to the working tree and index only.
+    #[instrument(skip(self))]
+    pub fn apply(&self, patch_file: &Path, options: &PatchApplyOptions) -> AppResult<PatchApplyOutcome> {
+        if self.am_in_progress() {
+            return Err(AppError::InvalidState(
+                "A patch series is already being applied; continue, skip or abort it first".to_string(),
+            ));
+        }
+
+        let content = std::fs::read(patch_file).map_err(AppError::Io)?;
+        let content = String::from_utf8_lossy(&content);
+        let patch_file = patch_file.to_string_lossy().to_string();
+
+        match detect_patch_format(&content) {
+            PatchFormat::Mailbox => {
+                let total = count_mailbox_patches(&content);
+                let mut args = vec!["am".to_string()];
+                if options.three_way {
+                    args.push("--3way".to_string());
+                }
+                if options.keep_committer_date {
+                    args.push("--committer-date-is-author-date".to_string());
+                }
+                if options.signoff {
+                    args.push("--signoff".to_string());
+                }
+                args.push(patch_file);
+
+                let output = self.git_output(&args)?;
+                if output.status.success() {
+                    info!("Applied {} mailbox patches", total);
+                    Ok(PatchApplyOutcome::Applied { count: total })
+                } else if self.am_in_progress() {
+                    Ok(PatchApplyOutcome::Conflict(self.current_am_conflict()?))
+                } else {
+                    Err(Self::command_error("git am", &output))
+                }
+            }
+            PatchFormat::PlainDiff => {
+                let mut args = vec!["apply".to_string(), "--index".to_string()];
+                if options.three_way {
+                    args.push("--3way".to_string());
+                }
+                args.push(patch_file);
+
+                let output = self.git_output(&args)?;
+                let conflicted_files = self.conflicted_files()?;
+                if output.status.success() && conflicted_files.is_empty() {
+                    info!("Applied plain diff to index and working tree");
+                    Ok(PatchApplyOutcome::Applied { count: 1 })
+                } else if !conflicted_files.is_empty() {
+                    warn!("Plain diff stopped with conflicts in {} files", conflicted_files.len());
+                    Ok(PatchApplyOutcome::DiffConflict { conflicted_files })
+                } else {
+                    Err(Self::command_error("git apply", &output))
+                }
+            }
+        }
+    }
+
+    /// Continue a stopped series after conflicts were resolved and staged
+    pub fn continue_apply(&self) -> AppResult<PatchApplyOutcome> {
+        self.resume("--continue")
+    }
+
+    /// Skip the conflicting patch and continue with the rest of the series
+    pub fn skip_patch(&self) -> AppResult<PatchApplyOutcome> {
+        self.resume("--skip")
+    }
+
+    /// Abort the series and restore the branch to its original state
+    pub fn abort_apply(&self) -> AppResult<()> {
+        self.run_git(&["am".to_string(), "--abort".to_string()])?;
+        info!("Aborted patch series");
+        Ok(())
+    }
+
+    /// Finish a conflicted plain diff once all its paths are resolved and staged
+    ///
+    /// Returns the paths that still have unmerged entries as a new conflict.
+    pub fn continue_diff_apply(&self, conflicted_files: &[String]) -> AppResult<PatchApplyOutcome> {
+        let unresolved: Vec<String> = self
+            .conflicted_files()?
+            .into_iter()
+            .filter(|path| conflicted_files.contains(path))
+            .collect();
+
+        if unresolved.is_empty() {
+            info!("Resolved plain diff conflicts");
+            Ok(PatchApplyOutcome::Applied { count: 1 })
+        } else {
+            Ok(PatchApplyOutcome::DiffConflict { conflicted_files: unresolved })
+        }
+    }
+
+    /// Abort a conflicted plain diff by resetting its conflicted paths to HEAD
+    pub fn abort_diff_apply(&self, conflicted_files: &[String]) -> AppResult<()> {
+        let mut args = vec!["checkout".to_string(), "HEAD".to_string(), "--".to_string()];
+        args.extend(conflicted_files.iter().cloned());
+        self.run_git(&args)?;
+        info!("Reset {} conflicted paths to HEAD", conflicted_files.len());
+        Ok(())
+    }
+
+    /// Check whether a `git am` session is waiting for user action
+    pub fn am_in_progress(&self) -> bool {
+        self.rebase_apply_dir().join("applying").exists()
+    }
+
+    fn resume(&self, flag: &str) -> AppResult<PatchApplyOutcome> {
+        if !self.am_in_progress() {
+            return Err(AppError::InvalidState("No patch series in progress".to_string()));
+        }
+
+        let total = Self::read_counter(&self.rebase_apply_dir().join("last")).unwrap_or(1);
+        let output = self.git_output(&["am".to_string(), flag.to_string()])?;
+        if output.status.success() {
+            Ok(PatchApplyOutcome::Applied { count: total })
+        } else if self.am_in_progress() {
+            Ok(PatchApplyOutcome::Conflict(self.current_am_conflict()?))
+        } else {
+            Err(Self::command_error("git am", &output))
+        }
+    }
+
+    fn current_am_conflict(&self) -> AppResult<PatchConflict> {
+        let dir = self.rebase_apply_dir();
+        let patch_number = Self::read_counter(&dir.join("next")).unwrap_or(1);
+        let total = Self::read_counter(&dir.join("last")).unwrap_or(patch_number);
+        let subject = std::fs::read_to_string(dir.join("final-commit"))
+            .ok()
+            .and_then(|message| message.lines().next().map(|line| line.to_string()))
+            .unwrap_or_default();
+
+        let conflict = PatchConflict {
+            patch_number,
+            total,
+            subject,
+            conflicted_files: self.conflicted_files()?,
+        };
+        warn!(
+            "Patch {}/{} stopped with conflicts: {}",
+            conflict.patch_number, conflict.total, conflict.subject
+        );
+        Ok(conflict)
+    }
+
+    fn conflicted_files(&self) -> AppResult<Vec<String>> {
+        let output = self.git_output(&[
+            "diff".to_string(),
+            "--name-only".to_string(),
+            "--diff-filter=U".to_string(),
+        ])?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    fn has_parent(&self, rev: &str) -> AppResult<bool> {
+        let output = self.git_output(&[
+            "rev-parse".to_string(),
+            "--verify".to_string(),
+            "--quiet".to_string(),
+            format!("{}^", rev),
+        ])?;
+        Ok(output.status.success())
+    }
+
+    fn rebase_apply_dir(&self) -> PathBuf {
+        self.git_dir().join("rebase-apply")
+    }
+
+    fn git_dir(&self) -> PathBuf {
+        git2::Repository::open(self.repo_path)
+            .map(|repo| repo.path().to_path_buf())
+            .unwrap_or_else(|_| self.repo_path.join(".git"))
+    }
+
+    fn read_counter(path: &Path) -> Option<usize> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    fn run_git(&self, args: &[String]) -> AppResult<Output> {
+        let output = self.git_output(args)?;
+        if output.status.success() {
+            Ok(output)
+        } else {
+            Err(Self::command_error(&format!("git {}", args[0]), &output))
+        }
+    }
+
+    fn git_output(&self, args: &[String]) -> AppResult<Output> {
+        debug!("Running git {:?}", args);
+        Command::new("git")
+            .args(args)
+            .current_dir(self.repo_path)
+            .output()
+            .map_err(AppError::Io)
+    }
+
+    fn command_error(command: &str, output: &Output) -> AppError {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        AppError::Application {
+            message: format!("{} failed: {}", command, stderr.trim()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git_with_env;
+    use tempfile::TempDir;
+
+    /// Commits as `Patch Author` at a fixed author date
+    fn git(dir: &Path, args: &[&str]) {
+        git_with_env(dir, "Patch Author", args, &[("GIT_AUTHOR_DATE", "2024-01-02T03:04:05Z")]);
+    }
+
+    fn init_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q", "-b", "main"]);
+        git(dir.path(), &["config", "user.name", "Committer"]);
+        git(dir.path(), &["config", "user.email", "committer@example.com"]);
+        std::fs::write(dir.path().join("file.txt"), "one\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        dir
+    }
+
+    #[test]
+    fn test_detect_patch_format() {
+        let mbox = "From 1234567890abcdef Mon Sep 17 00:00:00 2001\nFrom: A <a@b.c>\nSubject: [PATCH] x\n";
+        assert_eq!(detect_patch_format(mbox), PatchFormat::Mailbox);
+
+        let diff = "diff --git a/x b/x\n--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n";
+        assert_eq!(detect_patch_format(diff), PatchFormat::PlainDiff);
+        assert_eq!(count_mailbox_patches(mbox), 1);
+    }
+
+    #[test]
+    fn test_export_and_apply_keeps_author() {
+        let source = init_repo();
+        std::fs::write(source.path().join("file.txt"), "one\ntwo\n").unwrap();
+        git(source.path(), &["commit", "-q", "-am", "add two"]);
+
+        let manager = PatchManager::new(source.path());
+        let options = PatchExportOptions {
+            output_dir: source.path().join("out"),
+            cover_letter: true,
+            ..Default::default()
+        };
+        let files = manager.export("HEAD", "HEAD", &options).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files[0].to_string_lossy().contains("cover-letter"));
+
+        let target = init_repo();
+        let target_manager = PatchManager::new(target.path());
+        let outcome = target_manager.apply(&files[1], &PatchApplyOptions::default()).unwrap();
+        assert_eq!(outcome, PatchApplyOutcome::Applied { count: 1 });
+
+        let repo = git2::Repository::open(target.path()).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.author().name(), Some("Patch Author"));
+        assert_eq!(head.author().when().seconds(), 1704164645);
+        assert_eq!(head.summary(), Some("add two"));
+    }
+
+    #[test]
+    fn test_plain_diff_conflict_is_resolved_or_reset() {
+        let repo = init_repo();
+        let diff_path = repo.path().join("change.diff");
+        std::fs::write(repo.path().join("file.txt"), "one\nfrom diff\n").unwrap();
+        let diff = Command::new("git").args(["diff"]).current_dir(repo.path()).output().unwrap();
+        std::fs::write(&diff_path, &diff.stdout).unwrap();
+        std::fs::write(repo.path().join("file.txt"), "one\nlocal\n").unwrap();
+        git(repo.path(), &["commit", "-q", "-am", "local change"]);
+
+        let manager = PatchManager::new(repo.path());
+        let conflicted_files = vec!["file.txt".to_string()];
+        let outcome = manager.apply(&diff_path, &PatchApplyOptions::default()).unwrap();
+        assert_eq!(outcome, PatchApplyOutcome::DiffConflict { conflicted_files: conflicted_files.clone() });
+        assert!(!manager.am_in_progress());
+
+        // Still unmerged until the resolution is staged
+        let outcome = manager.continue_diff_apply(&conflicted_files).unwrap();
+        assert!(matches!(outcome, PatchApplyOutcome::DiffConflict { .. }));
+
+        manager.abort_diff_apply(&conflicted_files).unwrap();
+        assert_eq!(std::fs::read_to_string(repo.path().join("file.txt")).unwrap(), "one\nlocal\n");
+        let outcome = manager.continue_diff_apply(&conflicted_files).unwrap();
+        assert_eq!(outcome, PatchApplyOutcome::Applied { count: 1 });
+    }
+}
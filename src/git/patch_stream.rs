@@ -0,0 +1,562 @@
+//! Streamed patches for files too large to diff in memory
+//!
+//! `git diff` for one file runs as a child process. Its output is copied to
+//! a spool file while a background thread indexes the hunk headers, so a
+//! viewer can show the first hunks while git is still writing the rest and
+//! page through a patch of any size holding only the index in memory. Hunk
+//! bodies are read back from the spool on demand.
+//!
+//! The spool is created exclusively in the private directory under `.git/`
+//! that textconv inputs use. git runs with `--no-textconv`: converters only
+//! run through [`TextconvRunner`](super::diff_driver::TextconvRunner), which
+//! enforces the configured timeout. Bodies that are not UTF-8 are decoded
+//! with the encoding [`content`](super::content) would pick for the file.
+
+use encoding_rs::{Encoding, UTF_8};
+use git2::Repository;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
+
+use super::{
+    content,
+    diff_driver::create_private_file,
+    revision_diff::{DiffSide, DiffSpec, RevisionFileChange},
+};
+use crate::error::{AppError, AppResult};
+
+/// `git hash-object -t tree /dev/null`
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+/// Indexed hunks are published to readers at least this often
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(50);
+/// ...or once this many hunks are pending
+const PUBLISH_BATCH: usize = 256;
+
+/// One file of a revision diff to stream, with extra `git diff` options
+#[derive(Debug, Clone)]
+pub struct PatchSource {
+    repo_path: PathBuf,
+    spec: DiffSpec,
+    change: RevisionFileChange,
+    options: Vec<String>,
+}
+
+impl PatchSource {
+    pub fn new(repo_path: PathBuf, spec: DiffSpec, change: RevisionFileChange, options: Vec<String>) -> Self {
+        Self {
+            repo_path,
+            spec,
+            change,
+            options,
+        }
+    }
+
+    pub fn change(&self) -> &RevisionFileChange {
+        &self.change
+    }
+
+    /// Start `git diff` with `context` lines of context; hunks arrive in the background
+    pub fn spawn(&self, context: usize) -> AppResult<PatchStream> {
+        let repo = Repository::open(&self.repo_path)?;
+        let args = diff_args(&repo, &self.spec, &self.change, &self.options, context)?;
+        PatchStream::spawn(&repo, &args)
+    }
+}
+
+/// Position of one hunk in the spool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkEntry {
+    /// `@@ -a,b +c,d @@` plus git's funcname context
+    pub header: String,
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    /// Body lines before this hunk, over all earlier hunks
+    pub first_row: usize,
+    /// Body lines (` `, `+` and `-`), without `\ No newline` markers
+    pub rows: usize,
+    /// Byte range of the body in the spool
+    pub offset: u64,
+    pub len: u64,
+}
+
+/// Hunks indexed so far
+#[derive(Debug, Clone, Default)]
+pub struct PatchIndex {
+    pub hunks: Vec<HunkEntry>,
+    pub rows: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// git reported `Binary files ... differ`
+    pub binary: bool,
+    /// Bytes of patch output read from git
+    pub bytes: u64,
+    /// git has exited and every hunk is indexed
+    pub complete: bool,
+    pub error: Option<String>,
+}
+
+impl PatchIndex {
+    /// Hunk containing body row `row`
+    pub fn hunk_at_row(&self, row: usize) -> Option<usize> {
+        if row >= self.rows {
+            return None;
+        }
+        Some(self.hunks.partition_point(|hunk| hunk.first_row + hunk.rows <= row))
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    index: RwLock<PatchIndex>,
+    cancelled: AtomicBool,
+}
+
+/// A running or finished `git diff` of one file
+#[derive(Debug)]
+pub struct PatchStream {
+    shared: Arc<Shared>,
+    spool: PathBuf,
+    reader: Mutex<Option<File>>,
+    /// Encoding of the patch text, chosen at the first body that is not UTF-8
+    encoding: Mutex<Option<&'static Encoding>>,
+    child: Arc<Mutex<Option<Child>>>,
+}
+
+impl PatchStream {
+    /// Run `git <args>` in `repo`'s working directory and index its patch output in the background
+    pub fn spawn(repo: &Repository, args: &[String]) -> AppResult<Self> {
+        let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+        let (spool, file) = create_private_file(repo.path(), "diff", ".patch")?;
+        let writer = BufWriter::with_capacity(1 << 16, file);
+
+        debug!("Streaming git {:?}", args);
+        let mut child = Command::new("git")
+            .args(args)
+            .current_dir(workdir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .inspect_err(|_| {
+                let _ = std::fs::remove_file(&spool);
+            })?;
+        let stdout = child.stdout.take().ok_or_else(|| AppError::application("git diff has no output pipe"))?;
+        let stderr = child.stderr.take();
+
+        let shared = Arc::new(Shared {
+            index: RwLock::new(PatchIndex::default()),
+            cancelled: AtomicBool::new(false),
+        });
+        let child = Arc::new(Mutex::new(Some(child)));
+        // `--no-index` exits with 1 when the files differ
+        let allow_exit_one = args.iter().any(|arg| arg == "--no-index");
+
+        let thread_shared = shared.clone();
+        let thread_child = child.clone();
+        std::thread::spawn(move || {
+            let stderr = std::thread::spawn(move || {
+                let mut buffer = Vec::new();
+                if let Some(mut pipe) = stderr {
+                    let _ = pipe.read_to_end(&mut buffer);
+                }
+                buffer
+            });
+            let result = index_patch(BufReader::with_capacity(1 << 16, stdout), writer, &thread_shared);
+
+            let status = thread_child.lock().ok().and_then(|mut child| child.take()).map(|mut child| child.wait());
+            let stderr = stderr.join().unwrap_or_default();
+            let error = match (result, status) {
+                (Err(e), _) => Some(e.to_string()),
+                (Ok(()), Some(Ok(status))) if status.success() || (allow_exit_one && status.code() == Some(1)) => None,
+                (Ok(()), Some(Ok(_))) => Some(format!("git diff failed: {}", String::from_utf8_lossy(&stderr).trim())),
+                (Ok(()), Some(Err(e))) => Some(e.to_string()),
+                // Killed by `Drop`
+                (Ok(()), None) => None,
+            };
+            if let Some(error) = &error {
+                warn!("Streaming diff stopped: {}", error);
+            }
+            if let Ok(mut index) = thread_shared.index.write() {
+                index.error = error;
+                index.complete = true;
+            }
+        });
+
+        Ok(Self {
+            shared,
+            spool,
+            reader: Mutex::new(None),
+            encoding: Mutex::new(None),
+            child,
+        })
+    }
+
+    /// Run `f` on the hunks indexed so far
+    pub fn with_index<R>(&self, f: impl FnOnce(&PatchIndex) -> R) -> R {
+        match self.shared.index.read() {
+            Ok(index) => f(&index),
+            Err(poisoned) => f(&poisoned.into_inner()),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.with_index(|index| index.complete)
+    }
+
+    /// Block until git has finished or `timeout` passes; returns whether it finished
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while !self.is_complete() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        true
+    }
+
+    /// Body of an indexed hunk, one diff line per text line
+    pub fn read_body(&self, entry: &HunkEntry) -> AppResult<String> {
+        let mut reader = self
+            .reader
+            .lock()
+            .map_err(|_| AppError::InvalidState("Patch spool reader poisoned".to_string()))?;
+        if reader.is_none() {
+            *reader = Some(File::open(&self.spool)?);
+        }
+        let file = reader.as_mut().expect("spool reader opened above");
+
+        let mut buffer = vec![0; entry.len as usize];
+        file.seek(SeekFrom::Start(entry.offset))?;
+        file.read_exact(&mut buffer)?;
+        drop(reader);
+
+        let buffer = match String::from_utf8(buffer) {
+            Ok(text) => return Ok(text),
+            Err(e) => e.into_bytes(),
+        };
+        let mut encoding = self
+            .encoding
+            .lock()
+            .map_err(|_| AppError::InvalidState("Patch encoding poisoned".to_string()))?;
+        let encoding = *encoding.get_or_insert_with(|| content::legacy_encoding(&buffer));
+        Ok(encoding.decode_without_bom_handling(&buffer).0.into_owned())
+    }
+
+    /// Encoding used for bodies that are not UTF-8, once one has been read
+    pub fn encoding(&self) -> &'static str {
+        self.encoding.lock().ok().and_then(|encoding| *encoding).unwrap_or(UTF_8).name()
+    }
+}
+
+impl Drop for PatchStream {
+    fn drop(&mut self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+        if let Some(mut child) = self.child.lock().ok().and_then(|mut child| child.take()) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        let _ = std::fs::remove_file(&self.spool);
+    }
+}
+
+/// Copy patch output to the spool, indexing hunks as they go by
+fn index_patch(mut input: impl BufRead, mut spool: BufWriter<File>, shared: &Shared) -> AppResult<()> {
+    let mut line = Vec::with_capacity(256);
+    let mut offset = 0u64;
+    let mut pending: Vec<HunkEntry> = Vec::new();
+    let mut current: Option<HunkEntry> = None;
+    let (mut rows, mut additions, mut deletions, mut binary) = (0, 0, 0, false);
+    let mut published = Instant::now();
+
+    let publish = |spool: &mut BufWriter<File>,
+                   pending: &mut Vec<HunkEntry>,
+                   totals: (usize, usize, usize, bool, u64)|
+     -> AppResult<()> {
+        // Readers may only see hunks whose bytes are in the spool
+        spool.flush()?;
+        if let Ok(mut index) = shared.index.write() {
+            index.hunks.append(pending);
+            (index.rows, index.additions, index.deletions, index.binary, index.bytes) = totals;
+        }
+        Ok(())
+    };
+
+    loop {
+        line.clear();
+        let read = input.read_until(b'\n', &mut line)?;
+        if read == 0 || shared.cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        spool.write_all(&line)?;
+        offset += read as u64;
+
+        match line.first() {
+            Some(b'@') if line.starts_with(b"@@ ") => {
+                if let Some(hunk) = current.take() {
+                    pending.push(hunk);
+                }
+                let header = String::from_utf8_lossy(&line).trim_end().to_string();
+                if let Some(mut hunk) = parse_header(&header) {
+                    hunk.first_row = rows;
+                    hunk.offset = offset;
+                    current = Some(hunk);
+                }
+            }
+            Some(prefix @ (b' ' | b'+' | b'-')) if current.is_some() => {
+                let hunk = current.as_mut().expect("checked above");
+                hunk.rows += 1;
+                hunk.len = offset - hunk.offset;
+                rows += 1;
+                match prefix {
+                    b'+' => additions += 1,
+                    b'-' => deletions += 1,
+                    _ => {}
+                }
+            }
+            Some(b'\\') if current.is_some() => {
+                // `\ No newline at end of file` belongs to the body but is not a row
+                let hunk = current.as_mut().expect("checked above");
+                hunk.len = offset - hunk.offset;
+            }
+            _ => {
+                // File headers of a following file (e.g. a rename git did not detect)
+                if let Some(hunk) = current.take() {
+                    pending.push(hunk);
+                }
+                binary |= line.starts_with(b"Binary files ") || line.starts_with(b"GIT binary patch");
+            }
+        }
+
+        if !pending.is_empty() && (pending.len() >= PUBLISH_BATCH || published.elapsed() >= PUBLISH_INTERVAL) {
+            publish(&mut spool, &mut pending, (rows, additions, deletions, binary, offset))?;
+            published = Instant::now();
+        }
+    }
+
+    if let Some(hunk) = current.take() {
+        pending.push(hunk);
+    }
+    publish(&mut spool, &mut pending, (rows, additions, deletions, binary, offset))
+}
+
+/// `@@ -a[,b] +c[,d] @@[ context]`
+fn parse_header(header: &str) -> Option<HunkEntry> {
+    let mut parts = header.strip_prefix("@@ ")?.split(' ');
+    let range = |part: Option<&str>, sign: char| -> Option<(usize, usize)> {
+        let part = part?.strip_prefix(sign)?;
+        match part.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((part.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(parts.next(), '-')?;
+    let (new_start, new_lines) = range(parts.next(), '+')?;
+    Some(HunkEntry {
+        header: header.to_string(),
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        first_row: 0,
+        rows: 0,
+        offset: 0,
+        len: 0,
+    })
+}
+
+/// Revision argument for a tree side; `None` for the index and the working tree
+fn side_arg(repo: &Repository, side: &DiffSide) -> AppResult<Option<String>> {
+    Ok(match side {
+        DiffSide::Revision(rev) => Some(rev.clone()),
+        DiffSide::Parent(rev) => {
+            let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+            Some(match commit.parents().next() {
+                Some(parent) => parent.id().to_string(),
+                None => EMPTY_TREE.to_string(),
+            })
+        }
+        DiffSide::MergeBase(a, b) => {
+            let a = repo.revparse_single(a)?.peel_to_commit()?.id();
+            let b = repo.revparse_single(b)?.peel_to_commit()?.id();
+            Some(repo.merge_base(a, b)?.to_string())
+        }
+        DiffSide::Index | DiffSide::WorkTree => None,
+    })
+}
+
+/// `git diff` arguments for one changed file of `spec`
+///
+/// `options` are extra diff options (algorithm, whitespace handling) and
+/// `context` the number of context lines.
+pub fn diff_args(
+    repo: &Repository,
+    spec: &DiffSpec,
+    change: &RevisionFileChange,
+    options: &[String],
+    context: usize,
+) -> AppResult<Vec<String>> {
+    let mut args: Vec<String> = ["--literal-pathspecs", "diff", "--no-color", "--no-ext-diff", "--no-textconv"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    args.push(format!("-U{}", context.min(i32::MAX as usize)));
+    args.push(if spec.detect_renames {
+        format!("-M{}%", spec.rename_threshold)
+    } else {
+        "--no-renames".to_string()
+    });
+    args.extend(options.iter().cloned());
+
+    // Same ordering rule as libgit2: tree, then index, then working tree
+    let rank = |side: &DiffSide| match side {
+        DiffSide::Index => 1,
+        DiffSide::WorkTree => 2,
+        _ => 0,
+    };
+    let (old, new) = if rank(&spec.old) > rank(&spec.new) {
+        args.push("-R".to_string());
+        (&spec.new, &spec.old)
+    } else {
+        (&spec.old, &spec.new)
+    };
+    let (old_path, new_path) = if rank(&spec.old) > rank(&spec.new) {
+        (change.new_path.as_deref(), change.old_path.as_deref())
+    } else {
+        (change.old_path.as_deref(), change.new_path.as_deref())
+    };
+
+    // Untracked files are not in any tree or the index; git diffs them against nothing
+    if *new == DiffSide::WorkTree && old_path.is_none() {
+        let path = new_path.ok_or_else(|| AppError::InvalidOperation("Change has no path".to_string()))?;
+        if repo.index()?.get_path(Path::new(path), 0).is_none() {
+            args.extend(["--no-index".to_string(), "--".to_string(), "/dev/null".to_string(), path.to_string()]);
+            return Ok(args);
+        }
+    }
+
+    match (old, new) {
+        (DiffSide::Index, DiffSide::WorkTree) => {}
+        (old, DiffSide::Index) => {
+            args.push("--cached".to_string());
+            args.extend(side_arg(repo, old)?);
+        }
+        (old, DiffSide::WorkTree) => args.extend(side_arg(repo, old)?),
+        (DiffSide::Index, _) | (DiffSide::WorkTree, _) => {
+            return Err(AppError::InvalidOperation(format!("Cannot stream {}", spec.label())));
+        }
+        (old, new) => {
+            args.extend(side_arg(repo, old)?);
+            args.extend(side_arg(repo, new)?);
+        }
+    }
+
+    args.push("--".to_string());
+    for path in [old_path, new_path].into_iter().flatten() {
+        if args.last().is_none_or(|last| last != path) {
+            args.push(path.to_string());
+        }
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use crate::git::revision_diff::diff_revisions;
+
+    #[test]
+    fn test_parse_header() {
+        let hunk = parse_header("@@ -10,4 +12 @@ fn main() {").unwrap();
+        assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (10, 4, 12, 1));
+        assert!(parse_header("@@ garbage @@").is_none());
+    }
+
+    #[test]
+    fn test_stream_indexes_hunks_and_reads_bodies() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let lines: Vec<String> = (0..5000).map(|i| format!("line {}", i)).collect();
+        std::fs::write(dir.path().join("big.txt"), lines.join("\n") + "\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "base"]);
+
+        let mut changed = lines.clone();
+        for i in (100..5000).step_by(1000) {
+            changed[i] = format!("changed {}", i);
+        }
+        changed.push("tail".to_string());
+        std::fs::write(dir.path().join("big.txt"), changed.join("\n")).unwrap();
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let spec = DiffSpec::unstaged();
+        let change = diff_revisions(&repo, &spec).unwrap().files.remove(0);
+        let args = diff_args(&repo, &spec, &change, &[], 1).unwrap();
+        let stream = PatchStream::spawn(&repo, &args).unwrap();
+        assert!(stream.wait(Duration::from_secs(10)));
+
+        stream.with_index(|index| {
+            assert_eq!(index.error, None);
+            assert_eq!(index.hunks.len(), 6);
+            assert_eq!((index.additions, index.deletions), (6, 5));
+            assert_eq!(index.hunks[1].first_row, index.hunks[0].rows);
+            assert_eq!(index.hunk_at_row(0), Some(0));
+            assert_eq!(index.hunk_at_row(index.hunks[0].rows), Some(1));
+            assert_eq!(index.hunk_at_row(index.rows), None);
+        });
+        let last = stream.with_index(|index| index.hunks[5].clone());
+        let body = stream.read_body(&last).unwrap();
+        assert_eq!(body.lines().filter(|line| !line.starts_with('\\')).count(), last.rows);
+        assert!(body.contains("+tail"));
+
+        // Untracked files are diffed against /dev/null
+        std::fs::write(dir.path().join("new.txt"), "a\nb\n").unwrap();
+        let diff = diff_revisions(&repo, &spec).unwrap();
+        let change = diff.files.iter().find(|file| file.path() == "new.txt").unwrap();
+        let stream = PatchStream::spawn(&repo, &diff_args(&repo, &spec, change, &[], 3).unwrap()).unwrap();
+        assert!(stream.wait(Duration::from_secs(10)));
+        stream.with_index(|index| {
+            assert_eq!(index.error, None);
+            assert_eq!(index.additions, 2);
+        });
+    }
+
+    #[test]
+    fn test_stream_skips_textconv_and_decodes_legacy_text() {
+        let dir = tempfile::tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "diff.slow.textconv", "sleep 30; cat"]);
+        std::fs::write(dir.path().join(".gitattributes"), "*.txt diff=slow\n").unwrap();
+        std::fs::write(dir.path().join("latin.txt"), b"caf\xe9\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "base"]);
+        std::fs::write(dir.path().join("latin.txt"), b"caf\xe9 cr\xe8me\n").unwrap();
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let spec = DiffSpec::unstaged();
+        let change = diff_revisions(&repo, &spec).unwrap().files.remove(0);
+        let stream = PatchStream::spawn(&repo, &diff_args(&repo, &spec, &change, &[], 3).unwrap()).unwrap();
+        assert!(stream.wait(Duration::from_secs(10)));
+        assert_eq!(stream.spool.parent(), Some(repo.path().join("ai-c-tmp").as_path()));
+
+        let entry = stream.with_index(|index| index.hunks[0].clone());
+        assert_eq!(stream.read_body(&entry).unwrap(), "-café\n+café crème\n");
+        assert_eq!(stream.encoding(), "windows-1252");
+
+        let spool = stream.spool.clone();
+        drop(stream);
+        assert!(!spool.exists());
+    }
+}
@@ -0,0 +1,614 @@
+//! Diffs between arbitrary revisions
+//!
+//! A [`DiffSpec`] names two sides — a revision, the first parent of a
+//! commit, the merge base of two revisions, the index or the working tree —
+//! plus optional pathspecs. [`diff_revisions`] picks the matching libgit2
+//! diff (tree/tree, tree/index, index/workdir, tree/workdir), detects
+//! renames and returns per-file line counts; [`file_sides`] then reads and
+//! classifies both sides of one file for the diff viewer. [`file_patches`]
+//! prints the same diff as `git apply`-able patches for export.
+
+use git2::{Delta, Diff, DiffFindOptions, DiffOptions, Oid, Patch, Repository, Tree};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::debug;
+
+use super::content::BlobContent;
+use super::diff_driver::{self, TextconvRunner};
+use crate::error::{AppError, AppResult};
+
+/// Default similarity (percent) for rename detection, as in `git diff -M`
+pub const DEFAULT_RENAME_THRESHOLD: u16 = 50;
+/// Files above this size get no line counts in the file list; diffing them
+/// in memory just to count lines would cost more than the list is worth
+pub const LINE_STATS_MAX_SIZE: u64 = 8 * 1024 * 1024;
+
+/// One side of a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffSide {
+    /// Any revision git can resolve: hash, branch, tag, `stash@{0}`, ...
+    Revision(String),
+    /// First parent of a commit; the empty tree for a root commit
+    Parent(String),
+    /// Merge base of two revisions (`a...b`)
+    MergeBase(String, String),
+    Index,
+    WorkTree,
+}
+
+impl DiffSide {
+    pub fn label(&self) -> String {
+        match self {
+            DiffSide::Revision(rev) => short_rev(rev),
+            DiffSide::Parent(rev) => format!("{}^", short_rev(rev)),
+            DiffSide::MergeBase(a, b) => format!("merge-base({}, {})", short_rev(a), short_rev(b)),
+            DiffSide::Index => "index".to_string(),
+            DiffSide::WorkTree => "working tree".to_string(),
+        }
+    }
+}
+
+/// Full hashes are shortened, symbolic names kept
+fn short_rev(rev: &str) -> String {
+    if rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit()) {
+        rev[..8].to_string()
+    } else {
+        rev.to_string()
+    }
+}
+
+/// What to compare
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSpec {
+    pub old: DiffSide,
+    pub new: DiffSide,
+    /// Pathspecs; empty means the whole tree
+    pub paths: Vec<String>,
+    pub detect_renames: bool,
+    pub rename_threshold: u16,
+}
+
+impl DiffSpec {
+    pub fn new(old: DiffSide, new: DiffSide) -> Self {
+        Self {
+            old,
+            new,
+            paths: Vec::new(),
+            detect_renames: true,
+            rename_threshold: DEFAULT_RENAME_THRESHOLD,
+        }
+    }
+
+    /// Changes introduced by one commit (or stash entry)
+    pub fn commit(rev: impl Into<String>) -> Self {
+        let rev = rev.into();
+        Self::new(DiffSide::Parent(rev.clone()), DiffSide::Revision(rev))
+    }
+
+    /// `git diff a b`
+    pub fn range(old: impl Into<String>, new: impl Into<String>) -> Self {
+        Self::new(DiffSide::Revision(old.into()), DiffSide::Revision(new.into()))
+    }
+
+    /// `git diff base...other`: what `other` changed since it forked from `base`
+    pub fn branch_comparison(base: impl Into<String>, other: impl Into<String>) -> Self {
+        let other = other.into();
+        Self::new(DiffSide::MergeBase(base.into(), other.clone()), DiffSide::Revision(other))
+    }
+
+    /// `git diff --cached`
+    pub fn staged() -> Self {
+        Self::new(DiffSide::Revision("HEAD".to_string()), DiffSide::Index)
+    }
+
+    /// `git diff`
+    pub fn unstaged() -> Self {
+        Self::new(DiffSide::Index, DiffSide::WorkTree)
+    }
+
+    /// `git stash show -p stash@{n}`
+    pub fn stash(index: usize) -> Self {
+        Self::commit(format!("stash@{{{}}}", index))
+    }
+
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    pub fn without_renames(mut self) -> Self {
+        self.detect_renames = false;
+        self
+    }
+
+    /// `HEAD..index`, `abc123^..abc123`, ...
+    pub fn label(&self) -> String {
+        format!("{}..{}", self.old.label(), self.new.label())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+    TypeChanged,
+}
+
+impl ChangeStatus {
+    fn from_delta(delta: Delta) -> Option<Self> {
+        match delta {
+            Delta::Added | Delta::Untracked => Some(ChangeStatus::Added),
+            Delta::Deleted => Some(ChangeStatus::Deleted),
+            Delta::Modified => Some(ChangeStatus::Modified),
+            Delta::Renamed => Some(ChangeStatus::Renamed),
+            Delta::Copied => Some(ChangeStatus::Copied),
+            Delta::Typechange => Some(ChangeStatus::TypeChanged),
+            Delta::Unmodified | Delta::Ignored | Delta::Unreadable | Delta::Conflicted => None,
+        }
+    }
+
+    /// One-letter code as in `git diff --name-status`
+    pub fn code(&self) -> char {
+        match self {
+            ChangeStatus::Added => 'A',
+            ChangeStatus::Deleted => 'D',
+            ChangeStatus::Modified => 'M',
+            ChangeStatus::Renamed => 'R',
+            ChangeStatus::Copied => 'C',
+            ChangeStatus::TypeChanged => 'T',
+        }
+    }
+}
+
+/// One changed file between the two sides
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevisionFileChange {
+    /// `None` for added files
+    pub old_path: Option<String>,
+    /// `None` for deleted files
+    pub new_path: Option<String>,
+    pub status: ChangeStatus,
+    pub is_binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+impl RevisionFileChange {
+    pub fn display_path(&self) -> String {
+        match (&self.old_path, &self.new_path) {
+            (Some(old), Some(new)) if old != new => format!("{} → {}", old, new),
+            (_, Some(path)) | (Some(path), None) => path.clone(),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// Path on the new side, or the old one for deletions
+    pub fn path(&self) -> &str {
+        self.new_path.as_deref().or(self.old_path.as_deref()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub spec: DiffSpec,
+    pub files: Vec<RevisionFileChange>,
+}
+
+impl RevisionDiff {
+    pub fn additions(&self) -> usize {
+        self.files.iter().map(|file| file.additions).sum()
+    }
+
+    pub fn deletions(&self) -> usize {
+        self.files.iter().map(|file| file.deletions).sum()
+    }
+}
+
+/// Tree of a revision side; `None` is the empty tree
+fn side_tree<'r>(repo: &'r Repository, side: &DiffSide) -> AppResult<Option<Tree<'r>>> {
+    match side {
+        DiffSide::Revision(rev) => Ok(Some(repo.revparse_single(rev)?.peel_to_tree()?)),
+        DiffSide::Parent(rev) => {
+            let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+            match commit.parents().next() {
+                Some(parent) => Ok(Some(parent.tree()?)),
+                None => Ok(None),
+            }
+        }
+        DiffSide::MergeBase(a, b) => {
+            let a = repo.revparse_single(a)?.peel_to_commit()?.id();
+            let b = repo.revparse_single(b)?.peel_to_commit()?.id();
+            let base = repo.merge_base(a, b)?;
+            Ok(Some(repo.find_commit(base)?.tree()?))
+        }
+        DiffSide::Index | DiffSide::WorkTree => Ok(None),
+    }
+}
+
+fn build_diff<'r>(repo: &'r Repository, spec: &DiffSpec) -> AppResult<Diff<'r>> {
+    build_diff_with(repo, spec, DiffOptions::new())
+}
+
+fn build_diff_with<'r>(repo: &'r Repository, spec: &DiffSpec, mut options: DiffOptions) -> AppResult<Diff<'r>> {
+    for path in &spec.paths {
+        options.pathspec(path);
+    }
+    // The new side of a worktree diff should list new files too
+    if spec.old == DiffSide::WorkTree || spec.new == DiffSide::WorkTree {
+        options.include_untracked(true).recurse_untracked_dirs(true).show_untracked_content(true);
+    }
+
+    // libgit2 only diffs "older" → "newer" in the order tree, index,
+    // worktree; other orders are computed swapped and reversed
+    let rank = |side: &DiffSide| match side {
+        DiffSide::Index => 1,
+        DiffSide::WorkTree => 2,
+        _ => 0,
+    };
+    let (old, new) = if rank(&spec.old) > rank(&spec.new) {
+        options.reverse(true);
+        (&spec.new, &spec.old)
+    } else {
+        (&spec.old, &spec.new)
+    };
+
+    let diff = match (old, new) {
+        (DiffSide::Index, DiffSide::Index) | (DiffSide::WorkTree, DiffSide::WorkTree) => {
+            repo.diff_tree_to_tree(None, None, Some(&mut options))?
+        }
+        (DiffSide::Index, DiffSide::WorkTree) => repo.diff_index_to_workdir(None, Some(&mut options))?,
+        (old, DiffSide::Index) => {
+            let tree = side_tree(repo, old)?;
+            repo.diff_tree_to_index(tree.as_ref(), None, Some(&mut options))?
+        }
+        (old, DiffSide::WorkTree) => {
+            let tree = side_tree(repo, old)?;
+            repo.diff_tree_to_workdir_with_index(tree.as_ref(), Some(&mut options))?
+        }
+        (old, new) => {
+            let old_tree = side_tree(repo, old)?;
+            let new_tree = side_tree(repo, new)?;
+            repo.diff_tree_to_tree(old_tree.as_ref(), new_tree.as_ref(), Some(&mut options))?
+        }
+    };
+    Ok(diff)
+}
+
+fn find_renames(diff: &mut Diff<'_>, spec: &DiffSpec) -> AppResult<()> {
+    if spec.detect_renames {
+        let mut find = DiffFindOptions::new();
+        find.renames(true)
+            .renames_from_rewrites(true)
+            .for_untracked(true)
+            .rename_threshold(spec.rename_threshold);
+        diff.find_similar(Some(&mut find))?;
+    }
+    Ok(())
+}
+
+/// Changed files between the two sides of `spec`
+pub fn diff_revisions(repo: &Repository, spec: &DiffSpec) -> AppResult<RevisionDiff> {
+    let mut diff = build_diff(repo, spec)?;
+    find_renames(&mut diff, spec)?;
+
+    let mut files = Vec::new();
+    for index in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(index) else {
+            continue;
+        };
+        let Some(status) = ChangeStatus::from_delta(delta.status()) else {
+            continue;
+        };
+        let path_of = |file: git2::DiffFile<'_>| file.path().map(|path| path.to_string_lossy().to_string());
+        let old_path = (status != ChangeStatus::Added).then(|| path_of(delta.old_file())).flatten();
+        let new_path = (status != ChangeStatus::Deleted).then(|| path_of(delta.new_file())).flatten();
+
+        let (mut additions, mut deletions, mut is_binary) = (0, 0, delta.flags().is_binary());
+        let too_large = [delta.old_file(), delta.new_file()]
+            .iter()
+            .any(|file| diff_file_size(repo, file) > LINE_STATS_MAX_SIZE);
+        if too_large {
+            debug!("Skipping line counts for {:?}: over {} bytes", new_path.as_ref().or(old_path.as_ref()), LINE_STATS_MAX_SIZE);
+        } else if let Some(patch) = Patch::from_diff(&diff, index)? {
+            let (_, added, deleted) = patch.line_stats()?;
+            additions = added;
+            deletions = deleted;
+            is_binary |= patch.delta().flags().is_binary();
+        }
+
+        files.push(RevisionFileChange {
+            old_path,
+            new_path,
+            status,
+            is_binary,
+            additions,
+            deletions,
+        });
+    }
+
+    Ok(RevisionDiff {
+        spec: spec.clone(),
+        files,
+    })
+}
+
+/// Patch text of one changed file, exactly as libgit2 prints it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    /// Raw bytes: file modes, CRLF, `\ No newline at end of file` and
+    /// binary literals are kept so the result applies with `git apply`
+    pub text: Vec<u8>,
+}
+
+/// `git diff --binary` patches of the files of `spec`, in [`diff_revisions`] order
+pub fn file_patches(repo: &Repository, spec: &DiffSpec) -> AppResult<Vec<FilePatch>> {
+    let mut options = DiffOptions::new();
+    options.show_binary(true);
+    let mut diff = build_diff_with(repo, spec, options)?;
+    find_renames(&mut diff, spec)?;
+
+    let mut patches = Vec::new();
+    for index in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(index) else {
+            continue;
+        };
+        let Some(status) = ChangeStatus::from_delta(delta.status()) else {
+            continue;
+        };
+        let path_of = |file: git2::DiffFile<'_>| file.path().map(|path| path.to_string_lossy().to_string());
+        let old_path = (status != ChangeStatus::Added).then(|| path_of(delta.old_file())).flatten();
+        let new_path = (status != ChangeStatus::Deleted).then(|| path_of(delta.new_file())).flatten();
+        let text = match Patch::from_diff(&diff, index)? {
+            Some(mut patch) => patch.to_buf()?.to_vec(),
+            None => Vec::new(),
+        };
+        patches.push(FilePatch { old_path, new_path, text });
+    }
+    Ok(patches)
+}
+
+/// Size of one side of a delta; blob sizes libgit2 left unset are read from the object header
+fn diff_file_size(repo: &Repository, file: &git2::DiffFile<'_>) -> u64 {
+    if file.size() > 0 || file.id().is_zero() {
+        return file.size();
+    }
+    repo.odb()
+        .and_then(|odb| odb.read_header(file.id()))
+        .map_or(0, |(size, _)| size as u64)
+}
+
+/// Size in bytes of `path` on one side, without reading its content
+fn side_size(repo: &Repository, side: &DiffSide, path: &str) -> AppResult<u64> {
+    let blob_size = |id: Oid| -> AppResult<u64> { Ok(repo.odb()?.read_header(id)?.0 as u64) };
+
+    match side {
+        DiffSide::WorkTree => {
+            let workdir = repo
+                .workdir()
+                .ok_or_else(|| AppError::InvalidState("Bare repository has no working tree".to_string()))?;
+            Ok(std::fs::metadata(workdir.join(path)).map_or(0, |metadata| metadata.len()))
+        }
+        DiffSide::Index => match repo.index()?.get_path(Path::new(path), 0) {
+            Some(entry) => blob_size(entry.id),
+            None => Ok(0),
+        },
+        tree_side => match side_tree(repo, tree_side)? {
+            Some(tree) => match tree.get_path(Path::new(path)) {
+                Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => blob_size(entry.id()),
+                _ => Ok(0),
+            },
+            None => Ok(0),
+        },
+    }
+}
+
+/// Size of the larger side of one changed file
+pub fn file_size(repo: &Repository, spec: &DiffSpec, change: &RevisionFileChange) -> AppResult<u64> {
+    let old = match change.old_path.as_deref() {
+        Some(path) => side_size(repo, &spec.old, path)?,
+        None => 0,
+    };
+    let new = match change.new_path.as_deref() {
+        Some(path) => side_size(repo, &spec.new, path)?,
+        None => 0,
+    };
+    Ok(old.max(new))
+}
+
+/// Raw bytes of `path` on one side; `None` when the file does not exist there
+fn side_bytes(repo: &Repository, side: &DiffSide, path: &str) -> AppResult<Option<Vec<u8>>> {
+    let blob_bytes = |id: Oid| -> AppResult<Option<Vec<u8>>> { Ok(Some(repo.find_blob(id)?.content().to_vec())) };
+
+    match side {
+        DiffSide::WorkTree => {
+            let workdir = repo
+                .workdir()
+                .ok_or_else(|| AppError::InvalidState("Bare repository has no working tree".to_string()))?;
+            let full = workdir.join(path);
+            if !full.is_file() {
+                return Ok(None);
+            }
+            Ok(Some(std::fs::read(full)?))
+        }
+        DiffSide::Index => {
+            let index = repo.index()?;
+            match index.get_path(Path::new(path), 0) {
+                Some(entry) => blob_bytes(entry.id),
+                None => Ok(None),
+            }
+        }
+        tree_side => match side_tree(repo, tree_side)? {
+            Some(tree) => match tree.get_path(Path::new(path)) {
+                Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => blob_bytes(entry.id()),
+                _ => Ok(None),
+            },
+            None => Ok(None),
+        },
+    }
+}
+
+/// Classified content of `path` on one side, converted by its textconv driver
+fn side_content(repo: &Repository, side: &DiffSide, path: Option<&str>, textconv: &TextconvRunner) -> AppResult<BlobContent> {
+    let Some(path) = path else { return Ok(BlobContent::missing()) };
+    Ok(match side_bytes(repo, side, path)? {
+        Some(bytes) => diff_driver::load_content(repo, path, &bytes, textconv),
+        None => BlobContent::missing(),
+    })
+}
+
+/// Old and new side of one changed file, classified and decoded
+pub fn file_sides(
+    repo: &Repository,
+    spec: &DiffSpec,
+    change: &RevisionFileChange,
+    textconv: &TextconvRunner,
+) -> AppResult<(BlobContent, BlobContent)> {
+    let old = side_content(repo, &spec.old, change.old_path.as_deref(), textconv)?;
+    let new = side_content(repo, &spec.new, change.new_path.as_deref(), textconv)?;
+    Ok((old, new))
+}
+
+/// Old and new text of one changed file; empty for binary content
+pub fn file_contents(
+    repo: &Repository,
+    spec: &DiffSpec,
+    change: &RevisionFileChange,
+    textconv: &TextconvRunner,
+) -> AppResult<(String, String)> {
+    let (old, new) = file_sides(repo, spec, change, textconv)?;
+    Ok((old.text, new.text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use git2::Signature;
+    use std::fs;
+
+    fn textconv() -> TextconvRunner {
+        TextconvRunner::new(std::time::Duration::from_secs(5))
+    }
+
+    fn commit_all(repo: &Repository, message: &str) -> Oid {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo.head().ok().and_then(|head| head.peel_to_commit().ok()).into_iter().collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap()
+    }
+
+    fn setup() -> (tempfile::TempDir, Repository) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\nfour\nfive\n").unwrap();
+        fs::write(dir.path().join("b.txt"), "bee\n").unwrap();
+        commit_all(&repo, "initial");
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_commit_diff_with_rename_and_root_commit() {
+        let (dir, repo) = setup();
+        let root = repo.head().unwrap().peel_to_commit().unwrap().id().to_string();
+        let root_diff = diff_revisions(&repo, &DiffSpec::commit(&root)).unwrap();
+        assert_eq!(root_diff.files.len(), 2);
+        assert!(root_diff.files.iter().all(|file| file.status == ChangeStatus::Added));
+
+        fs::rename(dir.path().join("a.txt"), dir.path().join("renamed.txt")).unwrap();
+        fs::write(dir.path().join("b.txt"), "bee\nsting\n").unwrap();
+        let head = commit_all(&repo, "rename").to_string();
+
+        let diff = diff_revisions(&repo, &DiffSpec::commit(&head)).unwrap();
+        let renamed = diff.files.iter().find(|file| file.status == ChangeStatus::Renamed).unwrap();
+        assert_eq!(renamed.display_path(), "a.txt → renamed.txt");
+        let modified = diff.files.iter().find(|file| file.path() == "b.txt").unwrap();
+        assert_eq!((modified.additions, modified.deletions), (1, 0));
+
+        let (old, new) = file_contents(&repo, &diff.spec, modified, &textconv()).unwrap();
+        assert_eq!((old.as_str(), new.as_str()), ("bee\n", "bee\nsting\n"));
+
+        let only_b = diff_revisions(&repo, &DiffSpec::range(&root, &head).with_path("b.txt")).unwrap();
+        assert_eq!(only_b.files.len(), 1);
+    }
+
+    #[test]
+    fn test_index_and_worktree_sides() {
+        let (dir, repo) = setup();
+        fs::write(dir.path().join("b.txt"), "staged\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("b.txt")).unwrap();
+        index.write().unwrap();
+        fs::write(dir.path().join("b.txt"), "unstaged\n").unwrap();
+        fs::write(dir.path().join("new.txt"), "new\n").unwrap();
+
+        let staged = diff_revisions(&repo, &DiffSpec::staged()).unwrap();
+        assert_eq!(staged.files.len(), 1);
+        let (old, new) = file_contents(&repo, &staged.spec, &staged.files[0], &textconv()).unwrap();
+        assert_eq!((old.as_str(), new.as_str()), ("bee\n", "staged\n"));
+
+        let unstaged = diff_revisions(&repo, &DiffSpec::unstaged()).unwrap();
+        assert!(unstaged.files.iter().any(|file| file.path() == "new.txt" && file.status == ChangeStatus::Added));
+        let b = unstaged.files.iter().find(|file| file.path() == "b.txt").unwrap();
+        assert_eq!(file_contents(&repo, &unstaged.spec, b, &textconv()).unwrap().1, "unstaged\n");
+
+        // Reversed sides swap additions and deletions
+        let reversed = diff_revisions(&repo, &DiffSpec::new(DiffSide::Index, DiffSide::Revision("HEAD".into()))).unwrap();
+        assert_eq!(reversed.files[0].path(), "b.txt");
+        assert_eq!(file_contents(&repo, &reversed.spec, &reversed.files[0], &textconv()).unwrap().1, "bee\n");
+    }
+
+    #[test]
+    fn test_branch_comparison_uses_merge_base() {
+        let (dir, repo) = setup();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        let main = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        fs::write(dir.path().join("b.txt"), "main change\n").unwrap();
+        commit_all(&repo, "main");
+
+        repo.set_head("refs/heads/feature").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        fs::write(dir.path().join("c.txt"), "feature\n").unwrap();
+        commit_all(&repo, "feature");
+
+        let diff = diff_revisions(&repo, &DiffSpec::branch_comparison(&main, "feature")).unwrap();
+        let paths: Vec<&str> = diff.files.iter().map(|file| file.path()).collect();
+        assert_eq!(paths, vec!["c.txt"]);
+        assert!(diff.spec.label().starts_with("merge-base("));
+    }
+
+    #[test]
+    fn test_file_patches_apply_exactly() {
+        let (dir, repo) = setup();
+        fs::write(dir.path().join("a.txt"), "one\r\ntwo\r\nthree").unwrap();
+        fs::write(dir.path().join("blob.bin"), [0u8, 1, 2, 255]).unwrap();
+        fs::remove_file(dir.path().join("b.txt")).unwrap();
+        let head = commit_all(&repo, "edit").to_string();
+
+        let patches = file_patches(&repo, &DiffSpec::commit(&head)).unwrap();
+        let text = |path: &str| {
+            let patch = patches.iter().find(|patch| patch.new_path.as_deref().or(patch.old_path.as_deref()) == Some(path)).unwrap();
+            String::from_utf8_lossy(&patch.text).to_string()
+        };
+        assert!(text("a.txt").contains("+three\n\\ No newline at end of file\n"));
+        assert!(text("a.txt").contains("+one\r\n"));
+        assert!(text("b.txt").contains("deleted file mode 100644\n"));
+        assert!(text("blob.bin").contains("GIT binary patch"));
+
+        // Applied in reverse, the patches undo the commit
+        let reverse: Vec<u8> = patches.iter().flat_map(|patch| patch.text.clone()).collect();
+        fs::write(dir.path().join("export.patch"), reverse).unwrap();
+        git(dir.path(), &["apply", "--check", "-R", "export.patch"]);
+    }
+}
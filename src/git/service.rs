@@ -5,6 +5,7 @@
 use chrono::{DateTime, Utc};
 use git2::{Repository, StatusOptions};
 use std::{
+    collections::HashSet,
     path::{Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant},
@@ -15,12 +16,31 @@ use tracing::{debug, error, info, instrument, warn};
 use super::{
     cache::{StatusCache, BranchCache}, find_git_root, operations::GitOperations, BranchInfo, CommitInfo, FileStatus, GitStatusFlags,
     RemoteInfo, StashInfo, TagInfo, GitFlowStatus,
+    changelog::{self, Changelog, ChangelogFormat, ChangelogOptions},
+    commit_lint::LintRules,
+    content::BlobContent,
+    diff_driver::{self, TextconvRunner},
+    trailers::{self, Identity},
+    clean::{self, CleanOptions, CleanPreview, RestoreReport, TrashBatch},
+    ignore::{self, IgnoreMatch, IgnoreTarget},
+    sparse::{self, SparseCheckout, SparseDirectory},
+    version::{self, Version, VersionOptions, VersionSuggestion},
+    insights::{self, InsightsOptions, RepositoryInsights},
+    log_filter::{self, FollowOptions, HistoryCursor, LogFilter},
+    notes::{self, CommitNote, NotesFetchResult},
+    revision_diff::{self, DiffSpec, FilePatch, RevisionDiff, RevisionFileChange},
+    edit::{self, EditBase, EditOutcome, EditTarget},
+    patch::{PatchApplyOptions, PatchApplyOutcome, PatchExportOptions, PatchManager},
+    patch_stream::PatchSource,
 };
 use crate::{
     config::GitConfig,
     error::{AppError, AppResult},
 };
 
+/// Directory inside `.git` that exported diffs are written to
+pub const DIFF_EXPORT_DIR: &str = "ai-c-exports";
+
 /// Git service for repository operations
 ///
 /// Provides high-performance Git operations with:
@@ -40,6 +60,8 @@ pub struct GitService {
     branch_cache: Arc<RwLock<BranchCache>>,
     /// Performance monitoring
     performance_monitor: PerformanceMonitor,
+    /// Textconv runner with its cache of converted blobs
+    textconv: Arc<TextconvRunner>,
     /// Configuration
     config: GitConfig,
     /// Whether this is a mock service (not a real Git repo)
@@ -68,18 +90,7 @@ impl GitService {
 
         debug!("Found Git repository at: {}", repo_path.display());
 
-        // Open repository
-        let repo = Repository::open(&repo_path).map_err(|e| {
-            warn!("Failed to open Git repository: {}", e);
-            AppError::Git(e)
-        })?;
-
-        // Initialize caches
-        let status_cache = Arc::new(RwLock::new(StatusCache::new()));
-        let branch_cache = Arc::new(RwLock::new(BranchCache::new()));
-
-        // Initialize performance monitor
-        let performance_monitor = PerformanceMonitor::new();
+        let service = Self::open(repo_path, config)?;
 
         let init_duration = init_start.elapsed();
 
@@ -93,17 +104,37 @@ impl GitService {
             debug!("Git service initialized in {:?}", init_duration);
         }
 
+        Ok(service)
+    }
+
+    /// Create a Git service for the repository rooted at `repo_path`
+    ///
+    /// Used by workspace mode, where repositories are not discovered from
+    /// the current directory.
+    pub fn open(repo_path: PathBuf, config: &GitConfig) -> AppResult<Self> {
+        // Open repository
+        let repo = Repository::open(&repo_path).map_err(|e| {
+            warn!("Failed to open Git repository: {}", e);
+            AppError::Git(e)
+        })?;
+
         Ok(Self {
             repo: Arc::new(Mutex::new(repo)),
             repo_path,
-            status_cache,
-            branch_cache,
-            performance_monitor,
+            status_cache: Arc::new(RwLock::new(StatusCache::new())),
+            branch_cache: Arc::new(RwLock::new(BranchCache::new())),
+            performance_monitor: PerformanceMonitor::new(),
+            textconv: Arc::new(TextconvRunner::new(Duration::from_secs(config.textconv_timeout_secs))),
             config: config.clone(),
             is_mock: false,
         })
     }
 
+    /// Whether this service runs without a real repository
+    pub fn is_mock(&self) -> bool {
+        self.is_mock
+    }
+
     /// Get repository file status
     ///
     /// Performance requirement: < 200ms for >10,000 files
@@ -245,11 +276,22 @@ impl GitService {
             AppError::Git(e)
         })?;
 
+        // libgit2 reports files outside the sparse checkout as deleted
+        let sparse_paths = if sparse::is_enabled(&repo) {
+            sparse::skip_worktree_paths(&repo)?
+        } else {
+            HashSet::new()
+        };
+
         let mut file_status_list = Vec::with_capacity(statuses.len());
 
         // Process each file status
         for entry in statuses.iter() {
             if let Some(path) = entry.path() {
+                if entry.status() == git2::Status::WT_DELETED && sparse_paths.contains(path) {
+                    continue;
+                }
+
                 let file_path = self.repo_path.join(path);
 
                 let file_status = FileStatus {
@@ -614,6 +656,10 @@ impl GitService {
     }
 
     /// Get file history for a specific file
+    ///
+    /// Follows renames (and copies when enabled) using the similarity
+    /// thresholds from the Git config; each commit carries the file's path
+    /// at that commit.
     #[instrument(skip(self))]
     pub async fn get_file_history(&self, file_path: &str, limit: Option<usize>) -> AppResult<Vec<CommitInfo>> {
         let limit = limit.unwrap_or(10);
@@ -628,42 +674,26 @@ impl GitService {
                     author_email: "mock@example.com".to_string(),
                     date: Utc::now(),
                     parents: vec![],
+                    path: Some(file_path.to_string()),
+                    previous_path: None,
                 },
             ]);
         }
 
-        let repo = self.repo.lock().await;
-        let mut revwalk = repo.revwalk().map_err(AppError::Git)?;
-        revwalk.push_head().map_err(AppError::Git)?;
-        revwalk.set_sorting(git2::Sort::TIME).map_err(AppError::Git)?;
-
-        let mut commits = Vec::new();
-        let mut count = 0;
-
-        for oid_result in revwalk {
-            if count >= limit {
-                break;
-            }
+        let operation_start = Instant::now();
+        let repo_path = self.repo_path.clone();
+        let path = file_path.to_string();
+        let options = FollowOptions::from(&self.config);
 
-            let oid = oid_result.map_err(AppError::Git)?;
-            let commit = repo.find_commit(oid).map_err(AppError::Git)?;
+        let commits = tokio::task::spawn_blocking(move || -> AppResult<Vec<CommitInfo>> {
+            let repo = Repository::open(&repo_path)?;
+            log_filter::follow_file_history(&repo, &path, &options, limit)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("File history task failed: {}", e)))??;
 
-            // Check if this commit affects the specified file
-            let tree = commit.tree().map_err(AppError::Git)?;
-            if tree.get_path(std::path::Path::new(file_path)).is_ok() {
-                let commit_info = CommitInfo {
-                    hash: commit.id().to_string(),
-                    short_hash: format!("{:.7}", commit.id()),
-                    message: commit.message().unwrap_or("").to_string(),
-                    author: commit.author().name().unwrap_or("").to_string(),
-                    author_email: commit.author().email().unwrap_or("").to_string(),
-                    date: DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(|| Utc::now()),
-                    parents: commit.parent_ids().map(|id| id.to_string()).collect(),
-                };
-                commits.push(commit_info);
-                count += 1;
-            }
-        }
+        self.performance_monitor
+            .record_operation("get_file_history".to_string(), operation_start.elapsed(), commits.len());
 
         Ok(commits)
     }
@@ -681,6 +711,8 @@ impl GitService {
                     author_email: "mock@example.com".to_string(),
                     date: Utc::now(),
                     parents: vec![],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "mock_commit_2".to_string(),
@@ -690,6 +722,8 @@ impl GitService {
                     author_email: "mock@example.com".to_string(),
                     date: Utc::now(),
                     parents: vec!["mock_commit_1".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
             ]);
         }
@@ -720,6 +754,8 @@ impl GitService {
                 date: DateTime::from_timestamp(commit.time().seconds(), 0)
                     .unwrap_or_else(|| Utc::now()),
                 parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+                path: None,
+                previous_path: None,
             };
 
             commits.push(commit_info);
@@ -730,7 +766,7 @@ impl GitService {
 
     /// Create a new branch
     #[instrument(skip(self))]
-    pub async fn create_branch(&self, name: &str, _target: Option<&str>) -> AppResult<BranchInfo> {
+    pub async fn create_branch(&self, name: &str, target: Option<&str>) -> AppResult<BranchInfo> {
         let operation_start = Instant::now();
         info!("Creating branch: {}", name);
 
@@ -753,8 +789,15 @@ impl GitService {
             });
         }
 
-        // TODO: Implement real branch creation logic
-        debug!("Mock create_branch operation for {}", name);
+        let repo_path = self.repo_path.clone();
+        let branch_name = name.to_string();
+        let target = target.map(str::to_string);
+        let branch = tokio::task::spawn_blocking(move || -> AppResult<BranchInfo> {
+            let mut repo = Repository::open(&repo_path)?;
+            GitOperations::new(&mut repo).create_branch(&branch_name, target.as_deref())
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Create branch task failed: {}", e)))??;
 
         let duration = operation_start.elapsed();
         self.performance_monitor.record_operation(
@@ -766,19 +809,7 @@ impl GitService {
         // Invalidate cache after creating branch
         self.invalidate_cache().await;
 
-        Ok(BranchInfo {
-            name: name.to_string(),
-            is_current: false,
-            is_remote: false,
-            is_local: true,
-            upstream: None,
-            ahead: 0,
-            behind: 0,
-            last_commit: "new_branch_commit".to_string(),
-            last_commit_message: "Created new branch".to_string(),
-            last_commit_author: "Git User".to_string(),
-            last_commit_date: Utc::now(),
-        })
+        Ok(branch)
     }
 
     /// Switch to a branch
@@ -816,8 +847,14 @@ impl GitService {
             return Ok(());
         }
 
-        // TODO: Implement real branch deletion logic
-        debug!("Mock delete_branch operation for {}", name);
+        let repo_path = self.repo_path.clone();
+        let branch_name = name.to_string();
+        tokio::task::spawn_blocking(move || -> AppResult<()> {
+            let mut repo = Repository::open(&repo_path)?;
+            GitOperations::new(&mut repo).delete_branch(&branch_name)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Delete branch task failed: {}", e)))??;
 
         let duration = operation_start.elapsed();
         self.performance_monitor.record_operation(
@@ -1200,6 +1237,7 @@ impl GitService {
             status_cache: Arc::new(RwLock::new(StatusCache::new())),
             branch_cache: Arc::new(RwLock::new(BranchCache::new())),
             performance_monitor: PerformanceMonitor::new(),
+            textconv: Arc::new(TextconvRunner::new(Duration::from_secs(config.textconv_timeout_secs))),
             config: config.clone(),
             is_mock: true,
         }
@@ -1246,11 +1284,30 @@ impl GitService {
         // Get the blob object
         let blob = repo.find_blob(tree_entry.id()).map_err(AppError::Git)?;
 
-        // Convert blob content to string
-        let content = std::str::from_utf8(blob.content())
-            .map_err(|_| AppError::InvalidOperation("File contains non-UTF8 content".to_string()))?;
+        // Decode the blob using the path's attributes and diff driver
+        let content = diff_driver::load_content(&repo, &relative_path.to_string_lossy(), blob.content(), &self.textconv);
+        if !content.is_text() {
+            return Err(AppError::InvalidOperation(format!("{:?} is a binary file", relative_path)));
+        }
+
+        Ok(content.text)
+    }
+
+    /// Get file content at a revision, using the repository-relative path the file had there
+    #[instrument(skip(self))]
+    pub fn get_file_content_at(&self, revision: &str, path: &str) -> AppResult<String> {
+        if self.is_mock {
+            return Ok(format!("// Mock content for file: {} at {}\n", path, revision));
+        }
+
+        let repo = self.get_repository()?;
+        let tree = repo.revparse_single(revision)?.peel_to_tree()?;
+        let entry = tree.get_path(Path::new(path)).map_err(|_| {
+            AppError::InvalidOperation(format!("File {} not found at {}", path, revision))
+        })?;
+        let blob = repo.find_blob(entry.id())?;
 
-        Ok(content.to_string())
+        Ok(diff_driver::load_content(&repo, path, blob.content(), &self.textconv).text)
     }
 
     /// Get diff between working directory and HEAD for a specific file
@@ -1274,13 +1331,157 @@ impl GitService {
         let old_content = self.get_file_content_at_head(file_path)?;
 
         // Get current working directory content
-        let new_content = tokio::fs::read_to_string(file_path)
-            .await
-            .unwrap_or_else(|_| String::new());
+        let new_content = match tokio::fs::read(file_path).await {
+            Ok(bytes) => {
+                let relative_path = file_path.strip_prefix(&self.repo_path).unwrap_or(file_path);
+                let repo = self.get_repository()?;
+                diff_driver::load_content(&repo, &relative_path.to_string_lossy(), &bytes, &self.textconv).text
+            }
+            Err(_) => String::new(),
+        };
 
         Ok((old_content, new_content))
     }
 
+    /// Changed files between any two revisions, the index or the working tree
+    #[instrument(skip(self))]
+    pub async fn diff_revisions(&self, spec: &DiffSpec) -> AppResult<RevisionDiff> {
+        if self.is_mock {
+            return Ok(RevisionDiff {
+                spec: spec.clone(),
+                files: vec![],
+            });
+        }
+
+        let repo_path = self.repo_path.clone();
+        let spec = spec.clone();
+        tokio::task::spawn_blocking(move || -> AppResult<RevisionDiff> {
+            let repo = Repository::open(&repo_path)?;
+            revision_diff::diff_revisions(&repo, &spec)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Diff task failed: {}", e)))?
+    }
+
+    /// Old and new content of one file of a revision diff
+    #[instrument(skip(self))]
+    pub async fn revision_file_contents(&self, spec: &DiffSpec, change: &RevisionFileChange) -> AppResult<(String, String)> {
+        let (old, new) = self.revision_file_sides(spec, change).await?;
+        Ok((old.text, new.text))
+    }
+
+    /// Both sides of one file of a revision diff, with binary, image and encoding details
+    #[instrument(skip(self))]
+    pub async fn revision_file_sides(&self, spec: &DiffSpec, change: &RevisionFileChange) -> AppResult<(BlobContent, BlobContent)> {
+        if self.is_mock {
+            return Ok((BlobContent::missing(), BlobContent::missing()));
+        }
+
+        let repo_path = self.repo_path.clone();
+        let spec = spec.clone();
+        let change = change.clone();
+        let textconv = self.textconv.clone();
+        tokio::task::spawn_blocking(move || -> AppResult<(BlobContent, BlobContent)> {
+            let repo = Repository::open(&repo_path)?;
+            revision_diff::file_sides(&repo, &spec, &change, &textconv)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Diff task failed: {}", e)))?
+    }
+
+    /// Size of the larger side of one file of a revision diff, from object headers
+    #[instrument(skip(self))]
+    pub async fn revision_file_size(&self, spec: &DiffSpec, change: &RevisionFileChange) -> AppResult<u64> {
+        if self.is_mock {
+            return Ok(0);
+        }
+
+        let repo_path = self.repo_path.clone();
+        let spec = spec.clone();
+        let change = change.clone();
+        tokio::task::spawn_blocking(move || -> AppResult<u64> {
+            let repo = Repository::open(&repo_path)?;
+            revision_diff::file_size(&repo, &spec, &change)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Diff task failed: {}", e)))?
+    }
+
+    /// `git apply`-able patch of every file of a revision diff, for export
+    #[instrument(skip(self))]
+    pub async fn revision_patches(&self, spec: &DiffSpec) -> AppResult<Vec<FilePatch>> {
+        if self.is_mock {
+            return Ok(vec![]);
+        }
+
+        let repo_path = self.repo_path.clone();
+        let spec = spec.clone();
+        tokio::task::spawn_blocking(move || -> AppResult<Vec<FilePatch>> {
+            let repo = Repository::open(&repo_path)?;
+            revision_diff::file_patches(&repo, &spec)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Diff task failed: {}", e)))?
+    }
+
+    /// Source for streaming the patch of one file of a revision diff from `git diff`
+    ///
+    /// `options` are extra `git diff` options (algorithm, whitespace).
+    pub fn patch_source(&self, spec: &DiffSpec, change: &RevisionFileChange, options: Vec<String>) -> AppResult<PatchSource> {
+        if self.is_mock {
+            return Err(AppError::InvalidState("No repository to stream a diff from".to_string()));
+        }
+        Ok(PatchSource::new(self.repo_path.clone(), spec.clone(), change.clone(), options))
+    }
+
+    /// Read a path from the working tree or the index for in-place editing
+    #[instrument(skip(self))]
+    pub async fn read_for_edit(&self, target: EditTarget, path: &str) -> AppResult<EditBase> {
+        if self.is_mock {
+            return Ok(EditBase {
+                target,
+                path: path.to_string(),
+                content: String::new(),
+                fingerprint: None,
+            });
+        }
+
+        let repo_path = self.repo_path.clone();
+        let path = path.to_string();
+        tokio::task::spawn_blocking(move || -> AppResult<EditBase> {
+            let repo = Repository::open(&repo_path)?;
+            edit::read_for_edit(&repo, target, &path)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Edit task failed: {}", e)))?
+    }
+
+    /// Write edited content back; reports a conflict if the target changed since it was read
+    #[instrument(skip(self, content))]
+    pub async fn write_edit(&self, base: &EditBase, content: &str, force: bool) -> AppResult<EditOutcome> {
+        if self.is_mock {
+            return Ok(EditOutcome::Written(EditBase {
+                content: content.to_string(),
+                ..base.clone()
+            }));
+        }
+
+        let repo_path = self.repo_path.clone();
+        let base = base.clone();
+        let content = content.to_string();
+        let result = tokio::task::spawn_blocking(move || -> AppResult<EditOutcome> {
+            let repo = Repository::open(&repo_path)?;
+            edit::write_edit(&repo, &base, &content, force)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Edit task failed: {}", e)))?;
+
+        if matches!(result, Ok(EditOutcome::Written(_))) {
+            self.invalidate_cache().await;
+        }
+        result
+    }
+
     /// Get commits (alias for get_commit_history for UI compatibility)
     pub async fn get_commits(&self, limit: usize) -> AppResult<Vec<CommitInfo>> {
         self.get_commit_history(limit).await
@@ -1299,6 +1500,8 @@ impl GitService {
                     author_email: "dev@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::hours(2),
                     parents: vec!["def456789012".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "def456789012".to_string(),
@@ -1308,6 +1511,8 @@ impl GitService {
                     author_email: "dev@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::days(1),
                     parents: vec!["ghi789012345".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
                 CommitInfo {
                     hash: "ghi789012345".to_string(),
@@ -1317,6 +1522,8 @@ impl GitService {
                     author_email: "docs@example.com".to_string(),
                     date: Utc::now() - chrono::Duration::days(3),
                     parents: vec!["jkl012345678".to_string()],
+                    path: None,
+                    previous_path: None,
                 },
             ]);
         }
@@ -1361,6 +1568,8 @@ impl GitService {
                 date: DateTime::from_timestamp(commit.time().seconds(), 0)
                     .unwrap_or_else(|| Utc::now()),
                 parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+                path: None,
+                previous_path: None,
             };
 
             commits.push(commit_info);
@@ -1776,36 +1985,59 @@ impl GitService {
             return Ok(());
         }
 
-        // In real implementation, this would perform git merge
         debug!("Merging branch {} into current branch", source_branch);
 
-        // TODO: Implement actual git merge logic using git2
-        // This would involve:
-        // 1. Getting current branch
-        // 2. Finding source branch commit
-        // 3. Performing merge
-        // 4. Handling conflicts if any
+        let repo_path = self.repo_path.clone();
+        let branch_name = source_branch.to_string();
+        let result = tokio::task::spawn_blocking(move || -> AppResult<()> {
+            let mut repo = Repository::open(&repo_path)?;
+            GitOperations::new(&mut repo).merge(&branch_name)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Merge task failed: {}", e)))?;
 
-        Ok(())
+        // A conflicted merge still changes the working tree
+        self.invalidate_cache().await;
+        result
     }
 
-    /// Push a branch to its remote
+    /// Push a branch to the remote its upstream lives on
+    ///
+    /// Falls back to `origin` (or the only remote) for a branch without upstream.
     #[instrument(skip(self))]
     pub async fn push_branch(&self, branch_name: &str) -> AppResult<()> {
+        self.push_branch_to(None, branch_name).await
+    }
+
+    /// Push a branch to `remote_name`, or to its push remote when `None`
+    #[instrument(skip(self))]
+    pub async fn push_branch_to(&self, remote_name: Option<&str>, branch_name: &str) -> AppResult<()> {
         if self.is_mock {
             debug!("Mock service: pushing branch {} (no-op)", branch_name);
             return Ok(());
         }
 
-        // In real implementation, this would push to remote
         debug!("Pushing branch {} to remote", branch_name);
 
-        // TODO: Implement actual git push logic
-        // This would involve:
-        // 1. Finding remote for branch
-        // 2. Pushing commits to remote
-        // 3. Setting up tracking if needed
+        let operation_start = Instant::now();
+        let repo_path = self.repo_path.clone();
+        let remote_name = remote_name.map(str::to_string);
+        let branch_name = branch_name.to_string();
+        tokio::task::spawn_blocking(move || -> AppResult<()> {
+            let mut repo = Repository::open(&repo_path)?;
+            let operations = GitOperations::new(&mut repo);
+            let remote_name = match remote_name {
+                Some(remote_name) => remote_name,
+                None => operations.push_remote_for(&branch_name)?,
+            };
+            operations.push_branch(&remote_name, &branch_name)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Push task failed: {}", e)))??;
 
+        self.performance_monitor
+            .record_operation("push_branch".to_string(), operation_start.elapsed(), 1);
+        self.invalidate_cache().await;
         Ok(())
     }
 
@@ -1890,6 +2122,631 @@ impl GitService {
         }
     }
 
+    // ================== History Query Methods ==================
+
+    /// Start a paged walk of the commit history matching `filter`
+    ///
+    /// The walk runs on its own thread and keeps its position between pages,
+    /// so callers can request pages in the background and keep the UI
+    /// responsive while large histories are scanned.
+    #[instrument(skip(self))]
+    pub async fn history_cursor(&self, filter: &LogFilter) -> AppResult<HistoryCursor> {
+        if self.is_mock {
+            return Ok(HistoryCursor::from_commits(self.get_commit_history(50).await?));
+        }
+        Ok(HistoryCursor::open(self.repo_path.clone(), filter.clone()))
+    }
+
+    /// Compute contributor, churn and hotspot insights on the blocking thread pool
+    #[instrument(skip(self))]
+    pub async fn compute_insights(&self, options: &InsightsOptions) -> AppResult<RepositoryInsights> {
+        if self.is_mock {
+            return Ok(RepositoryInsights {
+                generated_at: Utc::now(),
+                bucket: options.bucket,
+                ..Default::default()
+            });
+        }
+
+        let operation_start = Instant::now();
+        let repo_path = self.repo_path.clone();
+        let options = options.clone();
+
+        let result = tokio::task::spawn_blocking(move || -> AppResult<RepositoryInsights> {
+            let repo = Repository::open(&repo_path)?;
+            insights::compute_insights(&repo, &options)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Insights task failed: {}", e)))??;
+
+        self.performance_monitor
+            .record_operation("compute_insights".to_string(), operation_start.elapsed(), result.commits_analyzed);
+
+        Ok(result)
+    }
+
+    /// Generate a changelog from the conventional commits in a range
+    #[instrument(skip(self))]
+    pub async fn generate_changelog(&self, options: &ChangelogOptions) -> AppResult<Changelog> {
+        if self.is_mock {
+            return Ok(Changelog {
+                version: options.version.clone(),
+                date: Utc::now().date_naive(),
+                from: options.from.clone(),
+                to: options.to.clone(),
+                breaking: vec![],
+                sections: vec![],
+                commit_count: 0,
+            });
+        }
+
+        let repo_path = self.repo_path.clone();
+        let options = options.clone();
+
+        tokio::task::spawn_blocking(move || -> AppResult<Changelog> {
+            let repo = Repository::open(&repo_path)?;
+            changelog::generate_changelog(&repo, &options)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Changelog task failed: {}", e)))?
+    }
+
+    /// Write rendered changelog content into the working tree
+    #[instrument(skip(self, content))]
+    pub async fn write_changelog(&self, content: &str, format: ChangelogFormat) -> AppResult<PathBuf> {
+        if self.is_mock {
+            return Ok(PathBuf::from(format.file_name()));
+        }
+
+        let repo = self.get_repository()?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| AppError::InvalidOperation("Repository has no working directory".to_string()))?;
+        let path = changelog::write_changelog(workdir, content, format)?;
+        self.invalidate_cache().await;
+        Ok(path)
+    }
+
+    /// Write an exported diff to `.git/ai-c-exports/` and return its path
+    pub fn write_diff_export(&self, content: &[u8], extension: &str) -> AppResult<PathBuf> {
+        let file_name = format!("diff-{}.{}", Utc::now().format("%Y%m%d-%H%M%S"), extension);
+        if self.is_mock {
+            return Ok(PathBuf::from(file_name));
+        }
+
+        let repo = self.get_repository()?;
+        let dir = repo.path().join(DIFF_EXPORT_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(file_name);
+        std::fs::write(&path, content)?;
+        info!("Exported diff to {}", path.display());
+        Ok(path)
+    }
+
+    /// Suggest the next semantic version from the commits since the latest version tag
+    #[instrument(skip(self))]
+    pub async fn suggest_version(&self, options: &VersionOptions) -> AppResult<VersionSuggestion> {
+        if self.is_mock {
+            let next = Version::new(0, 1, 0);
+            return Ok(VersionSuggestion {
+                latest: None,
+                level: version::BumpLevel::Minor,
+                tag_name: format!("v{}", next),
+                next,
+                commit_count: 0,
+                breaking: 0,
+                features: 0,
+                fixes: 0,
+            });
+        }
+
+        let repo_path = self.repo_path.clone();
+        let options = options.clone();
+
+        tokio::task::spawn_blocking(move || -> AppResult<VersionSuggestion> {
+            let repo = Repository::open(&repo_path)?;
+            version::suggest_version(&repo, &options)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Version suggestion task failed: {}", e)))?
+    }
+
+    /// Write `version` into the root manifests; returns the changed files
+    #[instrument(skip(self))]
+    pub async fn bump_manifest_versions(&self, version: &Version) -> AppResult<Vec<String>> {
+        if self.is_mock {
+            return Ok(vec![]);
+        }
+
+        let repo = self.get_repository()?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| AppError::InvalidOperation("Repository has no working directory".to_string()))?;
+        let changed = version::bump_manifests(workdir, version)?;
+        if !changed.is_empty() {
+            self.invalidate_cache().await;
+        }
+        Ok(changed)
+    }
+
+    /// Commit message lint rules of the repository (defaults when unconfigured)
+    #[instrument(skip(self))]
+    pub async fn load_lint_rules(&self) -> AppResult<LintRules> {
+        if self.is_mock {
+            return Ok(LintRules::default());
+        }
+
+        let repo = self.get_repository()?;
+        match repo.workdir() {
+            Some(workdir) => LintRules::load(workdir),
+            None => Ok(LintRules::default()),
+        }
+    }
+
+    /// Whether new commit messages get a `Signed-off-by` trailer by default
+    pub fn auto_signoff(&self) -> bool {
+        self.config.auto_signoff
+    }
+
+    /// The configured committer (`user.name` / `user.email`)
+    #[instrument(skip(self))]
+    pub async fn committer_identity(&self) -> AppResult<Option<Identity>> {
+        if self.is_mock {
+            return Ok(None);
+        }
+
+        let repo = self.get_repository()?;
+        let identity = repo
+            .signature()
+            .ok()
+            .and_then(|signature| Some(Identity::new(signature.name()?, signature.email()?)));
+        Ok(identity)
+    }
+
+    /// Recent authors and co-authors for the co-author picker, resolved
+    /// through `.mailmap` and excluding the committer
+    #[instrument(skip(self))]
+    pub async fn recent_coauthors(&self, limit: usize) -> AppResult<Vec<Identity>> {
+        if self.is_mock {
+            return Ok(vec![]);
+        }
+
+        let commits = self.get_commit_history(200).await?;
+        let me = self.committer_identity().await?;
+        let repo = self.get_repository()?;
+        let mailmap = repo.mailmap().ok();
+        Ok(trailers::recent_coauthors(&commits, mailmap.as_ref(), me.as_ref(), limit))
+    }
+
+    // ================== Notes Methods ==================
+
+    /// Configured notes refs, normalized to `refs/notes/...`
+    pub fn notes_refs(&self) -> Vec<String> {
+        let refs: Vec<String> = self.config.notes_refs.iter().map(|name| notes::normalize_notes_ref(name)).collect();
+        if refs.is_empty() {
+            vec![notes::DEFAULT_NOTES_REF.to_string()]
+        } else {
+            refs
+        }
+    }
+
+    /// Notes of one commit under every configured notes ref
+    #[instrument(skip(self))]
+    pub async fn get_commit_notes(&self, commit: &str) -> AppResult<Vec<CommitNote>> {
+        if self.is_mock {
+            return Ok(vec![]);
+        }
+
+        let repo_path = self.repo_path.clone();
+        let commit = commit.to_string();
+        let refs = self.notes_refs();
+        tokio::task::spawn_blocking(move || -> AppResult<Vec<CommitNote>> {
+            let repo = Repository::open(&repo_path)?;
+            notes::notes_for_commit(&repo, &commit, &refs)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Notes task failed: {}", e)))?
+    }
+
+    /// Every note under the configured notes refs
+    #[instrument(skip(self))]
+    pub async fn list_notes(&self) -> AppResult<Vec<CommitNote>> {
+        if self.is_mock {
+            return Ok(vec![]);
+        }
+
+        let repo_path = self.repo_path.clone();
+        let refs = self.notes_refs();
+        tokio::task::spawn_blocking(move || -> AppResult<Vec<CommitNote>> {
+            let repo = Repository::open(&repo_path)?;
+            notes::list_notes(&repo, &refs)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Notes task failed: {}", e)))?
+    }
+
+    /// Add or replace the note of `commit` under `notes_ref`
+    #[instrument(skip(self, message))]
+    pub async fn set_commit_note(&self, commit: &str, notes_ref: &str, message: &str) -> AppResult<()> {
+        if self.is_mock {
+            return Ok(());
+        }
+
+        let repo = self.get_repository()?;
+        notes::set_note(&repo, notes_ref, commit, message)?;
+        Ok(())
+    }
+
+    /// Remove the note of `commit` under `notes_ref`; `false` if there was none
+    #[instrument(skip(self))]
+    pub async fn remove_commit_note(&self, commit: &str, notes_ref: &str) -> AppResult<bool> {
+        if self.is_mock {
+            return Ok(false);
+        }
+
+        let repo = self.get_repository()?;
+        notes::remove_note(&repo, notes_ref, commit)
+    }
+
+    /// Push the configured notes refs; `None` uses `origin` or the first remote
+    #[instrument(skip(self))]
+    pub async fn push_notes(&self, remote: Option<&str>) -> AppResult<(String, Vec<String>)> {
+        if self.is_mock {
+            return Ok(("origin".to_string(), vec![]));
+        }
+
+        let repo_path = self.repo_path.clone();
+        let remote = remote.map(str::to_string);
+        let refs = self.notes_refs();
+        tokio::task::spawn_blocking(move || -> AppResult<(String, Vec<String>)> {
+            let repo = Repository::open(&repo_path)?;
+            let remote = match remote {
+                Some(remote) => remote,
+                None => notes::default_remote(&repo)?,
+            };
+            let pushed = notes::push_notes(&repo, &remote, &refs)?;
+            Ok((remote, pushed))
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Notes push task failed: {}", e)))?
+    }
+
+    /// Fetch the configured notes refs, fast-forwarding the local ones
+    #[instrument(skip(self))]
+    pub async fn fetch_notes(&self, remote: Option<&str>) -> AppResult<(String, Vec<NotesFetchResult>)> {
+        if self.is_mock {
+            return Ok(("origin".to_string(), vec![]));
+        }
+
+        let repo_path = self.repo_path.clone();
+        let remote = remote.map(str::to_string);
+        let refs = self.notes_refs();
+        tokio::task::spawn_blocking(move || -> AppResult<(String, Vec<NotesFetchResult>)> {
+            let repo = Repository::open(&repo_path)?;
+            let remote = match remote {
+                Some(remote) => remote,
+                None => notes::default_remote(&repo)?,
+            };
+            let results = notes::fetch_notes(&repo, &remote, &refs)?;
+            Ok((remote, results))
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Notes fetch task failed: {}", e)))?
+    }
+
+    // ================== Clean & Trash Methods ==================
+
+    /// List the untracked/ignored files a clean would remove, with sizes
+    #[instrument(skip(self))]
+    pub async fn preview_clean(&self, options: &CleanOptions) -> AppResult<CleanPreview> {
+        if self.is_mock {
+            return Ok(CleanPreview::default());
+        }
+
+        let repo_path = self.repo_path.clone();
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || -> AppResult<CleanPreview> {
+            let repo = Repository::open(&repo_path)?;
+            clean::preview_clean(&repo, &options)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Clean preview task failed: {}", e)))?
+    }
+
+    /// Move the files selected by `options` into `.git/ai-c-trash`
+    ///
+    /// Returns the created trash batch, or `None` if nothing was cleaned.
+    #[instrument(skip(self))]
+    pub async fn clean(&self, options: &CleanOptions) -> AppResult<Option<TrashBatch>> {
+        if self.is_mock {
+            debug!("Mock service: cleaning working tree (no-op)");
+            return Ok(None);
+        }
+
+        let operation_start = Instant::now();
+        let repo_path = self.repo_path.clone();
+        let options = options.clone();
+        let batch = tokio::task::spawn_blocking(move || -> AppResult<Option<TrashBatch>> {
+            let repo = Repository::open(&repo_path)?;
+            clean::clean(&repo, &options)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Clean task failed: {}", e)))??;
+
+        self.invalidate_cache().await;
+        self.performance_monitor.record_operation(
+            "clean".to_string(),
+            operation_start.elapsed(),
+            batch.as_ref().map_or(0, |b| b.entries.len()),
+        );
+        Ok(batch)
+    }
+
+    /// Trash batches created by [`GitService::clean`], newest first
+    pub async fn list_trash(&self) -> AppResult<Vec<TrashBatch>> {
+        if self.is_mock {
+            return Ok(Vec::new());
+        }
+
+        let repo = self.get_repository()?;
+        clean::list_trash(&repo)
+    }
+
+    /// Move the entries of a trash batch back into the working tree
+    #[instrument(skip(self))]
+    pub async fn restore_trash(&self, batch_id: &str) -> AppResult<RestoreReport> {
+        if self.is_mock {
+            return Err(AppError::InvalidState("Cannot restore trash in mock mode".to_string()));
+        }
+
+        let repo = self.get_repository()?;
+        let report = clean::restore_trash(&repo, batch_id)?;
+        self.invalidate_cache().await;
+        Ok(report)
+    }
+
+    // ================== Ignore Rule Methods ==================
+
+    /// Append an ignore rule to `target`; returns `false` if it already exists
+    #[instrument(skip(self))]
+    pub async fn add_ignore_rule(&self, target: &IgnoreTarget, pattern: &str) -> AppResult<bool> {
+        if self.is_mock {
+            debug!("Mock service: adding ignore rule {} (no-op)", pattern);
+            return Ok(false);
+        }
+
+        let repo = self.get_repository()?;
+        let added = ignore::add_ignore_rule(&repo, target, pattern)?;
+        if added {
+            self.invalidate_cache().await;
+        }
+        Ok(added)
+    }
+
+    /// The ignore rule that matches `path`, with its source file and line
+    pub async fn explain_ignore(&self, path: &str) -> AppResult<Option<IgnoreMatch>> {
+        if self.is_mock {
+            return Ok(None);
+        }
+
+        let repo = self.get_repository()?;
+        ignore::explain_ignore(&repo, path)
+    }
+
+    // ================== Sparse Checkout Methods ==================
+
+    /// Sparse-checkout configuration and number of materialized paths
+    pub async fn sparse_checkout(&self) -> AppResult<SparseCheckout> {
+        if self.is_mock {
+            return Ok(SparseCheckout::default());
+        }
+
+        let repo = self.get_repository()?;
+        sparse::sparse_checkout(&repo)
+    }
+
+    /// Directories of `HEAD` classified against the cone `directories`
+    pub async fn sparse_directory_tree(&self, directories: &[String]) -> AppResult<Vec<SparseDirectory>> {
+        if self.is_mock {
+            return Ok(Vec::new());
+        }
+
+        let operation_start = Instant::now();
+        let repo_path = self.repo_path.clone();
+        let directories = directories.to_vec();
+        let tree = tokio::task::spawn_blocking(move || -> AppResult<Vec<SparseDirectory>> {
+            let repo = Repository::open(&repo_path)?;
+            sparse::directory_tree(&repo, &directories)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Sparse tree task failed: {}", e)))??;
+
+        self.performance_monitor
+            .record_operation("sparse_directory_tree".to_string(), operation_start.elapsed(), tree.len());
+        Ok(tree)
+    }
+
+    /// Replace the cone with `directories`, enabling sparse checkout if needed
+    #[instrument(skip(self))]
+    pub async fn set_sparse_directories(&self, directories: &[String]) -> AppResult<()> {
+        if self.is_mock {
+            debug!("Mock service: setting sparse checkout (no-op)");
+            return Ok(());
+        }
+
+        let repo_path = self.repo_path.clone();
+        let directories = directories.to_vec();
+        tokio::task::spawn_blocking(move || -> AppResult<()> {
+            let repo = Repository::open(&repo_path)?;
+            sparse::set_directories(&repo, &directories)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Sparse checkout task failed: {}", e)))??;
+
+        self.invalidate_cache().await;
+        Ok(())
+    }
+
+    /// Disable sparse checkout and materialize every file
+    #[instrument(skip(self))]
+    pub async fn disable_sparse_checkout(&self) -> AppResult<()> {
+        if self.is_mock {
+            return Err(AppError::InvalidState("Cannot change sparse checkout in mock mode".to_string()));
+        }
+
+        let repo_path = self.repo_path.clone();
+        tokio::task::spawn_blocking(move || -> AppResult<()> {
+            let repo = Repository::open(&repo_path)?;
+            sparse::disable(&repo)
+        })
+        .await
+        .map_err(|e| AppError::application(format!("Sparse checkout task failed: {}", e)))??;
+
+        self.invalidate_cache().await;
+        Ok(())
+    }
+
+    /// Tracked paths outside the sparse checkout
+    pub async fn sparse_excluded_paths(&self) -> AppResult<Vec<String>> {
+        if self.is_mock {
+            return Ok(Vec::new());
+        }
+
+        let repo = self.get_repository()?;
+        if !sparse::is_enabled(&repo) {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<String> = sparse::skip_worktree_paths(&repo)?.into_iter().collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    // ================== Patch Exchange Methods ==================
+
+    /// Export the inclusive commit range `oldest..=newest` as mbox patch files
+    #[instrument(skip(self))]
+    pub async fn export_patches(&self, oldest: &str, newest: &str, options: &PatchExportOptions) -> AppResult<Vec<PathBuf>> {
+        if self.is_mock {
+            debug!("Mock service: exporting patches {}..{} (no-op)", oldest, newest);
+            return Ok(vec![]);
+        }
+
+        let operation_start = Instant::now();
+        let (oldest, newest, options) = (oldest.to_string(), newest.to_string(), options.clone());
+        let files = self
+            .run_patch_manager(move |patches| patches.export(&oldest, &newest, &options))
+            .await?;
+
+        self.performance_monitor
+            .record_operation("export_patches".to_string(), operation_start.elapsed(), files.len());
+
+        Ok(files)
+    }
+
+    /// Apply an mbox series or plain diff onto the current branch
+    ///
+    /// Stops at the first conflicting patch; use `continue_patch_apply`,
+    /// `skip_patch` or `abort_patch_apply` to resolve the series. A plain diff
+    /// with conflicts is finished by `continue_diff_apply` or `abort_diff_apply`.
+    #[instrument(skip(self))]
+    pub async fn apply_patches(&self, patch_file: &Path, options: &PatchApplyOptions) -> AppResult<PatchApplyOutcome> {
+        if self.is_mock {
+            debug!("Mock service: applying patches from {} (no-op)", patch_file.display());
+            return Ok(PatchApplyOutcome::Applied { count: 0 });
+        }
+
+        let (patch_file, options) = (patch_file.to_path_buf(), options.clone());
+        let outcome = self
+            .run_patch_manager(move |patches| patches.apply(&patch_file, &options))
+            .await;
+        self.invalidate_cache().await;
+        outcome
+    }
+
+    /// Continue applying a patch series after conflicts were resolved
+    #[instrument(skip(self))]
+    pub async fn continue_patch_apply(&self) -> AppResult<PatchApplyOutcome> {
+        if self.is_mock {
+            return Ok(PatchApplyOutcome::Applied { count: 0 });
+        }
+
+        let outcome = self.run_patch_manager(|patches| patches.continue_apply()).await;
+        self.invalidate_cache().await;
+        outcome
+    }
+
+    /// Skip the conflicting patch of the series being applied
+    #[instrument(skip(self))]
+    pub async fn skip_patch(&self) -> AppResult<PatchApplyOutcome> {
+        if self.is_mock {
+            return Ok(PatchApplyOutcome::Applied { count: 0 });
+        }
+
+        let outcome = self.run_patch_manager(|patches| patches.skip_patch()).await;
+        self.invalidate_cache().await;
+        outcome
+    }
+
+    /// Abort the patch series and restore the original branch state
+    #[instrument(skip(self))]
+    pub async fn abort_patch_apply(&self) -> AppResult<()> {
+        if self.is_mock {
+            return Ok(());
+        }
+
+        let result = self.run_patch_manager(|patches| patches.abort_apply()).await;
+        self.invalidate_cache().await;
+        result
+    }
+
+    /// Finish a conflicted plain diff once its paths are resolved and staged
+    #[instrument(skip(self))]
+    pub async fn continue_diff_apply(&self, conflicted_files: &[String]) -> AppResult<PatchApplyOutcome> {
+        if self.is_mock {
+            return Ok(PatchApplyOutcome::Applied { count: 0 });
+        }
+
+        let conflicted_files = conflicted_files.to_vec();
+        let outcome = self
+            .run_patch_manager(move |patches| patches.continue_diff_apply(&conflicted_files))
+            .await;
+        self.invalidate_cache().await;
+        outcome
+    }
+
+    /// Abort a conflicted plain diff by resetting its conflicted paths to HEAD
+    #[instrument(skip(self))]
+    pub async fn abort_diff_apply(&self, conflicted_files: &[String]) -> AppResult<()> {
+        if self.is_mock {
+            return Ok(());
+        }
+
+        let conflicted_files = conflicted_files.to_vec();
+        let result = self
+            .run_patch_manager(move |patches| patches.abort_diff_apply(&conflicted_files))
+            .await;
+        self.invalidate_cache().await;
+        result
+    }
+
+    /// Check whether a patch series is waiting for conflict resolution
+    pub fn is_patch_apply_in_progress(&self) -> bool {
+        !self.is_mock && PatchManager::new(&self.repo_path).am_in_progress()
+    }
+
+    /// Run `git format-patch` / `git am` work off the async runtime
+    async fn run_patch_manager<T, F>(&self, task: F) -> AppResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&PatchManager) -> AppResult<T> + Send + 'static,
+    {
+        let repo_path = self.repo_path.clone();
+        tokio::task::spawn_blocking(move || task(&PatchManager::new(&repo_path)))
+            .await
+            .map_err(|e| AppError::application(format!("Patch task failed: {}", e)))?
+    }
+
     /// Calculate ahead/behind counts for a branch against its upstream
     fn calculate_ahead_behind(&self, repo: &git2::Repository, branch_name: &str) -> Result<(usize, usize), git2::Error> {
         // Get the local branch
@@ -0,0 +1,366 @@
+//! Sparse-checkout management (cone mode)
+//!
+//! libgit2 does not understand sparse checkouts, so the cone is read from
+//! `.git/info/sparse-checkout` and the index skip-worktree bits, and changes
+//! go through `git sparse-checkout` which also updates the working tree.
+
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use tracing::{debug, info};
+
+use crate::error::{AppError, AppResult};
+
+/// `GIT_INDEX_ENTRY_SKIP_WORKTREE`, stored in the extended index flags
+const SKIP_WORKTREE: u16 = 1 << 14;
+
+/// Current sparse-checkout configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparseCheckout {
+    /// `core.sparseCheckout` is set
+    pub enabled: bool,
+    /// `core.sparseCheckoutCone` is set (patterns are directories)
+    pub cone: bool,
+    /// Directories checked out recursively, as listed by `git sparse-checkout list`
+    pub directories: Vec<String>,
+    /// Index entries present in the working tree
+    pub materialized: usize,
+    /// All index entries
+    pub total: usize,
+}
+
+impl SparseCheckout {
+    /// Tracked paths that are not materialized
+    pub fn outside(&self) -> usize {
+        self.total - self.materialized
+    }
+
+    /// Whether the cone includes `path` (a file relative to the repository root)
+    pub fn includes(&self, path: &str) -> bool {
+        !self.enabled || cone_includes(&self.directories, path)
+    }
+}
+
+/// How a directory relates to the cone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SparseInclusion {
+    /// Listed in the cone, or below a listed directory
+    Included,
+    /// Ancestor of a listed directory: only its direct files are checked out
+    Partial,
+    /// Not checked out
+    Excluded,
+}
+
+/// Directory of the `HEAD` tree, for the cone picker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparseDirectory {
+    /// Path relative to the repository root, without trailing slash
+    pub path: String,
+    /// Number of parent directories
+    pub depth: usize,
+    /// Whether the directory contains subdirectories
+    pub has_children: bool,
+    pub inclusion: SparseInclusion,
+}
+
+impl SparseDirectory {
+    /// Last path component
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Read the sparse-checkout configuration and count materialized paths
+pub fn sparse_checkout(repo: &Repository) -> AppResult<SparseCheckout> {
+    let enabled = config_bool(repo, "core.sparseCheckout");
+    let cone = config_bool(repo, "core.sparseCheckoutCone");
+
+    let directories = if enabled && cone {
+        match fs::read_to_string(sparse_file(repo)) {
+            Ok(content) => parse_cone_patterns(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(AppError::Io(e)),
+        }
+    } else {
+        Vec::new()
+    };
+
+    let index = current_index(repo)?;
+    let total = index.len();
+    let skipped = index.iter().filter(|entry| entry.flags_extended & SKIP_WORKTREE != 0).count();
+
+    Ok(SparseCheckout {
+        enabled,
+        cone,
+        directories,
+        materialized: total - skipped,
+        total,
+    })
+}
+
+/// Whether `core.sparseCheckout` is enabled
+pub fn is_enabled(repo: &Repository) -> bool {
+    config_bool(repo, "core.sparseCheckout")
+}
+
+/// Read a boolean setting, preferring `config.worktree`
+///
+/// `git sparse-checkout` stores its settings per worktree, which libgit2
+/// does not read.
+fn config_bool(repo: &Repository, key: &str) -> bool {
+    let worktree_config = repo.path().join("config.worktree");
+    if worktree_config.exists() {
+        if let Ok(value) = git2::Config::open(&worktree_config).and_then(|config| config.get_bool(key)) {
+            return value;
+        }
+    }
+
+    repo.config()
+        .and_then(|config| config.get_bool(key))
+        .unwrap_or(false)
+}
+
+/// Tracked paths marked skip-worktree, i.e. outside the sparse checkout
+pub fn skip_worktree_paths(repo: &Repository) -> AppResult<HashSet<String>> {
+    let index = current_index(repo)?;
+    Ok(index
+        .iter()
+        .filter(|entry| entry.flags_extended & SKIP_WORKTREE != 0)
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect())
+}
+
+/// Recursive directories of a cone-mode pattern file
+///
+/// Cone files list every parent as `/dir/` followed by `!/dir/*/`; only
+/// directories without the exclusion are checked out recursively.
+pub fn parse_cone_patterns(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let parents: HashSet<&str> = lines
+        .iter()
+        .filter_map(|line| line.strip_prefix('!')?.strip_suffix("*/"))
+        .collect();
+
+    lines
+        .iter()
+        .filter(|line| line.starts_with('/') && line.ends_with('/') && line.len() > 1)
+        .filter(|line| !parents.contains(*line))
+        .map(|line| unescape(line.trim_matches('/')))
+        .collect()
+}
+
+/// Cone-mode membership of a file: files in the root, in any listed directory
+/// and directly inside the parents of a listed directory are checked out
+pub fn cone_includes(directories: &[String], path: &str) -> bool {
+    let parent = path.rsplit_once('/').map_or("", |(parent, _)| parent);
+    parent.is_empty()
+        || directories
+            .iter()
+            .any(|dir| is_within(parent, dir) || is_within(dir, parent))
+}
+
+/// How the cone `directories` treats the directory `path`
+pub fn classify_directory(directories: &[String], path: &str) -> SparseInclusion {
+    if directories.iter().any(|dir| is_within(path, dir)) {
+        SparseInclusion::Included
+    } else if directories.iter().any(|dir| is_within(dir, path)) {
+        SparseInclusion::Partial
+    } else {
+        SparseInclusion::Excluded
+    }
+}
+
+/// Directories of the `HEAD` tree, depth first, classified against `directories`
+pub fn directory_tree(repo: &Repository, directories: &[String]) -> AppResult<Vec<SparseDirectory>> {
+    let tree = match repo.head() {
+        Ok(head) => head.peel_to_tree()?,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::Git(e)),
+    };
+
+    let mut result: Vec<SparseDirectory> = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(ObjectType::Tree) {
+            let path = format!("{}{}", root, entry.name().unwrap_or_default());
+            let depth = root.matches('/').count();
+            let inclusion = classify_directory(directories, &path);
+            result.push(SparseDirectory {
+                path,
+                depth,
+                has_children: false,
+                inclusion,
+            });
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let parents: HashSet<String> = result
+        .iter()
+        .filter_map(|dir| dir.path.rsplit_once('/').map(|(parent, _)| parent.to_string()))
+        .collect();
+    for dir in &mut result {
+        dir.has_children = parents.contains(&dir.path);
+    }
+
+    Ok(result)
+}
+
+/// Drop directories already covered by another listed directory
+pub fn normalize_directories(directories: &[String]) -> Vec<String> {
+    let mut sorted: Vec<String> = directories
+        .iter()
+        .map(|dir| dir.trim_matches('/').to_string())
+        .filter(|dir| !dir.is_empty())
+        .collect();
+    sorted.sort();
+    sorted.dedup();
+
+    sorted
+        .iter()
+        .filter(|dir| !sorted.iter().any(|other| other != *dir && is_within(dir, other)))
+        .cloned()
+        .collect()
+}
+
+/// Replace the cone with `directories` (`git sparse-checkout set --cone`)
+///
+/// Enables sparse checkout if needed; an empty list keeps only root files.
+pub fn set_directories(repo: &Repository, directories: &[String]) -> AppResult<()> {
+    let directories = normalize_directories(directories);
+    let mut args = vec!["sparse-checkout".to_string(), "set".to_string(), "--cone".to_string(), "--".to_string()];
+    args.extend(directories.iter().cloned());
+
+    run_git(repo, &args)?;
+    info!("Sparse checkout set to {} directories", directories.len());
+    Ok(())
+}
+
+/// Turn sparse checkout off and materialize every file
+pub fn disable(repo: &Repository) -> AppResult<()> {
+    run_git(repo, &["sparse-checkout".to_string(), "disable".to_string()])?;
+    info!("Sparse checkout disabled");
+    Ok(())
+}
+
+/// `path` equals `dir` or lies below it
+fn is_within(path: &str, dir: &str) -> bool {
+    path == dir || (path.starts_with(dir) && path.as_bytes().get(dir.len()) == Some(&b'/'))
+}
+
+/// Cone patterns escape glob characters with a backslash
+fn unescape(pattern: &str) -> String {
+    let mut result = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// The index as on disk; `git sparse-checkout` rewrites it behind libgit2's back
+fn current_index(repo: &Repository) -> AppResult<git2::Index> {
+    let mut index = repo.index()?;
+    index.read(true)?;
+    Ok(index)
+}
+
+fn sparse_file(repo: &Repository) -> PathBuf {
+    repo.path().join("info").join("sparse-checkout")
+}
+
+fn run_git(repo: &Repository, args: &[String]) -> AppResult<()> {
+    let workdir = repo
+        .workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| AppError::InvalidOperation("Bare repositories have no working tree".to_string()))?;
+
+    debug!("Running git {:?}", args);
+    let output = Command::new("git").args(args).current_dir(workdir).output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(AppError::application(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        for file in ["README.md", "apps/web/index.js", "apps/api/main.rs", "libs/core/lib.rs", "apps/notes.txt"] {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, file).unwrap();
+        }
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "init"]);
+        let repo = Repository::open(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    #[test]
+    fn test_cone_patterns() {
+        let content = "/*\n!/*/\n/apps/\n!/apps/*/\n/apps/web/\n/libs/\n";
+        let dirs = parse_cone_patterns(content);
+        assert_eq!(dirs, ["apps/web", "libs"]);
+
+        assert!(cone_includes(&dirs, "README.md"));
+        assert!(cone_includes(&dirs, "apps/notes.txt"));
+        assert!(cone_includes(&dirs, "apps/web/src/index.js"));
+        assert!(!cone_includes(&dirs, "apps/api/main.rs"));
+        assert!(!cone_includes(&dirs, "application/main.rs"));
+
+        let normalized = normalize_directories(&["libs/core".to_string(), "libs/".to_string(), "apps/web".to_string()]);
+        assert_eq!(normalized, ["apps/web", "libs"]);
+    }
+
+    #[test]
+    fn test_set_and_disable() {
+        let (dir, repo) = setup();
+        assert!(!sparse_checkout(&repo).unwrap().enabled);
+
+        set_directories(&repo, &["apps/web".to_string()]).unwrap();
+        let sparse = sparse_checkout(&repo).unwrap();
+        assert!(sparse.enabled && sparse.cone);
+        assert_eq!(sparse.directories, ["apps/web"]);
+        assert_eq!((sparse.materialized, sparse.total), (3, 5));
+        assert!(!dir.path().join("libs/core/lib.rs").exists());
+
+        let skipped = skip_worktree_paths(&repo).unwrap();
+        assert!(skipped.contains("libs/core/lib.rs") && skipped.contains("apps/api/main.rs"));
+
+        let tree = directory_tree(&repo, &sparse.directories).unwrap();
+        let find = |path: &str| tree.iter().find(|dir| dir.path == path).unwrap().clone();
+        assert_eq!(find("apps").inclusion, SparseInclusion::Partial);
+        assert!(find("apps").has_children);
+        assert_eq!(find("apps/web").inclusion, SparseInclusion::Included);
+        assert_eq!(find("libs/core").inclusion, SparseInclusion::Excluded);
+        assert_eq!(find("libs/core").depth, 1);
+
+        disable(&repo).unwrap();
+        let sparse = sparse_checkout(&repo).unwrap();
+        assert_eq!(sparse.materialized, sparse.total);
+        assert!(dir.path().join("libs/core/lib.rs").exists());
+    }
+}
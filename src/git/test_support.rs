@@ -0,0 +1,27 @@
+//! Helpers for tests that build repositories with the git CLI
+
+use std::{path::Path, process::Command};
+
+/// Run `git` in `dir` as `tester`, asserting success; returns stdout
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    git_as(dir, "tester", args)
+}
+
+/// Run `git` in `dir` with `author` as user name and a derived email
+pub fn git_as(dir: &Path, author: &str, args: &[&str]) -> String {
+    git_with_env(dir, author, args, &[])
+}
+
+/// Like [`git_as`], with extra environment such as `GIT_AUTHOR_DATE`
+pub fn git_with_env(dir: &Path, author: &str, args: &[&str], env: &[(&str, &str)]) -> String {
+    let email = format!("{}@example.com", author.to_lowercase().replace(' ', "."));
+    let output = Command::new("git")
+        .args(["-c", &format!("user.name={}", author), "-c", &format!("user.email={}", email)])
+        .args(args)
+        .envs(env.iter().copied())
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
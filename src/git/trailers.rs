@@ -0,0 +1,304 @@
+//! Commit message trailers
+//!
+//! Parses and edits the trailer block at the end of a commit message the
+//! way `git interpret-trailers` does: the last paragraph is a trailer block
+//! when every line is a `Token: value` trailer (or an indented continuation
+//! of one), or when at least a quarter of its lines are trailers and one of
+//! them was generated by git (`Signed-off-by`, `(cherry picked from ...)`).
+
+use git2::{Mailmap, Signature};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+
+use super::CommitInfo;
+
+pub const CO_AUTHORED_BY: &str = "Co-authored-by";
+pub const SIGNED_OFF_BY: &str = "Signed-off-by";
+
+/// Line prefixes git itself writes into trailer blocks
+const GIT_GENERATED_PREFIXES: &[&str] = &["Signed-off-by: ", "(cherry picked from commit "];
+
+/// One `Token: value` trailer
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Trailer {
+    pub token: String,
+    pub value: String,
+}
+
+impl Trailer {
+    pub fn new(token: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn co_author(identity: &Identity) -> Self {
+        Self::new(CO_AUTHORED_BY, identity.to_string())
+    }
+
+    pub fn signed_off_by(identity: &Identity) -> Self {
+        Self::new(SIGNED_OFF_BY, identity.to_string())
+    }
+
+    /// Parse `Token: value`; the token may not contain whitespace
+    pub fn parse(line: &str) -> Option<Self> {
+        let (token, value) = line.split_once(':')?;
+        let token = token.trim_end();
+        let valid = !token.is_empty() && token.chars().all(|c| c.is_alphanumeric() || c == '-');
+        valid.then(|| Self::new(token, value.trim()))
+    }
+
+    /// Tokens compare case-insensitively, values exactly
+    pub fn matches(&self, other: &Trailer) -> bool {
+        self.token.eq_ignore_ascii_case(&other.token) && self.value == other.value
+    }
+
+    pub fn is_token(&self, token: &str) -> bool {
+        self.token.eq_ignore_ascii_case(token)
+    }
+}
+
+impl fmt::Display for Trailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.token, self.value)
+    }
+}
+
+/// A `Name <email>` identity as used in co-author and sign-off trailers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+impl Identity {
+    pub fn new(name: impl Into<String>, email: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            email: email.into(),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let (name, rest) = value.split_once('<')?;
+        let email = rest.strip_suffix('>')?.trim();
+        let name = name.trim();
+        (!name.is_empty() && !email.is_empty()).then(|| Self::new(name, email))
+    }
+
+    /// Canonical identity according to `.mailmap`
+    pub fn resolve(&self, mailmap: &Mailmap) -> Self {
+        Signature::now(&self.name, &self.email)
+            .and_then(|signature| mailmap.resolve_signature(&signature))
+            .ok()
+            .and_then(|signature| Some(Self::new(signature.name()?, signature.email()?)))
+            .unwrap_or_else(|| self.clone())
+    }
+
+    fn key(&self) -> String {
+        self.email.to_lowercase()
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// Line range of the trailer block, `None` if the message has none
+fn trailer_block(lines: &[&str]) -> Option<(usize, usize)> {
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty() && !line.starts_with('#'))?
+        + 1;
+    let start = lines[..end]
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map_or(0, |index| index + 1);
+    // The subject is never a trailer block
+    if start == 0 {
+        return None;
+    }
+
+    let block: Vec<&str> = lines[start..end].iter().copied().filter(|line| !line.starts_with('#')).collect();
+    if block.first().is_some_and(|line| line.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    let mut trailers = 0;
+    let mut others = 0;
+    let mut git_generated = false;
+    for line in &block {
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        git_generated |= GIT_GENERATED_PREFIXES.iter().any(|prefix| line.starts_with(prefix));
+        if Trailer::parse(line).is_some() {
+            trailers += 1;
+        } else {
+            others += 1;
+        }
+    }
+
+    let is_block = trailers > 0 && (others == 0 || (git_generated && trailers * 3 >= others));
+    is_block.then_some((start, end))
+}
+
+/// Split a message into its text and its trailers; continuation lines are
+/// folded into the previous trailer's value
+pub fn split_trailers(message: &str) -> (String, Vec<Trailer>) {
+    let lines: Vec<&str> = message.lines().collect();
+    let Some((start, end)) = trailer_block(&lines) else {
+        return (message.trim_end().to_string(), Vec::new());
+    };
+
+    let mut trailers: Vec<Trailer> = Vec::new();
+    for line in &lines[start..end] {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            if let Some(last) = trailers.last_mut() {
+                last.value.push(' ');
+                last.value.push_str(line.trim());
+            }
+        } else if let Some(trailer) = Trailer::parse(line) {
+            trailers.push(trailer);
+        }
+    }
+
+    (lines[..start].join("\n").trim_end().to_string(), trailers)
+}
+
+pub fn parse_trailers(message: &str) -> Vec<Trailer> {
+    split_trailers(message).1
+}
+
+/// Join text and trailers with the blank line git expects between them
+pub fn format_message(text: &str, trailers: &[Trailer]) -> String {
+    let text = text.trim_end();
+    if trailers.is_empty() {
+        return text.to_string();
+    }
+    let block = trailers.iter().map(Trailer::to_string).collect::<Vec<_>>().join("\n");
+    if text.is_empty() {
+        block
+    } else {
+        format!("{}\n\n{}", text, block)
+    }
+}
+
+pub fn has_trailer(message: &str, trailer: &Trailer) -> bool {
+    parse_trailers(message).iter().any(|t| t.matches(trailer))
+}
+
+/// Append a trailer unless an identical one exists (`--if-exists addIfDifferent`)
+pub fn add_trailer(message: &str, trailer: &Trailer) -> String {
+    let (text, mut trailers) = split_trailers(message);
+    if !trailers.iter().any(|t| t.matches(trailer)) {
+        trailers.push(trailer.clone());
+    }
+    format_message(&text, &trailers)
+}
+
+pub fn remove_trailer(message: &str, trailer: &Trailer) -> String {
+    let (text, mut trailers) = split_trailers(message);
+    trailers.retain(|t| !t.matches(trailer));
+    format_message(&text, &trailers)
+}
+
+/// Recent commit authors and co-authors, newest first and deduplicated by
+/// email after `.mailmap` resolution, without `exclude` (usually oneself)
+pub fn recent_coauthors(
+    commits: &[CommitInfo],
+    mailmap: Option<&Mailmap>,
+    exclude: Option<&Identity>,
+    limit: usize,
+) -> Vec<Identity> {
+    let resolve = |identity: Identity| match mailmap {
+        Some(mailmap) => identity.resolve(mailmap),
+        None => identity,
+    };
+
+    let mut seen: HashSet<String> = exclude.map(|identity| resolve(identity.clone()).key()).into_iter().collect();
+    let mut identities = Vec::new();
+    for commit in commits {
+        let co_authors = parse_trailers(&commit.message)
+            .into_iter()
+            .filter(|trailer| trailer.is_token(CO_AUTHORED_BY))
+            .filter_map(|trailer| Identity::parse(&trailer.value));
+        for identity in std::iter::once(Identity::new(&commit.author, &commit.author_email)).chain(co_authors) {
+            let identity = resolve(identity);
+            if !identity.email.is_empty() && seen.insert(identity.key()) {
+                identities.push(identity);
+                if identities.len() >= limit {
+                    return identities;
+                }
+            }
+        }
+    }
+    identities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_trailers() {
+        let message = "feat: pairing\n\nBody text.\n\nCo-authored-by: Ada <ada@example.com>\nRefs: #12\n  and #13\n";
+        let (text, trailers) = split_trailers(message);
+        assert_eq!(text, "feat: pairing\n\nBody text.");
+        assert_eq!(trailers[0], Trailer::new(CO_AUTHORED_BY, "Ada <ada@example.com>"));
+        assert_eq!(trailers[1].value, "#12 and #13");
+
+        // A body paragraph is not a trailer block, nor is the subject
+        assert!(parse_trailers("fix: x\n\nThis is: a sentence\nnot trailers").is_empty());
+        assert!(parse_trailers("Subject: with colon").is_empty());
+
+        // Mixed blocks need a git-generated trailer
+        let mixed = "fix: x\n\nsome note\nmore note\nSigned-off-by: Bo <bo@example.com>";
+        assert_eq!(parse_trailers(mixed).len(), 1);
+    }
+
+    #[test]
+    fn test_add_and_remove_trailer() {
+        let ada = Trailer::co_author(&Identity::new("Ada", "ada@example.com"));
+        let message = add_trailer("fix: x\n\nBody.", &ada);
+        assert_eq!(message, "fix: x\n\nBody.\n\nCo-authored-by: Ada <ada@example.com>");
+        assert_eq!(add_trailer(&message, &ada), message);
+
+        let signed = Trailer::signed_off_by(&Identity::new("Bo", "bo@example.com"));
+        let message = add_trailer(&message, &signed);
+        assert!(message.ends_with("ada@example.com>\nSigned-off-by: Bo <bo@example.com>"));
+        assert_eq!(remove_trailer(&message, &ada), "fix: x\n\nBody.\n\nSigned-off-by: Bo <bo@example.com>");
+        assert_eq!(add_trailer("fix: x", &ada), "fix: x\n\nCo-authored-by: Ada <ada@example.com>");
+    }
+
+    #[test]
+    fn test_recent_coauthors() {
+        let commit = |author: &str, email: &str, message: &str| CommitInfo {
+            hash: String::new(),
+            short_hash: String::new(),
+            message: message.to_string(),
+            author: author.to_string(),
+            author_email: email.to_string(),
+            date: chrono::Utc::now(),
+            parents: Vec::new(),
+            path: None,
+            previous_path: None,
+        };
+        let commits = vec![
+            commit("Me", "me@example.com", "fix: a\n\nCo-authored-by: Ada <ada@example.com>"),
+            commit("Ada L", "ADA@example.com", "feat: b"),
+            commit("Old Bo", "bo@old.example.com", "chore: c"),
+        ];
+        let mailmap = Mailmap::from_buffer("Bo <bo@example.com> <bo@old.example.com>").unwrap();
+        let me = Identity::new("Me", "me@example.com");
+
+        let identities = recent_coauthors(&commits, Some(&mailmap), Some(&me), 10);
+        assert_eq!(identities, vec![Identity::new("Ada", "ada@example.com"), Identity::new("Bo", "bo@example.com")]);
+        assert_eq!(recent_coauthors(&commits, None, None, 1).len(), 1);
+    }
+}
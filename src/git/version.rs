@@ -0,0 +1,647 @@
+//! Semantic version suggestions
+//!
+//! Finds the latest version tag, derives the next version from the
+//! conventional commits since that tag (breaking → major, `feat` → minor,
+//! `fix`/`perf` → patch) and rewrites the version field of the manifests in
+//! the repository root.
+
+use git2::{Oid, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt, path::Path};
+
+use crate::{
+    error::AppResult,
+    git::conventional::ConventionalCommit,
+};
+
+/// Identifier used for prerelease suggestions when none is configured
+pub const DEFAULT_PRERELEASE: &str = "rc";
+
+/// A semantic version (`1.4.0-rc.2+build.5`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Dot-separated prerelease identifiers, empty for releases
+    pub pre: Vec<String>,
+    pub build: Option<String>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self { major, minor, patch, pre: Vec::new(), build: None }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let (text, build) = match text.split_once('+') {
+            Some((text, build)) if is_identifier_list(build) => (text, Some(build.to_string())),
+            Some(_) => return None,
+            None => (text, None),
+        };
+        let (core, pre) = match text.split_once('-') {
+            Some((core, pre)) if is_identifier_list(pre) => (core, pre.split('.').map(str::to_string).collect()),
+            Some(_) => return None,
+            None => (text, Vec::new()),
+        };
+
+        let mut numbers = core.split('.').map(|part| {
+            let valid = !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) && (part == "0" || !part.starts_with('0'));
+            valid.then(|| part.parse::<u64>().ok()).flatten()
+        });
+        let (major, minor, patch) = (numbers.next()??, numbers.next()??, numbers.next()??);
+        if numbers.next().is_some() {
+            return None;
+        }
+
+        Some(Self { major, minor, patch, pre, build })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// The release this version leads up to (prerelease and build dropped)
+    pub fn release(&self) -> Self {
+        Self::new(self.major, self.minor, self.patch)
+    }
+
+    /// Increment the release version by `level`
+    pub fn bump(&self, level: BumpLevel) -> Self {
+        match level {
+            BumpLevel::Major => Self::new(self.major + 1, 0, 0),
+            BumpLevel::Minor => Self::new(self.major, self.minor + 1, 0),
+            BumpLevel::Patch => Self::new(self.major, self.minor, self.patch + 1),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Semver precedence; build metadata is ignored
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_prerelease(&self.pre, &other.pre),
+            })
+    }
+}
+
+fn compare_prerelease(a: &[String], b: &[String]) -> Ordering {
+    for (left, right) in a.iter().zip(b) {
+        let ordering = match (left.parse::<u64>(), right.parse::<u64>()) {
+            (Ok(left), Ok(right)) => left.cmp(&right),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => left.cmp(right),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+fn is_identifier_list(text: &str) -> bool {
+    text.split('.')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+}
+
+/// Size of a version increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BumpLevel {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl BumpLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BumpLevel::Major => "major",
+            BumpLevel::Minor => "minor",
+            BumpLevel::Patch => "patch",
+        }
+    }
+}
+
+/// How version tags are named and whether a prerelease is wanted
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VersionOptions {
+    /// Tag prefix such as `v` or `app/v`; `None` uses `gitflow.prefix.versiontag`
+    /// and otherwise accepts `v` and bare versions
+    pub tag_prefix: Option<String>,
+    /// Prerelease identifier (`rc`, `beta`) to suggest a prerelease
+    pub prerelease: Option<String>,
+    /// Revision whose history is inspected
+    pub target: Option<String>,
+}
+
+/// A tag that parses as a version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionTag {
+    pub name: String,
+    pub prefix: String,
+    pub version: Version,
+}
+
+/// Suggested next version with the reasoning behind it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionSuggestion {
+    /// Latest version tag, `None` for the first release
+    pub latest: Option<VersionTag>,
+    pub level: BumpLevel,
+    pub next: Version,
+    /// Tag name for `next`, using the latest tag's prefix
+    pub tag_name: String,
+    pub commit_count: usize,
+    pub breaking: usize,
+    pub features: usize,
+    pub fixes: usize,
+}
+
+impl VersionSuggestion {
+    /// One-line explanation for prompts
+    pub fn describe(&self) -> String {
+        let since = match &self.latest {
+            Some(tag) => format!("{} commits since {}", self.commit_count, tag.name),
+            None => format!("{} commits, no version tag yet", self.commit_count),
+        };
+        format!(
+            "{} ({} breaking, {} feat, {} fix) → {} bump",
+            since,
+            self.breaking,
+            self.features,
+            self.fixes,
+            self.level.label()
+        )
+    }
+}
+
+/// Parse `name` as a version tag using `prefix` (see [`VersionOptions::tag_prefix`])
+pub fn parse_version_tag(name: &str, prefix: Option<&str>) -> Option<VersionTag> {
+    let candidates: &[&str] = match prefix {
+        Some(prefix) => &[prefix][..],
+        None => &["v", ""][..],
+    };
+    candidates.iter().find_map(|prefix| {
+        let version = Version::parse(name.strip_prefix(prefix)?)?;
+        Some(VersionTag {
+            name: name.to_string(),
+            prefix: prefix.to_string(),
+            version,
+        })
+    })
+}
+
+/// Highest version tag on the history of `target`
+///
+/// Tags on other lines, such as a maintenance branch, are skipped like
+/// `git describe` does, so the walk from the tag counts only `target`'s commits.
+pub fn latest_version_tag(repo: &Repository, prefix: Option<&str>, target: Oid) -> AppResult<Option<VersionTag>> {
+    let names = repo.tag_names(None)?;
+    let mut latest: Option<VersionTag> = None;
+    for tag in names.iter().flatten().filter_map(|name| parse_version_tag(name, prefix)) {
+        if latest.as_ref().is_some_and(|latest| latest.version >= tag.version) {
+            continue;
+        }
+        let tagged = repo
+            .revparse_single(&format!("refs/tags/{}", tag.name))
+            .and_then(|object| object.peel_to_commit());
+        let Ok(tagged) = tagged else {
+            continue;
+        };
+        if tagged.id() == target || repo.graph_descendant_of(target, tagged.id())? {
+            latest = Some(tag);
+        }
+    }
+    Ok(latest)
+}
+
+/// Version tag prefix configured for git-flow (`gitflow.prefix.versiontag`)
+pub fn configured_tag_prefix(repo: &Repository) -> Option<String> {
+    repo.config().ok()?.get_string("gitflow.prefix.versiontag").ok()
+}
+
+/// Suggest the next version from the commits since the latest version tag
+pub fn suggest_version(repo: &Repository, options: &VersionOptions) -> AppResult<VersionSuggestion> {
+    let tag_prefix = options.tag_prefix.clone().or_else(|| configured_tag_prefix(repo));
+    let target = repo
+        .revparse_single(options.target.as_deref().unwrap_or("HEAD"))?
+        .peel_to_commit()?;
+    let latest = latest_version_tag(repo, tag_prefix.as_deref(), target.id())?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+    revwalk.push(target.id())?;
+    if let Some(tag) = &latest {
+        let tagged = repo.revparse_single(&format!("refs/tags/{}", tag.name))?.peel_to_commit()?;
+        revwalk.hide(tagged.id())?;
+    }
+
+    let (mut commit_count, mut breaking, mut features, mut fixes) = (0, 0, 0, 0);
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        commit_count += 1;
+        if let Some(parsed) = ConventionalCommit::parse(commit.message().unwrap_or_default()) {
+            if parsed.breaking {
+                breaking += 1;
+            }
+            match parsed.commit_type.as_str() {
+                "feat" => features += 1,
+                "fix" | "perf" => fixes += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let base = latest.as_ref().map(|tag| tag.version.clone()).unwrap_or_else(|| Version::new(0, 0, 0));
+    let level = if breaking > 0 {
+        BumpLevel::Major
+    } else if features > 0 {
+        BumpLevel::Minor
+    } else {
+        BumpLevel::Patch
+    };
+    let next = next_version(&base, level, options.prerelease.as_deref());
+
+    let prefix = match (tag_prefix, &latest) {
+        (Some(prefix), _) => prefix,
+        (None, Some(tag)) => tag.prefix.clone(),
+        (None, None) => "v".to_string(),
+    };
+
+    Ok(VersionSuggestion {
+        tag_name: format!("{}{}", prefix, next),
+        latest,
+        level,
+        next,
+        commit_count,
+        breaking,
+        features,
+        fixes,
+    })
+}
+
+/// Next version after `current` for a change of size `level`
+///
+/// A prerelease of `current` already contains every bump up to the level
+/// its release version implies (`2.0.0-rc.1` covers major), in which case
+/// only the prerelease counter advances or the prerelease is released.
+pub fn next_version(current: &Version, level: BumpLevel, prerelease: Option<&str>) -> Version {
+    let covered = current.is_prerelease() && level <= implied_level(current);
+    let release = if covered { current.release() } else { current.bump(level) };
+
+    match prerelease {
+        None => release,
+        Some(identifier) => {
+            let counter = match current.pre.as_slice() {
+                [id, n] if covered && id == identifier => n.parse::<u64>().map_or(1, |n| n + 1),
+                _ => 1,
+            };
+            Version {
+                pre: vec![identifier.to_string(), counter.to_string()],
+                ..release
+            }
+        }
+    }
+}
+
+/// Largest bump already present in a prerelease's release version
+fn implied_level(version: &Version) -> BumpLevel {
+    if version.minor == 0 && version.patch == 0 {
+        BumpLevel::Major
+    } else if version.patch == 0 {
+        BumpLevel::Minor
+    } else {
+        BumpLevel::Patch
+    }
+}
+
+/// Manifests whose version field is updated on release
+const MANIFESTS: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml"];
+
+/// Set the version field of the manifests in `workdir`
+///
+/// Edits are textual so formatting and comments survive. When `Cargo.toml`
+/// names its package, the matching entry of `Cargo.lock` is updated too so
+/// the release commit builds with `--locked`. Returns the files that changed.
+pub fn bump_manifests(workdir: &Path, version: &Version) -> AppResult<Vec<String>> {
+    let version = version.to_string();
+    let mut changed = Vec::new();
+
+    for name in MANIFESTS {
+        let path = workdir.join(name);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let updated = match *name {
+            "package.json" => set_json_version(&content, &version),
+            "Cargo.toml" => set_toml_version(&content, &["package", "workspace.package"], &version),
+            _ => set_toml_version(&content, &["project", "tool.poetry"], &version),
+        };
+        if let Some(updated) = updated.filter(|updated| *updated != content) {
+            std::fs::write(&path, updated)?;
+            changed.push(name.to_string());
+        }
+    }
+
+    let lock_path = workdir.join("Cargo.lock");
+    let package = std::fs::read_to_string(workdir.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| toml_package_name(&content));
+    if let (Some(package), Ok(content)) = (package, std::fs::read_to_string(&lock_path)) {
+        if let Some(updated) = set_lock_version(&content, &package, &version).filter(|updated| *updated != content) {
+            std::fs::write(&lock_path, updated)?;
+            changed.push("Cargo.lock".to_string());
+        }
+    }
+
+    Ok(changed)
+}
+
+/// `name` of the `[package]` section of a Cargo manifest
+fn toml_package_name(content: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_package = trimmed == "[package]";
+        } else if in_package {
+            if let Some((key, value)) = trimmed.split_once('=') {
+                if key.trim() == "name" {
+                    return Some(value.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Replace the version of the `[[package]]` entry called `name` in a lockfile
+///
+/// Entries from a registry carry a `source` line and are left alone, so a
+/// dependency that shares the name is never touched.
+fn set_lock_version(content: &str, name: &str, version: &str) -> Option<String> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut start = 0;
+
+    while start < lines.len() {
+        if lines[start].trim() != "[[package]]" {
+            start += 1;
+            continue;
+        }
+        let end = (start + 1..lines.len())
+            .find(|&i| lines[i].trim().starts_with('['))
+            .unwrap_or(lines.len());
+        let entry = &lines[start + 1..end];
+        let field = |key: &str| {
+            entry.iter().position(|line| {
+                line.split_once('=').is_some_and(|(k, _)| k.trim() == key)
+            })
+        };
+        let is_local = field("source").is_none();
+        let named = field("name").is_some_and(|i| entry[i].split_once('=').unwrap().1.trim() == format!("\"{}\"", name));
+        if let (true, true, Some(index)) = (is_local, named, field("version")) {
+            let mut out: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+            let newline = if entry[index].ends_with('\n') { "\n" } else { "" };
+            out[start + 1 + index] = format!("version = \"{}\"{}", version, newline);
+            return Some(out.concat());
+        }
+        start = end;
+    }
+
+    None
+}
+
+/// Replace `version = "..."` in the first of `sections` that declares it
+fn set_toml_version(content: &str, sections: &[&str], version: &str) -> Option<String> {
+    let mut section = String::new();
+    let mut out = Vec::new();
+    let mut done = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = trimmed.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+        } else if !done && sections.contains(&section.as_str()) {
+            if let Some((key, value)) = trimmed.split_once('=') {
+                let value = value.trim();
+                if key.trim() == "version" && value.starts_with('"') {
+                    let start = line.find('"')?;
+                    let end = start + 1 + line[start + 1..].find('"')?;
+                    out.push(format!("{}{}{}", &line[..start + 1], version, &line[end..]));
+                    done = true;
+                    continue;
+                }
+            }
+        }
+        out.push(line.to_string());
+    }
+
+    done.then(|| out.concat())
+}
+
+/// Replace the value of the top-level `"version"` key of a JSON document
+fn set_json_version(content: &str, version: &str) -> Option<String> {
+    let bytes = content.as_bytes();
+    let mut depth = 0usize;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            b'"' => {
+                let end = string_end(bytes, index)?;
+                if depth == 1 && &content[index..=end] == "\"version\"" {
+                    let rest = &content[end + 1..];
+                    let colon = rest.find(':')?;
+                    let value = rest[colon + 1..].trim_start();
+                    if !rest[..colon].trim().is_empty() || !value.starts_with('"') {
+                        return None;
+                    }
+                    let value_start = content.len() - value.len();
+                    let value_end = string_end(bytes, value_start)?;
+                    return Some(format!("{}\"{}\"{}", &content[..value_start], version, &content[value_end + 1..]));
+                }
+                index = end;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    None
+}
+
+/// Index of the quote closing the string that starts at `start`
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' => return Some(index),
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use tempfile::TempDir;
+
+    fn version(text: &str) -> Version {
+        Version::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_order() {
+        assert_eq!(version("1.2.3-rc.1+build.7").to_string(), "1.2.3-rc.1+build.7");
+        assert!(Version::parse("1.2").is_none());
+        assert!(Version::parse("01.2.3").is_none());
+        assert!(Version::parse("1.2.3-").is_none());
+
+        let mut versions = ["1.0.0", "1.0.0-rc.1", "1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-rc.11", "1.0.0-rc.2", "0.9.9"]
+            .map(version);
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(Version::to_string).collect();
+        assert_eq!(sorted, ["0.9.9", "1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-rc.1", "1.0.0-rc.2", "1.0.0-rc.11", "1.0.0"]);
+
+        assert_eq!(parse_version_tag("app/v2.0.0", Some("app/v")).unwrap().version, version("2.0.0"));
+        assert_eq!(parse_version_tag("v1.0.0", None).unwrap().prefix, "v");
+        assert_eq!(parse_version_tag("1.0.0", None).unwrap().prefix, "");
+        assert!(parse_version_tag("v1.0.0", Some("app/v")).is_none());
+    }
+
+    #[test]
+    fn test_next_version() {
+        assert_eq!(next_version(&version("1.2.3"), BumpLevel::Minor, None), version("1.3.0"));
+        assert_eq!(next_version(&version("1.2.3"), BumpLevel::Major, Some("rc")), version("2.0.0-rc.1"));
+        assert_eq!(next_version(&version("2.0.0-rc.1"), BumpLevel::Minor, Some("rc")), version("2.0.0-rc.2"));
+        assert_eq!(next_version(&version("2.0.0-rc.3"), BumpLevel::Patch, None), version("2.0.0"));
+        assert_eq!(next_version(&version("2.0.0-beta.3"), BumpLevel::Patch, Some("rc")), version("2.0.0-rc.1"));
+        assert_eq!(next_version(&version("1.2.4-rc.1"), BumpLevel::Minor, Some("rc")), version("1.3.0-rc.1"));
+    }
+
+    #[test]
+    fn test_suggest_version() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "feat: initial"]);
+        git(dir.path(), &["tag", "release-1.4.2"]);
+        git(dir.path(), &["tag", "release-1.4.10"]);
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "fix: crash"]);
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let options = VersionOptions { tag_prefix: Some("release-".to_string()), ..Default::default() };
+        let suggestion = suggest_version(&repo, &options).unwrap();
+        assert_eq!(suggestion.latest.as_ref().unwrap().name, "release-1.4.10");
+        assert_eq!(suggestion.level, BumpLevel::Patch);
+        assert_eq!(suggestion.tag_name, "release-1.4.11");
+
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "feat(api)!: drop v1 endpoints"]);
+        let suggestion = suggest_version(&repo, &options).unwrap();
+        assert_eq!(suggestion.commit_count, 2);
+        assert_eq!(suggestion.level, BumpLevel::Major);
+        assert_eq!(suggestion.next, version("2.0.0"));
+
+        let suggestion = suggest_version(&repo, &VersionOptions::default()).unwrap();
+        assert!(suggestion.latest.is_none());
+        assert_eq!(suggestion.tag_name, "v1.0.0");
+
+        git(dir.path(), &["tag", "v0.3.1"]);
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "refactor!: rename config keys"]);
+        let suggestion = suggest_version(&repo, &VersionOptions::default()).unwrap();
+        assert_eq!(suggestion.level, BumpLevel::Major);
+        assert_eq!(suggestion.tag_name, "v1.0.0");
+    }
+
+    #[test]
+    fn test_suggest_version_skips_tags_off_the_target_history() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q", "-b", "main"]);
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "feat!: first release"]);
+        git(dir.path(), &["tag", "v1.0.0"]);
+        git(dir.path(), &["checkout", "-q", "-b", "maint"]);
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "fix: backport"]);
+        git(dir.path(), &["tag", "v1.5.1"]);
+        git(dir.path(), &["checkout", "-q", "main"]);
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "fix: typo"]);
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let suggestion = suggest_version(&repo, &VersionOptions::default()).unwrap();
+        assert_eq!(suggestion.latest.as_ref().unwrap().name, "v1.0.0");
+        assert_eq!(suggestion.commit_count, 1);
+        assert_eq!(suggestion.level, BumpLevel::Patch);
+        assert_eq!(suggestion.tag_name, "v1.0.1");
+
+        let maint = VersionOptions { target: Some("maint".to_string()), ..Default::default() };
+        assert_eq!(suggest_version(&repo, &maint).unwrap().latest.unwrap().name, "v1.5.1");
+    }
+
+    #[test]
+    fn test_bump_manifests() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\" # keep\n\n[dependencies]\nserde = { version = \"1\" }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            "{\n  \"name\": \"demo\",\n  \"config\": { \"version\": \"x\" },\n  \"version\": \"0.1.0\"\n}\n",
+        )
+        .unwrap();
+
+        std::fs::write(
+            dir.path().join("Cargo.lock"),
+            "version = 3\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\ndependencies = [\n \"serde\",\n]\n\n\
+             [[package]]\nname = \"demo\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+        )
+        .unwrap();
+
+        let changed = bump_manifests(dir.path(), &version("0.2.0-rc.1")).unwrap();
+        assert_eq!(changed, ["Cargo.toml", "package.json", "Cargo.lock"]);
+
+        let cargo = std::fs::read_to_string(dir.path().join("Cargo.toml")).unwrap();
+        assert!(cargo.contains("version = \"0.2.0-rc.1\" # keep\n"));
+        assert!(cargo.contains("serde = { version = \"1\" }"));
+        let package = std::fs::read_to_string(dir.path().join("package.json")).unwrap();
+        assert!(package.contains("\"config\": { \"version\": \"x\" }"));
+        assert!(package.contains("\"version\": \"0.2.0-rc.1\"\n"));
+        let lock = std::fs::read_to_string(dir.path().join("Cargo.lock")).unwrap();
+        assert!(lock.contains("name = \"demo\"\nversion = \"0.2.0-rc.1\"\ndependencies"));
+        assert!(lock.contains("version = \"0.1.0\"\nsource"));
+
+        assert!(bump_manifests(dir.path(), &version("0.2.0-rc.1")).unwrap().is_empty());
+    }
+}
@@ -44,8 +44,11 @@ pub enum TagType {
 pub struct RemoteInfo {
     pub name: String,
     pub url: String,
+    pub push_url: Option<String>, // 单独配置的push URL（remote.<name>.pushurl）
     pub fetch_refs: Vec<String>,
     pub push_refs: Vec<String>,
+    pub tracking_branches: Vec<String>,
+    pub last_fetch: Option<chrono::DateTime<chrono::Utc>>,
     pub is_connected: bool,
 }
 
@@ -110,8 +113,13 @@ pub trait TagManager: Send + Sync {
 pub trait RemoteManager: Send + Sync {
     async fn list_remotes(&self) -> Result<Vec<RemoteInfo>, GitError>;
     async fn add_remote(&self, name: &str, url: &str) -> Result<RemoteInfo, GitError>;
+    async fn add_remote_with_fetch(&self, name: &str, url: &str, fetch_refspecs: &[String]) -> Result<RemoteInfo, GitError>;
+    async fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<Vec<String>, GitError>;
     async fn remove_remote(&self, name: &str) -> Result<(), GitError>;
     async fn set_remote_url(&self, name: &str, url: &str) -> Result<(), GitError>;
+    async fn set_remote_push_url(&self, name: &str, url: Option<&str>) -> Result<(), GitError>;
+    async fn stale_tracking_branches(&self, remote: &str) -> Result<Vec<String>, GitError>;
+    async fn prune_remote(&self, remote: &str) -> Result<Vec<String>, GitError>;
     async fn fetch(&self, remote: &str, refs: Option<Vec<String>>) -> Result<(), GitError>;
     async fn push(&self, remote: &str, refs: Vec<String>, force: bool) -> Result<(), GitError>;
     async fn pull(&self, remote: &str, branch: &str, strategy: MergeStrategy) -> Result<(), GitError>;
@@ -711,6 +719,103 @@ impl DefaultRemoteManager {
     pub fn new(git_service: Arc<GitService>) -> Self {
         Self { git_service }
     }
+
+    /// 构建远程信息（refspec、push URL、跟踪分支和最后fetch时间）
+    fn build_remote_info(repo: &Repository, remote: &git2::Remote) -> Result<RemoteInfo, GitError> {
+        let name = remote.name().unwrap_or("").to_string();
+        let fetch_refs = remote.fetch_refspecs()?.iter().flatten().map(|s| s.to_string()).collect();
+        let push_refs = remote.push_refspecs()?.iter().flatten().map(|s| s.to_string()).collect();
+
+        let mut tracking_branches = Vec::new();
+        let mut last_fetch: Option<chrono::DateTime<chrono::Utc>> = None;
+
+        for reference in repo.references_glob(&format!("refs/remotes/{}/*", name))? {
+            let reference = reference?;
+            let Some(ref_name) = reference.name() else { continue };
+            if ref_name.ends_with("/HEAD") {
+                continue;
+            }
+
+            // 最后一次fetch时间取自远程跟踪分支reflog的最新条目
+            if let Ok(reflog) = repo.reflog(ref_name) {
+                if let Some(entry) = reflog.get(0) {
+                    let updated = chrono::DateTime::from_timestamp(entry.committer().when().seconds(), 0);
+                    if updated > last_fetch {
+                        last_fetch = updated;
+                    }
+                }
+            }
+
+            tracking_branches.push(ref_name.trim_start_matches("refs/remotes/").to_string());
+        }
+        tracking_branches.sort();
+
+        Ok(RemoteInfo {
+            name,
+            url: remote.url().unwrap_or("").to_string(),
+            push_url: remote.pushurl().map(|s| s.to_string()),
+            fetch_refs,
+            push_refs,
+            tracking_branches,
+            last_fetch,
+            is_connected: false, // 需要测试连接
+        })
+    }
+
+    /// 远程认证回调：SSH agent、凭据助手或默认凭据
+    pub(crate) fn remote_callbacks(repo: &Repository) -> git2::RemoteCallbacks<'static> {
+        let config = repo.config().ok();
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(move |url, username, allowed| {
+            if allowed.contains(git2::CredentialType::SSH_KEY) {
+                git2::Cred::ssh_key_from_agent(username.unwrap_or("git"))
+            } else if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+                match &config {
+                    Some(config) => git2::Cred::credential_helper(config, url, username),
+                    None => git2::Cred::default(),
+                }
+            } else {
+                git2::Cred::default()
+            }
+        });
+        callbacks
+    }
+
+    /// 查找远端已不存在的远程跟踪分支
+    fn find_stale_tracking_refs(repo: &Repository, remote_name: &str) -> Result<Vec<String>, GitError> {
+        let mut remote = repo.find_remote(remote_name)?;
+        let connection = remote.connect_auth(git2::Direction::Fetch, Some(Self::remote_callbacks(repo)), None)?;
+        let advertised: std::collections::HashSet<String> = connection
+            .list()?
+            .iter()
+            .map(|head| head.name().to_string())
+            .collect();
+        drop(connection);
+
+        let mut stale = Vec::new();
+        for reference in repo.references_glob(&format!("refs/remotes/{}/*", remote_name))? {
+            let reference = reference?;
+            let Some(ref_name) = reference.name() else { continue };
+            if ref_name.ends_with("/HEAD") {
+                continue;
+            }
+
+            // 通过fetch refspec反向映射到远端引用名
+            let source = remote
+                .refspecs()
+                .filter(|spec| spec.direction() == git2::Direction::Fetch && spec.dst_matches(ref_name))
+                .find_map(|spec| spec.rtransform(ref_name).ok())
+                .and_then(|buf| buf.as_str().map(|s| s.to_string()));
+
+            if let Some(source) = source {
+                if !advertised.contains(&source) {
+                    stale.push(ref_name.to_string());
+                }
+            }
+        }
+
+        Ok(stale)
+    }
 }
 
 #[async_trait]
@@ -719,18 +824,9 @@ impl RemoteManager for DefaultRemoteManager {
         let repo = self.git_service.get_repository()?;
         let mut remotes = Vec::new();
 
-        for remote_name in repo.remotes()?.iter() {
-            if let Some(name) = remote_name {
-                if let Ok(remote) = repo.find_remote(name) {
-                    let remote_info = RemoteInfo {
-                        name: name.to_string(),
-                        url: remote.url().unwrap_or("").to_string(),
-                        fetch_refs: vec![], // 需要进一步解析
-                        push_refs: vec![],  // 需要进一步解析
-                        is_connected: false, // 需要测试连接
-                    };
-                    remotes.push(remote_info);
-                }
+        for remote_name in repo.remotes()?.iter().flatten() {
+            if let Ok(remote) = repo.find_remote(remote_name) {
+                remotes.push(Self::build_remote_info(&repo, &remote)?);
             }
         }
 
@@ -738,21 +834,48 @@ impl RemoteManager for DefaultRemoteManager {
     }
 
     async fn add_remote(&self, name: &str, url: &str) -> Result<RemoteInfo, GitError> {
+        self.add_remote_with_fetch(name, url, &[]).await
+    }
+
+    async fn add_remote_with_fetch(&self, name: &str, url: &str, fetch_refspecs: &[String]) -> Result<RemoteInfo, GitError> {
         let repo = self.git_service.get_repository()?;
-        repo.remote(name, url)?;
 
-        let remote_info = RemoteInfo {
-            name: name.to_string(),
-            url: url.to_string(),
-            fetch_refs: vec![],
-            push_refs: vec![],
-            is_connected: false,
+        // 未指定refspec时使用默认的 +refs/heads/*:refs/remotes/<name>/*
+        let remote = match fetch_refspecs.split_first() {
+            Some((first, rest)) => {
+                repo.remote_with_fetch(name, url, first)?;
+                for refspec in rest {
+                    repo.remote_add_fetch(name, refspec)?;
+                }
+                repo.find_remote(name)?
+            }
+            None => repo.remote(name, url)?,
         };
 
+        let remote_info = Self::build_remote_info(&repo, &remote)?;
+
         info!("Added remote: {} -> {}", name, url);
         Ok(remote_info)
     }
 
+    async fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<Vec<String>, GitError> {
+        let repo = self.git_service.get_repository()?;
+
+        // 返回无法自动重命名的非默认refspec
+        let problems: Vec<String> = repo
+            .remote_rename(old_name, new_name)?
+            .iter()
+            .flatten()
+            .map(|s| s.to_string())
+            .collect();
+
+        if !problems.is_empty() {
+            warn!("Remote {} renamed with refspecs left untouched: {:?}", old_name, problems);
+        }
+        info!("Renamed remote: {} -> {}", old_name, new_name);
+        Ok(problems)
+    }
+
     async fn remove_remote(&self, name: &str) -> Result<(), GitError> {
         let repo = self.git_service.get_repository()?;
         repo.remote_delete(name)?;
@@ -767,9 +890,41 @@ impl RemoteManager for DefaultRemoteManager {
         Ok(())
     }
 
-    async fn fetch(&self, _remote: &str, _refs: Option<Vec<String>>) -> Result<(), GitError> {
-        // 获取远程分支的实现
-        info!("Fetching from remote (not implemented)");
+    async fn set_remote_push_url(&self, name: &str, url: Option<&str>) -> Result<(), GitError> {
+        let repo = self.git_service.get_repository()?;
+        repo.remote_set_pushurl(name, url)?;
+        info!("Set remote push URL: {} -> {:?}", name, url);
+        Ok(())
+    }
+
+    async fn stale_tracking_branches(&self, remote: &str) -> Result<Vec<String>, GitError> {
+        let repo = self.git_service.get_repository()?;
+        Self::find_stale_tracking_refs(&repo, remote)
+    }
+
+    async fn prune_remote(&self, remote: &str) -> Result<Vec<String>, GitError> {
+        let repo = self.git_service.get_repository()?;
+        let stale = Self::find_stale_tracking_refs(&repo, remote)?;
+
+        for ref_name in &stale {
+            repo.find_reference(ref_name)?.delete()?;
+            debug!("Pruned stale tracking branch: {}", ref_name);
+        }
+
+        info!("Pruned {} stale tracking branches from {}", stale.len(), remote);
+        Ok(stale.iter().map(|r| r.trim_start_matches("refs/remotes/").to_string()).collect())
+    }
+
+    async fn fetch(&self, remote: &str, refs: Option<Vec<String>>) -> Result<(), GitError> {
+        let repo = self.git_service.get_repository()?;
+        let mut git_remote = repo.find_remote(remote)?;
+
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(Self::remote_callbacks(&repo));
+
+        let refspecs = refs.unwrap_or_default();
+        git_remote.fetch(&refspecs, Some(&mut fetch_options), None)?;
+        info!("Fetched from remote: {}", remote);
         Ok(())
     }
 
@@ -790,4 +945,86 @@ impl RemoteManager for DefaultRemoteManager {
         info!("Testing remote connection (not implemented)");
         Ok(false)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GitConfig;
+    use crate::git::test_support::git;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// A clone of an origin with `main` and `topic`, and its remote manager
+    fn clone_with_origin(root: &Path) -> DefaultRemoteManager {
+        let origin = root.join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "main"]);
+        std::fs::write(origin.join("a.txt"), "one\n").unwrap();
+        git(&origin, &["add", "."]);
+        git(&origin, &["commit", "-q", "-m", "first"]);
+        git(&origin, &["branch", "topic"]);
+        git(root, &["clone", "-q", "origin", "work"]);
+
+        let service = GitService::open(root.join("work"), &GitConfig::default()).unwrap();
+        DefaultRemoteManager::new(Arc::new(service))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_add_rename_and_remove_remote() {
+        let root = TempDir::new().unwrap();
+        let manager = clone_with_origin(root.path());
+
+        let origin = manager.list_remotes().await.unwrap();
+        assert_eq!(origin.len(), 1);
+        assert_eq!(origin[0].tracking_branches, ["origin/main", "origin/topic"]);
+
+        let upstream = manager.add_remote("upstream", "https://example.com:8443/repo.git").await.unwrap();
+        assert_eq!(upstream.fetch_refs, ["+refs/heads/*:refs/remotes/upstream/*"]);
+        let mirror = manager
+            .add_remote_with_fetch("mirror", "https://example.com/m.git", &["+refs/heads/main:refs/remotes/mirror/main".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(mirror.fetch_refs, ["+refs/heads/main:refs/remotes/mirror/main"]);
+
+        // Tracking branches move with the default refspec
+        assert!(manager.rename_remote("origin", "source").await.unwrap().is_empty());
+        let remotes = manager.list_remotes().await.unwrap();
+        let source = remotes.iter().find(|remote| remote.name == "source").unwrap();
+        assert_eq!(source.tracking_branches, ["source/main", "source/topic"]);
+        assert!(remotes.iter().all(|remote| remote.name != "origin"));
+
+        manager.remove_remote("mirror").await.unwrap();
+        assert_eq!(manager.list_remotes().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_set_fetch_and_push_urls() {
+        let root = TempDir::new().unwrap();
+        let manager = clone_with_origin(root.path());
+
+        manager.set_remote_url("origin", "https://example.com/fetch.git").await.unwrap();
+        manager.set_remote_push_url("origin", Some("git@example.com:push.git")).await.unwrap();
+        let origin = &manager.list_remotes().await.unwrap()[0];
+        assert_eq!(origin.url, "https://example.com/fetch.git");
+        assert_eq!(origin.push_url.as_deref(), Some("git@example.com:push.git"));
+
+        // Clearing the push URL falls back to the fetch URL
+        manager.set_remote_push_url("origin", None).await.unwrap();
+        assert_eq!(manager.list_remotes().await.unwrap()[0].push_url, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_prune_stale_tracking_branches() {
+        let root = TempDir::new().unwrap();
+        let manager = clone_with_origin(root.path());
+        assert!(manager.stale_tracking_branches("origin").await.unwrap().is_empty());
+
+        git(&root.path().join("origin"), &["branch", "-q", "-D", "topic"]);
+        assert_eq!(manager.stale_tracking_branches("origin").await.unwrap(), ["refs/remotes/origin/topic"]);
+
+        assert_eq!(manager.prune_remote("origin").await.unwrap(), ["origin/topic"]);
+        assert_eq!(manager.list_remotes().await.unwrap()[0].tracking_branches, ["origin/main"]);
+        assert!(manager.prune_remote("origin").await.unwrap().is_empty());
+    }
+}
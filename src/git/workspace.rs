@@ -0,0 +1,535 @@
+//! Multi-repository workspace
+//!
+//! Discovers the repositories of a workspace, summarizes each one for the
+//! sidebar badges (branch, dirty, ahead/behind) and runs bulk fetch and
+//! fast-forward pull operations across all of them.
+
+use chrono::{DateTime, Utc};
+use git2::{BranchType, Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+use tracing::{debug, info, warn};
+
+use super::{workflow::DefaultRemoteManager, GitService};
+use crate::{
+    config::{GitConfig, WorkspaceConfig},
+    error::{AppError, AppResult},
+};
+
+/// Summary of one workspace repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceRepoStatus {
+    /// Display name (directory name)
+    pub name: String,
+    pub path: PathBuf,
+    /// Checked out branch; `None` for a detached HEAD
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+    /// Why the repository could not be inspected
+    pub error: Option<String>,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+impl WorkspaceRepoStatus {
+    fn new(path: &Path) -> Self {
+        Self {
+            name: repository_name(path),
+            path: path.to_path_buf(),
+            branch: None,
+            upstream: None,
+            ahead: 0,
+            behind: 0,
+            staged: 0,
+            unstaged: 0,
+            untracked: 0,
+            conflicted: 0,
+            error: None,
+            refreshed_at: Utc::now(),
+        }
+    }
+
+    /// Number of files with uncommitted work (including untracked files)
+    pub fn changed_files(&self) -> usize {
+        self.staged + self.unstaged + self.untracked + self.conflicted
+    }
+
+    /// Whether the repository has uncommitted work
+    pub fn is_dirty(&self) -> bool {
+        self.changed_files() > 0
+    }
+
+    /// Compact badge text, e.g. `main ●3 ↑1 ↓2`
+    pub fn badge(&self) -> String {
+        if self.error.is_some() {
+            return "⚠ error".to_string();
+        }
+
+        let mut badge = self.branch.clone().unwrap_or_else(|| "(detached)".to_string());
+        if self.conflicted > 0 {
+            badge.push_str(&format!(" ✖{}", self.conflicted));
+        }
+        if self.is_dirty() {
+            badge.push_str(&format!(" ●{}", self.changed_files()));
+        }
+        if self.ahead > 0 {
+            badge.push_str(&format!(" ↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            badge.push_str(&format!(" ↓{}", self.behind));
+        }
+        badge
+    }
+}
+
+/// Bulk operation run across all workspace repositories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkspaceOperation {
+    /// Fetch every remote
+    Fetch,
+    /// Fetch the upstream and fast-forward the current branch
+    Pull,
+}
+
+impl WorkspaceOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorkspaceOperation::Fetch => "Fetch",
+            WorkspaceOperation::Pull => "Pull",
+        }
+    }
+}
+
+/// Outcome of a bulk operation for one repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceOperationResult {
+    pub path: PathBuf,
+    pub operation: WorkspaceOperation,
+    /// Summary message on success, error message on failure
+    pub outcome: Result<String, String>,
+}
+
+/// The repositories of a workspace and their cached Git services
+///
+/// Services are opened lazily and kept, so switching back and forth between
+/// repositories does not reopen them.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    repositories: Vec<PathBuf>,
+    services: HashMap<PathBuf, GitService>,
+    /// Repository of the service the application started with
+    initial: Option<PathBuf>,
+    git_config: GitConfig,
+}
+
+impl Workspace {
+    /// Build a workspace from the configured repositories
+    ///
+    /// `current` is the service the application started with; its
+    /// repository is added to the workspace if the configuration does not
+    /// already list it.
+    pub fn new(config: &WorkspaceConfig, git_config: &GitConfig, base_dir: &Path, current: Option<&GitService>) -> Self {
+        let mut repositories = discover_repositories(config, base_dir);
+        let mut services = HashMap::new();
+        let mut initial = None;
+
+        if let Some(service) = current.filter(|service| !service.is_mock()) {
+            let path = normalize(service.repo_path());
+            if !repositories.contains(&path) {
+                repositories.insert(0, path.clone());
+            }
+            services.insert(path.clone(), service.clone());
+            initial = Some(path);
+        }
+
+        info!("Workspace contains {} repositories", repositories.len());
+        Self {
+            repositories,
+            services,
+            initial,
+            git_config: git_config.clone(),
+        }
+    }
+
+    pub fn repositories(&self) -> &[PathBuf] {
+        &self.repositories
+    }
+
+    /// Repository the application started in, if it is part of the workspace
+    pub fn initial_repository(&self) -> Option<&Path> {
+        self.initial.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.repositories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty()
+    }
+
+    /// Git service for a workspace repository, opened on first use
+    pub fn service(&mut self, path: &Path) -> AppResult<GitService> {
+        if let Some(service) = self.services.get(path) {
+            return Ok(service.clone());
+        }
+
+        let service = GitService::open(path.to_path_buf(), &self.git_config)?;
+        self.services.insert(path.to_path_buf(), service.clone());
+        Ok(service)
+    }
+
+    /// Inspect all repositories on the blocking thread pool
+    pub async fn inspect_all(&self) -> Vec<WorkspaceRepoStatus> {
+        let handles: Vec<_> = self
+            .repositories
+            .iter()
+            .cloned()
+            .map(|path| tokio::task::spawn_blocking(move || inspect_repository(&path)))
+            .collect();
+
+        let mut statuses = Vec::with_capacity(handles.len());
+        for (handle, path) in handles.into_iter().zip(&self.repositories) {
+            statuses.push(handle.await.unwrap_or_else(|e| {
+                let mut status = WorkspaceRepoStatus::new(path);
+                status.error = Some(format!("Inspection task failed: {}", e));
+                status
+            }));
+        }
+        statuses
+    }
+
+    /// Run `operation` on all repositories concurrently
+    pub async fn run_bulk(&self, operation: WorkspaceOperation) -> Vec<WorkspaceOperationResult> {
+        let handles: Vec<_> = self
+            .repositories
+            .iter()
+            .cloned()
+            .map(|path| {
+                tokio::task::spawn_blocking(move || match operation {
+                    WorkspaceOperation::Fetch => fetch_repository(&path),
+                    WorkspaceOperation::Pull => pull_repository(&path),
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(handles.len());
+        for (handle, path) in handles.into_iter().zip(&self.repositories) {
+            let outcome = match handle.await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(e) => Err(format!("{} task failed: {}", operation.name(), e)),
+            };
+            if let Err(error) = &outcome {
+                warn!("{} failed for {}: {}", operation.name(), path.display(), error);
+            }
+            results.push(WorkspaceOperationResult {
+                path: path.clone(),
+                operation,
+                outcome,
+            });
+        }
+        results
+    }
+}
+
+/// Resolve the configured repositories and scan directories
+///
+/// Explicitly listed repositories keep their configured order and are kept
+/// even when they cannot be opened, so the sidebar can flag them. Scanned
+/// repositories are appended in path order; duplicates are dropped.
+pub fn discover_repositories(config: &WorkspaceConfig, base_dir: &Path) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut repositories = Vec::new();
+
+    for path in &config.repositories {
+        let path = normalize(&base_dir.join(path));
+        if seen.insert(path.clone()) {
+            repositories.push(path);
+        }
+    }
+
+    let mut scanned = Vec::new();
+    for dir in &config.scan_dirs {
+        scan_directory(&base_dir.join(dir), config.scan_depth, &mut scanned);
+    }
+    scanned.sort();
+
+    for path in scanned {
+        let path = normalize(&path);
+        if seen.insert(path.clone()) {
+            repositories.push(path);
+        }
+    }
+
+    debug!("Discovered {} workspace repositories", repositories.len());
+    repositories
+}
+
+/// Collect repositories below `dir`, without descending into repositories
+fn scan_directory(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Cannot scan workspace directory {}: {}", dir.display(), e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden || !path.is_dir() {
+            continue;
+        }
+
+        if path.join(".git").exists() {
+            found.push(path);
+        } else if depth > 0 {
+            scan_directory(&path, depth - 1, found);
+        }
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn repository_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Summarize branch, working tree and upstream state of a repository
+///
+/// Never fails: problems are reported through [`WorkspaceRepoStatus::error`].
+pub fn inspect_repository(path: &Path) -> WorkspaceRepoStatus {
+    let mut status = WorkspaceRepoStatus::new(path);
+    if let Err(e) = fill_status(path, &mut status) {
+        status.error = Some(e.to_string());
+    }
+    status
+}
+
+fn fill_status(path: &Path, status: &mut WorkspaceRepoStatus) -> AppResult<()> {
+    let repo = Repository::open(path)?;
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .exclude_submodules(true);
+
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let flags = entry.status();
+        if flags.is_conflicted() {
+            status.conflicted += 1;
+            continue;
+        }
+        if flags.is_wt_new() {
+            status.untracked += 1;
+            continue;
+        }
+        if flags.intersects(
+            git2::Status::INDEX_NEW
+                | git2::Status::INDEX_MODIFIED
+                | git2::Status::INDEX_DELETED
+                | git2::Status::INDEX_RENAMED
+                | git2::Status::INDEX_TYPECHANGE,
+        ) {
+            status.staged += 1;
+        }
+        if flags.intersects(
+            git2::Status::WT_MODIFIED | git2::Status::WT_DELETED | git2::Status::WT_RENAMED | git2::Status::WT_TYPECHANGE,
+        ) {
+            status.unstaged += 1;
+        }
+    }
+
+    let head = match repo.head() {
+        Ok(head) => head,
+        // Unborn branch: nothing to compare yet
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+            status.branch = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|head| head.symbolic_target().map(|t| t.trim_start_matches("refs/heads/").to_string()));
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if !head.is_branch() {
+        return Ok(());
+    }
+
+    let Some(branch_name) = head.shorthand() else {
+        return Ok(());
+    };
+    status.branch = Some(branch_name.to_string());
+
+    let branch = repo.find_branch(branch_name, BranchType::Local)?;
+    if let Ok(upstream) = branch.upstream() {
+        status.upstream = upstream.name()?.map(str::to_string);
+        if let (Some(local), Some(remote)) = (head.target(), upstream.get().target()) {
+            let (ahead, behind) = repo.graph_ahead_behind(local, remote)?;
+            status.ahead = ahead;
+            status.behind = behind;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch every remote of the repository
+pub fn fetch_repository(path: &Path) -> AppResult<String> {
+    let repo = Repository::open(path)?;
+    let remotes = repo.remotes()?;
+    let names: Vec<&str> = remotes.iter().flatten().collect();
+
+    if names.is_empty() {
+        return Ok("No remotes".to_string());
+    }
+
+    for name in &names {
+        let mut remote = repo.find_remote(name)?;
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(DefaultRemoteManager::remote_callbacks(&repo));
+        remote.fetch::<&str>(&[], Some(&mut fetch_options), None)?;
+    }
+
+    info!("Fetched {} remotes in {}", names.len(), path.display());
+    Ok(format!("Fetched {}", names.join(", ")))
+}
+
+/// Fetch the upstream of the current branch and fast-forward onto it
+///
+/// Diverged branches are left alone: bulk pulls never create merge commits.
+pub fn pull_repository(path: &Path) -> AppResult<String> {
+    let repo = Repository::open(path)?;
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Err(AppError::InvalidState("HEAD is detached".to_string()));
+    }
+
+    let branch_ref = head
+        .name()
+        .ok_or_else(|| AppError::InvalidState("Branch name is not valid UTF-8".to_string()))?
+        .to_string();
+    let remote_name = repo.branch_upstream_remote(&branch_ref).map_err(|_| {
+        AppError::InvalidState(format!("{} has no upstream", head.shorthand().unwrap_or(&branch_ref)))
+    })?;
+    let remote_name = remote_name
+        .as_str()
+        .ok_or_else(|| AppError::InvalidState("Remote name is not valid UTF-8".to_string()))?;
+
+    let mut remote = repo.find_remote(remote_name)?;
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.remote_callbacks(DefaultRemoteManager::remote_callbacks(&repo));
+    remote.fetch::<&str>(&[], Some(&mut fetch_options), None)?;
+
+    let upstream_ref = repo.branch_upstream_name(&branch_ref)?;
+    let upstream_ref = upstream_ref
+        .as_str()
+        .ok_or_else(|| AppError::InvalidState("Upstream name is not valid UTF-8".to_string()))?;
+    let upstream = repo.find_reference(upstream_ref)?;
+    let upstream_commit = repo.reference_to_annotated_commit(&upstream)?;
+
+    let (analysis, _) = repo.merge_analysis(&[&upstream_commit])?;
+    if analysis.is_up_to_date() {
+        return Ok("Already up to date".to_string());
+    }
+    if !analysis.is_fast_forward() {
+        return Err(AppError::InvalidOperation(
+            "Branch has diverged from its upstream; merge or rebase manually".to_string(),
+        ));
+    }
+
+    // Update the working tree first so a conflicting local change aborts the
+    // pull before the branch moves
+    let target = repo.find_object(upstream_commit.id(), None)?;
+    repo.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))?;
+
+    let mut branch = repo.find_reference(&branch_ref)?;
+    branch.set_target(upstream_commit.id(), &format!("pull: fast-forward to {}", upstream_ref))?;
+
+    let short = upstream_commit.id().to_string();
+    info!("Fast-forwarded {} to {}", path.display(), &short[..7]);
+    Ok(format!("Fast-forwarded to {}", &short[..7]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_support::git;
+    use tempfile::TempDir;
+
+    fn commit(dir: &Path, file: &str, content: &str) {
+        std::fs::write(dir.join(file), content).unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", file]);
+    }
+
+    #[test]
+    fn test_discover_repositories() {
+        let root = TempDir::new().unwrap();
+        for repo in ["alpha", "group/beta", "group/beta/nested"] {
+            std::fs::create_dir_all(root.path().join(repo)).unwrap();
+            git(&root.path().join(repo), &["init", "-q"]);
+        }
+        std::fs::create_dir_all(root.path().join("plain")).unwrap();
+
+        let config = WorkspaceConfig {
+            repositories: vec![PathBuf::from("group/beta")],
+            scan_dirs: vec![PathBuf::from(".")],
+            scan_depth: 1,
+        };
+        let names: Vec<String> = discover_repositories(&config, root.path())
+            .iter()
+            .map(|path| path.strip_prefix(normalize(root.path())).unwrap().display().to_string())
+            .collect();
+
+        // Listed repositories first, no descent into repositories, no duplicates
+        assert_eq!(names, ["group/beta", "alpha"]);
+    }
+
+    #[test]
+    fn test_inspect_and_pull_repository() {
+        let root = TempDir::new().unwrap();
+        let origin = root.path().join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "main"]);
+        commit(&origin, "a.txt", "one\n");
+
+        git(root.path(), &["clone", "-q", "origin", "clone"]);
+        let clone = root.path().join("clone");
+        commit(&origin, "b.txt", "two\n");
+        git(&clone, &["fetch", "-q"]);
+        commit(&clone, "c.txt", "three\n");
+        std::fs::write(clone.join("untracked.txt"), "x").unwrap();
+
+        let status = inspect_repository(&clone);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!((status.ahead, status.behind), (1, 1));
+        assert_eq!(status.untracked, 1);
+        assert!(status.is_dirty());
+        assert_eq!(status.badge(), "main ●1 ↑1 ↓1");
+
+        // Diverged branches are never merged
+        assert!(pull_repository(&clone).is_err());
+
+        git(&clone, &["reset", "-q", "--hard", "origin/main"]);
+        commit(&origin, "d.txt", "four\n");
+        assert!(pull_repository(&clone).unwrap().starts_with("Fast-forwarded"));
+        assert!(clone.join("d.txt").exists());
+        assert_eq!(pull_repository(&clone).unwrap(), "Already up to date");
+    }
+}
@@ -1,5 +1,14 @@
-use ai_c::{initialize_logging, App, config::Config, error::AppResult, git::GitService};
-use std::{env, process, time::{Duration, Instant}};
+use ai_c::{
+    initialize_logging, App,
+    config::Config,
+    error::{AppError, AppResult},
+    git::{DiffSpec, GitService},
+    ui::{
+        diff::{export, DiffProcessorConfig, ExportFormat},
+        theme::Theme,
+    },
+};
+use std::{env, path::PathBuf, process, time::{Duration, Instant}};
 use tracing::{info, warn, debug};
 
 #[tokio::main]
@@ -19,6 +28,15 @@ async fn main() -> AppResult<()> {
         process::exit(0);
     }
 
+    // Export a diff without starting the TUI; stdout carries the export
+    if args.get(1).map(String::as_str) == Some("export-diff") {
+        if let Err(e) = run_export_diff(&args[2..]).await {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Initialize logging
     initialize_logging().map_err(|e| ai_c::error::AppError::application(&e.to_string()))?;
     let start_time = Instant::now();
@@ -104,18 +122,102 @@ async fn run_demo_mode(start_time: Instant) -> AppResult<()> {
     Ok(())
 }
 
+/// `ai-c export-diff [--format F] [--output FILE] [--annotations FILE] [--staged] [REV | A..B | A...B] [-- PATHS]`
+async fn run_export_diff(args: &[String]) -> AppResult<()> {
+    let mut format = None;
+    let mut output: Option<PathBuf> = None;
+    let mut annotations: Option<PathBuf> = None;
+    let mut staged = false;
+    let mut revision: Option<String> = None;
+    let mut paths = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| AppError::InvalidOperation(format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "--format" | "-f" => format = Some(ExportFormat::parse(&value()?)?),
+            "--output" | "-o" => output = Some(PathBuf::from(value()?)),
+            "--annotations" => annotations = Some(PathBuf::from(value()?)),
+            "--staged" | "--cached" => staged = true,
+            "--" => paths.extend(iter.by_ref().cloned()),
+            other if other.starts_with('-') => {
+                return Err(AppError::InvalidOperation(format!("Unknown option {}", other)));
+            }
+            other if revision.is_none() => revision = Some(other.to_string()),
+            other => return Err(AppError::InvalidOperation(format!("Unexpected argument {}", other))),
+        }
+    }
+
+    // Without --format, go by the output file's extension; patches suit stdout
+    let output = output.filter(|path| path.as_os_str() != "-");
+    let format = format
+        .or_else(|| {
+            let extension = output.as_ref()?.extension()?.to_str()?;
+            ExportFormat::parse(extension).ok()
+        })
+        .unwrap_or(ExportFormat::Patch);
+
+    let mut spec = match (staged, revision.as_deref()) {
+        (true, _) => DiffSpec::staged(),
+        (false, None) => DiffSpec::unstaged(),
+        (false, Some(rev)) => match rev.split_once("...").or_else(|| rev.split_once("..")) {
+            Some((base, other)) if rev.contains("...") => DiffSpec::branch_comparison(base, other),
+            Some((old, new)) => DiffSpec::range(old, new),
+            None => DiffSpec::commit(rev),
+        },
+    };
+    for path in paths {
+        spec = spec.with_path(path);
+    }
+
+    let config = Config::load().await.unwrap_or_default();
+    let git_service = GitService::new(&config.git).await?;
+    if git_service.is_mock() {
+        return Err(AppError::InvalidState("Not inside a Git repository".to_string()));
+    }
+
+    let diff = git_service.diff_revisions(&spec).await?;
+    let mut export = export::revision_export(&git_service, &diff, DiffProcessorConfig::from(&config.ui.diff)).await?;
+    if let Some(path) = annotations {
+        let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        export.annotations = export::annotations_from_json(&value);
+    }
+
+    let theme = Theme::load(&config.ui.theme).unwrap_or_else(|_| Theme::default_theme());
+    let content = export.render(format, &theme);
+    match output {
+        Some(path) => {
+            std::fs::write(&path, content)?;
+            eprintln!("Exported {} files as {} to {}", export.files.len(), format.label(), path.display());
+        }
+        None => std::io::Write::write_all(&mut std::io::stdout(), &content)?,
+    }
+    Ok(())
+}
+
 fn print_help() {
     println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
     println!("{}", env!("CARGO_PKG_DESCRIPTION"));
     println!();
     println!("USAGE:");
     println!("    {} [OPTIONS]", env!("CARGO_PKG_NAME"));
+    println!("    {} export-diff [EXPORT OPTIONS] [REV | A..B | A...B] [-- PATHS]", env!("CARGO_PKG_NAME"));
     println!();
     println!("OPTIONS:");
     println!("    -h, --help       Print this help message and exit");
     println!("    -V, --version    Print version information and exit");
     println!("        --demo       Run in demo mode (non-interactive)");
     println!();
+    println!("EXPORT OPTIONS:");
+    println!("    -f, --format <F>         html, patch or markdown (default: from --output, else patch)");
+    println!("    -o, --output <FILE>      Write to FILE instead of stdout");
+    println!("        --annotations <FILE> AI review findings (JSON) to include as comments");
+    println!("        --staged             Export staged changes instead of unstaged ones");
+    println!();
     println!("ENVIRONMENT:");
     println!("    AI_C_DEMO_MODE   Set to run in demo mode");
     println!("    RUST_LOG         Set logging level (debug, info, warn, error)");
@@ -124,6 +226,7 @@ fn print_help() {
     println!("    {}              Start interactive TUI mode", env!("CARGO_PKG_NAME"));
     println!("    {} --demo       Run in demo mode", env!("CARGO_PKG_NAME"));
     println!("    {} --version    Show version information", env!("CARGO_PKG_NAME"));
+    println!("    {} export-diff -o review.html main...feature", env!("CARGO_PKG_NAME"));
 }
 
 async fn run_full_tui_mode(start_time: Instant) -> AppResult<()> {
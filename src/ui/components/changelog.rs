@@ -0,0 +1,240 @@
+//! Changelog panel: generates a release section from conventional commits
+//! and lets the user edit it before it is written to CHANGELOG.md.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use std::path::PathBuf;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app::state::AppState,
+    error::AppResult,
+    git::{Changelog, ChangelogFormat, ChangelogOptions},
+    ui::{
+        diff::{InlineEditor, InlineEditorConfig},
+        theme::Theme,
+    },
+};
+
+/// Outcome reported to the owner of the panel when it closes
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangelogPanelEvent {
+    /// The edited changelog was written to this file
+    Saved(PathBuf),
+    /// The user chose to continue without a changelog
+    Skipped,
+    Cancelled,
+}
+
+/// Editable changelog preview
+pub struct ChangelogPanelComponent {
+    is_open: bool,
+    options: ChangelogOptions,
+    format: ChangelogFormat,
+    changelog: Option<Changelog>,
+    generate_task: Option<JoinHandle<AppResult<Changelog>>>,
+    editor: InlineEditor,
+    error: Option<String>,
+    /// Offer Ctrl+N to continue without writing (used by release finish)
+    allow_skip: bool,
+}
+
+impl ChangelogPanelComponent {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            options: ChangelogOptions::default(),
+            format: ChangelogFormat::Markdown,
+            changelog: None,
+            generate_task: None,
+            editor: InlineEditor::new(InlineEditorConfig {
+                show_line_numbers: false,
+                auto_indent: false,
+                ..Default::default()
+            }),
+            error: None,
+            allow_skip: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Open the panel and generate the changelog for `options`
+    pub fn open(&mut self, state: &AppState, options: ChangelogOptions, allow_skip: bool) {
+        self.is_open = true;
+        self.options = options;
+        self.allow_skip = allow_skip;
+        self.format = ChangelogFormat::Markdown;
+        self.start_generate(state);
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        if let Some(task) = self.generate_task.take() {
+            task.abort();
+        }
+    }
+
+    fn start_generate(&mut self, state: &AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        if let Some(task) = self.generate_task.take() {
+            task.abort();
+        }
+
+        let options = self.options.clone();
+        self.error = None;
+        self.changelog = None;
+        self.editor.set_text("");
+        self.generate_task = Some(tokio::spawn(async move { git_service.generate_changelog(&options).await }));
+    }
+
+    fn sync(&mut self) {
+        if self.generate_task.as_ref().is_some_and(|task| task.is_finished()) {
+            if let Some(task) = self.generate_task.take() {
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task)) {
+                    Ok(Ok(changelog)) => {
+                        self.changelog = Some(changelog);
+                        self.load_editor();
+                    }
+                    Ok(Err(e)) => self.error = Some(e.to_string()),
+                    Err(e) => self.error = Some(format!("Changelog generation failed: {}", e)),
+                }
+            }
+        }
+    }
+
+    /// Replace the editor content with the generated changelog
+    fn load_editor(&mut self) {
+        if let Some(changelog) = &self.changelog {
+            match changelog.render(self.format) {
+                Ok(content) => self.editor.set_text(&content),
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.sync();
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
+            .split(area);
+
+        let range = format!(
+            "{}..{}",
+            self.changelog
+                .as_ref()
+                .and_then(|c| c.from.clone())
+                .or_else(|| self.options.from.clone())
+                .unwrap_or_else(|| "(root)".to_string()),
+            self.options.to
+        );
+        let summary = match (&self.error, &self.changelog) {
+            (Some(error), _) => format!("⚠ {}", error),
+            (None, None) => format!("Collecting commits in {}...", range),
+            (None, Some(changelog)) => format!(
+                "{}: {} commits, {} breaking | Format: {} | Other commits: {}",
+                range,
+                changelog.commit_count,
+                changelog.breaking.len(),
+                self.format.label(),
+                if self.options.include_other { "shown" } else { "hidden" }
+            ),
+        };
+        let keys = format!(
+            "[Ctrl+S] Write {} [Ctrl+T] Markdown/JSON [Ctrl+O] Other commits{} [Esc] Cancel",
+            self.format.file_name(),
+            if self.allow_skip { " [Ctrl+N] Skip" } else { "" }
+        );
+
+        let header = Paragraph::new(vec![
+            Line::from(Span::styled(summary, theme.text_style())),
+            Line::from(Span::styled(keys, theme.muted_style())),
+        ])
+        .block(
+            Block::default()
+                .title(match &self.options.version {
+                    Some(version) => format!("Changelog {}", version),
+                    None => "Changelog (Unreleased)".to_string(),
+                })
+                .borders(Borders::ALL)
+                .border_style(theme.accent_border_style()),
+        )
+        .wrap(Wrap { trim: true });
+        frame.render_widget(header, chunks[0]);
+
+        if let Err(e) = self.editor.render(frame, chunks[1], theme) {
+            self.error = Some(e.to_string());
+        }
+    }
+
+    /// Handle a key; returns an event once the panel closes
+    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<Option<ChangelogPanelEvent>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => {
+                self.close();
+                return Ok(Some(ChangelogPanelEvent::Cancelled));
+            }
+            KeyCode::Char('s') if ctrl => return Ok(self.save(state)),
+            KeyCode::Char('n') if ctrl && self.allow_skip => {
+                self.close();
+                return Ok(Some(ChangelogPanelEvent::Skipped));
+            }
+            KeyCode::Char('t') if ctrl => {
+                self.format = match self.format {
+                    ChangelogFormat::Markdown => ChangelogFormat::Json,
+                    ChangelogFormat::Json => ChangelogFormat::Markdown,
+                };
+                self.load_editor();
+            }
+            KeyCode::Char('o') if ctrl => {
+                self.options.include_other = !self.options.include_other;
+                self.start_generate(state);
+            }
+            _ if self.changelog.is_some() => {
+                self.editor.handle_key(key)?;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn save(&mut self, state: &mut AppState) -> Option<ChangelogPanelEvent> {
+        let git_service = state.git_service.clone()?;
+        self.changelog.as_ref()?;
+
+        let content = self.editor.text();
+        let format = self.format;
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.write_changelog(&content, format))
+        });
+        match result {
+            Ok(path) => {
+                state.add_info(format!("Changelog written to {}", path.display()));
+                self.close();
+                Some(ChangelogPanelEvent::Saved(path))
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to write changelog: {}", e));
+                None
+            }
+        }
+    }
+}
+
+impl Default for ChangelogPanelComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,384 @@
+//! Clean panel for the Status tab: dry-run preview of untracked and ignored
+//! files, clean into the trash and restore from it.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    app::state::AppState,
+    error::AppResult,
+    git::{CleanIgnored, CleanOptions, CleanPreview, TrashBatch},
+    ui::{
+        components::modals::{ConfirmationModal, Modal, ModalResult},
+        keyboard::{NavigationHandler, ShortcutManager},
+        theme::Theme,
+    },
+};
+
+/// What the panel currently lists
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CleanView {
+    Preview,
+    Trash,
+}
+
+/// Action waiting for confirmation
+#[derive(Debug, Clone, PartialEq)]
+enum CleanConfirmAction {
+    Clean,
+    Restore(String),
+}
+
+/// Clean preview / trash panel
+pub struct CleanPanelComponent {
+    is_open: bool,
+    view: CleanView,
+    options: CleanOptions,
+    preview: Option<CleanPreview>,
+    preview_task: Option<JoinHandle<AppResult<CleanPreview>>>,
+    trash: Vec<TrashBatch>,
+    error: Option<String>,
+    selected_index: usize,
+    list_state: ListState,
+    shortcut_manager: ShortcutManager,
+    confirm_modal: ConfirmationModal,
+    pending_confirm: Option<CleanConfirmAction>,
+}
+
+impl CleanPanelComponent {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            view: CleanView::Preview,
+            options: CleanOptions::default(),
+            preview: None,
+            preview_task: None,
+            trash: Vec::new(),
+            error: None,
+            selected_index: 0,
+            list_state: ListState::default(),
+            shortcut_manager: ShortcutManager::new(),
+            confirm_modal: ConfirmationModal::new(),
+            pending_confirm: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Open the panel with a fresh dry-run preview
+    pub fn open(&mut self, state: &AppState) {
+        self.is_open = true;
+        self.view = CleanView::Preview;
+        self.start_preview(state);
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        if let Some(task) = self.preview_task.take() {
+            task.abort();
+        }
+    }
+
+    fn start_preview(&mut self, state: &AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        if let Some(task) = self.preview_task.take() {
+            task.abort();
+        }
+
+        let options = self.options.clone();
+        self.error = None;
+        self.selected_index = 0;
+        self.preview_task = Some(tokio::spawn(async move { git_service.preview_clean(&options).await }));
+    }
+
+    fn load_trash(&mut self, state: &AppState) {
+        let Some(git_service) = &state.git_service else {
+            return;
+        };
+        self.selected_index = 0;
+        match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(git_service.list_trash())) {
+            Ok(trash) => self.trash = trash,
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn sync(&mut self) {
+        if self.preview_task.as_ref().is_some_and(|task| task.is_finished()) {
+            if let Some(task) = self.preview_task.take() {
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task)) {
+                    Ok(Ok(preview)) => self.preview = Some(preview),
+                    Ok(Err(e)) => self.error = Some(e.to_string()),
+                    Err(e) => self.error = Some(format!("Clean preview failed: {}", e)),
+                }
+            }
+        }
+    }
+
+    fn item_count(&self) -> usize {
+        match self.view {
+            CleanView::Preview => self.preview.as_ref().map_or(0, |p| p.candidates.len()),
+            CleanView::Trash => self.trash.len(),
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.sync();
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
+            .split(area);
+
+        let summary = match (&self.error, self.view) {
+            (Some(error), _) => format!("⚠ {}", error),
+            (None, CleanView::Preview) => match (&self.preview, &self.preview_task) {
+                (_, Some(_)) => "Scanning working tree...".to_string(),
+                (Some(preview), None) => format!(
+                    "Dry run: {} entries, {} would be moved to .git/ai-c-trash",
+                    preview.candidates.len(),
+                    format_size(preview.total_size)
+                ),
+                (None, None) => String::new(),
+            },
+            (None, CleanView::Trash) => format!("{} trash batches", self.trash.len()),
+        };
+        let options = format!(
+            "Mode: {} | Directories: {} | [i] Mode [d] Dirs [x] Clean [t] Trash [C] Close",
+            self.options.ignored.label(),
+            if self.options.directories { "yes" } else { "no" }
+        );
+
+        let header = Paragraph::new(vec![
+            Line::from(Span::styled(summary, theme.text_style())),
+            Line::from(Span::styled(options, theme.muted_style())),
+        ])
+        .block(
+            Block::default()
+                .title(match self.view {
+                    CleanView::Preview => "Clean Working Tree",
+                    CleanView::Trash => "Trash ([Enter] Restore)",
+                })
+                .borders(Borders::ALL)
+                .border_style(theme.accent_border_style()),
+        )
+        .wrap(Wrap { trim: true });
+        frame.render_widget(header, chunks[0]);
+
+        let items: Vec<ListItem> = match self.view {
+            CleanView::Preview => self
+                .preview
+                .iter()
+                .flat_map(|preview| &preview.candidates)
+                .map(|candidate| {
+                    let style = if candidate.is_ignored { theme.muted_style() } else { theme.warning_style() };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:>10}  ", format_size(candidate.size)), theme.info_style()),
+                        Span::styled(candidate.path.clone(), style),
+                        Span::styled(if candidate.is_ignored { "  (ignored)" } else { "" }, theme.muted_style()),
+                    ]))
+                })
+                .collect(),
+            CleanView::Trash => self
+                .trash
+                .iter()
+                .map(|batch| {
+                    ListItem::new(format!(
+                        "{}  {:>4} entries {:>10}  {}",
+                        batch.created_at.format("%Y-%m-%d %H:%M:%S"),
+                        batch.entries.len(),
+                        format_size(batch.total_size),
+                        batch.id
+                    ))
+                })
+                .collect(),
+        };
+
+        self.list_state.select((!items.is_empty()).then_some(self.selected_index));
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).border_style(theme.border_style()))
+            .style(theme.text_style())
+            .highlight_style(theme.highlight_style())
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
+
+        if self.confirm_modal.is_open() {
+            self.confirm_modal.render(frame, area, theme);
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        if self.confirm_modal.is_open() {
+            let result = self.confirm_modal.handle_key_event(key)?;
+            if let (ModalResult::Confirmed, Some(action)) = (result, self.pending_confirm.take()) {
+                self.execute(action, state);
+            }
+            return Ok(());
+        }
+
+        if let Some(nav_key) = self.shortcut_manager.is_navigation_key(&key) {
+            let item_count = self.item_count();
+            let mut nav_handler = CleanNavigationHandler { component: self, item_count };
+            nav_handler.handle_navigation(nav_key);
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('C') => self.close(),
+            KeyCode::Char('i') => {
+                self.options.ignored = match self.options.ignored {
+                    CleanIgnored::Exclude => CleanIgnored::Include,
+                    CleanIgnored::Include => CleanIgnored::Only,
+                    CleanIgnored::Only => CleanIgnored::Exclude,
+                };
+                self.view = CleanView::Preview;
+                self.start_preview(state);
+            }
+            KeyCode::Char('d') => {
+                self.options.directories = !self.options.directories;
+                self.view = CleanView::Preview;
+                self.start_preview(state);
+            }
+            KeyCode::Char('t') => match self.view {
+                CleanView::Preview => {
+                    self.view = CleanView::Trash;
+                    self.load_trash(state);
+                }
+                CleanView::Trash => {
+                    self.view = CleanView::Preview;
+                    self.start_preview(state);
+                }
+            },
+            KeyCode::Char('x') if self.view == CleanView::Preview && self.preview_task.is_none() => {
+                if let Some(preview) = self.preview.as_ref().filter(|p| !p.candidates.is_empty()) {
+                    self.pending_confirm = Some(CleanConfirmAction::Clean);
+                    self.confirm_modal.open_with_buttons(
+                        "Clean Working Tree",
+                        &format!(
+                            "Move {} entries ({}) to .git/ai-c-trash?\nThey can be restored from the trash view.",
+                            preview.candidates.len(),
+                            format_size(preview.total_size)
+                        ),
+                        "Clean",
+                        "Cancel",
+                    );
+                }
+            }
+            KeyCode::Enter if self.view == CleanView::Trash => {
+                if let Some(batch) = self.trash.get(self.selected_index) {
+                    self.pending_confirm = Some(CleanConfirmAction::Restore(batch.id.clone()));
+                    self.confirm_modal.open_with_buttons(
+                        "Restore From Trash",
+                        &format!("Restore {} entries from {}?", batch.entries.len(), batch.id),
+                        "Restore",
+                        "Cancel",
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn execute(&mut self, action: CleanConfirmAction, state: &mut AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+
+        match action {
+            CleanConfirmAction::Clean => {
+                let options = self.options.clone();
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.clean(&options))
+                });
+                match result {
+                    Ok(Some(batch)) => state.add_info(format!(
+                        "Moved {} entries ({}) to trash batch {}",
+                        batch.entries.len(),
+                        format_size(batch.total_size),
+                        batch.id
+                    )),
+                    Ok(None) => state.add_info("Nothing to clean".to_string()),
+                    Err(e) => state.add_error(format!("Clean failed: {}", e)),
+                }
+                self.start_preview(state);
+            }
+            CleanConfirmAction::Restore(batch_id) => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.restore_trash(&batch_id))
+                });
+                match result {
+                    Ok(report) if report.skipped.is_empty() => {
+                        state.add_info(format!("Restored {} entries", report.restored.len()))
+                    }
+                    Ok(report) => state.add_error(format!(
+                        "Restored {} entries; {} already exist and stay in the trash: {}",
+                        report.restored.len(),
+                        report.skipped.len(),
+                        report.skipped.join(", ")
+                    )),
+                    Err(e) => state.add_error(format!("Restore failed: {}", e)),
+                }
+                self.load_trash(state);
+            }
+        }
+
+        if let Ok(status) = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.get_status())
+        }) {
+            state.update_git_status(status);
+        }
+    }
+}
+
+impl Default for CleanPanelComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Human readable byte size
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Helper structure for navigation handling with dynamic item count
+struct CleanNavigationHandler<'a> {
+    component: &'a mut CleanPanelComponent,
+    item_count: usize,
+}
+
+impl<'a> NavigationHandler for CleanNavigationHandler<'a> {
+    fn selected_index(&self) -> usize {
+        self.component.selected_index
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.component.selected_index = index;
+    }
+
+    fn item_count(&self) -> usize {
+        self.item_count
+    }
+}
@@ -0,0 +1,457 @@
+//! Command palette: fuzzy search over every action of the current view
+//!
+//! Commands come from the shortcut registry, the keys the active view
+//! handles, the file context menu and the operations toolbar. Recently run
+//! commands are ranked first. Commands that need a parameter (a branch to
+//! check out, a name for a new branch) chain into a second picker.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::collections::VecDeque;
+
+use crate::{app::state::TabType, ui::theme::Theme};
+
+use super::{view_keys, MenuAction, Shortcut, ShortcutAction, ShortcutContext};
+
+/// Recently run commands remembered for ranking
+const RECENT_LIMIT: usize = 20;
+
+/// What running a palette command does
+#[derive(Debug, Clone)]
+pub enum PaletteAction {
+    /// A registered shortcut; the key tells apart actions bound twice (Tab / Shift+Tab)
+    Shortcut(ShortcutAction, Shortcut),
+    /// Replay a key in the active view
+    Key(KeyEvent),
+    /// File context menu action on the selected file
+    Menu(MenuAction),
+    /// Value chosen in a follow-up picker
+    Picked(BranchPicker, String),
+}
+
+/// Follow-up pickers for branch commands that take a branch name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchPicker {
+    Checkout,
+    Create,
+    Delete,
+    Merge,
+}
+
+impl BranchPicker {
+    pub fn title(&self) -> &'static str {
+        match self {
+            BranchPicker::Checkout => "Checkout branch",
+            BranchPicker::Create => "New branch name",
+            BranchPicker::Delete => "Delete branch",
+            BranchPicker::Merge => "Merge into current branch",
+        }
+    }
+
+    /// The typed query itself is the value, not a pick from the list
+    fn takes_text(&self) -> bool {
+        matches!(self, BranchPicker::Create)
+    }
+}
+
+/// One entry of the palette
+#[derive(Debug, Clone)]
+pub struct PaletteCommand {
+    /// Stable id for recent-use ranking
+    pub id: String,
+    pub label: String,
+    /// Where the command comes from, e.g. "Status" or "File menu"
+    pub group: String,
+    pub keybinding: Option<String>,
+    pub action: PaletteAction,
+}
+
+impl PaletteCommand {
+    pub fn new(id: impl Into<String>, label: impl Into<String>, group: &str, action: PaletteAction) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            group: group.to_string(),
+            keybinding: None,
+            action,
+        }
+    }
+
+    pub fn with_keybinding(mut self, keybinding: impl Into<String>) -> Self {
+        self.keybinding = Some(keybinding.into());
+        self
+    }
+}
+
+/// Display form of a key as typed: case is kept, so `c` and `C` differ
+pub fn key_label(key: &KeyEvent) -> String {
+    match key.code {
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => c.to_string(),
+        _ => Shortcut::from_key_event(*key).to_string(),
+    }
+}
+
+/// Commands for the keys the active view handles in `context`
+pub fn view_commands(tab: TabType, context: &ShortcutContext) -> Vec<PaletteCommand> {
+    let group = tab.name();
+    let key_command = |code: KeyCode, label: &str, group: &str| {
+        let key = KeyEvent::new(code, KeyModifiers::NONE);
+        PaletteCommand::new(format!("{}:{}", group, key_label(&key)), label, group, PaletteAction::Key(key))
+            .with_keybinding(key_label(&key))
+    };
+
+    let mut commands: Vec<PaletteCommand> = if *context == ShortcutContext::DiffViewer {
+        view_keys::for_diff().iter().map(|key| key_command(key.code, key.label, "Diff")).collect()
+    } else {
+        view_keys::for_tab(tab)
+            .iter()
+            .filter(|key| key.context == *context)
+            .map(|key| key_command(key.code, key.label, group))
+            .collect()
+    };
+
+    // A diff takes the number keys itself
+    if *context != ShortcutContext::DiffViewer {
+        commands.extend(TabType::all().iter().enumerate().map(|(index, tab)| {
+            key_command(KeyCode::Char((b'1' + index as u8) as char), &format!("Go to {}", tab.name()), "Tabs")
+        }));
+    }
+    commands
+}
+
+/// Subsequence match of `query` in `text`, ignoring case
+///
+/// Returns a score (higher is better) and the matched character positions.
+/// Consecutive matches and matches at word starts score higher, so "cb"
+/// prefers "Checkout branch" over "Create commit branch".
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0i64;
+    let mut next = 0;
+    for (index, c) in chars.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(std::iter::once(query[next])) {
+            continue;
+        }
+        score += 1;
+        if positions.last().is_some_and(|&last| last + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || !chars[index - 1].is_alphanumeric() {
+            score += 8;
+        }
+        positions.push(index);
+        next += 1;
+    }
+    if next < query.len() {
+        return None;
+    }
+    // Earlier and tighter matches first
+    score -= (positions[0] + positions.last().copied().unwrap_or(0) - positions[0]) as i64 / 4;
+    Some((score, positions))
+}
+
+/// Command palette overlay
+pub struct CommandPaletteComponent {
+    open: bool,
+    query: String,
+    commands: Vec<PaletteCommand>,
+    /// Indices into `commands` in ranked order, with matched label positions
+    matches: Vec<(usize, Vec<usize>)>,
+    list_state: ListState,
+    picker: Option<BranchPicker>,
+    /// Ids of recently run commands, most recent first
+    recent: VecDeque<String>,
+}
+
+impl CommandPaletteComponent {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            commands: Vec::new(),
+            matches: Vec::new(),
+            list_state: ListState::default(),
+            picker: None,
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open over the commands of the current view
+    pub fn open(&mut self, commands: Vec<PaletteCommand>) {
+        self.show(None, commands);
+    }
+
+    /// Open a follow-up picker for a command parameter
+    pub fn open_picker(&mut self, kind: BranchPicker, choices: Vec<PaletteCommand>) {
+        self.show(Some(kind), choices);
+    }
+
+    fn show(&mut self, picker: Option<BranchPicker>, commands: Vec<PaletteCommand>) {
+        self.open = true;
+        self.picker = picker;
+        self.query.clear();
+        self.commands = commands;
+        self.refilter();
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.picker = None;
+        self.commands.clear();
+        self.matches.clear();
+    }
+
+    /// Rank commands by match score, then by how recently they were run
+    fn refilter(&mut self) {
+        let recency = |id: &str| self.recent.iter().position(|recent| recent == id);
+        let mut ranked: Vec<(i64, usize, Vec<usize>)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, command)| {
+                let (score, positions) = fuzzy_match(&self.query, &command.label)?;
+                let bonus = recency(&command.id).map_or(0, |position| (RECENT_LIMIT - position) as i64 * 4);
+                Some((score + bonus, index, positions))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        self.matches = ranked.into_iter().map(|(_, index, positions)| (index, positions)).collect();
+        self.list_state.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn remember(&mut self, id: &str) {
+        self.recent.retain(|recent| recent != id);
+        self.recent.push_front(id.to_string());
+        self.recent.truncate(RECENT_LIMIT);
+    }
+
+    fn move_selection(&mut self, forward: bool) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let count = self.matches.len();
+        let current = self.list_state.selected().unwrap_or(0);
+        let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
+        self.list_state.select(Some(next));
+    }
+
+    /// Handle a key while open; returns the action to run once one is chosen
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<PaletteAction> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('p') if ctrl => self.close(),
+            KeyCode::Up => self.move_selection(false),
+            KeyCode::Down | KeyCode::Tab => self.move_selection(true),
+            KeyCode::Char('k') if ctrl => self.move_selection(false),
+            KeyCode::Char('j') if ctrl => self.move_selection(true),
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refilter();
+            }
+            KeyCode::Enter => return self.accept(),
+            _ => {}
+        }
+        None
+    }
+
+    fn accept(&mut self) -> Option<PaletteAction> {
+        if let Some(kind) = self.picker.filter(|kind| kind.takes_text()) {
+            let value = self.query.trim().to_string();
+            if value.is_empty() {
+                return None;
+            }
+            self.close();
+            return Some(PaletteAction::Picked(kind, value));
+        }
+
+        let selected = self.list_state.selected()?;
+        let (index, _) = self.matches.get(selected)?;
+        let command = self.commands.get(*index)?.clone();
+        self.remember(&command.id);
+        self.close();
+        Some(command.action)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if !self.open {
+            return;
+        }
+
+        let width = (area.width * 3 / 5).clamp(40.min(area.width), area.width);
+        let height = (area.height * 3 / 5).clamp(8.min(area.height), area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 4,
+            width,
+            height,
+        };
+        frame.render_widget(Clear, popup);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(popup);
+
+        let title = match self.picker {
+            Some(kind) => kind.title().to_string(),
+            None => "Commands".to_string(),
+        };
+        let prompt = Paragraph::new(Line::from(vec![
+            Span::styled("> ", theme.info_style()),
+            Span::styled(self.query.clone(), theme.text_style()),
+            Span::styled("█", theme.muted_style()),
+        ]))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(theme.accent_border_style()),
+        );
+        frame.render_widget(prompt, chunks[0]);
+
+        let inner_width = chunks[1].width.saturating_sub(4) as usize;
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .filter_map(|(index, positions)| Some((self.commands.get(*index)?, positions)))
+            .map(|(command, positions)| {
+                let mut spans: Vec<Span> = command
+                    .label
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let style = if positions.contains(&i) { theme.info_style() } else { theme.text_style() };
+                        Span::styled(c.to_string(), style)
+                    })
+                    .collect();
+
+                let group = format!("  {}", command.group);
+                let key = command.keybinding.clone().unwrap_or_default();
+                let used = command.label.chars().count() + group.chars().count() + key.chars().count();
+                spans.push(Span::styled(group, theme.muted_style()));
+                spans.push(Span::raw(" ".repeat(inner_width.saturating_sub(used).max(1))));
+                spans.push(Span::styled(key, theme.warning_style()));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let footer = match self.picker {
+            Some(kind) if kind.takes_text() => "Enter: create · Esc: cancel".to_string(),
+            _ => format!("{} of {} · ↑↓ select · Enter run · Esc close", self.matches.len(), self.commands.len()),
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(footer)
+                    .borders(Borders::ALL)
+                    .border_style(theme.border_style()),
+            )
+            .highlight_style(theme.highlight_style())
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
+    }
+}
+
+impl Default for CommandPaletteComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(id: &str, label: &str) -> PaletteCommand {
+        PaletteCommand::new(id, label, "Test", PaletteAction::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)))
+    }
+
+    fn type_query(palette: &mut CommandPaletteComponent, query: &str) {
+        for c in query.chars() {
+            palette.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    fn ranked(palette: &CommandPaletteComponent) -> Vec<&str> {
+        palette.matches.iter().map(|(index, _)| palette.commands[*index].id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let (_, positions) = fuzzy_match("chb", "Checkout branch").unwrap();
+        assert_eq!(positions, vec![0, 1, 9]);
+        assert!(fuzzy_match("xyz", "Checkout branch").is_none());
+        assert_eq!(fuzzy_match("", "anything"), Some((0, Vec::new())));
+
+        // Word starts and runs beat scattered letters
+        let word_start = fuzzy_match("cb", "Checkout branch").unwrap().0;
+        let scattered = fuzzy_match("cb", "Recombine").unwrap().0;
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_recent_commands_rank_first() {
+        let mut palette = CommandPaletteComponent::new();
+        let commands = vec![command("stage", "Stage file"), command("staged", "Show staged changes"), command("push", "Push")];
+
+        palette.open(commands.clone());
+        assert_eq!(ranked(&palette), vec!["stage", "staged", "push"]);
+        palette.handle_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+        assert!(matches!(palette.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)), Some(PaletteAction::Key(_))));
+        assert!(!palette.is_open());
+
+        palette.open(commands.clone());
+        assert_eq!(ranked(&palette)[0], "push");
+        type_query(&mut palette, "st");
+        assert_eq!(ranked(&palette), vec!["stage", "staged"]);
+    }
+
+    #[test]
+    fn test_view_commands_only_in_their_view_context() {
+        let labels = |context: ShortcutContext| -> Vec<String> {
+            view_commands(TabType::Stash, &context).into_iter().map(|command| command.label).collect()
+        };
+        assert!(labels(ShortcutContext::StashList).iter().any(|label| label == "Drop selected stash"));
+        assert!(!labels(ShortcutContext::Global).iter().any(|label| label == "Drop selected stash"));
+    }
+
+    #[test]
+    fn test_text_picker_returns_query() {
+        let mut palette = CommandPaletteComponent::new();
+        palette.open_picker(BranchPicker::Create, Vec::new());
+        assert!(palette.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).is_none());
+        type_query(&mut palette, "feature/x");
+        match palette.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)) {
+            Some(PaletteAction::Picked(BranchPicker::Create, name)) => assert_eq!(name, "feature/x"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
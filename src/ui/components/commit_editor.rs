@@ -0,0 +1,428 @@
+//! Commit editor: writes the commit message for the staged changes, linting
+//! it against the repository rules on every keystroke. Co-author and
+//! sign-off trailers can be toggled without retyping them.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    ai::agents::{CommitAgent, CommitAgentConfig, CommitGenerationRequest, CommitMessageStyle},
+    app::state::AppState,
+    error::AppResult,
+    git::{
+        commit_lint::{fix_message, lint_message},
+        trailers::{self, Identity, Trailer},
+        LintReport, LintRules, LintSeverity,
+    },
+    ui::{
+        diff::{InlineEditor, InlineEditorConfig, LineDiagnostic},
+        theme::Theme,
+    },
+};
+
+/// Generation attempts before the remaining violations are left to the user
+const MAX_GENERATE_ATTEMPTS: usize = 3;
+/// Identities offered by the co-author picker
+const MAX_COAUTHORS: usize = 20;
+
+/// Commit message editor with inline lint diagnostics
+pub struct CommitEditorComponent {
+    is_open: bool,
+    rules: LintRules,
+    report: LintReport,
+    editor: InlineEditor,
+    generate_task: Option<JoinHandle<AppResult<(String, LintReport)>>>,
+    error: Option<String>,
+    /// Committer, used for `Signed-off-by`
+    committer: Option<Identity>,
+    /// Co-author picker (Ctrl+A)
+    coauthors: Vec<Identity>,
+    picker_open: bool,
+    picker_state: ListState,
+}
+
+impl CommitEditorComponent {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            rules: LintRules::default(),
+            report: LintReport::default(),
+            editor: InlineEditor::new(InlineEditorConfig {
+                show_line_numbers: false,
+                auto_indent: false,
+                ..Default::default()
+            }),
+            generate_task: None,
+            error: None,
+            committer: None,
+            coauthors: Vec::new(),
+            picker_open: false,
+            picker_state: ListState::default(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Open an empty editor and load the repository lint rules; the
+    /// committer's sign-off is pre-filled when `auto_signoff` is configured
+    pub fn open(&mut self, state: &AppState) {
+        self.is_open = true;
+        self.error = None;
+        self.picker_open = false;
+        self.editor.set_text("");
+
+        let Some(git_service) = state.git_service.clone() else {
+            self.rules = LintRules::default();
+            self.relint();
+            return;
+        };
+        let (rules, committer) = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current()
+                .block_on(async { (git_service.load_lint_rules().await, git_service.committer_identity().await) })
+        });
+        self.rules = rules.unwrap_or_else(|e| {
+            self.error = Some(format!("Using default lint rules: {}", e));
+            LintRules::default()
+        });
+        self.committer = committer.ok().flatten();
+
+        if git_service.auto_signoff() {
+            if let Some(committer) = self.committer.clone() {
+                self.toggle_trailer(Trailer::signed_off_by(&committer));
+            }
+        }
+        self.relint();
+    }
+
+    /// Add the trailer, or remove it when already present
+    fn toggle_trailer(&mut self, trailer: Trailer) {
+        let (text, mut trailer_block) = trailers::split_trailers(&self.editor.text());
+        if trailer_block.iter().any(|t| t.matches(&trailer)) {
+            trailer_block.retain(|t| !t.matches(&trailer));
+        } else {
+            trailer_block.push(trailer);
+        }
+
+        // Keep an empty subject line above the trailers so typing starts at the top
+        let message = if text.trim().is_empty() && !trailer_block.is_empty() {
+            format!("\n\n{}", trailers::format_message("", &trailer_block))
+        } else {
+            trailers::format_message(&text, &trailer_block)
+        };
+        self.editor.set_text(&message);
+        self.relint();
+    }
+
+    fn open_picker(&mut self, state: &AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.recent_coauthors(MAX_COAUTHORS))
+        });
+        match result {
+            Ok(coauthors) if coauthors.is_empty() => self.error = Some("No recent authors to pick from".to_string()),
+            Ok(coauthors) => {
+                self.coauthors = coauthors;
+                self.picker_state.select(Some(0));
+                self.picker_open = true;
+            }
+            Err(e) => self.error = Some(format!("Failed to load recent authors: {}", e)),
+        }
+    }
+
+    fn handle_picker_key(&mut self, key: KeyEvent) {
+        let selected = self.picker_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.picker_open = false,
+            KeyCode::Up | KeyCode::Char('k') => self.picker_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.picker_state.select(Some((selected + 1).min(self.coauthors.len().saturating_sub(1))))
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(identity) = self.coauthors.get(selected) {
+                    self.toggle_trailer(Trailer::co_author(identity));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        if let Some(task) = self.generate_task.take() {
+            task.abort();
+        }
+    }
+
+    /// Lint the current text and show the violations next to their lines
+    fn relint(&mut self) {
+        let text = self.editor.text();
+        self.report = lint_message(&text, &self.rules);
+
+        // Lint lines skip `#` comments; map them back to editor lines
+        let lines: Vec<usize> = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.starts_with('#'))
+            .map(|(index, _)| index)
+            .collect();
+        let diagnostics = self
+            .report
+            .violations
+            .iter()
+            .map(|v| LineDiagnostic {
+                line: lines.get(v.line).copied().unwrap_or(v.line),
+                message: v.message.clone(),
+                is_error: v.severity == LintSeverity::Error,
+            })
+            .collect();
+        self.editor.set_diagnostics(diagnostics);
+    }
+
+    fn start_generate(&mut self, state: &AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        let staged_files: Vec<_> = state
+            .git_state
+            .file_status
+            .iter()
+            .filter(|file| file.status.is_staged())
+            .cloned()
+            .collect();
+        if staged_files.is_empty() {
+            self.error = Some("Nothing staged to describe".to_string());
+            return;
+        }
+        if let Some(task) = self.generate_task.take() {
+            task.abort();
+        }
+
+        let rules = self.rules.clone();
+        self.error = None;
+        self.generate_task = Some(tokio::spawn(async move {
+            let mut agent = CommitAgent::new(CommitAgentConfig::default(), Some(git_service));
+            let request = CommitGenerationRequest {
+                staged_files,
+                context: None,
+                style: CommitMessageStyle::Conventional,
+                max_length: Some(rules.header_max_length),
+                include_description: true,
+            };
+            agent
+                .generate_linted_commit_message(request, &rules, MAX_GENERATE_ATTEMPTS)
+                .await
+        }));
+    }
+
+    fn sync(&mut self) {
+        if self.generate_task.as_ref().is_some_and(|task| task.is_finished()) {
+            if let Some(task) = self.generate_task.take() {
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task)) {
+                    Ok(Ok((message, _))) => {
+                        // Keep the co-authors and sign-off already chosen
+                        let (_, chosen) = trailers::split_trailers(&self.editor.text());
+                        let message = chosen.iter().fold(message, |message, t| trailers::add_trailer(&message, t));
+                        self.editor.set_text(&message);
+                        self.relint();
+                    }
+                    Ok(Err(e)) => self.error = Some(e.to_string()),
+                    Err(e) => self.error = Some(format!("Commit message generation failed: {}", e)),
+                }
+            }
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.sync();
+
+        let violations_height = (self.report.violations.len().min(6) as u16 + 2).max(3);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(4),
+                Constraint::Min(0),
+                Constraint::Length(violations_height),
+            ])
+            .split(area);
+
+        let errors = self
+            .report
+            .violations
+            .iter()
+            .filter(|v| v.severity == LintSeverity::Error)
+            .count();
+        let (summary, style) = match &self.error {
+            Some(error) => (format!("⚠ {}", error), theme.warning_style()),
+            None if self.generate_task.is_some() => ("Generating commit message...".to_string(), theme.text_style()),
+            None if self.rules.severity == LintSeverity::Off => ("Linting is off".to_string(), theme.muted_style()),
+            None if self.report.is_clean() => ("✓ Message passes all lint rules".to_string(), theme.success_style()),
+            None => (
+                format!("{} errors, {} warnings", errors, self.report.violations.len() - errors),
+                if errors > 0 { theme.error_style() } else { theme.warning_style() },
+            ),
+        };
+        let header = Paragraph::new(vec![
+            Line::from(Span::styled(summary, style)),
+            Line::from(Span::styled(
+                "[Ctrl+S] Commit [Ctrl+L] Auto-fix [Ctrl+G] Generate [Ctrl+A] Co-author [Ctrl+O] Sign-off [Esc] Cancel",
+                theme.muted_style(),
+            )),
+        ])
+        .block(
+            Block::default()
+                .title("Commit")
+                .borders(Borders::ALL)
+                .border_style(theme.accent_border_style()),
+        )
+        .wrap(Wrap { trim: true });
+        frame.render_widget(header, chunks[0]);
+
+        if let Err(e) = self.editor.render(frame, chunks[1], theme) {
+            self.error = Some(e.to_string());
+        }
+
+        let items: Vec<ListItem> = self
+            .report
+            .violations
+            .iter()
+            .map(|v| {
+                let style = if v.severity == LintSeverity::Error { theme.error_style() } else { theme.warning_style() };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:>3}: ", v.line + 1), theme.muted_style()),
+                    Span::styled(v.message.clone(), style),
+                    Span::styled(format!(" [{}]", v.rule), theme.muted_style()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items).block(
+            Block::default()
+                .title("Lint")
+                .borders(Borders::ALL)
+                .border_style(theme.border_style()),
+        );
+        frame.render_widget(list, chunks[2]);
+
+        if self.picker_open {
+            self.render_picker(frame, chunks[1], theme);
+        }
+    }
+
+    fn render_picker(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let chosen = trailers::parse_trailers(&self.editor.text());
+        let items: Vec<ListItem> = self
+            .coauthors
+            .iter()
+            .map(|identity| {
+                let mark = if chosen.iter().any(|t| t.matches(&Trailer::co_author(identity))) { "✓ " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::styled(mark, theme.success_style()),
+                    Span::styled(identity.to_string(), theme.text_style()),
+                ]))
+            })
+            .collect();
+
+        let width = area.width.min(60);
+        let height = area.height.min(self.coauthors.len() as u16 + 2);
+        let popup = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title("Co-authors [Enter] Toggle [Esc] Close")
+                    .borders(Borders::ALL)
+                    .border_style(theme.accent_border_style()),
+            )
+            .highlight_style(theme.highlight_style());
+        frame.render_widget(Clear, popup);
+        frame.render_stateful_widget(list, popup, &mut self.picker_state);
+    }
+
+    /// Handle a key; returns `true` once the editor has closed
+    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<bool> {
+        if self.picker_open {
+            self.handle_picker_key(key);
+            return Ok(false);
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => {
+                self.close();
+                return Ok(true);
+            }
+            KeyCode::Char('s') if ctrl => return Ok(self.commit(state)),
+            KeyCode::Char('l') if ctrl => {
+                self.editor.set_text(&fix_message(&self.editor.text(), &self.rules));
+                self.relint();
+            }
+            KeyCode::Char('g') if ctrl => self.start_generate(state),
+            KeyCode::Char('a') if ctrl => self.open_picker(state),
+            KeyCode::Char('o') if ctrl => match self.committer.clone() {
+                Some(committer) => self.toggle_trailer(Trailer::signed_off_by(&committer)),
+                None => self.error = Some("Set user.name and user.email to sign off".to_string()),
+            },
+            _ if self.generate_task.is_none() => {
+                self.editor.handle_key(key)?;
+                self.relint();
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn commit(&mut self, state: &mut AppState) -> bool {
+        let Some(git_service) = state.git_service.clone() else {
+            return false;
+        };
+        if self.report.has_errors() {
+            self.error = Some("Fix the lint errors before committing (Ctrl+L fixes some)".to_string());
+            return false;
+        }
+        if !state.git_state.file_status.iter().any(|file| file.status.is_staged()) {
+            self.error = Some("Nothing staged to commit".to_string());
+            return false;
+        }
+
+        let message = self
+            .editor
+            .text()
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                let oid = git_service.commit(message.trim_end()).await?;
+                let status = git_service.get_status().await?;
+                AppResult::Ok((oid, status))
+            })
+        });
+        match result {
+            Ok((oid, status)) => {
+                state.update_git_status(status);
+                state.add_info(format!("Committed {}", &oid.to_string()[..7]));
+                self.close();
+                true
+            }
+            Err(e) => {
+                self.error = Some(format!("Commit failed: {}", e));
+                false
+            }
+        }
+    }
+}
+
+impl Default for CommitEditorComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
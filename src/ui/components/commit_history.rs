@@ -1,21 +1,32 @@
 //! Git commit history component with advanced features
 
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
     Frame,
 };
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use tokio::task::JoinHandle;
 
 use crate::{
     app::state::AppState,
+    config::DiffConfig,
     error::AppResult,
-    git::CommitInfo,
+    git::{
+        notes, trailers, CommitInfo, CommitNote, DiffSpec, HistoryCursor, HistoryPage, LogFilter, NotesFetchStatus, PatchApplyOptions,
+        PatchApplyOutcome, PatchConflict, PatchExportOptions,
+    },
     ui::{
-        components::DiffViewerComponent,
+        components::{
+            modals::{InputModal, Modal, ModalResult},
+            view_keys::ViewKey,
+            Component, DiffViewerComponent, FilterComponent, ShortcutContext,
+        },
+        diff::{InlineEditor, InlineEditorConfig},
         keyboard::{ShortcutManager, NavigationHandler, ActionKey},
         theme::Theme,
     },
@@ -41,13 +52,60 @@ pub struct CommitHistoryComponent {
     diff_viewer: DiffViewerComponent,
     commits_per_page: usize,
     current_page: usize,
-    search_filter: String,
-    author_filter: Option<String>,
+    /// Filter panel used to build history queries
+    filter_panel: FilterComponent,
+    /// Query the loaded commits were produced by
+    log_filter: LogFilter,
+    /// Commits loaded so far for the current query
+    loaded_commits: Vec<CommitInfo>,
+    /// Whether the walk has more matching commits beyond `loaded_commits`
+    has_more: bool,
+    /// Walk of the current query, positioned after the loaded commits
+    history_cursor: Option<HistoryCursor>,
+    /// Background request for the next page of commits
+    history_task: Option<JoinHandle<AppResult<(HistoryCursor, HistoryPage)>>>,
+    /// Status update the loaded commits correspond to; `None` forces a reload
+    loaded_at: Option<DateTime<Utc>>,
+    history_error: Option<String>,
     branch_colors: HashMap<String, Color>,
     shortcut_manager: ShortcutManager,
+    /// Other end of the selected commit range for patch export, as an
+    /// offset into `loaded_commits` so the range can span pages
+    range_anchor: Option<usize>,
+    /// Prompt for the patch file to apply
+    patch_input: InputModal,
+    /// Patch series stopped by conflicts, waiting for continue/skip/abort
+    patch_conflict: Option<PatchConflict>,
+    /// Files a plain diff left conflicted, cleared once they are staged
+    diff_conflict: Option<Vec<String>>,
+    /// Notes per commit hash, loaded when the details pane shows the commit
+    notes_cache: HashMap<String, Vec<CommitNote>>,
+    /// Configured notes ref that E/D act on
+    notes_ref_index: usize,
+    /// Note being edited as (commit, notes ref)
+    note_target: Option<(String, String)>,
+    note_editor: InlineEditor,
 }
 
 impl CommitHistoryComponent {
+    /// 提交列表的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Enter, "View selected commit").hint("Enter: View"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('/'), "Filter history"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('a'), "Add history filter"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('m'), "Cycle history layout"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('f'), "Toggle changed files"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('v'), "Mark patch range"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('x'), "Export range as patches"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('X'), "Export range with cover letter"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('i'), "Apply patches…"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('E'), "Edit commit note").hint("E/D: Note"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('D'), "Delete commit note"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('R'), "Switch notes ref").hint("R: Notes ref"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('P'), "Push notes").hint("P/F: Push/Fetch notes"),
+        ViewKey::new(ShortcutContext::CommitList, KeyCode::Char('F'), "Fetch notes"),
+    ];
+
     pub fn new() -> Self {
         Self {
             selected_index: 0,
@@ -57,13 +115,49 @@ impl CommitHistoryComponent {
             diff_viewer: DiffViewerComponent::new(),
             commits_per_page: 50,
             current_page: 0,
-            search_filter: String::new(),
-            author_filter: None,
+            filter_panel: FilterComponent::new().with_apply_on_enter(),
+            log_filter: LogFilter::default(),
+            loaded_commits: Vec::new(),
+            has_more: false,
+            history_cursor: None,
+            history_task: None,
+            loaded_at: None,
+            history_error: None,
             branch_colors: Self::init_branch_colors(),
             shortcut_manager: ShortcutManager::new(),
+            range_anchor: None,
+            patch_input: InputModal::new(),
+            patch_conflict: None,
+            diff_conflict: None,
+            notes_cache: HashMap::new(),
+            notes_ref_index: 0,
+            note_target: None,
+            note_editor: InlineEditor::new(InlineEditorConfig {
+                show_line_numbers: false,
+                auto_indent: false,
+                ..Default::default()
+            }),
         }
     }
 
+    pub fn set_diff_config(&mut self, config: &DiffConfig) {
+        self.diff_viewer.set_diff_config(config);
+    }
+
+    /// Whether the note editor, the filter panel or the patch path prompt
+    /// should receive every key
+    pub fn captures_text_input(&self) -> bool {
+        self.note_target.is_some()
+            || self.filter_panel.is_open()
+            || self.patch_input.is_open()
+            || self.diff_viewer.is_editing()
+    }
+
+    /// Whether the commit diff is shown and not editing
+    pub fn shows_diff(&self) -> bool {
+        self.show_commit_details && !self.captures_text_input()
+    }
+
     fn init_branch_colors() -> HashMap<String, Color> {
         let mut colors = HashMap::new();
         colors.insert("main".to_string(), Color::Green);
@@ -76,11 +170,23 @@ impl CommitHistoryComponent {
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        self.sync_history(state);
+
         if self.show_commit_details {
             self.render_commit_details_view(frame, area, state, theme);
         } else {
             self.render_commit_list_view(frame, area, state, theme);
         }
+
+        if self.patch_input.is_open() {
+            self.patch_input.render(frame, area, theme);
+        }
+        if self.filter_panel.is_open() {
+            self.filter_panel.render(frame, area, state, theme);
+        }
+        if self.note_target.is_some() {
+            self.render_note_editor(frame, area, theme);
+        }
     }
 
     fn render_commit_list_view(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
@@ -111,14 +217,39 @@ impl CommitHistoryComponent {
         self.render_footer(frame, chunks[2], &commits, theme);
     }
 
-    fn render_header(&self, frame: &mut Frame, area: Rect, _state: &AppState, theme: &Theme) {
-        let header_text = format!(
-            "📈 History [{}] | Filter: {} | Author: {} | Mode: {:?}",
+    fn render_header(&self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        let mut header_text = format!(
+            "📈 History [{}] | Filter: {} | Mode: {:?}",
             self.commits_per_page,
-            if self.search_filter.is_empty() { "none" } else { &self.search_filter },
-            self.author_filter.as_deref().unwrap_or("all"),
+            self.log_filter.describe(),
             self.display_mode
         );
+        if let Some(notes_ref) = self.active_notes_ref(state) {
+            header_text.push_str(&format!(" | Notes: {}", notes::short_notes_ref(&notes_ref)));
+        }
+
+        if let Some(error) = &self.history_error {
+            header_text.push_str(&format!(" | ⚠ {}", error));
+        } else if self.history_task.is_some() {
+            header_text.push_str(" | Loading...");
+        }
+
+        if let Some(conflict) = &self.patch_conflict {
+            header_text = format!(
+                "⚠ Patch {}/{} conflicts: {} ({} files) | [C]ontinue [S]kip [A]bort",
+                conflict.patch_number,
+                conflict.total,
+                conflict.subject,
+                conflict.conflicted_files.len()
+            );
+        } else if let Some(conflicted_files) = &self.diff_conflict {
+            header_text = format!(
+                "⚠ Diff conflicts in {} files: stage the resolutions | [C]ontinue [A]bort",
+                conflicted_files.len()
+            );
+        } else if let Some((start, end)) = self.selected_range() {
+            header_text.push_str(&format!(" | Range: {} commits", end - start + 1));
+        }
 
         let header = Paragraph::new(header_text)
             .block(
@@ -144,8 +275,10 @@ impl CommitHistoryComponent {
                     theme.text_style()
                 };
 
+                let marker = if self.is_in_range(index) { "◆" } else { "●" };
+
                 let commit_line = Line::from(vec![
-                    Span::styled("●", Style::default().fg(Color::Yellow)),
+                    Span::styled(marker, Style::default().fg(Color::Yellow)),
                     Span::raw(" "),
                     Span::styled(
                         &commit.hash[..8],
@@ -275,29 +408,36 @@ impl CommitHistoryComponent {
     fn render_commit_details_view(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
         let commits = self.get_filtered_commits(state);
         if let Some(commit) = commits.get(self.selected_index) {
+            // Room for up to four trailer lines below the message
+            let trailer_count = trailers::parse_trailers(&commit.message).len().min(4) as u16;
+            let commit_notes = self.commit_notes(&commit.hash, state);
+            let note_lines = commit_notes.iter().map(|note| note.message.lines().count() + 1).sum::<usize>().min(6) as u16;
+            let info_height = 8
+                + if trailer_count > 0 { trailer_count + 2 } else { 0 }
+                + if note_lines > 0 { note_lines + 1 } else { 0 };
             if self.show_file_list {
                 // Split into commit info and file list
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Length(8),   // Commit info
+                        Constraint::Length(info_height),   // Commit info
                         Constraint::Min(0),      // File list
                     ])
                     .split(area);
 
-                self.render_commit_info(frame, chunks[0], commit, theme);
+                self.render_commit_info(frame, chunks[0], commit, &commit_notes, theme);
                 self.render_commit_files(frame, chunks[1], commit, theme);
             } else {
                 // Show diff viewer
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
-                        Constraint::Length(8),   // Commit info
+                        Constraint::Length(info_height),   // Commit info
                         Constraint::Min(0),      // Diff viewer
                     ])
                     .split(area);
 
-                self.render_commit_info(frame, chunks[0], commit, theme);
+                self.render_commit_info(frame, chunks[0], commit, &commit_notes, theme);
 
                 // Render diff viewer
                 tokio::task::block_in_place(|| {
@@ -311,8 +451,9 @@ impl CommitHistoryComponent {
         }
     }
 
-    fn render_commit_info(&self, frame: &mut Frame, area: Rect, commit: &CommitInfo, theme: &Theme) {
-        let info_text = vec![
+    fn render_commit_info(&self, frame: &mut Frame, area: Rect, commit: &CommitInfo, commit_notes: &[CommitNote], theme: &Theme) {
+        let (message, trailer_block) = trailers::split_trailers(&commit.message);
+        let mut info_text = vec![
             Line::from(vec![
                 Span::styled("Commit: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::styled(&commit.hash, Style::default().fg(Color::Yellow)),
@@ -332,8 +473,35 @@ impl CommitHistoryComponent {
             Line::from(vec![
                 Span::styled("Message: ", Style::default().add_modifier(Modifier::BOLD)),
             ]),
-            Line::from(commit.message.clone()),
+            Line::from(message),
         ];
+        // Trailers go above the message so a long message cannot push them out of view
+        if !trailer_block.is_empty() {
+            let mut lines = vec![
+                Line::from(""),
+                Line::from(Span::styled("Trailers: ", Style::default().add_modifier(Modifier::BOLD))),
+            ];
+            lines.extend(trailer_block.into_iter().map(|trailer| {
+                Line::from(vec![
+                    Span::styled(format!("{}: ", trailer.token), Style::default().fg(Color::Cyan)),
+                    Span::raw(trailer.value),
+                ])
+            }));
+            info_text.splice(3..3, lines);
+        }
+        // Notes follow the trailers, each under its notes ref
+        if !commit_notes.is_empty() {
+            let at = info_text.len() - 3;
+            let mut lines = vec![Line::from("")];
+            for note in commit_notes {
+                lines.push(Line::from(Span::styled(
+                    format!("Notes ({}):", note.short_ref()),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )));
+                lines.extend(note.message.lines().map(|line| Line::from(Span::styled(format!("  {}", line), Style::default().fg(Color::Magenta)))));
+            }
+            info_text.splice(at..at, lines);
+        }
 
         let info_paragraph = Paragraph::new(info_text)
             .block(
@@ -347,19 +515,19 @@ impl CommitHistoryComponent {
         frame.render_widget(info_paragraph, area);
     }
 
-    fn render_commit_files(&self, frame: &mut Frame, area: Rect, commit: &CommitInfo, theme: &Theme) {
-        // Mock file list for now - in real implementation, get from git service
-        let files = vec![
-            "src/main.rs",
-            "src/lib.rs",
-            "Cargo.toml",
-            "README.md",
-        ];
+    fn render_commit_files(&self, frame: &mut Frame, area: Rect, _commit: &CommitInfo, theme: &Theme) {
+        // Files of the commit diff loaded into the diff viewer
+        let files = self.diff_viewer.revision_diff().map(|diff| diff.files.as_slice()).unwrap_or_default();
 
         let items: Vec<ListItem> = files
             .iter()
             .map(|file| {
-                ListItem::new(format!("M  {}", file))
+                let counts = if file.is_binary {
+                    "binary".to_string()
+                } else {
+                    format!("+{} -{}", file.additions, file.deletions)
+                };
+                ListItem::new(format!("{}  {}  {}", file.status.code(), file.display_path(), counts))
                     .style(theme.text_style())
             })
             .collect();
@@ -367,7 +535,7 @@ impl CommitHistoryComponent {
         let list = List::new(items)
             .block(
                 Block::default()
-                    .title("Changed Files")
+                    .title(format!("Changed Files ({})", files.len()))
                     .borders(Borders::ALL)
                     .border_style(theme.border_style()),
             );
@@ -375,64 +543,123 @@ impl CommitHistoryComponent {
         frame.render_widget(list, area);
     }
 
-    fn render_footer(&self, frame: &mut Frame, area: Rect, commits: &[CommitInfo], theme: &Theme) {
-        let total_pages = (commits.len() + self.commits_per_page - 1) / self.commits_per_page;
+    fn render_footer(&self, frame: &mut Frame, area: Rect, _commits: &[CommitInfo], theme: &Theme) {
+        let total_pages = self.loaded_commits.len().div_ceil(self.commits_per_page);
         let footer_text = format!(
-            "Page {}/{} | {} commits | [M]ode [Enter]Details [F]iles [/]Search [A]uthor [G]raph",
+            "Page {}/{}{} | {}{} commits | [M]ode [Enter]Details [F]iles [/]Filter [A]uthor [G]raph [V]Range [X]Export [I]Apply",
             self.current_page + 1,
             total_pages.max(1),
-            commits.len()
+            if self.has_more { "+" } else { "" },
+            self.loaded_commits.len(),
+            if self.has_more { "+" } else { "" }
         );
 
         let footer = Paragraph::new(footer_text)
-            .style(Style::default().fg(Color::Gray))
+            .style(theme.muted_style())
             .wrap(Wrap { trim: true });
 
         frame.render_widget(footer, area);
     }
 
-    fn get_filtered_commits(&self, state: &AppState) -> Vec<CommitInfo> {
-        // Get commits from git service (mock data for now)
-        let all_commits = if let Some(git_service) = &state.git_service {
-            tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(async {
-                    git_service.get_commit_history(self.commits_per_page).await.unwrap_or_default()
-                })
-            })
+    /// Commits of the current page
+    fn get_filtered_commits(&self, _state: &AppState) -> Vec<CommitInfo> {
+        let start_idx = self.current_page * self.commits_per_page;
+        let end_idx = (start_idx + self.commits_per_page).min(self.loaded_commits.len());
+
+        if start_idx < end_idx {
+            self.loaded_commits[start_idx..end_idx].to_vec()
         } else {
             vec![]
-        };
+        }
+    }
 
-        // Apply filters
-        let mut filtered: Vec<CommitInfo> = all_commits.into_iter()
-            .filter(|commit| {
-                // Search filter
-                if !self.search_filter.is_empty() {
-                    if !commit.message.to_lowercase().contains(&self.search_filter.to_lowercase())
-                        && !commit.hash.to_lowercase().contains(&self.search_filter.to_lowercase()) {
-                        return false;
+    /// Collect finished background walks and start new ones when needed
+    fn sync_history(&mut self, state: &AppState) {
+        if self.history_task.as_ref().is_some_and(|task| task.is_finished()) {
+            if let Some(task) = self.history_task.take() {
+                let result = tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task));
+                match result {
+                    Ok(Ok((cursor, page))) => {
+                        if page.offset == 0 {
+                            self.loaded_commits = page.commits;
+                        } else {
+                            self.loaded_commits.truncate(page.offset);
+                            self.loaded_commits.extend(page.commits);
+                        }
+                        self.has_more = page.has_more;
+                        self.history_cursor = Some(cursor);
+                        self.history_error = None;
+
+                        // A reload may have returned fewer commits than before
+                        let pages = self.loaded_commits.len().div_ceil(self.commits_per_page).max(1);
+                        if self.current_page >= pages {
+                            self.current_page = pages - 1;
+                            self.selected_index = 0;
+                        }
                     }
+                    Ok(Err(e)) => self.history_error = Some(e.to_string()),
+                    Err(e) => self.history_error = Some(format!("History query failed: {}", e)),
                 }
+            }
+        }
 
-                // Author filter
-                if let Some(ref author_filter) = self.author_filter {
-                    if !commit.author.to_lowercase().contains(&author_filter.to_lowercase()) {
-                        return false;
-                    }
-                }
+        // Walk again when the repository status was refreshed. The new walk
+        // covers every loaded page at once, and the old commits stay visible
+        // until it returns.
+        if self.loaded_at != Some(state.git_state.last_status_update) {
+            self.loaded_at = Some(state.git_state.last_status_update);
+            let limit = self.loaded_commits.len().max(self.commits_per_page);
+            self.start_history_query(state, None, limit);
+            return;
+        }
 
-                true
-            })
-            .collect();
+        // Fetch the next page ahead of time once the current page reaches the loaded end
+        let needed = (self.current_page + 2) * self.commits_per_page;
+        if self.has_more && self.history_task.is_none() && self.loaded_commits.len() < needed {
+            if let Some(cursor) = self.history_cursor.take() {
+                self.start_history_query(state, Some(cursor), self.commits_per_page);
+            }
+        }
+    }
 
-        // Pagination
-        let start_idx = self.current_page * self.commits_per_page;
-        let end_idx = (start_idx + self.commits_per_page).min(filtered.len());
+    /// Request the next `limit` commits in the background, continuing
+    /// `cursor` or starting a new walk for the current filter
+    fn start_history_query(&mut self, state: &AppState, cursor: Option<HistoryCursor>, limit: usize) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
 
-        if start_idx < filtered.len() {
-            filtered[start_idx..end_idx].to_vec()
-        } else {
-            vec![]
+        if let Some(task) = self.history_task.take() {
+            task.abort();
+        }
+
+        let filter = self.log_filter.clone();
+        self.history_task = Some(tokio::spawn(async move {
+            let cursor = match cursor {
+                Some(cursor) => cursor,
+                None => git_service.history_cursor(&filter).await?,
+            };
+            let page = cursor.next_page(limit).await?;
+            Ok((cursor, page))
+        }));
+    }
+
+    /// Replace the query and reload from the first page
+    fn set_log_filter(&mut self, filter: LogFilter) {
+        self.log_filter = filter;
+        self.current_page = 0;
+        self.selected_index = 0;
+        self.range_anchor = None;
+        self.has_more = false;
+        self.loaded_commits.clear();
+        self.history_cursor = None;
+        self.loaded_at = None;
+    }
+
+    fn apply_filter_panel(&mut self, state: &mut AppState) {
+        match self.filter_panel.to_log_filter() {
+            Ok(filter) => self.set_log_filter(filter),
+            Err(e) => state.add_error(format!("Invalid history filter: {}", e)),
         }
     }
 
@@ -443,10 +670,62 @@ impl CommitHistoryComponent {
     }
 
     pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        if self.filter_panel.is_open() {
+            self.filter_panel.handle_key_event(key, state)?;
+            if self.filter_panel.take_apply_request() {
+                self.apply_filter_panel(state);
+            }
+            return Ok(());
+        }
+
+        if self.patch_input.is_open() {
+            if let ModalResult::Input(path) = self.patch_input.handle_key_event(key)? {
+                if !path.trim().is_empty() {
+                    self.apply_patch_file(path.trim(), state);
+                }
+            }
+            return Ok(());
+        }
+
+        if self.patch_conflict.is_some() && self.handle_patch_conflict_key(key, state) {
+            return Ok(());
+        }
+        if self.diff_conflict.is_some() && self.handle_diff_conflict_key(key, state) {
+            return Ok(());
+        }
+
+        if self.note_target.is_some() {
+            return self.handle_note_editor_key(key, state);
+        }
+        if self.handle_notes_key(key, state) {
+            return Ok(());
+        }
+
         if self.show_commit_details {
             return self.handle_details_key_event(key, state);
         }
 
+        // Filter keys work on an empty result too
+        match key.code {
+            KeyCode::Char('/') => {
+                // Build a history query in the filter panel
+                self.filter_panel.open();
+                return Ok(());
+            }
+            KeyCode::Char('a') => {
+                // Add a filter (e.g. author) straight away
+                self.filter_panel.open();
+                self.filter_panel.add_filter();
+                return Ok(());
+            }
+            KeyCode::Esc if !self.log_filter.is_empty() => {
+                self.filter_panel.clear_all_filters();
+                self.set_log_filter(LogFilter::default());
+                return Ok(());
+            }
+            _ => {}
+        }
+
         let commits = self.get_filtered_commits(state);
 
         // Reset selected_index if no commits available
@@ -476,14 +755,26 @@ impl CommitHistoryComponent {
                 self.show_file_list = !self.show_file_list;
                 return Ok(());
             }
-            KeyCode::Char('/') => {
-                // Start search (History-specific)
-                tracing::info!("Search mode activated");
+            KeyCode::Char('v') => {
+                // Mark the other end of a commit range for patch export
+                self.range_anchor = match self.range_anchor {
+                    Some(_) => None,
+                    None => Some(self.selected_offset()),
+                };
                 return Ok(());
             }
-            KeyCode::Char('a') => {
-                // Filter by author (History-specific)
-                tracing::info!("Author filter activated");
+            KeyCode::Char('x') | KeyCode::Char('X') => {
+                // Export the selected range as mbox patches (X adds a cover letter)
+                let cover_letter = key.code == KeyCode::Char('X');
+                self.export_selected_range(cover_letter, state);
+                return Ok(());
+            }
+            KeyCode::Char('i') => {
+                self.patch_input.open_with_placeholder(
+                    "Apply Patches",
+                    "Path to mbox or diff file:",
+                    "patches/0001-example.patch",
+                );
                 return Ok(());
             }
             KeyCode::PageUp => {
@@ -495,8 +786,8 @@ impl CommitHistoryComponent {
                 return Ok(());
             }
             KeyCode::PageDown => {
-                // Page navigation (History-specific)
-                let total_pages = (commits.len() + self.commits_per_page - 1) / self.commits_per_page;
+                // Page navigation (History-specific); later pages are walked in the background
+                let total_pages = self.loaded_commits.len().div_ceil(self.commits_per_page);
                 if self.current_page < total_pages.saturating_sub(1) {
                     self.current_page += 1;
                     self.selected_index = 0;
@@ -528,13 +819,8 @@ impl CommitHistoryComponent {
                     }
                 }
                 ActionKey::Cancel => {
-                    // Clear filters or exit details
-                    if self.show_commit_details {
-                        self.show_commit_details = false;
-                    } else {
-                        self.search_filter.clear();
-                        self.author_filter = None;
-                    }
+                    // Exit details (filters are cleared above)
+                    self.show_commit_details = false;
                 }
                 _ => {
                     // 其他动作键暂时忽略
@@ -545,8 +831,346 @@ impl CommitHistoryComponent {
         Ok(())
     }
 
+    /// Offset of the selected commit in `loaded_commits`
+    fn selected_offset(&self) -> usize {
+        self.current_page * self.commits_per_page + self.selected_index
+    }
+
+    /// Selected commit range as (newest, oldest) offsets into `loaded_commits`
+    fn selected_range(&self) -> Option<(usize, usize)> {
+        let selected = self.selected_offset();
+        self.range_anchor.map(|anchor| (anchor.min(selected), anchor.max(selected)))
+    }
+
+    /// Whether the commit at `index` on the current page is in the selected range
+    fn is_in_range(&self, index: usize) -> bool {
+        let offset = self.current_page * self.commits_per_page + index;
+        self.selected_range()
+            .map(|(start, end)| offset >= start && offset <= end)
+            .unwrap_or(false)
+    }
+
+    fn export_selected_range(&mut self, cover_letter: bool, state: &mut AppState) {
+        let selected = self.selected_offset();
+        let (start, end) = self.selected_range().unwrap_or((selected, selected));
+        let (Some(newest), Some(oldest)) = (self.loaded_commits.get(start), self.loaded_commits.get(end)) else {
+            return;
+        };
+        let (newest, oldest) = (newest.hash.clone(), oldest.hash.clone());
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+
+        let options = PatchExportOptions {
+            cover_letter,
+            ..Default::default()
+        };
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                git_service.export_patches(&oldest, &newest, &options).await
+            })
+        });
+
+        match result {
+            Ok(files) => {
+                state.add_info(format!(
+                    "Exported {} patch files to {}",
+                    files.len(),
+                    options.output_dir.display()
+                ));
+                self.range_anchor = None;
+            }
+            Err(e) => state.add_error(format!("Failed to export patches: {}", e)),
+        }
+    }
+
+    fn apply_patch_file(&mut self, path: &str, state: &mut AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+
+        let patch_path = std::path::PathBuf::from(path);
+        let patch_path = if patch_path.is_absolute() {
+            patch_path
+        } else {
+            git_service.repo_path().join(patch_path)
+        };
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                git_service.apply_patches(&patch_path, &PatchApplyOptions::default()).await
+            })
+        });
+        self.handle_patch_outcome(result, state);
+    }
+
+    /// Handle continue/skip/abort while a patch series is stopped; returns true if consumed
+    fn handle_patch_conflict_key(&mut self, key: KeyEvent, state: &mut AppState) -> bool {
+        let Some(git_service) = state.git_service.clone() else {
+            return false;
+        };
+
+        match key.code {
+            KeyCode::Char('C') => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.continue_patch_apply())
+                });
+                self.handle_patch_outcome(result, state);
+                true
+            }
+            KeyCode::Char('S') => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.skip_patch())
+                });
+                self.handle_patch_outcome(result, state);
+                true
+            }
+            KeyCode::Char('A') => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.abort_patch_apply())
+                });
+                match result {
+                    Ok(()) => {
+                        self.patch_conflict = None;
+                        state.add_info("Aborted patch series".to_string());
+                    }
+                    Err(e) => state.add_error(format!("Failed to abort patch series: {}", e)),
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handle continue/abort while a plain diff has conflicts; returns true if consumed
+    fn handle_diff_conflict_key(&mut self, key: KeyEvent, state: &mut AppState) -> bool {
+        let (Some(git_service), Some(conflicted_files)) = (state.git_service.clone(), self.diff_conflict.clone()) else {
+            return false;
+        };
+
+        match key.code {
+            KeyCode::Char('C') => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.continue_diff_apply(&conflicted_files))
+                });
+                self.handle_patch_outcome(result, state);
+                true
+            }
+            KeyCode::Char('A') => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.abort_diff_apply(&conflicted_files))
+                });
+                match result {
+                    Ok(()) => {
+                        self.diff_conflict = None;
+                        self.loaded_at = None;
+                        state.add_info(format!("Reset {} conflicted files to HEAD", conflicted_files.len()));
+                    }
+                    Err(e) => state.add_error(format!("Failed to reset conflicted files: {}", e)),
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_patch_outcome(&mut self, result: AppResult<PatchApplyOutcome>, state: &mut AppState) {
+        match result {
+            Ok(PatchApplyOutcome::Applied { count }) => {
+                self.patch_conflict = None;
+                self.diff_conflict = None;
+                self.loaded_at = None;
+                state.add_info(format!("Applied {} patches", count));
+            }
+            Ok(PatchApplyOutcome::Conflict(conflict)) => {
+                state.add_error(format!(
+                    "Patch {}/{} '{}' has conflicts in: {}",
+                    conflict.patch_number,
+                    conflict.total,
+                    conflict.subject,
+                    conflict.conflicted_files.join(", ")
+                ));
+                self.patch_conflict = Some(conflict);
+            }
+            Ok(PatchApplyOutcome::DiffConflict { conflicted_files }) => {
+                state.add_error(format!(
+                    "Diff has conflicts in: {}; stage the resolved files",
+                    conflicted_files.join(", ")
+                ));
+                self.diff_conflict = Some(conflicted_files);
+            }
+            Err(e) => state.add_error(format!("Failed to apply patches: {}", e)),
+        }
+    }
+
+    /// Configured notes ref that note edits go to
+    fn active_notes_ref(&self, state: &AppState) -> Option<String> {
+        let refs = state.git_service.as_ref()?.notes_refs();
+        refs.get(self.notes_ref_index % refs.len().max(1)).cloned()
+    }
+
+    /// Notes of a commit, cached until notes change
+    fn commit_notes(&mut self, commit: &str, state: &AppState) -> Vec<CommitNote> {
+        if let Some(commit_notes) = self.notes_cache.get(commit) {
+            return commit_notes.clone();
+        }
+        let Some(git_service) = &state.git_service else {
+            return Vec::new();
+        };
+        let commit_notes = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.get_commit_notes(commit))
+        })
+        .unwrap_or_else(|e| {
+            tracing::debug!("Failed to load notes of {}: {}", commit, e);
+            Vec::new()
+        });
+        self.notes_cache.insert(commit.to_string(), commit_notes.clone());
+        commit_notes
+    }
+
+    /// Notes keys: E edit, D remove, R switch notes ref, P push, F fetch
+    fn handle_notes_key(&mut self, key: KeyEvent, state: &mut AppState) -> bool {
+        let Some(git_service) = state.git_service.clone() else {
+            return false;
+        };
+        let selected = self.get_filtered_commits(state).get(self.selected_index).map(|c| c.hash.clone());
+        let notes_ref = self.active_notes_ref(state).unwrap_or_else(|| notes::DEFAULT_NOTES_REF.to_string());
+
+        match (key.code, selected) {
+            (KeyCode::Char('E'), Some(commit)) => {
+                let existing = self
+                    .commit_notes(&commit, state)
+                    .into_iter()
+                    .find(|note| note.notes_ref == notes_ref)
+                    .map(|note| note.message)
+                    .unwrap_or_default();
+                self.note_editor.set_text(&existing);
+                self.note_target = Some((commit, notes_ref));
+            }
+            (KeyCode::Char('D'), Some(commit)) => {
+                let short = notes::short_notes_ref(&notes_ref);
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(git_service.remove_commit_note(&commit, &notes_ref))) {
+                    Ok(true) => state.add_info(format!("Removed {} note from {}", short, &commit[..7.min(commit.len())])),
+                    Ok(false) => state.add_info(format!("No {} note on this commit", short)),
+                    Err(e) => state.add_error(format!("Failed to remove note: {}", e)),
+                }
+                self.notes_cache.remove(&commit);
+            }
+            (KeyCode::Char('R'), _) => {
+                self.notes_ref_index = (self.notes_ref_index + 1) % git_service.notes_refs().len().max(1);
+                if let Some(notes_ref) = self.active_notes_ref(state) {
+                    state.add_info(format!("Editing notes in {}", notes_ref));
+                }
+            }
+            (KeyCode::Char('P'), _) => match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(git_service.push_notes(None))) {
+                Ok((_, pushed)) if pushed.is_empty() => state.add_info("No local notes to push".to_string()),
+                Ok((remote, pushed)) => state.add_info(format!("Pushed {} to {}", pushed.join(", "), remote)),
+                Err(e) => state.add_error(format!("Failed to push notes: {}", e)),
+            },
+            (KeyCode::Char('F'), _) => {
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(git_service.fetch_notes(None))) {
+                    Ok((remote, results)) => {
+                        let summary: Vec<String> = results
+                            .iter()
+                            .map(|result| {
+                                let short = notes::short_notes_ref(&result.notes_ref);
+                                match &result.status {
+                                    NotesFetchStatus::Missing => format!("{}: not on {}", short, remote),
+                                    NotesFetchStatus::UpToDate => format!("{}: up to date", short),
+                                    NotesFetchStatus::Updated => format!("{}: updated", short),
+                                    NotesFetchStatus::LocalAhead => format!("{}: local is ahead", short),
+                                    NotesFetchStatus::Diverged { tracking_ref } => {
+                                        format!("{}: diverged, run git notes --ref {} merge {}", short, short, tracking_ref)
+                                    }
+                                }
+                            })
+                            .collect();
+                        state.add_info(format!("Fetched notes from {}: {}", remote, summary.join("; ")));
+                    }
+                    Err(e) => state.add_error(format!("Failed to fetch notes: {}", e)),
+                }
+                self.notes_cache.clear();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn handle_note_editor_key(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.note_target = None,
+            KeyCode::Char('s') if ctrl => {
+                let (Some((commit, notes_ref)), Some(git_service)) = (self.note_target.clone(), state.git_service.clone()) else {
+                    return Ok(());
+                };
+                let message = self.note_editor.text();
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(async {
+                        if message.trim().is_empty() {
+                            git_service.remove_commit_note(&commit, &notes_ref).await.map(|_| ())
+                        } else {
+                            git_service.set_commit_note(&commit, &notes_ref, &message).await
+                        }
+                    })
+                });
+                match result {
+                    Ok(()) => {
+                        state.add_info(format!("Saved {} note", notes::short_notes_ref(&notes_ref)));
+                        self.notes_cache.remove(&commit);
+                        self.note_target = None;
+                    }
+                    Err(e) => state.add_error(format!("Failed to save note: {}", e)),
+                }
+            }
+            _ => {
+                self.note_editor.handle_key(key)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn render_note_editor(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some((commit, notes_ref)) = &self.note_target else {
+            return;
+        };
+        let width = (area.width * 7 / 10).max(40).min(area.width);
+        let height = (area.height * 6 / 10).max(10).min(area.height);
+        let popup = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(popup);
+
+        let header = Paragraph::new(Line::from(Span::styled(
+            "[Ctrl+S] Save (empty removes) [Esc] Cancel",
+            theme.muted_style(),
+        )))
+        .block(
+            Block::default()
+                .title(format!("Note {} on {}", notes::short_notes_ref(notes_ref), &commit[..7.min(commit.len())]))
+                .borders(Borders::ALL)
+                .border_style(theme.accent_border_style()),
+        );
+        frame.render_widget(Clear, popup);
+        frame.render_widget(header, chunks[0]);
+        if let Err(e) = self.note_editor.render(frame, chunks[1], theme) {
+            tracing::debug!("Failed to render note editor: {}", e);
+        }
+    }
+
     fn handle_details_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
         match key.code {
+            // The diff viewer's editor takes every key while open
+            _ if self.diff_viewer.is_editing() => {
+                tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(async {
+                        if let Err(e) = self.diff_viewer.handle_key(key).await {
+                            tracing::debug!("Diff viewer key handling failed: {}", e);
+                        }
+                    })
+                });
+            }
             KeyCode::Esc => {
                 self.show_commit_details = false;
             }
@@ -569,20 +1193,38 @@ impl CommitHistoryComponent {
         Ok(())
     }
 
-    fn load_commit_diff(&mut self, commit: &CommitInfo, _state: &AppState) -> AppResult<()> {
-        // In real implementation, would get commit diff from git service
-        let old_content = "// Original version\nfn main() {\n    println!(\"Hello\");\n}".to_string();
-        let new_content = "// Updated version\nfn main() {\n    println!(\"Hello, World!\");\n    println!(\"New feature added\");\n}".to_string();
+    fn load_commit_diff(&mut self, commit: &CommitInfo, state: &AppState) -> AppResult<()> {
+        // File history entries carry the path the file had in that commit,
+        // and the path in the parent when the commit renamed it
+        if let (Some(path), Some(git_service)) = (&commit.path, &state.git_service) {
+            let old_path = commit.previous_path.as_ref().unwrap_or(path);
+            let new_content = git_service.get_file_content_at(&commit.hash, path)?;
+            let old_content = git_service
+                .get_file_content_at(&format!("{}^", commit.hash), old_path)
+                .unwrap_or_default();
+            let file_path = std::path::PathBuf::from(path);
 
-        let file_path = std::path::PathBuf::from("src/main.rs");
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    if let Err(e) = self.diff_viewer.load_git_diff(&file_path, old_content, new_content).await {
+                        tracing::error!("Failed to load commit diff: {}", e);
+                    }
+                })
+            });
+            return Ok(());
+        }
 
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                if let Err(e) = self.diff_viewer.load_git_diff(&file_path, old_content, new_content).await {
-                    tracing::error!("Failed to load commit diff: {}", e);
-                }
-            })
-        });
+        // Whole commit against its first parent, one file at a time
+        if let Some(git_service) = &state.git_service {
+            let spec = DiffSpec::commit(&commit.hash);
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    if let Err(e) = self.diff_viewer.load_revision_diff(git_service, &spec).await {
+                        tracing::error!("Failed to load commit diff: {}", e);
+                    }
+                })
+            });
+        }
 
         Ok(())
     }
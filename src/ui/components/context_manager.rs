@@ -84,13 +84,25 @@ impl ContextMenuManager {
         Ok(())
     }
 
+    /// Menu items for a context, without opening the menu
+    pub fn menu_items(&self, context: &ContextInfo) -> Vec<MenuItem> {
+        self.generate_menu_items(context)
+    }
+
     /// Generate menu items based on context
     fn generate_menu_items(&self, context: &ContextInfo) -> Vec<MenuItem> {
         match context.context_type {
             ContextType::File => {
                 let default_path = String::new();
                 let file_path = context.get_data("file_path").unwrap_or(&default_path);
-                self.menu_component.get_file_context_menu(file_path)
+                let is_untracked = context.get_data("is_untracked")
+                    .map(|s| s == "true")
+                    .unwrap_or(false);
+
+                let mut items = self.menu_component.get_file_context_menu(file_path);
+                items.push(MenuItem::separator("sep_ignore"));
+                items.extend(self.menu_component.get_ignore_menu_items(file_path, is_untracked));
+                items
             }
             ContextType::Commit => {
                 let default_hash = String::new();
@@ -289,6 +301,15 @@ impl ContextMenuManager {
         self.menu_component.handle_mouse_event(mouse, area)
     }
 
+    /// Handle a key while the menu is open, returning the chosen action
+    pub fn handle_key(&mut self, key: KeyEvent) -> AppResult<Option<MenuAction>> {
+        let action = self.menu_component.handle_key(key)?;
+        if action.is_some() {
+            self.last_action = action.clone();
+        }
+        Ok(action)
+    }
+
     /// Get the last executed action
     pub fn get_last_action(&self) -> Option<&MenuAction> {
         self.last_action.as_ref()
@@ -383,6 +404,14 @@ impl ContextMenuManager {
         self.show_context_menu(context)
     }
 
+    /// Show context menu for a working tree file; untracked files can be ignored
+    pub fn show_worktree_file_menu(&mut self, file_path: &str, is_untracked: bool, position: (u16, u16)) -> AppResult<()> {
+        let context = ContextInfo::new(ContextType::File, position)
+            .with_data("file_path", file_path)
+            .with_data("is_untracked", &is_untracked.to_string());
+        self.show_context_menu(context)
+    }
+
     /// Show context menu for commit
     pub fn show_commit_menu(&mut self, commit_hash: &str, position: (u16, u16)) -> AppResult<()> {
         let context = ContextInfo::new(ContextType::Commit, position)
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 use crate::{
     app::state::AppState,
     error::AppResult,
+    git::ignore::{ignore_pattern, ignore_targets, IgnoreRuleKind, IgnoreTarget},
     ui::theme::Theme,
 };
 
@@ -45,6 +46,10 @@ pub enum MenuAction {
     GitBlame,
     GitHistory,
 
+    // Ignore rules
+    GitIgnore { pattern: String, target: IgnoreTarget },
+    GitExplainIgnore,
+
     // File operations
     FileOpen,
     FileEdit,
@@ -215,12 +220,12 @@ impl ContextMenuComponent {
 
                 if mouse.column >= menu_area.x && mouse.column < menu_area.x + menu_area.width &&
                    mouse.row >= menu_area.y && mouse.row < menu_area.y + menu_area.height {
-                    // Click on menu - select item
+                    // Click on menu - select item (first row is the border)
                     let item_index = (mouse.row - menu_area.y) as usize;
-                    if item_index < self.items.len() {
-                        self.selected_index = item_index;
-                        self.list_state.select(Some(item_index));
-                        return self.execute_selected_action();
+                    if item_index >= 1 && item_index <= self.items.len() {
+                        self.selected_index = item_index - 1;
+                        self.list_state.select(Some(item_index - 1));
+                        return self.enter_or_execute();
                     }
                 } else {
                     // Click outside menu - close it
@@ -324,6 +329,55 @@ impl ContextMenuComponent {
         ]
     }
 
+    /// Get ignore rule items for a file
+    ///
+    /// Untracked files get an "Ignore" submenu with one entry per rule kind,
+    /// each offering every ignore file the rule can be written to.
+    pub fn get_ignore_menu_items(&self, file_path: &str, is_untracked: bool) -> Vec<MenuItem> {
+        let mut items = Vec::new();
+
+        if is_untracked {
+            let targets = ignore_targets(file_path);
+            let rule_kinds = [
+                (IgnoreRuleKind::File, "ignore_file", "This File"),
+                (IgnoreRuleKind::Extension, "ignore_extension", "By Extension"),
+                (IgnoreRuleKind::Directory, "ignore_directory", "This Directory"),
+            ];
+
+            let submenu: Vec<MenuItem> = rule_kinds
+                .iter()
+                .filter_map(|(kind, id, label)| {
+                    let destinations: Vec<MenuItem> = targets
+                        .iter()
+                        .filter_map(|target| {
+                            let pattern = ignore_pattern(file_path, *kind, target)?;
+                            let label = format!("{}  {}", target.label(), pattern);
+                            Some(MenuItem::new(
+                                &format!("{}_{}", id, target.label()),
+                                &label,
+                                MenuAction::GitIgnore { pattern, target: target.clone() },
+                            ))
+                        })
+                        .collect();
+
+                    (!destinations.is_empty()).then(|| {
+                        MenuItem::new(id, label, MenuAction::Custom(id.to_string())).with_submenu(destinations)
+                    })
+                })
+                .collect();
+
+            if !submenu.is_empty() {
+                items.push(MenuItem::new("git_ignore", "Ignore", MenuAction::Custom("git_ignore".to_string()))
+                    .with_icon("🙈")
+                    .with_submenu(submenu));
+            }
+        }
+
+        items.push(MenuItem::new("git_explain_ignore", "Explain Why Ignored", MenuAction::GitExplainIgnore)
+            .with_icon("❔"));
+        items
+    }
+
     /// Get commit-specific context menu
     pub fn get_commit_context_menu(&self, commit_hash: &str) -> Vec<MenuItem> {
         vec![
@@ -471,6 +525,32 @@ impl ContextMenuComponent {
         }
     }
 
+    /// Handle a key while the menu is open, returning the chosen action
+    pub fn handle_key(&mut self, key: KeyEvent) -> AppResult<Option<MenuAction>> {
+        if !self.is_open {
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Backspace => {
+                if self.menu_stack.is_empty() {
+                    self.close();
+                } else {
+                    self.go_back();
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_down(),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => return self.enter_or_execute(),
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.go_back();
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
     /// Go back to parent menu
     pub fn go_back(&mut self) -> bool {
         if let Some((parent_items, parent_index)) = self.menu_stack.pop() {
@@ -511,7 +591,7 @@ impl ContextMenuComponent {
         let height = self.items.len() as u16 + 2; // +2 for borders
 
         let x = if self.position.0 + width > container_area.x + container_area.width {
-            container_area.x + container_area.width - width
+            (container_area.x + container_area.width).saturating_sub(width)
         } else {
             self.position.0
         }.max(container_area.x);
@@ -520,13 +600,14 @@ impl ContextMenuComponent {
             if self.position.1 > height {
                 self.position.1 - height
             } else {
-                container_area.y + container_area.height - height
+                (container_area.y + container_area.height).saturating_sub(height)
             }
         } else {
             self.position.1
         }.max(container_area.y);
 
-        Rect::new(x, y, width, height)
+        // Never draw outside the container
+        Rect::new(x, y, width, height).intersection(container_area)
     }
 
     /// Calculate menu width based on content
@@ -622,30 +703,7 @@ impl Component for ContextMenuComponent {
     }
 
     fn handle_key_event(&mut self, key: KeyEvent, _state: &mut AppState) -> AppResult<()> {
-        if !self.is_open {
-            return Ok(());
-        }
-
-        match key.code {
-            KeyCode::Esc => {
-                if !self.go_back() {
-                    self.close();
-                }
-            }
-            KeyCode::Up => self.move_up(),
-            KeyCode::Down => self.move_down(),
-            KeyCode::Enter => {
-                let _ = self.enter_or_execute()?;
-            }
-            KeyCode::Left => {
-                self.go_back();
-            }
-            KeyCode::Right => {
-                let _ = self.enter_or_execute()?;
-            }
-            _ => {}
-        }
-
+        self.handle_key(key)?;
         Ok(())
     }
 }
\ No newline at end of file
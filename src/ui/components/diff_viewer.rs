@@ -1,8 +1,14 @@
 use crate::{
     app::state::AppState,
+    config::DiffConfig,
     error::AppResult,
+    git::{content::describe_change, edit::match_line_endings, BlobContent, ContentAttributes, DiffSide, DiffSpec, EditBase, EditOutcome, EditTarget, GitService, RevisionDiff},
     ui::{
-        diff::{DiffViewer, DiffViewerConfig, DiffProcessorConfig, SyntaxHighlighterConfig, DiffMode},
+        diff::{
+            export, stream, DiffExport, DiffViewer, DiffViewerConfig, DiffProcessorConfig, SyntaxHighlighterConfig, DiffMode, ExportFormat,
+            InlineEditor, InlineEditorConfig, ReviewAnnotation,
+        },
+        components::{view_keys::ViewKey, ShortcutContext},
         theme::Theme,
     },
 };
@@ -11,10 +17,10 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Gauge, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 use tokio::sync::RwLock;
 use std::sync::Arc;
 use tracing::{debug, info};
@@ -31,9 +37,65 @@ pub struct DiffViewerComponent {
     loading: bool,
     /// 错误信息
     error_message: Option<String>,
+    /// 修订版本差异（提交、分支比较、暂存区）的文件列表
+    revision_diff: Option<RevisionDiff>,
+    /// 当前显示的修订差异文件
+    selected_file: usize,
+    /// 切换文件时读取内容
+    git_service: Option<GitService>,
+    /// 多文件差异时显示文件大纲
+    show_outline: bool,
+    /// 每个文件最后的滚动位置（滚动偏移、选中的差异块）
+    positions: HashMap<String, (usize, usize)>,
+    /// 当前显示的文件
+    current_path: Option<String>,
+    /// 正在就地编辑的差异新侧
+    side_edit: Option<SideEdit>,
+    /// 最近一次编辑保存或导出的结果
+    notice: Option<String>,
+    /// 正在选择导出格式
+    export_prompt: bool,
+    /// 打开导出提示时记录的主题和 AI 审查注释
+    export_theme: Option<Theme>,
+    annotations: Vec<ReviewAnnotation>,
 }
 
+/// 就地编辑工作区或暂存区中的文件
+struct SideEdit {
+    editor: InlineEditor,
+    base: EditBase,
+    /// 冲突或未保存修改的提示
+    warning: Option<String>,
+    /// 下一次 Ctrl+S 覆盖其他地方的修改
+    force: bool,
+    /// 下一次 Esc 放弃未保存的修改
+    discard: bool,
+}
+
+/// 每次展开增加的上下文行数
+const CONTEXT_EXPAND_STEP: usize = 10;
+
 impl DiffViewerComponent {
+    /// 差异查看器的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('n'), "Next hunk"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('p'), "Previous hunk"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char(']'), "Next file"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('['), "Previous file"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('o'), "File outline"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('+'), "More context lines"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('-'), "Fewer context lines"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('z'), "Toggle whole file"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('e'), "Edit new side"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('E'), "Edit new side in the index"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('x'), "Export diff"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('m'), "Switch diff layout"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('l'), "Toggle line numbers"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('w'), "Toggle whitespace changes"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('i'), "Cycle intraline highlighting"),
+        ViewKey::new(ShortcutContext::DiffViewer, KeyCode::Char('s'), "Toggle diff statistics"),
+    ];
+
     pub fn new() -> Self {
         let config = DiffViewerConfig::default();
         let processor_config = DiffProcessorConfig::default();
@@ -47,37 +109,265 @@ impl DiffViewerComponent {
             show_stats: false,
             loading: false,
             error_message: None,
+            revision_diff: None,
+            selected_file: 0,
+            git_service: None,
+            show_outline: true,
+            positions: HashMap::new(),
+            current_path: None,
+            side_edit: None,
+            notice: None,
+            export_prompt: false,
+            export_theme: None,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// 是否正在编辑或选择导出格式（此时 Esc/q 由本组件处理）
+    pub fn is_editing(&self) -> bool {
+        self.side_edit.is_some() || self.export_prompt
+    }
+
+    /// 导出当前差异（修订差异时导出全部文件），附带 AI 审查注释
+    async fn export(&mut self, format: ExportFormat) -> AppResult<()> {
+        let mut export = match (&self.revision_diff, &self.git_service) {
+            (Some(diff), Some(git_service)) => {
+                let config = self.viewer.read().await.processor_config().clone();
+                export::revision_export(git_service, diff, config).await?
+            }
+            _ => {
+                let Some(file) = self.viewer.read().await.export_file() else {
+                    self.notice = Some("Nothing to export".to_string());
+                    return Ok(());
+                };
+                DiffExport {
+                    title: file.path().to_string(),
+                    files: vec![file],
+                    annotations: Vec::new(),
+                }
+            }
+        };
+        export.annotations = self.annotations.clone();
+
+        let theme = self.export_theme.take().unwrap_or_else(Theme::default_theme);
+        let content = export.render(format, &theme);
+        let path = match &self.git_service {
+            Some(git_service) => git_service.write_diff_export(&content, format.extension())?,
+            None => {
+                let path = PathBuf::from(format!("ai-c-diff.{}", format.extension()));
+                std::fs::write(&path, &content)?;
+                path
+            }
+        };
+        let notes = if export.annotations.is_empty() {
+            String::new()
+        } else {
+            format!(" with {} review notes", export.annotations.len())
+        };
+        self.notice = Some(format!("Exported {} {}{} to {}", export.files.len(), format.label(), notes, path.display()));
+        Ok(())
+    }
+
+    /// 导出格式提示中的按键
+    async fn handle_export_key(&mut self, key: KeyEvent) -> AppResult<bool> {
+        let format = match key.code {
+            KeyCode::Char('h') => ExportFormat::Html,
+            KeyCode::Char('p') => ExportFormat::Patch,
+            KeyCode::Char('m') => ExportFormat::Markdown,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.export_prompt = false;
+                return Ok(true);
+            }
+            _ => return Ok(true),
+        };
+        self.export_prompt = false;
+        if let Err(e) = self.export(format).await {
+            self.notice = Some(format!("Export failed: {}", e));
+        }
+        Ok(true)
+    }
+
+    /// 开始编辑当前文件：`staged` 时编辑暂存版本，否则编辑差异的新侧
+    async fn start_edit(&mut self, staged: bool) -> AppResult<()> {
+        let (Some(diff), Some(git_service)) = (&self.revision_diff, &self.git_service) else {
+            self.notice = Some("Open a working tree or staged diff to edit it".to_string());
+            return Ok(());
+        };
+        let Some(change) = diff.files.get(self.selected_file) else {
+            return Ok(());
+        };
+        if change.is_binary {
+            self.notice = Some("Binary files cannot be edited".to_string());
+            return Ok(());
+        }
+
+        let target = match (&diff.spec.new, staged) {
+            (_, true) | (DiffSide::Index, false) => EditTarget::Index,
+            (DiffSide::WorkTree, false) => EditTarget::WorkTree,
+            _ => {
+                self.notice = Some("Only working tree or staged content can be edited; E edits the staged version".to_string());
+                return Ok(());
+            }
+        };
+
+        let path = change.path().to_string();
+        let base = git_service.read_for_edit(target, &path).await?;
+        let mut editor = InlineEditor::new(InlineEditorConfig::default());
+        editor.set_text_for_path(&base.content, PathBuf::from(&path));
+        info!("Editing {} in the {}", path, target.label());
+
+        self.notice = None;
+        self.side_edit = Some(SideEdit {
+            editor,
+            base,
+            warning: None,
+            force: false,
+            discard: false,
+        });
+        Ok(())
+    }
+
+    /// 保存编辑内容并刷新差异
+    async fn save_edit(&mut self) -> AppResult<()> {
+        let (Some(edit), Some(git_service)) = (self.side_edit.as_mut(), self.git_service.clone()) else {
+            return Ok(());
+        };
+
+        let content = match_line_endings(&edit.base.content, &edit.editor.text());
+        match git_service.write_edit(&edit.base, &content, edit.force).await? {
+            EditOutcome::Written(base) => {
+                let message = format!("Saved {} to the {}", base.path, base.target.label());
+                edit.editor.mark_saved(&message);
+                edit.base = base;
+                edit.warning = None;
+                edit.force = false;
+                self.notice = Some(message);
+                self.refresh().await
+            }
+            EditOutcome::Conflict { .. } => {
+                edit.warning = Some(format!(
+                    "{} changed in the {} since editing started. Ctrl+S again to overwrite, Esc to close",
+                    edit.base.path,
+                    edit.base.target.label()
+                ));
+                edit.force = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// 重新计算当前修订差异，停留在同一文件和位置
+    pub async fn refresh(&mut self) -> AppResult<()> {
+        let (Some(diff), Some(git_service)) = (&self.revision_diff, self.git_service.clone()) else {
+            return Ok(());
+        };
+        let spec = diff.spec.clone();
+        let path = diff.files.get(self.selected_file).map(|change| change.path().to_string());
+
+        let refreshed = git_service.diff_revisions(&spec).await?;
+        let index = path
+            .and_then(|path| refreshed.files.iter().position(|change| change.path() == path))
+            .unwrap_or_else(|| self.selected_file.min(refreshed.files.len().saturating_sub(1)));
+        self.revision_diff = Some(refreshed);
+        self.select_file(index).await
+    }
+
+    /// 编辑时的按键
+    async fn handle_edit_key(&mut self, key: KeyEvent) -> AppResult<bool> {
+        let Some(edit) = self.side_edit.as_mut() else {
+            return Ok(false);
+        };
+
+        match key {
+            KeyEvent {
+                code: KeyCode::Char('s'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                if let Err(e) = self.save_edit().await {
+                    if let Some(edit) = self.side_edit.as_mut() {
+                        edit.warning = Some(e.to_string());
+                    }
+                }
+            }
+            KeyEvent {
+                code: KeyCode::Esc,
+                ..
+            } => {
+                if edit.editor.is_modified() && !edit.discard {
+                    edit.warning = Some("Unsaved changes: Ctrl+S to save, Esc again to discard".to_string());
+                    edit.discard = true;
+                } else {
+                    self.side_edit = None;
+                }
+            }
+            _ => {
+                edit.discard = false;
+                edit.editor.handle_key(key)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// 记录当前文件的位置，离开后再回来时恢复
+    async fn remember_position(&mut self) {
+        if let Some(path) = self.current_path.take() {
+            let position = self.viewer.read().await.position();
+            self.positions.insert(path, position);
+        }
+    }
+
+    /// 加载新文件后恢复其上次的位置
+    async fn restore_position(&mut self, path: String) {
+        if let Some(&position) = self.positions.get(&path) {
+            self.viewer.write().await.restore_position(position);
+        }
+        self.current_path = Some(path);
+    }
+
+    /// 应用差异配置（算法、按文件类型的算法、空白模式）
+    pub fn set_diff_config(&mut self, config: &DiffConfig) {
+        match self.viewer.try_write() {
+            Ok(mut viewer) => {
+                viewer.set_processor_config(DiffProcessorConfig::from(config));
+                viewer.set_highlighter_config(SyntaxHighlighterConfig::from(config));
+            }
+            Err(_) => debug!("Diff viewer busy, keeping the previous diff settings"),
         }
     }
 
     /// 加载文件差异
     pub async fn load_file_diff(&mut self, old_path: &PathBuf, new_path: &PathBuf) -> AppResult<()> {
+        self.remember_position().await;
         self.loading = true;
         self.error_message = None;
+        self.revision_diff = None;
 
         let result: AppResult<()> = async {
-            // 读取文件内容
-            let old_content = if old_path.exists() {
-                tokio::fs::read_to_string(old_path).await.unwrap_or_default()
-            } else {
-                String::new()
-            };
-
-            let new_content = if new_path.exists() {
-                tokio::fs::read_to_string(new_path).await.unwrap_or_default()
-            } else {
-                String::new()
-            };
+            // 读取并识别文件内容（二进制、图片、编码）
+            let mut sides = Vec::with_capacity(2);
+            for path in [old_path, new_path] {
+                sides.push(match tokio::fs::read(path).await {
+                    Ok(bytes) => BlobContent::from_bytes(&bytes, &ContentAttributes::default()),
+                    Err(_) => BlobContent::missing(),
+                });
+            }
+            let (old, new) = (&sides[0], &sides[1]);
 
             // 使用新文件路径作为差异标识
             let mut viewer = self.viewer.write().await;
-            viewer.load_diff(&old_content, &new_content, new_path).await?;
+            viewer.set_funcname(None);
+            viewer.load_diff(&old.text, &new.text, new_path).await?;
+            viewer.set_notices(describe_change(old, new));
 
             info!("Loaded diff for files: {:?} vs {:?}", old_path, new_path);
             Ok(())
         }.await;
 
         self.loading = false;
+        if result.is_ok() {
+            self.restore_position(new_path.display().to_string()).await;
+        }
 
         match result {
             Ok(()) => Ok(()),
@@ -90,11 +380,14 @@ impl DiffViewerComponent {
 
     /// 加载Git差异
     pub async fn load_git_diff(&mut self, file_path: &PathBuf, old_content: String, new_content: String) -> AppResult<()> {
+        self.remember_position().await;
         self.loading = true;
         self.error_message = None;
+        self.revision_diff = None;
 
         let result: AppResult<()> = async {
             let mut viewer = self.viewer.write().await;
+            viewer.set_funcname(None);
             viewer.load_diff(&old_content, &new_content, file_path).await?;
 
             info!("Loaded Git diff for file: {:?}", file_path);
@@ -102,6 +395,9 @@ impl DiffViewerComponent {
         }.await;
 
         self.loading = false;
+        if result.is_ok() {
+            self.restore_position(file_path.display().to_string()).await;
+        }
 
         match result {
             Ok(()) => Ok(()),
@@ -112,13 +408,134 @@ impl DiffViewerComponent {
         }
     }
 
+    /// 加载两个修订版本之间的差异（提交、分支比较、暂存区等），显示第一个文件
+    pub async fn load_revision_diff(&mut self, git_service: &GitService, spec: &DiffSpec) -> AppResult<()> {
+        self.remember_position().await;
+        self.loading = true;
+        self.error_message = None;
+
+        let result = git_service.diff_revisions(spec).await;
+        self.loading = false;
+
+        match result {
+            Ok(diff) => {
+                info!("Loaded revision diff {} ({} files)", diff.spec.label(), diff.files.len());
+                self.revision_diff = Some(diff);
+                self.git_service = Some(git_service.clone());
+                self.select_file(0).await
+            }
+            Err(e) => {
+                self.error_message = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// 当前修订差异
+    pub fn revision_diff(&self) -> Option<&RevisionDiff> {
+        self.revision_diff.as_ref()
+    }
+
+    /// 在修订差异中切换到第 index 个文件
+    async fn select_file(&mut self, index: usize) -> AppResult<()> {
+        let (Some(diff), Some(git_service)) = (&self.revision_diff, &self.git_service) else {
+            return Ok(());
+        };
+        let Some(change) = diff.files.get(index) else {
+            self.viewer.write().await.clear();
+            return Ok(());
+        };
+
+        self.selected_file = index;
+        let file_path = PathBuf::from(change.path());
+        debug!("Showing {} ({}/{})", change.display_path(), index + 1, diff.files.len());
+
+        // 超大文件由 git 生成补丁并流式显示，不读入两侧内容
+        let size = git_service.revision_file_size(&diff.spec, change).await?;
+        if self.viewer.read().await.should_stream(size) {
+            let options = stream::git_options(self.viewer.read().await.processor_config(), &file_path);
+            let source = git_service.patch_source(&diff.spec, change, options)?;
+            info!("Streaming diff of {} ({} bytes)", change.display_path(), size);
+
+            self.remember_position().await;
+            self.viewer.write().await.load_stream(source, &file_path)?;
+            self.restore_position(file_path.display().to_string()).await;
+            return Ok(());
+        }
+
+        let (old, new) = git_service.revision_file_sides(&diff.spec, change).await?;
+        self.remember_position().await;
+        let mut viewer = self.viewer.write().await;
+        let driver = new.driver.as_ref().or(old.driver.as_ref());
+        viewer.set_funcname(driver.and_then(|driver| driver.funcname.as_deref()));
+        viewer.load_diff(&old.text, &new.text, &file_path).await?;
+        viewer.set_notices(describe_change(&old, &new));
+        drop(viewer);
+        self.restore_position(file_path.display().to_string()).await;
+        Ok(())
+    }
+
+    /// 切换到下一个/上一个文件，返回是否切换
+    async fn step_file(&mut self, forward: bool) -> AppResult<bool> {
+        let Some(count) = self.revision_diff.as_ref().map(|diff| diff.files.len()) else {
+            return Ok(false);
+        };
+        let index = if forward {
+            (self.selected_file + 1).min(count.saturating_sub(1))
+        } else {
+            self.selected_file.saturating_sub(1)
+        };
+        if index == self.selected_file {
+            return Ok(false);
+        }
+        if let Err(e) = self.select_file(index).await {
+            self.error_message = Some(e.to_string());
+            return Err(e);
+        }
+        Ok(true)
+    }
+
     /// 处理键盘输入
     pub async fn handle_key(&mut self, key: KeyEvent) -> AppResult<bool> {
         if self.loading {
             return Ok(false);
         }
+        if self.side_edit.is_some() {
+            return self.handle_edit_key(key).await;
+        }
+        if self.export_prompt {
+            return self.handle_export_key(key).await;
+        }
+        self.notice = None;
 
         match key {
+            // 就地编辑
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                self.start_edit(false).await?;
+                Ok(true)
+            }
+            KeyEvent {
+                code: KeyCode::Char('E'),
+                ..
+            } => {
+                self.start_edit(true).await?;
+                Ok(true)
+            }
+
+            // 导出为 HTML / 补丁 / Markdown
+            KeyEvent {
+                code: KeyCode::Char('x'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                self.export_prompt = true;
+                Ok(true)
+            }
+
             // 基础导航
             KeyEvent {
                 code: KeyCode::Up,
@@ -167,13 +584,16 @@ impl DiffViewerComponent {
             }
 
             // 差异块导航
+            // 到达文件边界时继续跳到相邻文件
             KeyEvent {
                 code: KeyCode::Char('n'),
                 modifiers: KeyModifiers::NONE,
                 ..
             } => {
-                let mut viewer = self.viewer.write().await;
-                viewer.next_hunk();
+                let moved = self.viewer.write().await.next_hunk();
+                if !moved && self.step_file(true).await? {
+                    self.viewer.write().await.restore_position((0, 0));
+                }
                 Ok(true)
             }
             KeyEvent {
@@ -181,10 +601,60 @@ impl DiffViewerComponent {
                 modifiers: KeyModifiers::NONE,
                 ..
             } => {
-                let mut viewer = self.viewer.write().await;
-                viewer.previous_hunk();
+                let moved = self.viewer.write().await.previous_hunk();
+                if !moved && self.step_file(false).await? {
+                    self.viewer.write().await.last_hunk();
+                }
+                Ok(true)
+            }
+
+            // 上下文折叠
+            KeyEvent {
+                code: KeyCode::Char('+') | KeyCode::Char('='),
+                ..
+            } => {
+                self.viewer.write().await.expand_context(CONTEXT_EXPAND_STEP).await?;
+                Ok(true)
+            }
+            KeyEvent {
+                code: KeyCode::Char('-'),
+                ..
+            } => {
+                self.viewer.write().await.collapse_context().await?;
                 Ok(true)
             }
+            KeyEvent {
+                code: KeyCode::Char('z'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                self.viewer.write().await.toggle_full_context().await?;
+                Ok(true)
+            }
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                self.show_outline = !self.show_outline;
+                Ok(true)
+            }
+
+            // 修订差异中的文件切换
+            KeyEvent {
+                code: KeyCode::Char(']'),
+                ..
+            } => {
+                self.step_file(true).await?;
+                Ok(self.revision_diff.is_some())
+            }
+            KeyEvent {
+                code: KeyCode::Char('['),
+                ..
+            } => {
+                self.step_file(false).await?;
+                Ok(self.revision_diff.is_some())
+            }
 
             // 视图切换
             KeyEvent {
@@ -214,6 +684,15 @@ impl DiffViewerComponent {
                 viewer.toggle_whitespace();
                 Ok(true)
             }
+            KeyEvent {
+                code: KeyCode::Char('i'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                let mut viewer = self.viewer.write().await;
+                viewer.cycle_intraline();
+                Ok(true)
+            }
 
             // 帮助和统计信息
             KeyEvent {
@@ -253,7 +732,7 @@ impl DiffViewerComponent {
     }
 
     /// 渲染组件
-    pub async fn render(&mut self, frame: &mut Frame<'_>, area: Rect, _state: &AppState, theme: &Theme) -> AppResult<()> {
+    pub async fn render(&mut self, frame: &mut Frame<'_>, area: Rect, state: &AppState, theme: &Theme) -> AppResult<()> {
         // 如果正在加载，显示加载指示器
         if self.loading {
             self.render_loading(frame, area, theme);
@@ -266,10 +745,78 @@ impl DiffViewerComponent {
             return Ok(());
         }
 
+        // 修订差异：顶部显示比较范围和当前文件
+        let area = if let Some(diff) = &self.revision_diff {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            frame.render_widget(Paragraph::new(self.revision_header(diff)), chunks[0]);
+
+            if self.show_outline && diff.files.len() > 1 {
+                let width = (chunks[1].width / 3).clamp(20, 48);
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(width), Constraint::Min(0)])
+                    .split(chunks[1]);
+                self.render_outline(frame, columns[0], diff, theme);
+                columns[1]
+            } else {
+                chunks[1]
+            }
+        } else {
+            area
+        };
+
+        // 编辑时由编辑器替换差异
+        if let Some(edit) = self.side_edit.as_mut() {
+            let message = edit.warning.clone().map(|warning| (warning, theme.colors.warning)).unwrap_or_else(|| {
+                (
+                    format!("Editing {} ({}) · Ctrl+S save · Esc close", edit.base.path, edit.base.target.label()),
+                    theme.colors.muted,
+                )
+            });
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            frame.render_widget(Paragraph::new(message.0).style(Style::default().fg(message.1)), chunks[0]);
+            return edit.editor.render(frame, chunks[1], theme);
+        }
+
         // 主要差异查看器
         let mut viewer = self.viewer.write().await;
         viewer.render(frame, area, theme).await?;
 
+        if let Some(notice) = &self.notice {
+            let line = Rect { y: area.bottom().saturating_sub(2), height: 1, x: area.x + 1, width: area.width.saturating_sub(2) };
+            frame.render_widget(Paragraph::new(notice.as_str()).style(Style::default().fg(theme.colors.info)), line);
+        }
+
+        if self.export_prompt {
+            // 导出使用当前主题和已完成的代理任务中的审查结果
+            self.export_theme = Some(theme.clone());
+            self.annotations = state
+                .agent_state
+                .task_results
+                .values()
+                .filter(|result| result.success)
+                .flat_map(|result| export::annotations_from_json(&result.data))
+                .collect();
+            let scope = match &self.revision_diff {
+                Some(diff) => format!("{} files", diff.files.len()),
+                None => "this file".to_string(),
+            };
+            let prompt = format!(
+                "Export {} ({} review notes): h HTML · p patch · m Markdown · Esc cancel",
+                scope,
+                self.annotations.len()
+            );
+            let line = Rect { y: area.bottom().saturating_sub(2), height: 1, x: area.x + 1, width: area.width.saturating_sub(2) };
+            frame.render_widget(Clear, line);
+            frame.render_widget(Paragraph::new(prompt).style(Style::default().fg(theme.colors.warning)), line);
+        }
+
         // 叠加层
         if self.show_help {
             self.render_help_overlay(frame, area, theme);
@@ -280,6 +827,71 @@ impl DiffViewerComponent {
         Ok(())
     }
 
+    /// 修订差异标题：范围、文件序号、状态和 +/- 行数
+    fn revision_header(&self, diff: &RevisionDiff) -> Line<'static> {
+        let mut spans = vec![Span::styled(
+            format!(" {} ", diff.spec.label()),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )];
+
+        match diff.files.get(self.selected_file) {
+            Some(change) => {
+                spans.push(Span::raw(format!(
+                    " [{}/{}] {} {}",
+                    self.selected_file + 1,
+                    diff.files.len(),
+                    change.status.code(),
+                    change.display_path()
+                )));
+                if change.is_binary {
+                    spans.push(Span::styled(" (binary)", Style::default().fg(Color::Yellow)));
+                } else {
+                    spans.push(Span::styled(format!(" +{}", change.additions), Style::default().fg(Color::Green)));
+                    spans.push(Span::styled(format!(" -{}", change.deletions), Style::default().fg(Color::Red)));
+                }
+                spans.push(Span::styled("  [/]: prev/next file", Style::default().fg(Color::DarkGray)));
+            }
+            None => spans.push(Span::styled(" no changes", Style::default().fg(Color::DarkGray))),
+        }
+
+        Line::from(spans)
+    }
+
+    /// 文件大纲：每个文件的状态和增删行数
+    fn render_outline(&self, frame: &mut Frame<'_>, area: Rect, diff: &RevisionDiff, theme: &Theme) {
+        let widest = diff.files.iter().map(|change| change.additions + change.deletions).max().unwrap_or(0).max(1);
+        let items: Vec<ListItem> = diff
+            .files
+            .iter()
+            .map(|change| {
+                let mut spans = vec![Span::styled(
+                    format!("{} ", change.status.code()),
+                    Style::default().fg(theme.colors.accent),
+                )];
+                if change.is_binary {
+                    spans.push(Span::styled("bin ", Style::default().fg(theme.colors.warning)));
+                } else {
+                    // 与 git --stat 类似的比例条
+                    let total = change.additions + change.deletions;
+                    let bar = (total * 6).div_ceil(widest).min(6);
+                    let plus = (bar * change.additions).checked_div(total).unwrap_or(0);
+                    spans.push(Span::styled("+".repeat(plus), Style::default().fg(theme.colors.success)));
+                    spans.push(Span::styled("-".repeat(bar - plus), Style::default().fg(theme.colors.error)));
+                    spans.push(Span::raw(" ".repeat(7 - bar)));
+                }
+                spans.push(Span::raw(change.display_path()));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let title = format!(" Files ({}) +{} -{} ", diff.files.len(), diff.additions(), diff.deletions());
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title).border_style(Style::default().fg(theme.colors.muted)))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected_file));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
     /// 渲染加载指示器
     fn render_loading(&self, frame: &mut Frame<'_>, area: Rect, theme: &Theme) {
         let block = Block::default()
@@ -334,13 +946,27 @@ impl DiffViewerComponent {
             Line::from("  ↑/↓          Scroll up/down"),
             Line::from("  PgUp/PgDn    Page up/down"),
             Line::from("  Home         Go to top"),
-            Line::from("  n            Next diff hunk"),
-            Line::from("  p            Previous diff hunk"),
+            Line::from("  n / p        Next/previous hunk, continuing into the next/previous file"),
+            Line::from("  ] / [        Next/previous file (commit, branch and staged diffs)"),
+            Line::from("  o            Show/hide the file outline"),
+            Line::from(""),
+            Line::from("Context:"),
+            Line::from("  + / -        Show more unchanged lines / fold back"),
+            Line::from("  z            Toggle the whole file"),
+            Line::from(""),
+            Line::from("Editing:"),
+            Line::from("  e            Edit the new side (working tree or index)"),
+            Line::from("  E            Edit the staged version; the working tree is untouched"),
+            Line::from("  Ctrl+S       Save and refresh the diff"),
+            Line::from(""),
+            Line::from("Export:"),
+            Line::from("  x            Export as HTML (h), patch (p) or Markdown (m)"),
             Line::from(""),
             Line::from("View Options:"),
             Line::from("  m            Toggle display mode (side-by-side/unified/inline)"),
             Line::from("  l            Toggle line numbers"),
             Line::from("  w            Toggle whitespace display"),
+            Line::from("  i            Intra-line highlight: word → char → off"),
             Line::from(""),
             Line::from("Other:"),
             Line::from("  s            Show/hide statistics"),
@@ -381,6 +1007,21 @@ impl DiffViewerComponent {
                 Line::from(format!("Lines deleted: {}", stats.lines_deleted)),
                 Line::from(format!("Processing time: {:?}", stats.processing_time)),
             ]);
+            let config = viewer.processor_config();
+            stats_lines.push(Line::from(format!(
+                "Algorithm: {} | Whitespace: {}{}",
+                config.algorithm.label(),
+                config.whitespace.label(),
+                if config.ignore_blank_lines { " | ignore blank lines" } else { "" }
+            )));
+            stats_lines.push(Line::from(format!(
+                "Intra-line: {} | Context: {}",
+                if config.word_level_diff { config.intraline_granularity.label() } else { "off" },
+                match viewer.context_lines() {
+                    usize::MAX => "whole file".to_string(),
+                    lines => format!("{} lines", lines),
+                }
+            )));
         } else {
             stats_lines.push(Line::from("No diff loaded"));
         }
@@ -435,4 +1076,65 @@ impl DiffViewerComponent {
         // 类似地，我们需要添加一个方法来检查是否有差异
         true // 临时返回true
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GitConfig;
+    use crate::git::test_support::git;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Two files changed in the last commit, each with two hunks
+    fn two_file_commit(dir: &Path) -> GitService {
+        let numbered = |changed: bool| -> String {
+            (1..=30)
+                .map(|n| if changed && (n == 5 || n == 25) { format!("changed {}\n", n) } else { format!("line {}\n", n) })
+                .collect()
+        };
+        git(dir, &["init", "-q", "-b", "main"]);
+        for name in ["a.txt", "b.txt"] {
+            std::fs::write(dir.join(name), numbered(false)).unwrap();
+        }
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "first"]);
+        for name in ["a.txt", "b.txt"] {
+            std::fs::write(dir.join(name), numbered(true)).unwrap();
+        }
+        git(dir, &["commit", "-q", "-am", "second"]);
+        GitService::open(dir.to_path_buf(), &GitConfig::default()).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_hunk_jumps_cross_files_and_stop_at_the_ends() {
+        let dir = TempDir::new().unwrap();
+        let service = two_file_commit(dir.path());
+        let spec = DiffSpec::new(DiffSide::Parent("HEAD".into()), DiffSide::Revision("HEAD".into()));
+        let mut component = DiffViewerComponent::new();
+        component.load_revision_diff(&service, &spec).await.unwrap();
+
+        let press = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut at = Vec::new();
+        for _ in 0..5 {
+            component.handle_key(press(KeyCode::Char('n'))).await.unwrap();
+            at.push((component.selected_file, component.viewer.read().await.position().1));
+        }
+        // The last `n` has nowhere to go and keeps the last hunk of the last file
+        assert_eq!(at, [(0, 1), (1, 0), (1, 1), (1, 1), (1, 1)]);
+
+        at.clear();
+        for _ in 0..5 {
+            component.handle_key(press(KeyCode::Char('p'))).await.unwrap();
+            at.push((component.selected_file, component.viewer.read().await.position().1));
+        }
+        // Stepping back into a file lands on its last hunk
+        assert_eq!(at, [(1, 0), (0, 1), (0, 0), (0, 0), (0, 0)]);
+        assert_eq!(component.viewer.read().await.position(), (0, 0));
+
+        // `]` past the last file stays put
+        component.handle_key(press(KeyCode::Char(']'))).await.unwrap();
+        assert!(component.handle_key(press(KeyCode::Char(']'))).await.unwrap());
+        assert_eq!(component.selected_file, 1);
+    }
+}
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 use crate::{
     app::state::AppState,
     error::AppResult,
+    git::{log_filter::parse_filter_date, LogFilter, LogPickaxe, MergeFilter},
     ui::theme::Theme,
 };
 
@@ -31,6 +32,11 @@ pub enum FilterType {
     Tag,
     FileSize,
     CommitMessage,
+    Committer,
+    Pickaxe,
+    PickaxeRegex,
+    Merges,
+    RevisionRange,
 }
 
 impl FilterType {
@@ -46,6 +52,11 @@ impl FilterType {
             FilterType::Tag => "Tag",
             FilterType::FileSize => "File Size",
             FilterType::CommitMessage => "Commit Message",
+            FilterType::Committer => "Committer",
+            FilterType::Pickaxe => "Content Change (-S)",
+            FilterType::PickaxeRegex => "Diff Regex (-G)",
+            FilterType::Merges => "Merges",
+            FilterType::RevisionRange => "Revision Range",
         }
     }
 
@@ -61,6 +72,11 @@ impl FilterType {
             FilterType::Tag => "Filter by tag name",
             FilterType::FileSize => "Filter by file size (e.g., >1MB, <100KB)",
             FilterType::CommitMessage => "Filter by commit message pattern",
+            FilterType::Committer => "Filter by committer",
+            FilterType::Pickaxe => "Commits changing the number of occurrences of a string",
+            FilterType::PickaxeRegex => "Commits whose added/removed lines match a regex",
+            FilterType::Merges => "Merge commits: 'only' or 'none'",
+            FilterType::RevisionRange => "Revision range to walk (e.g., main..feature)",
         }
     }
 
@@ -76,6 +92,11 @@ impl FilterType {
             FilterType::Tag => "e.g., v1.*, release-*",
             FilterType::FileSize => "e.g., >1MB, <100KB, 50KB-2MB",
             FilterType::CommitMessage => "e.g., fix:, feat:, refactor",
+            FilterType::Committer => "e.g., ci-bot",
+            FilterType::Pickaxe => "e.g., parse_config",
+            FilterType::PickaxeRegex => "e.g., fn \\w+_test",
+            FilterType::Merges => "only | none",
+            FilterType::RevisionRange => "e.g., v1.0..HEAD, main...feature",
         }
     }
 }
@@ -124,6 +145,12 @@ pub struct FilterComponent {
     mode: FilterMode,
     input_modal: InputModal,
     current_editing_index: Option<usize>,
+    /// Type of the filter being added (when not editing an existing one)
+    adding_type: Option<FilterType>,
+    /// Whether Enter in the filter list applies and closes instead of editing
+    apply_on_enter: bool,
+    /// Set when the user applied the filters with Enter
+    apply_requested: bool,
     preset_filters: HashMap<String, Vec<FilterItem>>,
 }
 
@@ -154,6 +181,11 @@ impl FilterComponent {
             FilterType::Tag,
             FilterType::FileSize,
             FilterType::CommitMessage,
+            FilterType::Committer,
+            FilterType::Pickaxe,
+            FilterType::PickaxeRegex,
+            FilterType::Merges,
+            FilterType::RevisionRange,
         ];
 
         let mut preset_filters = HashMap::new();
@@ -183,10 +215,19 @@ impl FilterComponent {
             mode: FilterMode::ViewFilters,
             input_modal: InputModal::new(),
             current_editing_index: None,
+            adding_type: None,
+            apply_on_enter: false,
+            apply_requested: false,
             preset_filters,
         }
     }
 
+    /// Make Enter in the filter list request an apply (see `take_apply_request`)
+    pub fn with_apply_on_enter(mut self) -> Self {
+        self.apply_on_enter = true;
+        self
+    }
+
     /// Open filter component
     pub fn open(&mut self) {
         self.is_open = true;
@@ -301,6 +342,54 @@ impl FilterComponent {
         }
     }
 
+    /// Take a pending apply request (Enter in the filter list)
+    pub fn take_apply_request(&mut self) -> bool {
+        std::mem::take(&mut self.apply_requested)
+    }
+
+    /// Build a commit history query from the active filters
+    ///
+    /// Negated filters have no `git log` equivalent and are skipped, as are
+    /// filter types that only apply to files (content type, size, ...).
+    pub fn to_log_filter(&self) -> AppResult<LogFilter> {
+        let mut filter = LogFilter {
+            ignore_case: true,
+            ..Default::default()
+        };
+
+        for item in self.get_active_filters().into_iter().filter(|f| !f.is_negated) {
+            let value = item.value.trim();
+            if value.is_empty() {
+                continue;
+            }
+
+            match item.filter_type {
+                FilterType::Author => filter.author = Some(regex::escape(value)),
+                FilterType::Committer => filter.committer = Some(regex::escape(value)),
+                FilterType::DateFrom => filter.since = Some(parse_filter_date(value, false)?),
+                FilterType::DateTo => filter.until = Some(parse_filter_date(value, true)?),
+                FilterType::FilePath => filter.paths.push(value.to_string()),
+                FilterType::FileExtension => {
+                    filter.paths.push(format!("*.{}", value.trim_start_matches("*.").trim_start_matches('.')));
+                }
+                FilterType::CommitMessage => filter.message = Some(value.to_string()),
+                FilterType::Pickaxe => filter.pickaxe = Some(LogPickaxe::String(value.to_string())),
+                FilterType::PickaxeRegex => filter.pickaxe = Some(LogPickaxe::Regex(value.to_string())),
+                FilterType::Merges => {
+                    filter.merges = match value.to_lowercase().as_str() {
+                        "only" | "merges" => MergeFilter::MergesOnly,
+                        "none" | "no" | "no-merges" => MergeFilter::NoMerges,
+                        _ => MergeFilter::All,
+                    };
+                }
+                FilterType::RevisionRange | FilterType::Branch => filter.range = Some(value.to_string()),
+                FilterType::Tag | FilterType::ContentType | FilterType::FileSize => {}
+            }
+        }
+
+        Ok(filter)
+    }
+
     /// Check if any filters match a given item
     pub fn matches_filters(&self, metadata: &HashMap<String, String>) -> bool {
         let active_filters = self.get_active_filters();
@@ -381,6 +470,16 @@ impl FilterComponent {
                     .map(|m| m.to_lowercase().contains(value))
                     .unwrap_or(false)
             }
+            FilterType::Committer => {
+                metadata.get("committer")
+                    .map(|c| c.to_lowercase().contains(value))
+                    .unwrap_or(false)
+            }
+            // History-only filters, evaluated by the revision walk
+            FilterType::Pickaxe
+            | FilterType::PickaxeRegex
+            | FilterType::Merges
+            | FilterType::RevisionRange => true,
         }
     }
 
@@ -585,6 +684,9 @@ impl Component for FilterComponent {
                         if let Some(filter) = self.filters.get_mut(index) {
                             filter.value = value;
                         }
+                    } else if let Some(filter_type) = self.adding_type.take() {
+                        self.filters.push(FilterItem::new(filter_type, value));
+                        self.filter_state.select(Some(self.filters.len() - 1));
                     }
                     self.mode = FilterMode::ViewFilters;
                     self.current_editing_index = None;
@@ -592,6 +694,7 @@ impl Component for FilterComponent {
                 ModalResult::Cancelled => {
                     self.mode = FilterMode::ViewFilters;
                     self.current_editing_index = None;
+                    self.adding_type = None;
                 }
                 ModalResult::None => {}
                 _ => {}
@@ -605,7 +708,12 @@ impl Component for FilterComponent {
                     KeyCode::Esc => self.close(),
                     KeyCode::Char('a') => self.add_filter(),
                     KeyCode::Char('d') | KeyCode::Delete => self.remove_selected_filter(),
-                    KeyCode::Char('e') | KeyCode::Enter => self.edit_selected_filter(),
+                    KeyCode::Char('e') => self.edit_selected_filter(),
+                    KeyCode::Enter if self.apply_on_enter => {
+                        self.apply_requested = true;
+                        self.close();
+                    }
+                    KeyCode::Enter => self.edit_selected_filter(),
                     KeyCode::Char(' ') => self.toggle_selected_filter(),
                     KeyCode::Char('n') => self.toggle_selected_negation(),
                     KeyCode::Char('c') => self.clear_all_filters(),
@@ -640,6 +748,7 @@ impl Component for FilterComponent {
                                     filter_type.placeholder(),
                                 );
                                 self.current_editing_index = None; // New filter
+                                self.adding_type = Some(filter_type);
                                 self.mode = FilterMode::EditingFilter;
                             }
                         }
@@ -191,7 +191,7 @@ impl HelpComponent {
             HelpCategory::General => {
                 "GENERAL HELP\n\n\
                 Welcome to AI-C TUI - An intelligent Git commit tool!\n\n\
-                • Tab navigation: Use numbers 1-7 or Tab/Shift+Tab to switch between views\n\
+                • Tab navigation: Use numbers 1-8 or Tab/Shift+Tab to switch between views\n\
                 • Universal shortcuts: ? for help, Esc to exit dialogs\n\
                 • All interfaces support vim-style navigation (hjkl) and arrow keys\n\
                 • Most operations are performed with single key presses\n\
@@ -203,7 +203,11 @@ impl HelpComponent {
                 4. Status - View current repository status and stage changes\n\
                 5. Remotes - Manage remote repositories\n\
                 6. History - Browse commit history and diffs\n\
-                7. GitFlow - Git Flow workflow management\n\n\
+                7. GitFlow - Git Flow workflow management\n\
+                8. Insights - Contributors, churn, hotspots and bus factor\n\n\
+                WORKSPACE MODE:\n\
+                List sibling repositories under [workspace] in ai-commit.toml\n\
+                (repositories = [...], scan_dirs = [...]) to show them all in a sidebar.\n\n\
                 TIP: Each section has context-specific help available in the Shortcuts tab.".to_string()
             }
             HelpCategory::Navigation => {
@@ -215,7 +219,7 @@ impl HelpComponent {
                 • Home/End: Jump to start/end of list\n\
                 • Space: Switch focus between panels\n\n\
                 TAB NAVIGATION:\n\
-                • 1-7: Jump directly to specific tabs\n\
+                • 1-8: Jump directly to specific tabs\n\
                 • Tab: Next tab\n\
                 • Shift+Tab: Previous tab\n\n\
                 UNIVERSAL ACTIONS:\n\
@@ -239,7 +243,9 @@ impl HelpComponent {
                 ADVANCED OPERATIONS:\n\
                 • m: Merge selected branch into current branch\n\
                 • p: Push branch to remote (if tracking is set up)\n\
-                • l: Select specific commit on branch\n\n\
+                • l: Select specific commit on branch\n\
+                • c: Compare - diff of what the selected branch changed since it\n\
+                  forked from the current branch (current...selected)\n\n\
                 BRANCH TYPES:\n\
                 • Local branches: Branches that exist in your local repository\n\
                 • Remote branches: Read-only references to remote repository state\n\
@@ -258,6 +264,13 @@ impl HelpComponent {
                 • d: Delete selected tag (with confirmation)\n\
                 • n: Create new tag at current HEAD\n\
                 • r: Refresh tag list\n\n\
+                CHANGELOG:\n\
+                • g: Changelog for the selected tag (since the previous tag)\n\
+                • G: Unreleased changelog (last tag to HEAD)\n\
+                • Commits are grouped by conventional type and scope\n\
+                • Breaking changes are listed in their own section\n\
+                • Edit the text, then Ctrl+S writes CHANGELOG.md\n\
+                • Ctrl+T: Switch Markdown/JSON  Ctrl+O: Include other commits  Esc: Cancel\n\n\
                 TAG TYPES:\n\
                 • Lightweight tags: Simple pointers to commits\n\
                 • Annotated tags: Include metadata (author, date, message)\n\
@@ -309,6 +322,8 @@ impl HelpComponent {
                 OPERATIONS:\n\
                 • Enter: List existing branches of selected type\n\
                 • n: Create new branch of selected type\n\
+                  (releases/hotfixes: pre-filled with the suggested version)\n\
+                • N: Start a release/hotfix as a prerelease (-rc.N)\n\
                 • f: Finish current branch (merge and cleanup)\n\
                 • l: Select specific branch to work with\n\
                 • r: Refresh GitFlow status\n\n\
@@ -321,6 +336,10 @@ impl HelpComponent {
                   - Created from 'develop'\n\
                   - Merged to both 'main' and 'develop'\n\
                   - Tagged on 'main'\n\
+                  - f opens the changelog editor first; Ctrl+S writes\n\
+                    CHANGELOG.md, Ctrl+N finishes without it\n\
+                  - The release commit bumps Cargo.toml, package.json\n\
+                    and pyproject.toml to the branch version\n\
                 \n\
                 Hotfix branches:\n\
                   - Created from 'main'\n\
@@ -330,6 +349,12 @@ impl HelpComponent {
                 Support branches:\n\
                   - Created from specific points in 'main'\n\
                   - Never merged back\n\n\
+                VERSION SUGGESTIONS:\n\
+                • Based on the highest version tag (gitflow.prefix.versiontag\n\
+                  sets a custom tag prefix, default 'v' or none)\n\
+                • Breaking change: major, feat: minor, fix/perf: patch\n\
+                • Cargo.toml, Cargo.lock, package.json and pyproject.toml\n\
+                  versions are bumped in the release commit\n\n\
                 TIP: Ensure you have 'main' and 'develop' branches before using GitFlow.".to_string()
             }
             HelpCategory::Shortcuts => {
@@ -338,9 +363,15 @@ impl HelpComponent {
                 • ?: Show/hide help\n\
                 • Esc: Cancel, back, or close dialogs\n\
                 • Tab/Shift+Tab: Navigate between tabs\n\
-                • 1-7: Jump to specific tab\n\
-                • Ctrl+C: Quit application\n\
-                • r: Refresh current view\n\n\
+                • 1-8: Jump to specific tab\n\
+                • Ctrl+P: Command palette - fuzzy search every action of the current view,\n\
+                  recently used first; branch actions then ask for the branch\n\
+                • q or Ctrl+Q: Quit application\n\
+                • r or F5: Refresh current view\n\n\
+                OPERATIONS TOOLBAR:\n\
+                • Alt+C: Checkout branch  Alt+N: Create branch  Alt+D: Delete branch\n\
+                • Alt+M: Merge branch into current  Alt+P: Push  Alt+U: Pull\n\
+                  (push and pull ask for confirmation first)\n\n\
                 NAVIGATION (all list views):\n\
                 • ↑↓ or kj: Move selection up/down\n\
                 • ←→ or hl: Move left/right, switch panels\n\
@@ -361,6 +392,60 @@ impl HelpComponent {
                 • /: Search in current view\n\
                 • Ctrl+F: Global search\n\
                 • Ctrl+G: Filter current view\n\n\
+                NOTES (History tab):\n\
+                • E: Add or edit the note of the selected commit, D: remove it\n\
+                • R: Switch the notes ref being edited (git.notes_refs, e.g. refs/notes/review)\n\
+                • P / F: Push or fetch the notes refs (origin, or the first remote)\n\
+                • Fetched notes only fast-forward; diverged notes are kept in refs/notes-remote/\n\n\
+                DIFFS:\n\
+                • Status: Enter shows the selected file (unstaged, or staged when fully staged)\n\
+                • Status: s shows every staged change (index vs HEAD)\n\
+                • History: Enter shows the whole commit against its first parent\n\
+                • n / p: Next/previous hunk, moving on to the next/previous file\n\
+                • ] / [: Next/previous file; renames are detected; o: file outline\n\
+                • + / -: More context / fold back; z: whole file\n\
+                • e: Edit the new side in place (working tree or index); E: edit the staged\n\
+                  version only; Ctrl+S saves, warns if the file changed meanwhile\n\
+                • i: Intra-line highlight of changed words → characters → off\n\
+                • Binary files (NUL bytes, or binary / -diff in .gitattributes) show sizes\n\
+                  and blob ids; PNG/GIF/JPEG/BMP/WebP also show dimensions\n\
+                • Non-UTF-8 text is decoded via working-tree-encoding, a BOM or detection\n\
+                  (UTF-16, GBK, Latin-1); line-ending changes are listed above the diff\n\
+                • diff=<driver> in .gitattributes: diff.<driver>.textconv converts files to\n\
+                  text first (git.textconv_timeout_secs), xfuncname labels hunk headers\n\
+                • x: Export the diff as HTML (h), a patch (p) or Markdown (m) to\n\
+                  .git/ai-c-exports, with AI review findings as comments;\n\
+                  from a shell: ai-c export-diff [-o FILE] [REV | A..B | A...B]\n\
+                • [ui.diff]: algorithm (myers, minimal, patience, histogram), per-type\n\
+                  file_algorithms, whitespace (ignore-eol/-change/-all), ignore_blank_lines,\n\
+                  intraline_highlight, intraline_granularity (word/char),\n\
+                  syntax_highlighting, syntax_theme (theme, base16-ocean.dark/light)\n\n\
+                COMMIT (Status tab):\n\
+                • c: Write the commit message; lint problems show next to each line\n\
+                • Ctrl+S: Commit (blocked while errors remain), Ctrl+L: auto-fix\n\
+                • Ctrl+G: Generate a message with the commit agent, fixed until it passes\n\
+                • Ctrl+A: Pick co-authors from recent authors (.mailmap aware)\n\
+                • Ctrl+O: Toggle your Signed-off-by (git.auto_signoff adds it by default)\n\
+                • Rules: .ai-c/commitlint.toml, otherwise .commitlintrc.json\n\n\
+                CLEAN (Status tab):\n\
+                • C: Preview untracked files that would be cleaned, with sizes\n\
+                • i / d: Cycle ignored-file mode / toggle directories\n\
+                • x: Move the previewed files to .git/ai-c-trash\n\
+                • t: Show trash batches, Enter restores the selected batch\n\n\
+                SPARSE CHECKOUT (Status tab):\n\
+                • S: Show the cone directories and how many paths are materialized\n\
+                • Space: Add/remove the selected directory, →/← expand or collapse\n\
+                • a: Apply the edited cone, u: undo edits, D: disable sparse checkout\n\n\
+                FILE MENU (Status tab):\n\
+                • m / right click: Open the context menu of the selected file\n\
+                • Ignore: Add a file, extension or directory rule to any .gitignore or .git/info/exclude\n\
+                • Explain Why Ignored: Show the matching rule and its source file\n\n\
+                WORKSPACE (multi-repository mode):\n\
+                • Alt+↑↓: Switch to previous/next repository\n\
+                • Alt+1-9: Switch to repository by position\n\
+                • Alt+F: Fetch all repositories\n\
+                • Alt+L: Pull (fast-forward) all repositories\n\
+                • Alt+W: Only show repositories with uncommitted work\n\n\
                 VIM USERS:\n\
                 All vim navigation keys (hjkl) are supported throughout the interface.".to_string()
             }
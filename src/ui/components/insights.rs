@@ -0,0 +1,405 @@
+//! Repository insights tab: contributors, churn, hotspots and bus factor
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, List, ListItem, ListState, Paragraph, Sparkline, Wrap,
+    },
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    app::state::AppState,
+    error::AppResult,
+    git::{InsightsBucket, InsightsOptions, RepositoryInsights},
+    ui::{
+        components::{
+            modals::{InputModal, Modal, ModalResult},
+            view_keys::ViewKey,
+            ShortcutContext,
+        },
+        keyboard::{NavigationHandler, NavigationKey, ShortcutManager},
+        theme::Theme,
+    },
+};
+
+/// Sections of the insights tab
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InsightsSection {
+    Authors,
+    Files,
+    Hotspots,
+    BusFactor,
+}
+
+impl InsightsSection {
+    const ALL: [InsightsSection; 4] = [
+        InsightsSection::Authors,
+        InsightsSection::Files,
+        InsightsSection::Hotspots,
+        InsightsSection::BusFactor,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            InsightsSection::Authors => "Authors",
+            InsightsSection::Files => "Churn",
+            InsightsSection::Hotspots => "Hotspots",
+            InsightsSection::BusFactor => "Bus Factor",
+        }
+    }
+}
+
+/// Insights tab component
+pub struct InsightsTabComponent {
+    insights: Option<RepositoryInsights>,
+    options: InsightsOptions,
+    /// Background computation of the insights
+    task: Option<JoinHandle<AppResult<RepositoryInsights>>>,
+    error: Option<String>,
+    section: InsightsSection,
+    selected_index: usize,
+    list_state: ListState,
+    shortcut_manager: ShortcutManager,
+    /// Prompt for the JSON export path
+    export_input: InputModal,
+}
+
+impl InsightsTabComponent {
+    /// 洞察视图的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::InsightsView, KeyCode::Char('g'), "Recompute insights").hint("g: Recompute"),
+        ViewKey::new(ShortcutContext::InsightsView, KeyCode::Char('b'), "Cycle time bucket").hint("b: Bucket"),
+        ViewKey::new(ShortcutContext::InsightsView, KeyCode::Char('e'), "Export insights as JSON").hint("e: Export JSON"),
+    ];
+
+    pub fn new() -> Self {
+        Self {
+            insights: None,
+            options: InsightsOptions::default(),
+            task: None,
+            error: None,
+            section: InsightsSection::Authors,
+            selected_index: 0,
+            list_state: ListState::default(),
+            shortcut_manager: ShortcutManager::new(),
+            export_input: InputModal::new(),
+        }
+    }
+
+    /// Start computing insights in the background
+    fn start_computation(&mut self, state: &AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+
+        let options = self.options.clone();
+        self.error = None;
+        self.task = Some(tokio::spawn(async move { git_service.compute_insights(&options).await }));
+    }
+
+    /// Pick up finished computations; compute on first display
+    fn sync(&mut self, state: &AppState) {
+        if self.task.as_ref().is_some_and(|task| task.is_finished()) {
+            if let Some(task) = self.task.take() {
+                let result = tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task));
+                match result {
+                    Ok(Ok(insights)) => self.insights = Some(insights),
+                    Ok(Err(e)) => self.error = Some(e.to_string()),
+                    Err(e) => self.error = Some(format!("Insights computation failed: {}", e)),
+                }
+            }
+        }
+
+        if self.insights.is_none() && self.task.is_none() && self.error.is_none() {
+            self.start_computation(state);
+        }
+    }
+
+    fn item_count(&self) -> usize {
+        let Some(insights) = &self.insights else {
+            return 0;
+        };
+
+        match self.section {
+            InsightsSection::Authors => insights.authors.len(),
+            InsightsSection::Files => insights.files.len(),
+            InsightsSection::Hotspots => insights.hotspots.len(),
+            InsightsSection::BusFactor => insights.bus_factor_warnings.len(),
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        self.sync(state);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Summary header
+                Constraint::Length(6), // Overall commit activity
+                Constraint::Min(0),    // Section list
+                Constraint::Length(6), // Selected author activity
+            ])
+            .split(area);
+
+        self.render_header(frame, chunks[0], theme);
+
+        let timeline = self.insights.as_ref().map(|i| i.commit_timeline.clone()).unwrap_or_default();
+        Self::render_sparkline(frame, chunks[1], "Commits over time", &timeline, theme);
+
+        self.render_section(frame, chunks[2], theme);
+
+        let (title, author_timeline) = match (&self.insights, self.section) {
+            (Some(insights), InsightsSection::Authors) => match insights.authors.get(self.selected_index) {
+                Some(author) => (format!("Activity of {}", author.name), author.timeline.clone()),
+                None => ("Author activity".to_string(), Vec::new()),
+            },
+            _ => ("Author activity (select an author)".to_string(), Vec::new()),
+        };
+        Self::render_sparkline(frame, chunks[3], &title, &author_timeline, theme);
+
+        if self.export_input.is_open() {
+            self.export_input.render(frame, area, theme);
+        }
+    }
+
+    fn render_header(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let summary = match (&self.insights, &self.error) {
+            (_, Some(error)) => format!("⚠ {}", error),
+            (None, None) => "Analyzing history...".to_string(),
+            (Some(insights), None) => format!(
+                "📊 {} commits | {} authors | {} files | {} hotspots | {} bus-factor warnings | Bucket: {:?}{}",
+                insights.commits_analyzed,
+                insights.authors.len(),
+                insights.files.len(),
+                insights.hotspots.len(),
+                insights.bus_factor_warnings.len(),
+                insights.bucket,
+                if self.task.is_some() { " | Refreshing..." } else { "" }
+            ),
+        };
+
+        let header = Paragraph::new(summary)
+            .block(
+                Block::default()
+                    .title("Repository Insights")
+                    .borders(Borders::ALL)
+                    .border_style(theme.border_style()),
+            )
+            .style(theme.text_style())
+            .wrap(Wrap { trim: true });
+        frame.render_widget(header, area);
+    }
+
+    /// Sparkline of the most recent buckets that fit into the area
+    fn render_sparkline(frame: &mut Frame, area: Rect, title: &str, data: &[u64], theme: &Theme) {
+        let width = area.width.saturating_sub(2) as usize;
+        let visible = &data[data.len().saturating_sub(width)..];
+
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .title(format!("{} (max {})", title, visible.iter().max().unwrap_or(&0)))
+                    .borders(Borders::ALL)
+                    .border_style(theme.border_style()),
+            )
+            .data(visible)
+            .style(theme.success_style());
+        frame.render_widget(sparkline, area);
+    }
+
+    fn render_section(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let tabs: Vec<Span> = InsightsSection::ALL
+            .iter()
+            .flat_map(|section| {
+                let style = if *section == self.section {
+                    theme.highlight_style().add_modifier(Modifier::BOLD)
+                } else {
+                    theme.muted_style()
+                };
+                [Span::styled(format!(" {} ", section.title()), style), Span::raw("│")]
+            })
+            .collect();
+
+        let items: Vec<ListItem> = match &self.insights {
+            None => Vec::new(),
+            Some(insights) => match self.section {
+                InsightsSection::Authors => insights
+                    .authors
+                    .iter()
+                    .map(|a| {
+                        ListItem::new(format!(
+                            "{:<24} {:>5} commits  +{:<7} -{:<7} <{}>",
+                            a.name, a.commits, a.lines_added, a.lines_removed, a.email
+                        ))
+                    })
+                    .collect(),
+                InsightsSection::Files => insights
+                    .files
+                    .iter()
+                    .map(|f| {
+                        ListItem::new(format!(
+                            "+{:<7} -{:<7} {:>4} commits  {}",
+                            f.lines_added, f.lines_removed, f.commits, f.path
+                        ))
+                    })
+                    .collect(),
+                InsightsSection::Hotspots => insights
+                    .hotspots
+                    .iter()
+                    .map(|h| {
+                        let bar = "█".repeat((h.score * 10.0).round() as usize);
+                        ListItem::new(format!(
+                            "{:<10} {:>4} commits {:>7} lines  {}",
+                            bar, h.commits, h.churn, h.path
+                        ))
+                    })
+                    .collect(),
+                InsightsSection::BusFactor => insights
+                    .bus_factor_warnings
+                    .iter()
+                    .map(|w| {
+                        ListItem::new(Line::from(vec![
+                            Span::styled("⚠ ", theme.warning_style()),
+                            Span::raw(format!(
+                                "{} — {:.0}% of {} commits by {}",
+                                w.path,
+                                w.share * 100.0,
+                                w.commits,
+                                w.author
+                            )),
+                        ]))
+                    })
+                    .collect(),
+            },
+        };
+
+        self.list_state.select((!items.is_empty()).then_some(self.selected_index));
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(Line::from(tabs))
+                    .title(
+                        Title::from("←/→ Section | [g] Recompute | [b] Bucket | [e] Export JSON")
+                            .position(Position::Bottom),
+                    )
+                    .borders(Borders::ALL)
+                    .border_style(theme.accent_border_style()),
+            )
+            .style(theme.text_style())
+            .highlight_style(theme.highlight_style())
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    /// Whether the export path input is open and takes every key
+    pub fn captures_text_input(&self) -> bool {
+        self.export_input.is_open()
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        if self.export_input.is_open() {
+            if let ModalResult::Input(path) = self.export_input.handle_key_event(key)? {
+                if !path.trim().is_empty() {
+                    self.export_json(path.trim(), state);
+                }
+            }
+            return Ok(());
+        }
+
+        if let Some(nav_key) = self.shortcut_manager.is_navigation_key(&key) {
+            match nav_key {
+                NavigationKey::Left | NavigationKey::Right => {
+                    let current = InsightsSection::ALL.iter().position(|s| *s == self.section).unwrap_or(0);
+                    let len = InsightsSection::ALL.len();
+                    let next = if nav_key == NavigationKey::Left { (current + len - 1) % len } else { (current + 1) % len };
+                    self.section = InsightsSection::ALL[next];
+                    self.selected_index = 0;
+                }
+                _ => {
+                    let item_count = self.item_count();
+                    let mut nav_handler = InsightsNavigationHandler {
+                        component: self,
+                        item_count,
+                    };
+                    nav_handler.handle_navigation(nav_key);
+                }
+            }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Char('g') => self.start_computation(state),
+            KeyCode::Char('b') => {
+                self.options.bucket = match self.options.bucket {
+                    InsightsBucket::Day => InsightsBucket::Week,
+                    InsightsBucket::Week => InsightsBucket::Month,
+                    InsightsBucket::Month => InsightsBucket::Day,
+                };
+                self.start_computation(state);
+            }
+            KeyCode::Char('e') if self.insights.is_some() => {
+                self.export_input.open_with_placeholder("Export Insights", "JSON file path:", "insights.json");
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn export_json(&mut self, path: &str, state: &mut AppState) {
+        let Some(insights) = &self.insights else {
+            return;
+        };
+
+        let target = std::path::PathBuf::from(path);
+        let target = match (&state.git_service, target.is_absolute()) {
+            (Some(git_service), false) => git_service.repo_path().join(target),
+            _ => target,
+        };
+
+        let result = insights
+            .to_json()
+            .and_then(|json| std::fs::write(&target, json).map_err(Into::into));
+        match result {
+            Ok(()) => state.add_info(format!("Exported insights to {}", target.display())),
+            Err(e) => state.add_error(format!("Failed to export insights: {}", e)),
+        }
+    }
+}
+
+impl Default for InsightsTabComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Helper structure for navigation handling with dynamic item count
+struct InsightsNavigationHandler<'a> {
+    component: &'a mut InsightsTabComponent,
+    item_count: usize,
+}
+
+impl<'a> NavigationHandler for InsightsNavigationHandler<'a> {
+    fn selected_index(&self) -> usize {
+        self.component.selected_index
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.component.selected_index = index;
+    }
+
+    fn item_count(&self) -> usize {
+        self.item_count
+    }
+}
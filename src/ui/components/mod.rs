@@ -12,15 +12,23 @@ pub mod ai_suggestions;
 pub mod search;
 pub mod filter;
 pub mod global_search;
+pub mod changelog;
+pub mod clean;
+pub mod commit_editor;
 pub mod commit_history;
+pub mod insights;
 pub mod context_menu;
 pub mod context_manager;
 pub mod shortcuts;
+pub mod sparse;
 pub mod search_cache;
 pub mod search_index;
 pub mod git_operations;
 pub mod agent_manager;
 pub mod help;
+pub mod workspace;
+pub mod command_palette;
+pub mod view_keys;
 
 pub use sidebar::SidebarComponent;
 pub use status_bar::StatusBarComponent;
@@ -32,7 +40,12 @@ pub use ai_suggestions::AISuggestionsComponent;
 pub use search::SearchComponent;
 pub use filter::FilterComponent;
 pub use global_search::GlobalSearchManager;
+pub use changelog::{ChangelogPanelComponent, ChangelogPanelEvent};
+pub use clean::CleanPanelComponent;
+pub use commit_editor::CommitEditorComponent;
+pub use sparse::SparsePanelComponent;
 pub use commit_history::CommitHistoryComponent;
+pub use insights::InsightsTabComponent;
 pub use context_menu::{ContextMenuComponent, MenuItem, MenuAction};
 pub use context_manager::{ContextMenuManager, ContextInfo, ContextType};
 pub use shortcuts::{ShortcutsManager, Shortcut, ShortcutAction, ShortcutContext};
@@ -41,6 +54,8 @@ pub use search_index::{SearchIndex, SearchMatch, IndexStats};
 pub use git_operations::{GitOperationsComponent, GitOperation};
 pub use agent_manager::{AgentManagerComponent, AgentInfo, AgentStatus};
 pub use help::{HelpComponent, HelpCategory};
+pub use workspace::WorkspacePanelComponent;
+pub use command_palette::{CommandPaletteComponent, PaletteAction, PaletteCommand, BranchPicker};
 
 use crossterm::event::KeyEvent;
 use ratatui::{layout::Rect, Frame};
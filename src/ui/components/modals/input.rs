@@ -51,6 +51,13 @@ impl InputModal {
         self.placeholder = placeholder.to_string();
     }
 
+    /// Open with an editable pre-filled value
+    pub fn open_with_value(&mut self, title: &str, prompt: &str, value: &str) {
+        self.open(title, prompt);
+        self.input = value.to_string();
+        self.cursor_position = self.input.len();
+    }
+
     /// Get the current input value
     pub fn get_input(&self) -> &str {
         &self.input
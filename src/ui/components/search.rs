@@ -14,7 +14,7 @@ use tokio::sync::mpsc;
 use crate::{
     app::state::AppState,
     error::AppResult,
-    git::service::GitService,
+    git::{service::GitService, CommitNote},
     ui::theme::Theme,
 };
 
@@ -250,31 +250,77 @@ impl SearchComponent {
             }
         }
 
+        // Tracked files that the sparse checkout leaves out of the working tree
+        const MAX_SPARSE_RESULTS: usize = 50;
+        let query = self.query.to_lowercase();
+        for path in git_service
+            .sparse_excluded_paths()
+            .await?
+            .into_iter()
+            .filter(|path| path.to_lowercase().contains(&query))
+            .take(MAX_SPARSE_RESULTS)
+        {
+            let relevance = self.calculate_relevance(&path, &self.query) * 0.5;
+            results.push(SearchResult {
+                title: path.clone(),
+                content: "Outside sparse checkout (not in working tree)".to_string(),
+                scope: SearchScope::Files,
+                file_path: Some(path),
+                line_number: None,
+                commit_hash: None,
+                branch_name: None,
+                relevance_score: relevance,
+            });
+        }
+
         Ok(results)
     }
 
-    /// Search commits
+    /// Search commits, including their git notes
     async fn search_commits(&self, git_service: &GitService) -> AppResult<Vec<SearchResult>> {
         let mut results = Vec::new();
         let commits = git_service.get_commits(100).await?;
+        let query = self.query.to_lowercase();
+
+        // Notes matching the query, by annotated commit
+        let mut matching_notes: HashMap<String, Vec<CommitNote>> = HashMap::new();
+        for note in git_service.list_notes().await.unwrap_or_default() {
+            if note.message.to_lowercase().contains(&query) {
+                matching_notes.entry(note.commit.clone()).or_default().push(note);
+            }
+        }
+        let note_summary = |notes: &[CommitNote]| {
+            notes
+                .iter()
+                .map(|note| format!("Note ({}): {}", note.short_ref(), note.message.lines().next().unwrap_or("")))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
 
         for commit in &commits {
-            let message_match = commit.message.to_lowercase().contains(&self.query.to_lowercase());
-            let author_match = commit.author.to_lowercase().contains(&self.query.to_lowercase());
-            let hash_match = commit.hash.to_lowercase().starts_with(&self.query.to_lowercase());
+            let message_match = commit.message.to_lowercase().contains(&query);
+            let author_match = commit.author.to_lowercase().contains(&query);
+            let hash_match = commit.hash.to_lowercase().starts_with(&query);
+            let notes = matching_notes.remove(&commit.hash);
 
-            if message_match || author_match || hash_match {
+            if message_match || author_match || hash_match || notes.is_some() {
                 let relevance = if hash_match {
                     1.0
                 } else if author_match {
                     0.8
-                } else {
+                } else if message_match {
                     self.calculate_relevance(&commit.message, &self.query)
+                } else {
+                    0.6
                 };
 
+                let mut content = format!("Author: {} | Date: {}", commit.author, commit.date);
+                if let Some(notes) = &notes {
+                    content = format!("{} | {}", note_summary(notes), content);
+                }
                 results.push(SearchResult {
                     title: format!("{} - {}", &commit.hash[..8], commit.message.lines().next().unwrap_or("")),
-                    content: format!("Author: {} | Date: {}", commit.author, commit.date),
+                    content,
                     scope: SearchScope::Commits,
                     file_path: None,
                     line_number: None,
@@ -285,6 +331,20 @@ impl SearchComponent {
             }
         }
 
+        // Notes on commits older than the searched history
+        for (commit, notes) in matching_notes {
+            results.push(SearchResult {
+                title: format!("{} - (note)", &commit[..8.min(commit.len())]),
+                content: note_summary(&notes),
+                scope: SearchScope::Commits,
+                file_path: None,
+                line_number: None,
+                commit_hash: Some(commit),
+                branch_name: None,
+                relevance_score: 0.5,
+            });
+        }
+
         Ok(results)
     }
 
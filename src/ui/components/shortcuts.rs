@@ -161,6 +161,7 @@ pub enum ShortcutAction {
     Refresh,
     Help,
     Settings,
+    CommandPalette,
 
     // Search
     Search,
@@ -178,6 +179,10 @@ pub enum ShortcutAction {
     GitPull,
     GitStash,
     GitBranch,
+    CheckoutBranch,
+    CreateBranch,
+    DeleteBranch,
+    MergeBranch,
 
     // File operations
     OpenFile,
@@ -204,6 +209,11 @@ pub enum ShortcutContext {
     FileList,         // Active in file list views
     CommitList,       // Active in commit list views
     BranchList,       // Active in branch list views
+    TagList,          // Active in the tag list
+    StashList,        // Active in the stash list
+    RemoteList,       // Active in the remote list
+    GitFlowView,      // Active in the GitFlow view
+    InsightsView,     // Active in the repository insights view
     DiffViewer,       // Active in diff viewer
     SearchMode,       // Active during search
     ModalOpen,        // Active when modal is open
@@ -281,6 +291,12 @@ impl ShortcutsManager {
                 ShortcutContext::Global,
                 "Show help"
             ),
+            ShortcutDefinition::new(
+                Shortcut::new(KeyCode::Char('p'), KeyModifiers::CONTROL),
+                ShortcutAction::CommandPalette,
+                ShortcutContext::Global,
+                "Open command palette"
+            ),
 
             // Search shortcuts
             ShortcutDefinition::new(
@@ -321,12 +337,13 @@ impl ShortcutsManager {
                 ShortcutContext::FileList,
                 "Show file diff"
             ),
+            // Ctrl+A and Ctrl+C select and copy text in every view
             ShortcutDefinition::new(
                 Shortcut::new(KeyCode::Char('a'), KeyModifiers::CONTROL),
                 ShortcutAction::GitAdd,
                 ShortcutContext::FileList,
                 "Add file to staging"
-            ),
+            ).disabled(),
             ShortcutDefinition::new(
                 Shortcut::new(KeyCode::Char('c'), KeyModifiers::CONTROL | KeyModifiers::SHIFT),
                 ShortcutAction::GitCommit,
@@ -334,6 +351,44 @@ impl ShortcutsManager {
                 "Create commit"
             ),
 
+            // Operations toolbar
+            ShortcutDefinition::new(
+                Shortcut::new(KeyCode::Char('c'), KeyModifiers::ALT),
+                ShortcutAction::CheckoutBranch,
+                ShortcutContext::Global,
+                "Checkout branch…"
+            ),
+            ShortcutDefinition::new(
+                Shortcut::new(KeyCode::Char('n'), KeyModifiers::ALT),
+                ShortcutAction::CreateBranch,
+                ShortcutContext::Global,
+                "Create new branch…"
+            ),
+            ShortcutDefinition::new(
+                Shortcut::new(KeyCode::Char('d'), KeyModifiers::ALT),
+                ShortcutAction::DeleteBranch,
+                ShortcutContext::Global,
+                "Delete branch…"
+            ),
+            ShortcutDefinition::new(
+                Shortcut::new(KeyCode::Char('m'), KeyModifiers::ALT),
+                ShortcutAction::MergeBranch,
+                ShortcutContext::Global,
+                "Merge branch into current…"
+            ),
+            ShortcutDefinition::new(
+                Shortcut::new(KeyCode::Char('p'), KeyModifiers::ALT),
+                ShortcutAction::GitPush,
+                ShortcutContext::Global,
+                "Push current branch"
+            ),
+            ShortcutDefinition::new(
+                Shortcut::new(KeyCode::Char('u'), KeyModifiers::ALT),
+                ShortcutAction::GitPull,
+                ShortcutContext::Global,
+                "Pull current branch"
+            ),
+
             // Navigation shortcuts
             ShortcutDefinition::new(
                 Shortcut::new(KeyCode::Tab, KeyModifiers::empty()),
@@ -372,7 +427,7 @@ impl ShortcutsManager {
                 ShortcutAction::CopyPath,
                 ShortcutContext::FileList,
                 "Copy file path"
-            ),
+            ).disabled(),
 
             // View toggles
             ShortcutDefinition::new(
@@ -412,6 +467,14 @@ impl ShortcutsManager {
         }
     }
 
+    /// Replace every context above Global with `context`
+    pub fn set_context(&mut self, context: ShortcutContext) {
+        self.context_stack.truncate(1);
+        if context != ShortcutContext::Global {
+            self.context_stack.push(context);
+        }
+    }
+
     /// Get current context
     pub fn current_context(&self) -> &ShortcutContext {
         self.context_stack.last().unwrap_or(&ShortcutContext::Global)
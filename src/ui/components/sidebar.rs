@@ -37,10 +37,11 @@ impl Component for SidebarComponent {
         theme: &Theme,
     ) {
         // Split sidebar into sections
+        let repository_height = if state.git_state.sparse_checkout.is_some() { 7 } else { 6 };
         let sections = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(6), // Repository info
+                Constraint::Length(repository_height), // Repository info
                 Constraint::Length(8), // Agent system status
                 Constraint::Min(0),    // Dynamic content based on current tab
             ])
@@ -70,7 +71,7 @@ impl SidebarComponent {
         state: &AppState,
         theme: &Theme,
     ) {
-        let repo_info = if state.git_state.is_repository {
+        let mut repo_info = if state.git_state.is_repository {
             vec![
                 format!("📁 Repository: ✓"),
                 format!(
@@ -96,6 +97,15 @@ impl SidebarComponent {
             ]
         };
 
+        if let Some(sparse) = &state.git_state.sparse_checkout {
+            repo_info.push(format!(
+                "🌲 Sparse: {}/{} paths ({} dirs)",
+                sparse.materialized,
+                sparse.total,
+                sparse.directories.len()
+            ));
+        }
+
         let items: Vec<ListItem> = repo_info
             .into_iter()
             .map(|info| ListItem::new(info))
@@ -130,6 +140,7 @@ impl SidebarComponent {
             TabType::Remotes => self.render_remotes_list(frame, area, state, theme),
             TabType::History => self.render_history_list(frame, area, state, theme),
             TabType::GitFlow => self.render_gitflow_list(frame, area, state, theme),
+            TabType::Insights => self.render_insights_list(frame, area, state, theme),
         }
     }
 
@@ -617,6 +628,34 @@ impl SidebarComponent {
         frame.render_stateful_widget(list, area, &mut list_state);
     }
 
+    fn render_insights_list(&self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        let insights_items = vec![
+            ListItem::new("Authors"),
+            ListItem::new("Churn"),
+            ListItem::new("Hotspots"),
+            ListItem::new("Bus Factor"),
+        ];
+
+        let is_focused = state.ui_state.current_focus == FocusArea::Sidebar;
+        let title = if is_focused { "Insights [FOCUSED]" } else { "Insights [Space to focus]" };
+
+        let mut list_state = ratatui::widgets::ListState::default();
+        list_state.select(Some(state.ui_state.sidebar_selected_index));
+
+        let list = List::new(insights_items)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(if is_focused { Style::default().fg(theme.colors.accent) } else { theme.border_style() }),
+            )
+            .style(Style::default().bg(theme.colors.secondary).fg(theme.colors.foreground))
+            .highlight_style(theme.highlight_style())
+            .highlight_symbol(">> ");
+
+        frame.render_stateful_widget(list, area, &mut list_state);
+    }
+
     fn render_history_list(&self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
         // Mock commit history data for now
         let history_items = vec![
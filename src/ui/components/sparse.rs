@@ -0,0 +1,413 @@
+//! Sparse-checkout panel for the Status tab: current cone, materialized path
+//! count and a directory tree picker to add or remove cone directories.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::collections::HashSet;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app::state::AppState,
+    error::AppResult,
+    git::{
+        sparse::{classify_directory, normalize_directories},
+        SparseCheckout, SparseDirectory, SparseInclusion,
+    },
+    ui::{
+        components::modals::{ConfirmationModal, Modal, ModalResult},
+        keyboard::{NavigationHandler, NavigationKey, ShortcutManager},
+        theme::Theme,
+    },
+};
+
+/// Action waiting for confirmation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SparseConfirmAction {
+    Apply,
+    Disable,
+}
+
+/// Sparse-checkout cone picker
+pub struct SparsePanelComponent {
+    is_open: bool,
+    sparse: Option<SparseCheckout>,
+    tree: Vec<SparseDirectory>,
+    load_task: Option<JoinHandle<AppResult<(SparseCheckout, Vec<SparseDirectory>)>>>,
+    /// Cone directories being edited, applied with `a`
+    pending: Vec<String>,
+    expanded: HashSet<String>,
+    message: Option<String>,
+    selected_index: usize,
+    list_state: ListState,
+    shortcut_manager: ShortcutManager,
+    confirm_modal: ConfirmationModal,
+    pending_confirm: Option<SparseConfirmAction>,
+}
+
+impl SparsePanelComponent {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            sparse: None,
+            tree: Vec::new(),
+            load_task: None,
+            pending: Vec::new(),
+            expanded: HashSet::new(),
+            message: None,
+            selected_index: 0,
+            list_state: ListState::default(),
+            shortcut_manager: ShortcutManager::new(),
+            confirm_modal: ConfirmationModal::new(),
+            pending_confirm: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Open the panel and read the current cone
+    pub fn open(&mut self, state: &AppState) {
+        self.is_open = true;
+        self.selected_index = 0;
+        self.start_load(state);
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        if let Some(task) = self.load_task.take() {
+            task.abort();
+        }
+    }
+
+    fn start_load(&mut self, state: &AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        if let Some(task) = self.load_task.take() {
+            task.abort();
+        }
+
+        self.message = None;
+        self.load_task = Some(tokio::spawn(async move {
+            let sparse = git_service.sparse_checkout().await?;
+            let tree = git_service.sparse_directory_tree(&sparse.directories).await?;
+            Ok((sparse, tree))
+        }));
+    }
+
+    fn sync(&mut self) {
+        if self.load_task.as_ref().is_some_and(|task| task.is_finished()) {
+            if let Some(task) = self.load_task.take() {
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task)) {
+                    Ok(Ok((sparse, tree))) => {
+                        // Show where the cone directories are
+                        self.expanded = tree
+                            .iter()
+                            .filter(|dir| dir.inclusion == SparseInclusion::Partial)
+                            .map(|dir| dir.path.clone())
+                            .collect();
+                        self.pending = sparse.directories.clone();
+                        self.sparse = Some(sparse);
+                        self.tree = tree;
+                    }
+                    Ok(Err(e)) => self.message = Some(e.to_string()),
+                    Err(e) => self.message = Some(format!("Reading sparse checkout failed: {}", e)),
+                }
+            }
+        }
+    }
+
+    /// Directories whose ancestors are all expanded
+    fn visible(&self) -> Vec<&SparseDirectory> {
+        self.tree
+            .iter()
+            .filter(|dir| {
+                dir.path
+                    .match_indices('/')
+                    .all(|(index, _)| self.expanded.contains(&dir.path[..index]))
+            })
+            .collect()
+    }
+
+    fn selected(&self) -> Option<SparseDirectory> {
+        self.visible().get(self.selected_index).map(|dir| (*dir).clone())
+    }
+
+    fn has_changes(&self) -> bool {
+        let applied = self.sparse.as_ref().map(|sparse| normalize_directories(&sparse.directories));
+        applied.as_deref() != Some(&normalize_directories(&self.pending)[..])
+    }
+
+    /// Edited cone differs, or applying would enable or convert to cone mode
+    fn can_apply(&self) -> bool {
+        self.has_changes() || self.sparse.as_ref().is_some_and(|sparse| !sparse.enabled || !sparse.cone)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.sync();
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(0)])
+            .split(area);
+
+        let summary = match (&self.sparse, &self.load_task) {
+            (_, Some(_)) => "Reading sparse checkout...".to_string(),
+            (Some(sparse), None) if !sparse.enabled => {
+                "Sparse checkout is off; pick directories and press [a] to enable cone mode".to_string()
+            }
+            (Some(sparse), None) => format!(
+                "{}: {}/{} paths materialized, {} outside | Cone: {}",
+                if sparse.cone { "Cone mode" } else { "Non-cone patterns (applying switches to cone mode)" },
+                sparse.materialized,
+                sparse.total,
+                sparse.outside(),
+                if sparse.directories.is_empty() { "root files only".to_string() } else { sparse.directories.join(", ") }
+            ),
+            (None, None) => String::new(),
+        };
+        let status = match &self.message {
+            Some(message) => Span::styled(format!("⚠ {}", message), theme.warning_style()),
+            None if self.has_changes() => Span::styled(
+                format!("Pending cone: {} directories, press [a] to apply", normalize_directories(&self.pending).len()),
+                theme.info_style(),
+            ),
+            None => Span::styled("No pending changes", theme.muted_style()),
+        };
+
+        let header = Paragraph::new(vec![
+            Line::from(Span::styled(summary, theme.text_style())),
+            Line::from(status),
+            Line::from(Span::styled(
+                "[Space] Toggle [→/←] Expand/Collapse [a] Apply [u] Undo [D] Disable [S] Close",
+                theme.muted_style(),
+            )),
+        ])
+        .block(
+            Block::default()
+                .title("Sparse Checkout")
+                .borders(Borders::ALL)
+                .border_style(theme.accent_border_style()),
+        )
+        .wrap(Wrap { trim: true });
+        frame.render_widget(header, chunks[0]);
+
+        let applied = self.sparse.as_ref().map(|sparse| sparse.directories.clone()).unwrap_or_default();
+        let items: Vec<ListItem> = self
+            .visible()
+            .iter()
+            .map(|dir| {
+                let inclusion = classify_directory(&self.pending, &dir.path);
+                let (check, style) = match inclusion {
+                    SparseInclusion::Included => ("[x]", theme.success_style()),
+                    SparseInclusion::Partial => ("[~]", theme.warning_style()),
+                    SparseInclusion::Excluded => ("[ ]", theme.muted_style()),
+                };
+                let marker = match (dir.has_children, self.expanded.contains(&dir.path)) {
+                    (false, _) => " ",
+                    (true, true) => "▾",
+                    (true, false) => "▸",
+                };
+                let changed = inclusion != classify_directory(&applied, &dir.path);
+
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{}{} ", "  ".repeat(dir.depth), marker)),
+                    Span::styled(format!("{} {}/", check, dir.name()), style),
+                    Span::styled(if changed { " *" } else { "" }, theme.info_style()),
+                ]))
+            })
+            .collect();
+
+        self.list_state.select((!items.is_empty()).then_some(self.selected_index));
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!("Directories ({})", self.tree.len()))
+                    .borders(Borders::ALL)
+                    .border_style(theme.border_style()),
+            )
+            .style(theme.text_style())
+            .highlight_style(theme.highlight_style())
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
+
+        if self.confirm_modal.is_open() {
+            self.confirm_modal.render(frame, area, theme);
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        if self.confirm_modal.is_open() {
+            let result = self.confirm_modal.handle_key_event(key)?;
+            if let (ModalResult::Confirmed, Some(action)) = (result, self.pending_confirm.take()) {
+                self.execute(action, state);
+            }
+            return Ok(());
+        }
+
+        if let Some(nav_key) = self.shortcut_manager.is_navigation_key(&key) {
+            match nav_key {
+                NavigationKey::Right => self.set_expanded(true),
+                NavigationKey::Left => self.set_expanded(false),
+                _ => {
+                    let item_count = self.visible().len();
+                    let mut nav_handler = SparseNavigationHandler { component: self, item_count };
+                    nav_handler.handle_navigation(nav_key);
+                }
+            }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('S') => self.close(),
+            KeyCode::Enter => {
+                if let Some(dir) = self.selected() {
+                    let expand = !self.expanded.contains(&dir.path);
+                    self.set_expanded(expand);
+                }
+            }
+            KeyCode::Char(' ') => self.toggle_selected(),
+            KeyCode::Char('u') => {
+                self.pending = self.sparse.as_ref().map(|sparse| sparse.directories.clone()).unwrap_or_default();
+                self.message = None;
+            }
+            KeyCode::Char('a') if self.load_task.is_none() && self.can_apply() => {
+                let directories = normalize_directories(&self.pending);
+                self.pending_confirm = Some(SparseConfirmAction::Apply);
+                self.confirm_modal.open_with_buttons(
+                    "Apply Sparse Checkout",
+                    &format!(
+                        "Check out {}?\nFiles outside the cone are removed from the working tree.",
+                        if directories.is_empty() {
+                            "only the files in the repository root".to_string()
+                        } else {
+                            directories.join(", ")
+                        }
+                    ),
+                    "Apply",
+                    "Cancel",
+                );
+            }
+            KeyCode::Char('D') if self.sparse.as_ref().is_some_and(|sparse| sparse.enabled) => {
+                self.pending_confirm = Some(SparseConfirmAction::Disable);
+                self.confirm_modal.open_with_buttons(
+                    "Disable Sparse Checkout",
+                    "Materialize every tracked file in the working tree?",
+                    "Disable",
+                    "Cancel",
+                );
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn set_expanded(&mut self, expand: bool) {
+        let Some(dir) = self.selected() else {
+            return;
+        };
+
+        if expand && dir.has_children {
+            self.expanded.insert(dir.path);
+        } else if !expand && !self.expanded.remove(&dir.path) {
+            // Already collapsed: move to the parent directory
+            if let Some((parent, _)) = dir.path.rsplit_once('/') {
+                if let Some(index) = self.visible().iter().position(|d| d.path == parent) {
+                    self.selected_index = index;
+                }
+            }
+        }
+    }
+
+    /// Add the selected directory to the cone, or remove it
+    fn toggle_selected(&mut self) {
+        let Some(dir) = self.selected() else {
+            return;
+        };
+        self.message = None;
+
+        if let Some(position) = self.pending.iter().position(|pending| *pending == dir.path) {
+            self.pending.remove(position);
+        } else if let Some(parent) = self
+            .pending
+            .iter()
+            .find(|pending| dir.path.starts_with(&format!("{}/", pending)))
+        {
+            self.message = Some(format!(
+                "{} is included through {}; cone mode cannot exclude subdirectories",
+                dir.path, parent
+            ));
+        } else {
+            self.pending.push(dir.path);
+            self.pending = normalize_directories(&self.pending);
+        }
+    }
+
+    fn execute(&mut self, action: SparseConfirmAction, state: &mut AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                match action {
+                    SparseConfirmAction::Apply => git_service.set_sparse_directories(&self.pending).await,
+                    SparseConfirmAction::Disable => git_service.disable_sparse_checkout().await,
+                }
+            })
+        });
+
+        match (result, action) {
+            (Ok(()), SparseConfirmAction::Apply) => state.add_info(format!(
+                "Sparse checkout set to {} directories",
+                normalize_directories(&self.pending).len()
+            )),
+            (Ok(()), SparseConfirmAction::Disable) => state.add_info("Sparse checkout disabled".to_string()),
+            (Err(e), _) => state.add_error(format!("Sparse checkout failed: {}", e)),
+        }
+
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                if let Ok(status) = git_service.get_status().await {
+                    state.update_git_status(status);
+                }
+                state.git_state.sparse_checkout =
+                    git_service.sparse_checkout().await.ok().filter(|sparse| sparse.enabled);
+            })
+        });
+        self.start_load(state);
+    }
+}
+
+impl Default for SparsePanelComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Helper structure for navigation handling with dynamic item count
+struct SparseNavigationHandler<'a> {
+    component: &'a mut SparsePanelComponent,
+    item_count: usize,
+}
+
+impl<'a> NavigationHandler for SparseNavigationHandler<'a> {
+    fn selected_index(&self) -> usize {
+        self.component.selected_index
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.component.selected_index = index;
+    }
+
+    fn item_count(&self) -> usize {
+        self.item_count
+    }
+}
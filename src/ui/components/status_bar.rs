@@ -61,6 +61,7 @@ impl StatusBarComponent {
             crate::app::state::TabType::Remotes => "↑↓: Navigate | f: Fetch | p: Push",
             crate::app::state::TabType::History => "↑↓: Navigate | Enter: View | f: Filter",
             crate::app::state::TabType::GitFlow => "↑↓: Navigate | Enter: Action",
+            crate::app::state::TabType::Insights => "←→: Section | g: Recompute | e: Export JSON",
         }
         .to_string()
     }
@@ -2,7 +2,7 @@
 //!
 //! Each tab represents a different view/functionality within the TUI.
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -10,15 +10,17 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
+use std::sync::Arc;
 use tracing::{debug, error};
 use chrono::Utc;
 
 use crate::{
     app::state::AppState,
-    error::AppResult,
-    git::CommitInfo,
+    config::DiffConfig,
+    error::{AppError, AppResult},
+    git::{changelog, version, ChangelogOptions, CommitInfo, DefaultRemoteManager, DiffSpec, GitService, RemoteManager, WorkflowRemoteInfo},
     ui::{
-        components::{Component, DiffViewerComponent, modals::{InputModal, Modal, ModalResult}},
+        components::{Component, ContextInfo, ContextMenuManager, ContextType, DiffViewerComponent, MenuAction, MenuItem, changelog::{ChangelogPanelComponent, ChangelogPanelEvent}, clean::CleanPanelComponent, commit_editor::CommitEditorComponent, sparse::SparsePanelComponent, view_keys::ViewKey, ShortcutContext, modals::{ConfirmationModal, InputModal, Modal, ModalResult}},
         theme::Theme,
         selection::{TextPosition, SelectionMode},
         keyboard::{ShortcutManager, NavigationKey, ActionKey, NavigationHandler},
@@ -92,20 +94,72 @@ pub struct StatusTabComponent {
     diff_viewer: DiffViewerComponent,
     show_diff: bool,
     shortcut_manager: ShortcutManager,
+    /// Clean preview and trash
+    clean_panel: CleanPanelComponent,
+    /// Sparse-checkout cone picker
+    sparse_panel: SparsePanelComponent,
+    /// Commit message editor with lint diagnostics
+    commit_editor: CommitEditorComponent,
+    /// File context menu (m / right click)
+    context_menu: ContextMenuManager,
+    /// Area of the file list in the last frame, for mouse hit testing
+    list_area: Rect,
 }
 
 impl StatusTabComponent {
+    /// 文件列表的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Enter, "Show diff of selected file").hint("Enter: Diff"),
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Char('s'), "Show staged changes").hint("s: Staged"),
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Char('+'), "Stage selected file").hint("+/-: Stage/Unstage"),
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Char('-'), "Unstage selected file"),
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Char('c'), "Write commit message").hint("c: Commit"),
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Char('C'), "Clean untracked files").hint("C: Clean"),
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Char('S'), "Sparse checkout").hint("S: Sparse"),
+        ViewKey::new(ShortcutContext::FileList, KeyCode::Char('m'), "Open file menu").hint("m: Menu"),
+    ];
+
     pub fn new() -> Self {
         Self {
             selected_index: 0,
             diff_viewer: DiffViewerComponent::new(),
             show_diff: false,
             shortcut_manager: ShortcutManager::new(),
+            clean_panel: CleanPanelComponent::new(),
+            sparse_panel: SparsePanelComponent::new(),
+            commit_editor: CommitEditorComponent::new(),
+            context_menu: ContextMenuManager::new(),
+            list_area: Rect::default(),
         }
     }
 
+    pub fn set_diff_config(&mut self, config: &DiffConfig) {
+        self.diff_viewer.set_diff_config(config);
+    }
+
+    /// Whether the commit editor, the clean preview, the sparse picker or the
+    /// diff viewer should receive every key (Esc and q close them instead of
+    /// quitting)
+    pub fn captures_text_input(&self) -> bool {
+        self.commit_editor.is_open()
+            || self.clean_panel.is_open()
+            || self.sparse_panel.is_open()
+            || self.show_diff
+    }
+
+    /// Whether the diff viewer is shown and not editing
+    pub fn shows_diff(&self) -> bool {
+        self.show_diff && !self.commit_editor.is_open() && !self.diff_viewer.is_editing()
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
-        if self.show_diff {
+        if self.commit_editor.is_open() {
+            self.commit_editor.render(frame, area, theme);
+        } else if self.clean_panel.is_open() {
+            self.clean_panel.render(frame, area, theme);
+        } else if self.sparse_panel.is_open() {
+            self.sparse_panel.render(frame, area, theme);
+        } else if self.show_diff {
             // 显示差异查看器
             let diff_area = area;
             tokio::task::block_in_place(|| {
@@ -139,21 +193,74 @@ impl StatusTabComponent {
             let list = List::new(items)
                 .block(
                     Block::default()
-                        .title("Git Status (Press Enter to view diff, Esc to go back)")
+                        .title("Git Status (Enter: diff, s: staged changes, c: commit, C: clean, S: sparse checkout, m: menu)")
                         .borders(Borders::ALL)
                         .border_style(theme.border_style()),
                 )
                 .style(theme.text_style());
 
             frame.render_widget(list, area);
+            self.list_area = area;
+
+            if self.context_menu.is_open() {
+                self.context_menu.render(frame, area, state, theme);
+            }
         }
     }
 
     pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        if self.commit_editor.is_open() {
+            if self.commit_editor.handle_key_event(key, state)? {
+                let count = state.git_state.file_status.len();
+                self.selected_index = self.selected_index.min(count.saturating_sub(1));
+            }
+            return Ok(());
+        }
+        if self.clean_panel.is_open() {
+            return self.clean_panel.handle_key_event(key, state);
+        }
+        if self.sparse_panel.is_open() {
+            return self.sparse_panel.handle_key_event(key, state);
+        }
+
+        // 右键菜单打开时接管所有按键
+        if self.context_menu.is_open() {
+            if let Some(action) = self.context_menu.handle_key(key)? {
+                self.execute_menu_action(action, state);
+            }
+            return Ok(());
+        }
+
+        // 编写提交信息（带 lint 检查）
+        if key.code == KeyCode::Char('c') && !self.show_diff {
+            self.commit_editor.open(state);
+            return Ok(());
+        }
+
+        // 清理未跟踪文件（预览 + 回收站）
+        if key.code == KeyCode::Char('C') && !self.show_diff {
+            self.clean_panel.open(state);
+            return Ok(());
+        }
+
+        // 稀疏检出（cone 模式）目录选择
+        if key.code == KeyCode::Char('S') && !self.show_diff {
+            self.sparse_panel.open(state);
+            return Ok(());
+        }
+
+        // 打开选中文件的右键菜单
+        if key.code == KeyCode::Char('m') && !self.show_diff {
+            let row = self.list_area.y + 2 + self.selected_index as u16;
+            let position = (self.list_area.x + 4, row.min(self.list_area.bottom().saturating_sub(1)));
+            self.open_context_menu(position, state)?;
+            return Ok(());
+        }
+
         if self.show_diff {
             // 在差异查看器模式下处理按键
             match key.code {
-                KeyCode::Esc => {
+                KeyCode::Esc | KeyCode::Char('q') if !self.diff_viewer.is_editing() => {
                     self.show_diff = false;
                     return Ok(());
                 }
@@ -187,12 +294,14 @@ impl StatusTabComponent {
             match action_key {
                 ActionKey::Confirm => {
                     // 显示选中文件的差异
-                    if let Some(_selected_file) = state.git_state.file_status.get(self.selected_index) {
-                        // TODO: Implement async file diff loading in a proper way
-                        // For now, just show the diff viewer
-                        self.show_diff = true;
+                    if let Some(selected_file) = state.git_state.file_status.get(self.selected_index).cloned() {
+                        self.open_file_diff(&selected_file, state);
                     }
                 }
+                ActionKey::Show => {
+                    // 暂存区与 HEAD 的差异（即将提交的内容）
+                    self.open_staged_diff(state);
+                }
                 ActionKey::SelectLine => {
                     // Select current line
                     let text_lines = state.git_state.file_status.iter()
@@ -293,17 +402,200 @@ impl<'a> NavigationHandler for StatusTabNavigationHandler<'a> {
 }
 
 impl StatusTabComponent {
-    /// 加载文件差异到差异查看器
-    async fn load_file_diff(&mut self, file_status: &crate::git::FileStatus, state: &AppState) -> AppResult<()> {
-        if let Some(git_service) = &state.git_service {
-            let file_path = std::path::PathBuf::from(&file_status.path);
+    /// Handle mouse input: left click selects a file, right click opens its context menu
+    pub fn handle_mouse_event(&mut self, mouse: MouseEvent, state: &mut AppState) -> AppResult<()> {
+        if self.commit_editor.is_open() || self.clean_panel.is_open() || self.sparse_panel.is_open() || self.show_diff {
+            return Ok(());
+        }
 
-            // 使用GitService的新方法获取文件差异
-            let (old_content, new_content) = git_service.get_file_diff(&file_path).await?;
-            self.diff_viewer.load_git_diff(&file_path, old_content, new_content).await?;
+        let clicked_index = self.file_index_at(mouse.column, mouse.row, state);
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Right) => {
+                self.context_menu.close();
+                if let Some(index) = clicked_index {
+                    self.selected_index = index;
+                    self.open_context_menu((mouse.column, mouse.row), state)?;
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) if self.context_menu.is_open() => {
+                if let Some(action) = self.context_menu.handle_mouse_event(mouse, self.list_area)? {
+                    self.execute_menu_action(action, state);
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = clicked_index {
+                    self.selected_index = index;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Index of the file rendered at a screen position (the list has no scroll offset)
+    fn file_index_at(&self, column: u16, row: u16, state: &AppState) -> Option<usize> {
+        let inner = self.list_area.inner(&ratatui::layout::Margin { horizontal: 1, vertical: 1 });
+        if column < inner.x || column >= inner.right() || row < inner.y || row >= inner.bottom() {
+            return None;
+        }
+
+        let index = (row - inner.y) as usize;
+        (index < state.git_state.file_status.len()).then_some(index)
+    }
+
+    fn open_context_menu(&mut self, position: (u16, u16), state: &AppState) -> AppResult<()> {
+        if let Some(file) = state.git_state.file_status.get(self.selected_index) {
+            let is_untracked = file.status.is_untracked();
+            self.context_menu.show_worktree_file_menu(&file.path, is_untracked, position)?;
         }
         Ok(())
     }
+
+    /// File menu entries of the selected file that the Status tab can run,
+    /// with submenus flattened into "Parent › Child" labels
+    pub fn file_menu_items(&self, state: &AppState) -> Vec<MenuItem> {
+        let Some(file) = state.git_state.file_status.get(self.selected_index) else {
+            return Vec::new();
+        };
+        let context = ContextInfo::new(ContextType::File, (0, 0))
+            .with_data("file_path", &file.path)
+            .with_data("is_untracked", &file.status.is_untracked().to_string());
+
+        fn flatten(items: Vec<MenuItem>, prefix: &str, out: &mut Vec<MenuItem>) {
+            for item in items {
+                let label = if prefix.is_empty() {
+                    item.label.clone()
+                } else {
+                    format!("{} › {}", prefix, item.label)
+                };
+                match item.submenu {
+                    Some(submenu) => flatten(submenu, &label, out),
+                    None if item.enabled && StatusTabComponent::runs_menu_action(&item.action) => out.push(MenuItem { label, ..item }),
+                    None => {}
+                }
+            }
+        }
+        let mut items = Vec::new();
+        flatten(self.context_menu.menu_items(&context), "", &mut items);
+        items
+    }
+
+    fn runs_menu_action(action: &MenuAction) -> bool {
+        matches!(
+            action,
+            MenuAction::GitIgnore { .. } | MenuAction::GitExplainIgnore | MenuAction::GitAdd | MenuAction::GitStage | MenuAction::GitDiff
+        )
+    }
+
+    /// Run a file menu action on the selected file without opening the menu
+    pub fn run_file_menu_action(&mut self, action: MenuAction, state: &mut AppState) {
+        if let Some(file_path) = state.git_state.file_status.get(self.selected_index).map(|file| file.path.clone()) {
+            self.run_menu_action(&file_path, action, state);
+        }
+    }
+
+    /// Run an action picked from the file context menu
+    fn execute_menu_action(&mut self, action: MenuAction, state: &mut AppState) {
+        let Some(file_path) = self
+            .context_menu
+            .get_current_context()
+            .and_then(|context| context.get_data("file_path"))
+            .cloned()
+        else {
+            return;
+        };
+        self.run_menu_action(&file_path, action, state);
+    }
+
+    fn run_menu_action(&mut self, file_path: &str, action: MenuAction, state: &mut AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        let file_path = file_path.to_string();
+
+        match action {
+            MenuAction::GitIgnore { pattern, target } => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.add_ignore_rule(&target, &pattern))
+                });
+                match result {
+                    Ok(true) => state.add_info(format!("Added '{}' to {}", pattern, target.label())),
+                    Ok(false) => state.add_info(format!("{} already contains '{}'", target.label(), pattern)),
+                    Err(e) => state.add_error(format!("Failed to update {}: {}", target.label(), e)),
+                }
+            }
+            MenuAction::GitExplainIgnore => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.explain_ignore(&file_path))
+                });
+                match result {
+                    Ok(Some(rule)) if rule.is_negated() => {
+                        state.add_info(format!("{} is not ignored, re-included by {}", file_path, rule.describe()))
+                    }
+                    Ok(Some(rule)) => state.add_info(format!("{} is ignored by {}", file_path, rule.describe())),
+                    Ok(None) => state.add_info(format!("{} is not matched by any ignore rule", file_path)),
+                    Err(e) => state.add_error(format!("Failed to check ignore rules: {}", e)),
+                }
+                return;
+            }
+            MenuAction::GitAdd | MenuAction::GitStage => {
+                let result = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(git_service.stage_file(&file_path))
+                });
+                if let Err(e) = result {
+                    state.add_error(format!("Failed to stage {}: {}", file_path, e));
+                }
+            }
+            MenuAction::GitDiff => {
+                if let Some(file) = state.git_state.file_status.iter().find(|file| file.path == file_path).cloned() {
+                    self.open_file_diff(&file, state);
+                }
+                return;
+            }
+            other => {
+                debug!("Context menu action {:?} is not handled by the Status tab", other);
+                state.add_info("This action is not available in the Status tab".to_string());
+                return;
+            }
+        }
+
+        if let Ok(status) = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.get_status())
+        }) {
+            self.selected_index = self.selected_index.min(status.len().saturating_sub(1));
+            state.update_git_status(status);
+        }
+    }
+
+    /// 显示文件差异：有未暂存修改时为索引与工作区，否则为 HEAD 与索引
+    fn open_file_diff(&mut self, file_status: &crate::git::FileStatus, state: &mut AppState) {
+        let spec = if file_status.status.is_modified() || !file_status.status.is_staged() {
+            DiffSpec::unstaged()
+        } else {
+            DiffSpec::staged()
+        };
+        self.open_revision_diff(spec.with_path(&file_status.path), state);
+    }
+
+    /// 显示全部已暂存修改（git diff --cached）
+    fn open_staged_diff(&mut self, state: &mut AppState) {
+        self.open_revision_diff(DiffSpec::staged(), state);
+    }
+
+    fn open_revision_diff(&mut self, spec: DiffSpec, state: &mut AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.diff_viewer.load_revision_diff(&git_service, &spec))
+        });
+        match result {
+            Ok(()) => self.show_diff = true,
+            Err(e) => state.add_error(format!("Failed to load diff {}: {}", spec.label(), e)),
+        }
+    }
 }
 
 /// Branches tab component with enhanced three-column layout
@@ -317,6 +609,9 @@ pub struct BranchesTabComponent {
     input_modal: InputModal,
     // 分页相关字段
     commits_pagination: CommitsPagination,
+    /// 分支比较（当前分支...选中分支）的差异查看器
+    diff_viewer: DiffViewerComponent,
+    show_diff: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -327,6 +622,17 @@ enum BranchViewMode {
 }
 
 impl BranchesTabComponent {
+    /// 分支列表的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::BranchList, KeyCode::Enter, "Checkout selected branch").hint("Enter: Checkout"),
+        ViewKey::new(ShortcutContext::BranchList, KeyCode::Char('c'), "Compare with current branch").hint("c: Compare"),
+        ViewKey::new(ShortcutContext::BranchList, KeyCode::Char('n'), "New branch"),
+        ViewKey::new(ShortcutContext::BranchList, KeyCode::Char('d'), "Delete selected branch"),
+        ViewKey::new(ShortcutContext::BranchList, KeyCode::Char('m'), "Merge selected branch"),
+        ViewKey::new(ShortcutContext::BranchList, KeyCode::Char('p'), "Push selected branch"),
+        ViewKey::new(ShortcutContext::BranchList, KeyCode::Char('u'), "Pull selected branch"),
+    ];
+
     pub fn new() -> Self {
         let mut list_state = ListState::default();
         list_state.select(Some(0));
@@ -341,10 +647,37 @@ impl BranchesTabComponent {
             shortcut_manager: ShortcutManager::new(),
             input_modal: InputModal::new(),
             commits_pagination: CommitsPagination::new(),
+            diff_viewer: DiffViewerComponent::new(),
+            show_diff: false,
         }
     }
 
+    pub fn set_diff_config(&mut self, config: &DiffConfig) {
+        self.diff_viewer.set_diff_config(config);
+    }
+
+    /// The branch comparison diff takes every key so Esc closes it
+    pub fn captures_text_input(&self) -> bool {
+        self.show_diff
+    }
+
+    /// Whether the diff viewer is shown and not editing
+    pub fn shows_diff(&self) -> bool {
+        self.show_diff && !self.diff_viewer.is_editing()
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        if self.show_diff {
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    if let Err(e) = self.diff_viewer.render(frame, area, state, theme).await {
+                        debug!("Failed to render branch comparison: {}", e);
+                    }
+                })
+            });
+            return;
+        }
+
         // Create three-panel layout: Actions bar + Main content (branches + details)
         let main_layout = Layout::default()
             .direction(Direction::Vertical)
@@ -380,7 +713,7 @@ impl BranchesTabComponent {
 
     fn render_action_buttons(&self, frame: &mut Frame, area: Rect, _state: &AppState, theme: &Theme) {
         let buttons = vec![
-            "Checkout", "Create New", "Delete", "Merge", "Pull", "Push", "Refresh"
+            "Checkout", "Create New", "Delete", "Merge", "Pull", "Push", "Compare (c)", "Refresh"
         ];
 
         let button_text = buttons.join(" | ");
@@ -711,6 +1044,41 @@ impl BranchesTabComponent {
         }
     }
 
+    /// Open `current...selected` in the diff viewer: what the selected branch
+    /// changed since it forked from the current one
+    fn open_branch_comparison(&mut self, state: &mut AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        let branches = match git_service.list_branches() {
+            Ok(branches) => branches,
+            Err(e) => {
+                state.add_error(format!("Failed to list branches: {}", e));
+                return;
+            }
+        };
+        let Some(selected) = branches.get(self.selected_index) else {
+            return;
+        };
+        let Some(current) = branches.iter().find(|branch| branch.is_current) else {
+            state.add_error("No current branch to compare against".to_string());
+            return;
+        };
+        if selected.name == current.name {
+            state.add_info("Select another branch to compare with the current one".to_string());
+            return;
+        }
+
+        let spec = DiffSpec::branch_comparison(&current.name, &selected.name);
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.diff_viewer.load_revision_diff(&git_service, &spec))
+        });
+        match result {
+            Ok(()) => self.show_diff = true,
+            Err(e) => state.add_error(format!("Failed to compare {} with {}: {}", current.name, selected.name, e)),
+        }
+    }
+
     pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
         // Handle modal input first if modal is open
         if self.input_modal.is_open() {
@@ -749,6 +1117,28 @@ impl BranchesTabComponent {
             return Ok(()); // Modal is open, consume all other events
         }
 
+        if self.show_diff {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') if !self.diff_viewer.is_editing() => self.show_diff = false,
+                _ => {
+                    tokio::task::block_in_place(|| {
+                        tokio::runtime::Handle::current().block_on(async {
+                            if let Err(e) = self.diff_viewer.handle_key(key).await {
+                                debug!("Diff viewer key handling failed: {}", e);
+                            }
+                        })
+                    });
+                }
+            }
+            return Ok(());
+        }
+
+        // 比较当前分支与选中分支（base...other）
+        if key.code == KeyCode::Char('c') {
+            self.open_branch_comparison(state);
+            return Ok(());
+        }
+
         let branch_count = if let Some(git_service) = &state.git_service {
             match git_service.list_branches() {
                 Ok(branches) => branches.len(),
@@ -1074,17 +1464,39 @@ impl<'a> NavigationHandler for BranchesTabNavigationHandler<'a> {
 pub struct TagsTabComponent {
     selected_index: usize,
     shortcut_manager: ShortcutManager,
+    changelog_panel: ChangelogPanelComponent,
 }
 
 impl TagsTabComponent {
+    /// 标签列表的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::TagList, KeyCode::Enter, "View selected tag").hint("Enter: View tag"),
+        ViewKey::new(ShortcutContext::TagList, KeyCode::Char('s'), "Show tag details"),
+        ViewKey::new(ShortcutContext::TagList, KeyCode::Char('n'), "New tag"),
+        ViewKey::new(ShortcutContext::TagList, KeyCode::Char('d'), "Delete selected tag"),
+        ViewKey::new(ShortcutContext::TagList, KeyCode::Char('g'), "Changelog since selected tag").hint("g/G: Changelog"),
+        ViewKey::new(ShortcutContext::TagList, KeyCode::Char('G'), "Open changelog"),
+    ];
+
     pub fn new() -> Self {
         Self {
             selected_index: 0,
             shortcut_manager: ShortcutManager::new(),
+            changelog_panel: ChangelogPanelComponent::new(),
         }
     }
 
+    /// Whether an open editor should receive every key
+    pub fn captures_text_input(&self) -> bool {
+        self.changelog_panel.is_open()
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        if self.changelog_panel.is_open() {
+            self.changelog_panel.render(frame, area, theme);
+            return;
+        }
+
         let tags = if let Some(git_service) = &state.git_service {
             // Use real Git service to fetch tags
             tokio::task::block_in_place(|| {
@@ -1119,7 +1531,7 @@ impl TagsTabComponent {
         let list = List::new(items)
             .block(
                 Block::default()
-                    .title("Tags")
+                    .title("Tags ([g] Changelog for tag, [G] Unreleased changelog)")
                     .borders(Borders::ALL)
                     .border_style(theme.border_style()),
             )
@@ -1128,7 +1540,44 @@ impl TagsTabComponent {
         frame.render_widget(list, area);
     }
 
+    /// Changelog of the selected tag, starting at the tag before it
+    fn open_tag_changelog(&mut self, state: &AppState) {
+        let Some(git_service) = &state.git_service else {
+            return;
+        };
+        let tags = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.list_tags())
+        })
+        .unwrap_or_default();
+
+        if let Some(tag) = tags.get(self.selected_index) {
+            let options = ChangelogOptions {
+                to: tag.name.clone(),
+                version: Some(changelog::version_from_tag(&tag.name)),
+                ..Default::default()
+            };
+            self.changelog_panel.open(state, options, false);
+        }
+    }
+
     pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        if self.changelog_panel.is_open() {
+            self.changelog_panel.handle_key_event(key, state)?;
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Char('g') => {
+                self.open_tag_changelog(state);
+                return Ok(());
+            }
+            KeyCode::Char('G') => {
+                self.changelog_panel.open(state, ChangelogOptions::default(), false);
+                return Ok(());
+            }
+            _ => {}
+        }
+
         // Get tag count for boundary checking using real Git service
         let tag_count = if let Some(git_service) = &state.git_service {
             tokio::task::block_in_place(|| {
@@ -1316,6 +1765,15 @@ pub struct StashTabComponent {
 }
 
 impl StashTabComponent {
+    /// 储藏列表的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::StashList, KeyCode::Enter, "Pop selected stash").hint("Enter: Pop"),
+        ViewKey::new(ShortcutContext::StashList, KeyCode::Char('a'), "Apply selected stash").hint("a: Apply"),
+        ViewKey::new(ShortcutContext::StashList, KeyCode::Char('n'), "New stash"),
+        ViewKey::new(ShortcutContext::StashList, KeyCode::Char('d'), "Drop selected stash"),
+        ViewKey::new(ShortcutContext::StashList, KeyCode::Char('s'), "Show stash contents"),
+    ];
+
     pub fn new() -> Self {
         Self {
             selected_index: 0,
@@ -1538,33 +1996,149 @@ impl<'a> NavigationHandler for StashTabNavigationHandler<'a> {
     }
 }
 
+/// 远程操作的输入类型
+#[derive(Debug, Clone, PartialEq)]
+enum RemoteInputKind {
+    Add,
+    Rename(String),
+    FetchUrl(String),
+    PushUrl(String),
+}
+
+/// 需要确认的破坏性远程操作
+#[derive(Debug, Clone, PartialEq)]
+enum RemoteConfirmAction {
+    Remove(String),
+    Prune(String),
+}
+
 /// Remotes tab component - manages Git remotes
 pub struct RemotesTabComponent {
     selected_index: usize,
     shortcut_manager: ShortcutManager,
+    remotes: Vec<WorkflowRemoteInfo>,
+    needs_refresh: bool,
+    input_modal: InputModal,
+    pending_input: Option<RemoteInputKind>,
+    confirmation_modal: ConfirmationModal,
+    pending_confirm: Option<RemoteConfirmAction>,
 }
 
 impl RemotesTabComponent {
+    /// 远程列表的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Enter, "Fetch selected remote").hint("Enter: Fetch"),
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Char('n'), "Add remote"),
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Char('d'), "Remove selected remote"),
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Char('R'), "Rename selected remote"),
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Char('U'), "Set fetch URL"),
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Char('P'), "Set push URL"),
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Char('p'), "Push current branch to selected remote"),
+        ViewKey::new(ShortcutContext::RemoteList, KeyCode::Char('x'), "Prune selected remote"),
+    ];
+
     pub fn new() -> Self {
         Self {
             selected_index: 0,
             shortcut_manager: ShortcutManager::new(),
+            remotes: Vec::new(),
+            needs_refresh: true,
+            input_modal: InputModal::new(),
+            pending_input: None,
+            confirmation_modal: ConfirmationModal::new(),
+            pending_confirm: None,
         }
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
-        let remotes = if let Some(git_service) = &state.git_service {
-            // Use actual remote data from git service
-            tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(async {
-                    git_service.list_remotes().await.unwrap_or_default()
-                })
-            })
-        } else {
-            vec![]
+    /// Whether an add/rename/URL prompt or a confirmation takes every key
+    pub fn captures_text_input(&self) -> bool {
+        self.input_modal.is_open() || self.confirmation_modal.is_open()
+    }
+
+    fn remote_manager(git_service: &GitService) -> DefaultRemoteManager {
+        DefaultRemoteManager::new(Arc::new(git_service.clone()))
+    }
+
+    /// 重新加载远程列表（模拟模式下回退到GitService的远程数据）
+    fn refresh_remotes(&mut self, state: &AppState) {
+        let Some(git_service) = &state.git_service else {
+            self.remotes.clear();
+            return;
         };
 
-        let items: Vec<ListItem> = remotes
+        let manager = Self::remote_manager(git_service);
+        self.remotes = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                match manager.list_remotes().await {
+                    Ok(remotes) => remotes,
+                    Err(e) => {
+                        debug!("Falling back to service remote list: {}", e);
+                        git_service
+                            .list_remotes()
+                            .await
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|remote| WorkflowRemoteInfo {
+                                push_url: (remote.push_url != remote.fetch_url).then_some(remote.push_url),
+                                name: remote.name,
+                                url: remote.fetch_url,
+                                fetch_refs: Vec::new(),
+                                push_refs: Vec::new(),
+                                tracking_branches: Vec::new(),
+                                last_fetch: None,
+                                is_connected: remote.is_connected,
+                            })
+                            .collect()
+                    }
+                }
+            })
+        });
+
+        if self.selected_index >= self.remotes.len() {
+            self.selected_index = self.remotes.len().saturating_sub(1);
+        }
+        self.needs_refresh = false;
+    }
+
+    fn selected_remote(&self) -> Option<&WorkflowRemoteInfo> {
+        self.remotes.get(self.selected_index)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        if self.needs_refresh {
+            self.refresh_remotes(state);
+        }
+
+        let main_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Action buttons bar
+                Constraint::Min(0),    // Main content area
+            ])
+            .split(area);
+
+        let actions_para = Paragraph::new(
+            " [Enter]Fetch | [n]Add | [R]Rename | [U]Fetch URL | [P]Push URL | [p]Push | [x]Prune | [d]Remove ",
+        )
+        .block(
+            Block::default()
+                .title("Remote Actions")
+                .borders(Borders::ALL)
+                .border_style(theme.border_style()),
+        )
+        .style(theme.text_style());
+        frame.render_widget(actions_para, main_layout[0]);
+
+        let content_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(30), // Remote list
+                Constraint::Min(0),     // Remote details
+            ])
+            .split(main_layout[1]);
+
+        let items: Vec<ListItem> = self
+            .remotes
             .iter()
             .enumerate()
             .map(|(index, remote)| {
@@ -1574,13 +2148,7 @@ impl RemotesTabComponent {
                     theme.text_style()
                 };
 
-                let status_indicator = if remote.is_connected { "✓" } else { "✗" };
-                let display_text = format!("  {} {} ({})",
-                    status_indicator,
-                    remote.name,
-                    remote.fetch_url
-                );
-
+                let display_text = format!("  {} ({})", remote.name, remote.tracking_branches.len());
                 ListItem::new(display_text).style(style)
             })
             .collect();
@@ -1588,29 +2156,132 @@ impl RemotesTabComponent {
         let list = List::new(items)
             .block(
                 Block::default()
-                    .title("Remotes")
+                    .title(format!("Remotes ({})", self.remotes.len()))
                     .borders(Borders::ALL)
-                    .border_style(theme.border_style()),
+                    .border_style(theme.accent_border_style()),
             )
             .style(theme.text_style());
+        frame.render_widget(list, content_layout[0]);
 
-        frame.render_widget(list, area);
+        self.render_remote_details(frame, content_layout[1], theme);
+
+        // Render modals on top if open
+        if self.input_modal.is_open() {
+            self.input_modal.render(frame, area, theme);
+        }
+        if self.confirmation_modal.is_open() {
+            self.confirmation_modal.render(frame, area, theme);
+        }
     }
 
-    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
-        // Get remote count for safe boundary checking
-        let remote_count = if let Some(git_service) = &state.git_service {
-            tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(async {
-                    git_service.list_remotes().await.unwrap_or_default().len()
+    fn render_remote_details(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let mut lines = Vec::new();
+
+        if let Some(remote) = self.selected_remote() {
+            let push_url = remote.push_url.as_deref().unwrap_or(&remote.url);
+            let last_fetch = remote
+                .last_fetch
+                .map(|time| {
+                    time.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
                 })
-            })
+                .unwrap_or_else(|| "never".to_string());
+
+            lines.push(Line::from(vec![
+                Span::styled("Name:       ", theme.muted_style()),
+                Span::styled(remote.name.clone(), theme.text_style().add_modifier(Modifier::BOLD)),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled("Fetch URL:  ", theme.muted_style()),
+                Span::styled(remote.url.clone(), theme.text_style()),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled("Push URL:   ", theme.muted_style()),
+                Span::styled(push_url.to_string(), theme.text_style()),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled("Last fetch: ", theme.muted_style()),
+                Span::styled(last_fetch, theme.info_style()),
+            ]));
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("Fetch refspecs:", theme.muted_style())));
+            for refspec in &remote.fetch_refs {
+                lines.push(Line::from(format!("  {}", refspec)));
+            }
+            if !remote.push_refs.is_empty() {
+                lines.push(Line::from(Span::styled("Push refspecs:", theme.muted_style())));
+                for refspec in &remote.push_refs {
+                    lines.push(Line::from(format!("  {}", refspec)));
+                }
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Tracking branches ({}):", remote.tracking_branches.len()),
+                theme.muted_style(),
+            )));
+            for branch in &remote.tracking_branches {
+                lines.push(Line::from(Span::styled(format!("  ◯ {}", branch), theme.success_style())));
+            }
         } else {
-            0
-        };
+            lines.push(Line::from(Span::styled(
+                "No remotes configured. Press n to add one.",
+                theme.muted_style(),
+            )));
+        }
+
+        let details = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Remote Details")
+                    .borders(Borders::ALL)
+                    .border_style(theme.border_style()),
+            )
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, area);
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        // Handle modal input first if a modal is open
+        if self.input_modal.is_open() {
+            match self.input_modal.handle_key_event(key)? {
+                ModalResult::Input(value) => {
+                    if let Some(kind) = self.pending_input.take() {
+                        self.apply_input(kind, value.trim(), state);
+                    }
+                }
+                ModalResult::Cancelled => {
+                    self.pending_input = None;
+                }
+                _ => {}
+            }
+            return Ok(()); // Modal is open, consume all other events
+        }
+
+        if self.confirmation_modal.is_open() {
+            match self.confirmation_modal.handle_key_event(key)? {
+                ModalResult::Confirmed => {
+                    if let Some(action) = self.pending_confirm.take() {
+                        self.apply_confirmed(action, state);
+                    }
+                }
+                ModalResult::Cancelled => {
+                    self.pending_confirm = None;
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.needs_refresh {
+            self.refresh_remotes(state);
+        }
 
         // 使用统一的快捷键管理器处理导航键
         if let Some(nav_key) = self.shortcut_manager.is_navigation_key(&key) {
+            let remote_count = self.remotes.len();
             let mut nav_handler = RemotesTabNavigationHandler {
                 component: self,
                 item_count: remote_count,
@@ -1619,113 +2290,116 @@ impl RemotesTabComponent {
             return Ok(());
         }
 
+        let selected_name = self.selected_remote().map(|remote| remote.name.clone());
+
+        // 重命名与URL设置使用专用字母键
+        match key.code {
+            KeyCode::Char('R') => {
+                if let Some(name) = selected_name {
+                    self.input_modal.open_with_placeholder("Rename Remote", &format!("New name for '{}':", name), &name);
+                    self.pending_input = Some(RemoteInputKind::Rename(name));
+                }
+                return Ok(());
+            }
+            KeyCode::Char('U') => {
+                if let Some(name) = selected_name {
+                    self.input_modal.open_with_placeholder("Set Fetch URL", &format!("Fetch URL for '{}':", name), "https://example.com/repo.git");
+                    self.pending_input = Some(RemoteInputKind::FetchUrl(name));
+                }
+                return Ok(());
+            }
+            KeyCode::Char('P') => {
+                if let Some(name) = selected_name {
+                    self.input_modal.open_with_placeholder(
+                        "Set Push URL",
+                        &format!("Push URL for '{}' (empty to use fetch URL):", name),
+                        "git@example.com:repo.git",
+                    );
+                    self.pending_input = Some(RemoteInputKind::PushUrl(name));
+                }
+                return Ok(());
+            }
+            KeyCode::Char('x') => {
+                if let Some(name) = selected_name {
+                    self.confirm_prune(name, state);
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+
         // 使用统一的快捷键管理器处理动作键
         if let Some(action_key) = self.shortcut_manager.is_action_key(&key) {
             match action_key {
                 ActionKey::Confirm => {
-                    // Connect to or inspect selected remote
-                    if let Some(git_service) = &state.git_service {
-                        if let Ok(remotes) = tokio::task::block_in_place(|| {
+                    if let (Some(name), Some(git_service)) = (selected_name, &state.git_service) {
+                        let manager = Self::remote_manager(git_service);
+                        let result = tokio::task::block_in_place(|| {
                             tokio::runtime::Handle::current().block_on(async {
-                                git_service.list_remotes().await
+                                manager.fetch(&name, None).await
                             })
-                        }) {
-                            if let Some(remote) = remotes.get(self.selected_index) {
-                                debug!("Inspect remote: {} ({})", remote.name, remote.fetch_url);
-                                debug!("Remote connected: {}", remote.is_connected);
-                                debug!("Push URL: {:?}", remote.push_url);
+                        });
+
+                        match result {
+                            Ok(()) => state.add_info(format!("Fetched from '{}'", name)),
+                            Err(e) => {
+                                let error_msg = format!("Failed to fetch from '{}': {}", name, e);
+                                error!("{}", error_msg);
+                                state.add_error(error_msg);
                             }
                         }
+                        self.needs_refresh = true;
                     }
                 }
                 ActionKey::New => {
-                    // Add new remote (placeholder - would require user input dialog)
-                    debug!("Add new remote operation requested");
-                    // NOTE: Real implementation would need a dialog to get remote name and URL
-                    // For now, just log that the operation was requested
-                    debug!("Remote addition would require user dialog for name and URL input");
+                    self.input_modal.open_with_placeholder(
+                        "Add Remote",
+                        "Enter: <name> <url> [fetch refspec...]",
+                        "upstream https://example.com/repo.git",
+                    );
+                    self.pending_input = Some(RemoteInputKind::Add);
                 }
                 ActionKey::Delete => {
-                    // Remove selected remote (placeholder - GitService doesn't have remove_remote method yet)
-                    if let Some(git_service) = &state.git_service {
-                        if let Ok(remotes) = tokio::task::block_in_place(|| {
-                            tokio::runtime::Handle::current().block_on(async {
-                                git_service.list_remotes().await
-                            })
-                        }) {
-                            if let Some(remote) = remotes.get(self.selected_index) {
-                                debug!("Remove remote requested: {}", remote.name);
-                                debug!("NOTE: Remote removal not yet implemented in GitService");
-                                // In real implementation, would call git_service.remove_remote(&remote.name)
-                            }
-                        }
+                    if let Some(name) = selected_name {
+                        self.confirmation_modal.open_with_buttons(
+                            "Remove Remote",
+                            &format!("Remove remote '{}' and all of its remote-tracking branches?", name),
+                            "Remove",
+                            "Cancel",
+                        );
+                        self.pending_confirm = Some(RemoteConfirmAction::Remove(name));
                     }
                 }
                 ActionKey::Push => {
-                    // Push to selected remote
-                    if let Some(git_service) = &state.git_service {
-                        if let Ok(remotes) = tokio::task::block_in_place(|| {
-                            tokio::runtime::Handle::current().block_on(async {
-                                git_service.list_remotes().await
-                            })
-                        }) {
-                            if let Some(remote) = remotes.get(self.selected_index) {
-                                debug!("Push to remote: {}", remote.name);
-                                // Use the existing push_branch method with current branch
-                                if let Ok(Some(current_branch)) = git_service.get_current_branch() {
-                                    tokio::task::block_in_place(|| {
-                                        tokio::runtime::Handle::current().block_on(async {
-                                            match git_service.push_branch(&current_branch.name).await {
-                                                Ok(()) => {
-                                                    debug!("Successfully pushed {} to remote {}", current_branch.name, remote.name);
-                                                }
-                                                Err(e) => {
-                                                    debug!("Failed to push {} to remote {}: {:?}", current_branch.name, remote.name, e);
-                                                }
-                                            }
-                                        })
-                                    });
-                                } else {
-                                    debug!("No current branch to push");
+                    // Push current branch to the selected remote
+                    if let (Some(name), Some(git_service)) = (selected_name, &state.git_service) {
+                        if let Ok(Some(current_branch)) = git_service.get_current_branch() {
+                            let result = tokio::task::block_in_place(|| {
+                                tokio::runtime::Handle::current().block_on(async {
+                                    git_service.push_branch_to(Some(&name), &current_branch.name).await
+                                })
+                            });
+                            match result {
+                                Ok(()) => state.add_info(format!("Pushed {} to '{}'", current_branch.name, name)),
+                                Err(e) => {
+                                    let error_msg = format!("Failed to push {} to '{}': {}", current_branch.name, name, e);
+                                    error!("{}", error_msg);
+                                    state.add_error(error_msg);
                                 }
                             }
+                        } else {
+                            debug!("No current branch to push");
                         }
                     }
                 }
                 ActionKey::Refresh => {
-                    // Refresh remote list
-                    if let Some(git_service) = &state.git_service {
-                        debug!("Refreshing remote list");
-                        tokio::task::block_in_place(|| {
-                            tokio::runtime::Handle::current().block_on(async {
-                                match git_service.get_status().await {
-                                    Ok(_status) => {
-                                        debug!("Successfully refreshed remote list");
-                                    }
-                                    Err(e) => {
-                                        debug!("Failed to refresh remote list: {:?}", e);
-                                    }
-                                }
-                            })
-                        });
-                    }
+                    debug!("Refreshing remote list");
+                    self.refresh_remotes(state);
                 }
                 ActionKey::SelectLine => {
-                    // Select current line with actual remote data
-                    if remote_count > 0 {
-                        if let Some(git_service) = &state.git_service {
-                            if let Ok(remotes) = tokio::task::block_in_place(|| {
-                                tokio::runtime::Handle::current().block_on(async {
-                                    git_service.list_remotes().await
-                                })
-                            }) {
-                                if let Some(remote) = remotes.get(self.selected_index) {
-                                    debug!("Select remote line: {} ({})", remote.name, remote.fetch_url);
-                                    let remote_info = format!("{} {} {}", remote.name, remote.fetch_url, remote.is_connected);
-                                    state.ui_state.selection_manager.select_line(self.selected_index, &[remote_info]);
-                                }
-                            }
-                        }
+                    if let Some(remote) = self.selected_remote() {
+                        let remote_info = format!("{} {}", remote.name, remote.url);
+                        state.ui_state.selection_manager.select_line(self.selected_index, &[remote_info]);
                     }
                 }
                 ActionKey::Cancel => {
@@ -1741,6 +2415,126 @@ impl RemotesTabComponent {
 
         Ok(())
     }
+
+    /// 列出过期的远程跟踪分支并请求确认清理
+    fn confirm_prune(&mut self, name: String, state: &mut AppState) {
+        let Some(git_service) = &state.git_service else { return };
+        let manager = Self::remote_manager(git_service);
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                manager.stale_tracking_branches(&name).await
+            })
+        });
+
+        match result {
+            Ok(stale) if stale.is_empty() => {
+                state.add_info(format!("No stale remote-tracking branches for '{}'", name));
+            }
+            Ok(stale) => {
+                let branches: Vec<&str> = stale.iter().map(|r| r.trim_start_matches("refs/remotes/")).collect();
+                self.confirmation_modal.open_with_buttons(
+                    "Prune Remote",
+                    &format!("Delete {} stale branches: {}?", stale.len(), branches.join(", ")),
+                    "Prune",
+                    "Cancel",
+                );
+                self.pending_confirm = Some(RemoteConfirmAction::Prune(name));
+            }
+            Err(e) => {
+                let error_msg = format!("Failed to check stale branches of '{}': {}", name, e);
+                error!("{}", error_msg);
+                state.add_error(error_msg);
+            }
+        }
+    }
+
+    fn apply_input(&mut self, kind: RemoteInputKind, value: &str, state: &mut AppState) {
+        let Some(git_service) = &state.git_service else { return };
+        let manager = Self::remote_manager(git_service);
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                match &kind {
+                    RemoteInputKind::Add => {
+                        let mut parts = value.split_whitespace();
+                        match (parts.next(), parts.next()) {
+                            (Some(name), Some(url)) => {
+                                let refspecs: Vec<String> = parts.map(|s| s.to_string()).collect();
+                                manager
+                                    .add_remote_with_fetch(name, url, &refspecs)
+                                    .await
+                                    .map(|_| format!("Added remote '{}'", name))
+                            }
+                            _ => Err(AppError::InvalidOperation(
+                                "Expected '<name> <url> [fetch refspec...]'".to_string(),
+                            )),
+                        }
+                    }
+                    RemoteInputKind::Rename(old_name) => {
+                        manager.rename_remote(old_name, value).await.map(|problems| {
+                            if problems.is_empty() {
+                                format!("Renamed remote '{}' to '{}'", old_name, value)
+                            } else {
+                                format!("Renamed remote '{}' to '{}' (refspecs not updated: {})", old_name, value, problems.join(", "))
+                            }
+                        })
+                    }
+                    RemoteInputKind::FetchUrl(name) => manager
+                        .set_remote_url(name, value)
+                        .await
+                        .map(|_| format!("Set fetch URL of '{}'", name)),
+                    RemoteInputKind::PushUrl(name) => {
+                        let push_url = (!value.is_empty()).then_some(value);
+                        manager
+                            .set_remote_push_url(name, push_url)
+                            .await
+                            .map(|_| format!("Set push URL of '{}'", name))
+                    }
+                }
+            })
+        });
+
+        match result {
+            Ok(message) => state.add_info(message),
+            Err(e) => {
+                let error_msg = format!("Remote operation failed: {}", e);
+                error!("{}", error_msg);
+                state.add_error(error_msg);
+            }
+        }
+        self.needs_refresh = true;
+    }
+
+    fn apply_confirmed(&mut self, action: RemoteConfirmAction, state: &mut AppState) {
+        let Some(git_service) = &state.git_service else { return };
+        let manager = Self::remote_manager(git_service);
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                match &action {
+                    RemoteConfirmAction::Remove(name) => manager
+                        .remove_remote(name)
+                        .await
+                        .map(|_| format!("Removed remote '{}'", name)),
+                    RemoteConfirmAction::Prune(name) => manager
+                        .prune_remote(name)
+                        .await
+                        .map(|pruned| format!("Pruned {} stale branches from '{}'", pruned.len(), name)),
+                }
+            })
+        });
+
+        match result {
+            Ok(message) => state.add_info(message),
+            Err(e) => {
+                let error_msg = format!("Remote operation failed: {}", e);
+                error!("{}", error_msg);
+                state.add_error(error_msg);
+            }
+        }
+        self.needs_refresh = true;
+    }
 }
 
 /// Helper structure for navigation handling with dynamic item count
@@ -1806,9 +2600,23 @@ pub struct GitFlowTabComponent {
     selected_index: usize,
     shortcut_manager: ShortcutManager,
     gitflow_branches: Vec<GitFlowBranchType>,
+    changelog_panel: ChangelogPanelComponent,
+    /// Release branch waiting for its changelog before being finished
+    pending_release: Option<(String, String)>,
+    version_modal: InputModal,
+    /// Flow type of the branch whose version is being entered
+    pending_start: Option<String>,
 }
 
 impl GitFlowTabComponent {
+    /// GitFlow 视图的按键
+    pub const KEYS: &'static [ViewKey] = &[
+        ViewKey::new(ShortcutContext::GitFlowView, KeyCode::Enter, "Show branches of selected type").hint("Enter: Execute"),
+        ViewKey::new(ShortcutContext::GitFlowView, KeyCode::Char('n'), "Start branch of selected type"),
+        ViewKey::new(ShortcutContext::GitFlowView, KeyCode::Char('N'), "Start pre-release"),
+        ViewKey::new(ShortcutContext::GitFlowView, KeyCode::Char('f'), "Finish branch of selected type").hint("f: Finish"),
+    ];
+
     pub fn new() -> Self {
         Self {
             selected_index: 0,
@@ -1819,10 +2627,24 @@ impl GitFlowTabComponent {
                 GitFlowBranchType::Hotfix,
                 GitFlowBranchType::Support,
             ],
+            changelog_panel: ChangelogPanelComponent::new(),
+            pending_release: None,
+            version_modal: InputModal::new(),
+            pending_start: None,
         }
     }
 
+    /// Whether an open editor should receive every key
+    pub fn captures_text_input(&self) -> bool {
+        self.changelog_panel.is_open() || self.version_modal.is_open()
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect, state: &AppState, theme: &Theme) {
+        if self.changelog_panel.is_open() {
+            self.changelog_panel.render(frame, area, theme);
+            return;
+        }
+
         // Create two-column layout: branch types + actions
         let main_layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -1837,6 +2659,10 @@ impl GitFlowTabComponent {
 
         // Render selected branch type details
         self.render_gitflow_details(frame, main_layout[1], state, theme);
+
+        if self.version_modal.is_open() {
+            self.version_modal.render(frame, area, theme);
+        }
     }
 
     fn render_gitflow_list(&mut self, frame: &mut Frame, area: Rect, _state: &AppState, theme: &Theme) {
@@ -1886,8 +2712,9 @@ impl GitFlowTabComponent {
                 }
                 GitFlowBranchType::Release => {
                     "Release Branch Workflow\n\n\
-                    • Create: n - Start new release\n\
-                    • Finish: f - Merge to main and develop\n\
+                    • Create: n - Start new release (suggested version)\n\
+                    • Prerelease: N - Start with an -rc.N version\n\
+                    • Finish: f - Write changelog, merge to main and develop\n\
                     • List: l - Show all release branches\n\
                     • Checkout: Enter - Switch to release\n\n\
                     Releases prepare for production deployment.\n\
@@ -1895,7 +2722,7 @@ impl GitFlowTabComponent {
                 }
                 GitFlowBranchType::Hotfix => {
                     "Hotfix Branch Workflow\n\n\
-                    • Create: n - Start new hotfix\n\
+                    • Create: n - Start new hotfix (suggested version)\n\
                     • Finish: f - Merge to main and develop\n\
                     • List: l - Show all hotfix branches\n\
                     • Checkout: Enter - Switch to hotfix\n\n\
@@ -1927,6 +2754,39 @@ impl GitFlowTabComponent {
     }
 
     pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        if self.changelog_panel.is_open() {
+            if let Some(event) = self.changelog_panel.handle_key_event(key, state)? {
+                self.continue_release_finish(event, state);
+            }
+            return Ok(());
+        }
+
+        if self.version_modal.is_open() {
+            if let ModalResult::Input(name) = self.version_modal.handle_key_event(key)? {
+                if let Some(flow_type) = self.pending_start.take() {
+                    self.start_branch(&flow_type, name.trim(), state);
+                }
+            }
+            return Ok(());
+        }
+
+        // Releases and hotfixes are named after their version
+        let versioned = matches!(
+            self.gitflow_branches.get(self.selected_index),
+            Some(GitFlowBranchType::Release | GitFlowBranchType::Hotfix)
+        );
+        match key.code {
+            KeyCode::Char('n') if versioned => {
+                self.open_version_prompt(None, state);
+                return Ok(());
+            }
+            KeyCode::Char('N') if versioned => {
+                self.open_version_prompt(Some(version::DEFAULT_PRERELEASE), state);
+                return Ok(());
+            }
+            _ => {}
+        }
+
         // 使用统一的快捷键管理器处理导航键
         if let Some(nav_key) = self.shortcut_manager.is_navigation_key(&key) {
             let item_count = self.gitflow_branches.len();
@@ -2041,26 +2901,28 @@ impl GitFlowTabComponent {
                 // Finish current branch of selected type
                 if let Some(selected_type) = self.gitflow_branches.get(self.selected_index) {
                     if let Some(git_service) = &state.git_service {
-                        let flow_type = selected_type.as_str().trim_end_matches('/');
+                        let flow_type = selected_type.as_str().trim_end_matches('/').to_string();
 
                         // First, get the current branch to see if it matches the selected type
-                        if let Ok(Some(current_branch)) = git_service.get_current_branch() {
-                            if current_branch.name.starts_with(selected_type.as_str()) {
-                                // Finish the current branch if it matches the selected type
-                                match tokio::task::block_in_place(|| {
-                                    tokio::runtime::Handle::current().block_on(async {
-                                        git_service.finish_gitflow_branch(flow_type, &current_branch.name).await
-                                    })
-                                }) {
-                                    Ok(()) => {
-                                        debug!("Successfully finished {} branch: {}", flow_type, current_branch.name);
-                                    }
-                                    Err(e) => {
-                                        debug!("Failed to finish {} branch: {:?}", flow_type, e);
-                                    }
+                        let current_branch = git_service
+                            .list_branches()
+                            .ok()
+                            .and_then(|branches| branches.into_iter().find(|b| b.is_current && b.is_local));
+                        if let Some(current_branch) = current_branch {
+                            if !current_branch.name.starts_with(selected_type.as_str()) {
+                                debug!("Current branch '{}' is not a {} branch", current_branch.name, flow_type);
+                            } else if *selected_type == GitFlowBranchType::Release {
+                                // Releases get a changelog section before they are merged
+                                let version = changelog::version_from_tag(&current_branch.name);
+                                let options = ChangelogOptions { version: Some(version), ..Default::default() };
+                                self.changelog_panel.open(state, options, true);
+                                self.pending_release = Some((flow_type, current_branch.name));
+                            } else if *selected_type == GitFlowBranchType::Hotfix {
+                                if self.commit_release(&current_branch.name, None, state) {
+                                    self.finish_branch(&flow_type, &current_branch.name, state);
                                 }
                             } else {
-                                debug!("Current branch '{}' is not a {} branch", current_branch.name, flow_type);
+                                self.finish_branch(&flow_type, &current_branch.name, state);
                             }
                         } else {
                             debug!("Could not determine current branch for finishing {} workflow", flow_type);
@@ -2073,6 +2935,141 @@ impl GitFlowTabComponent {
 
         Ok(())
     }
+
+    /// Finish the pending release once the changelog panel has closed
+    fn continue_release_finish(&mut self, event: ChangelogPanelEvent, state: &mut AppState) {
+        let Some((flow_type, branch_name)) = self.pending_release.take() else {
+            return;
+        };
+
+        let changelog_file = match event {
+            ChangelogPanelEvent::Saved(path) => path.file_name().map(|name| name.to_string_lossy().to_string()),
+            ChangelogPanelEvent::Skipped => None,
+            ChangelogPanelEvent::Cancelled => {
+                state.add_info(format!("Finishing {} cancelled", branch_name));
+                return;
+            }
+        };
+
+        if self.commit_release(&branch_name, changelog_file.as_deref(), state) {
+            self.finish_branch(&flow_type, &branch_name, state);
+        }
+    }
+
+    /// Create the release commit: manifest version bumps plus the changelog.
+    /// Returns `false` if committing failed and finishing should stop.
+    fn commit_release(&self, branch_name: &str, changelog_file: Option<&str>, state: &mut AppState) -> bool {
+        let Some(git_service) = state.git_service.clone() else {
+            return false;
+        };
+        let version_text = changelog::version_from_tag(branch_name);
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                let mut files: Vec<String> = changelog_file.map(str::to_string).into_iter().collect();
+                match version::Version::parse(&version_text) {
+                    Some(version) => files.extend(git_service.bump_manifest_versions(&version).await?),
+                    None => debug!("Branch {} does not name a semantic version; manifests untouched", branch_name),
+                }
+                if files.is_empty() {
+                    return Ok(files);
+                }
+                for file in &files {
+                    git_service.stage_file(file).await?;
+                }
+                git_service.commit(&format!("chore(release): {}", version_text)).await?;
+                Ok::<_, AppError>(files)
+            })
+        });
+
+        match result {
+            Ok(files) if files.is_empty() => true,
+            Ok(files) => {
+                state.add_info(format!("Release commit for {}: {}", version_text, files.join(", ")));
+                true
+            }
+            Err(e) => {
+                state.add_error(format!("Failed to create release commit: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Ask for the version of a new release/hotfix, pre-filled with a suggestion
+    fn open_version_prompt(&mut self, prerelease: Option<&str>, state: &mut AppState) {
+        let Some(selected_type) = self.gitflow_branches.get(self.selected_index) else {
+            return;
+        };
+        let Some(git_service) = &state.git_service else {
+            return;
+        };
+        let flow_type = selected_type.as_str().trim_end_matches('/').to_string();
+
+        // Inspect the branch the new one starts from
+        let base = match selected_type {
+            GitFlowBranchType::Hotfix => ["main", "master"].as_slice(),
+            _ => ["develop"].as_slice(),
+        };
+        let target = git_service.list_branches().ok().and_then(|branches| {
+            base.iter()
+                .find(|name| branches.iter().any(|b| b.is_local && b.name == **name))
+                .map(|name| name.to_string())
+        });
+        let options = version::VersionOptions {
+            prerelease: prerelease.map(str::to_string),
+            target,
+            ..Default::default()
+        };
+
+        match tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.suggest_version(&options))
+        }) {
+            Ok(suggestion) => {
+                self.version_modal.open_with_value(
+                    &format!("Start {}", flow_type),
+                    &suggestion.describe(),
+                    &suggestion.next.to_string(),
+                );
+                self.pending_start = Some(flow_type);
+            }
+            Err(e) => state.add_error(format!("Failed to suggest a version: {}", e)),
+        }
+    }
+
+    fn start_branch(&self, flow_type: &str, name: &str, state: &mut AppState) {
+        let Some(git_service) = &state.git_service else {
+            return;
+        };
+        if name.is_empty() {
+            return;
+        }
+
+        match tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(git_service.create_gitflow_branch(flow_type, name))
+        }) {
+            Ok(branch) => state.add_info(format!("Successfully created {} branch '{}'", flow_type, branch.name)),
+            Err(e) => state.add_error(format!("Failed to create {} branch: {}", flow_type, e)),
+        }
+    }
+
+    fn finish_branch(&self, flow_type: &str, branch_name: &str, state: &mut AppState) {
+        let Some(git_service) = &state.git_service else {
+            return;
+        };
+
+        match tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                git_service.finish_gitflow_branch(flow_type, branch_name).await
+            })
+        }) {
+            Ok(()) => {
+                debug!("Successfully finished {} branch: {}", flow_type, branch_name);
+            }
+            Err(e) => {
+                debug!("Failed to finish {} branch: {:?}", flow_type, e);
+            }
+        }
+    }
 }
 
 /// Helper structure for navigation handling with dynamic item count
@@ -0,0 +1,110 @@
+//! Keys the views handle themselves
+//!
+//! Every tab declares the plain keys its `handle_key_event` matches in a
+//! `KEYS` table next to that handler. The command palette lists and replays
+//! them, and the bottom help bar is built from their hints, so a key added
+//! to a view shows up in both places from one entry.
+
+use crossterm::event::KeyCode;
+
+use crate::app::state::TabType;
+
+use super::{
+    BranchesTabComponent, CommitHistoryComponent, DiffViewerComponent, GitFlowTabComponent, InsightsTabComponent,
+    RemotesTabComponent, ShortcutContext, StashTabComponent, StatusTabComponent, TagsTabComponent,
+};
+
+/// One key a view handles
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewKey {
+    /// Context the view is in when it handles the key
+    pub context: ShortcutContext,
+    pub code: KeyCode,
+    /// Palette label
+    pub label: &'static str,
+    /// Help bar fragment; keys without one are only listed in the palette
+    pub hint: Option<&'static str>,
+}
+
+impl ViewKey {
+    pub const fn new(context: ShortcutContext, code: KeyCode, label: &'static str) -> Self {
+        Self { context, code, label, hint: None }
+    }
+
+    pub const fn hint(self, hint: &'static str) -> Self {
+        Self { hint: Some(hint), ..self }
+    }
+}
+
+/// Key table of a tab's list view
+pub fn for_tab(tab: TabType) -> &'static [ViewKey] {
+    match tab {
+        TabType::Status => StatusTabComponent::KEYS,
+        TabType::Branches => BranchesTabComponent::KEYS,
+        TabType::Tags => TagsTabComponent::KEYS,
+        TabType::Stash => StashTabComponent::KEYS,
+        TabType::Remotes => RemotesTabComponent::KEYS,
+        TabType::History => CommitHistoryComponent::KEYS,
+        TabType::GitFlow => GitFlowTabComponent::KEYS,
+        TabType::Insights => InsightsTabComponent::KEYS,
+    }
+}
+
+/// Key table of the diff viewer, shared by every tab that shows a diff
+pub fn for_diff() -> &'static [ViewKey] {
+    DiffViewerComponent::KEYS
+}
+
+/// Bottom help bar text for `tab`
+pub fn help_bar(tab: TabType) -> String {
+    let navigation = match tab {
+        TabType::Status => "↑/↓: Select file | Space: Switch panel",
+        TabType::Branches => "↑/↓: Select branch | Space: Switch panel",
+        TabType::Tags => "↑/↓: Select tag | Space: Switch panel",
+        TabType::Stash => "↑/↓: Select stash | Space: Switch panel",
+        TabType::Remotes => "↑/↓: Select remote | Space: Switch panel",
+        TabType::History => "↑/↓: Select commit | Space: Switch panel",
+        TabType::GitFlow => "↑/↓: Navigate | Space: Switch panel",
+        TabType::Insights => "←/→: Section | ↑/↓: Select",
+    };
+    let mut parts = vec![navigation];
+    parts.extend(for_tab(tab).iter().filter_map(|key| key.hint));
+    if tab != TabType::Insights {
+        parts.push("?: Help");
+    }
+    parts.extend(["1-8: Switch tabs", "Ctrl+P: Commands"]);
+    parts.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_unique_per_context() {
+        for tab in TabType::all().iter().copied() {
+            let keys = for_tab(tab);
+            for (index, key) in keys.iter().enumerate() {
+                assert!(
+                    !keys[index + 1..].iter().any(|other| other.context == key.context && other.code == key.code),
+                    "{:?} lists {:?} twice",
+                    tab,
+                    key.code
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_help_bar_from_hints() {
+        assert_eq!(
+            help_bar(TabType::Status),
+            "↑/↓: Select file | Space: Switch panel | Enter: Diff | s: Staged | +/-: Stage/Unstage | c: Commit | \
+             C: Clean | S: Sparse | m: Menu | ?: Help | 1-8: Switch tabs | Ctrl+P: Commands"
+        );
+        assert_eq!(
+            help_bar(TabType::Insights),
+            "←/→: Section | ↑/↓: Select | g: Recompute | b: Bucket | e: Export JSON | 1-8: Switch tabs | Ctrl+P: Commands"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Workspace sidebar: every repository of a multi-repo workspace with
+//! branch, dirty and ahead/behind badges, instant switching and bulk
+//! fetch/pull.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::{collections::HashMap, path::PathBuf};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+use crate::{
+    app::state::AppState,
+    error::AppResult,
+    git::{Workspace, WorkspaceOperation, WorkspaceOperationResult, WorkspaceRepoStatus},
+    ui::theme::Theme,
+};
+
+/// Workspace sidebar component
+pub struct WorkspacePanelComponent {
+    workspace: Option<Workspace>,
+    /// Latest badges, in workspace order
+    statuses: Vec<WorkspaceRepoStatus>,
+    /// Repository the application is currently working on
+    active: Option<PathBuf>,
+    /// Only list repositories with uncommitted work
+    dirty_only: bool,
+    list_state: ListState,
+    status_task: Option<JoinHandle<Vec<WorkspaceRepoStatus>>>,
+    bulk_task: Option<(WorkspaceOperation, JoinHandle<Vec<WorkspaceOperationResult>>)>,
+    /// Outcome of the last bulk operation per repository
+    results: HashMap<PathBuf, WorkspaceOperationResult>,
+    /// Messages waiting to be turned into notifications
+    pending_messages: Vec<(bool, String)>,
+}
+
+impl WorkspacePanelComponent {
+    pub fn new() -> Self {
+        Self {
+            workspace: None,
+            statuses: Vec::new(),
+            active: None,
+            dirty_only: false,
+            list_state: ListState::default(),
+            status_task: None,
+            bulk_task: None,
+            results: HashMap::new(),
+            pending_messages: Vec::new(),
+        }
+    }
+
+    pub fn set_workspace(&mut self, workspace: Workspace) {
+        self.active = workspace
+            .initial_repository()
+            .map(PathBuf::from)
+            .or_else(|| workspace.repositories().first().cloned());
+        self.statuses.clear();
+        self.results.clear();
+        self.workspace = Some(workspace);
+    }
+
+    /// Whether workspace mode is active
+    pub fn is_enabled(&self) -> bool {
+        self.workspace.as_ref().is_some_and(|workspace| !workspace.is_empty())
+    }
+
+    /// Re-inspect all repositories in the background
+    fn start_status_refresh(&mut self) {
+        let Some(workspace) = self.workspace.clone() else {
+            return;
+        };
+        if let Some(task) = self.status_task.take() {
+            task.abort();
+        }
+        self.status_task = Some(tokio::spawn(async move { workspace.inspect_all().await }));
+    }
+
+    fn start_bulk(&mut self, operation: WorkspaceOperation) {
+        if self.bulk_task.is_some() {
+            self.pending_messages
+                .push((true, "A workspace operation is already running".to_string()));
+            return;
+        }
+        let Some(workspace) = self.workspace.clone() else {
+            return;
+        };
+
+        info!("Starting workspace {} for {} repositories", operation.name(), workspace.len());
+        self.results.clear();
+        self.bulk_task = Some((operation, tokio::spawn(async move { workspace.run_bulk(operation).await })));
+    }
+
+    /// Pick up finished background work; inspect on first display
+    fn sync(&mut self) {
+        if self.status_task.as_ref().is_some_and(|task| task.is_finished()) {
+            if let Some(task) = self.status_task.take() {
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task)) {
+                    Ok(statuses) => self.statuses = statuses,
+                    Err(e) => self.pending_messages.push((true, format!("Workspace refresh failed: {}", e))),
+                }
+            }
+        }
+
+        if self.bulk_task.as_ref().is_some_and(|(_, task)| task.is_finished()) {
+            if let Some((operation, task)) = self.bulk_task.take() {
+                match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(task)) {
+                    Ok(results) => {
+                        let failed = results.iter().filter(|r| r.outcome.is_err()).count();
+                        self.pending_messages.push((
+                            failed > 0,
+                            format!(
+                                "{} all: {} succeeded, {} failed",
+                                operation.name(),
+                                results.len() - failed,
+                                failed
+                            ),
+                        ));
+                        self.results = results.into_iter().map(|r| (r.path.clone(), r)).collect();
+                    }
+                    Err(e) => self.pending_messages.push((true, format!("{} all failed: {}", operation.name(), e))),
+                }
+                // Ahead/behind and working trees changed
+                self.start_status_refresh();
+            }
+        }
+
+        if self.statuses.is_empty() && self.status_task.is_none() && self.is_enabled() {
+            self.start_status_refresh();
+        }
+    }
+
+    /// Repositories currently listed, honoring the dirty-only filter
+    fn visible(&self) -> Vec<&WorkspaceRepoStatus> {
+        self.statuses
+            .iter()
+            .filter(|status| !self.dirty_only || status.is_dirty())
+            .collect()
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, _state: &AppState, theme: &Theme) {
+        self.sync();
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(4)])
+            .split(area);
+
+        let visible = self.visible();
+        let selected = visible
+            .iter()
+            .position(|status| Some(&status.path) == self.active.as_ref());
+
+        let items: Vec<ListItem> = visible
+            .iter()
+            .enumerate()
+            .map(|(index, status)| {
+                let badge_style = if status.error.is_some() || status.conflicted > 0 {
+                    theme.error_style()
+                } else if status.is_dirty() {
+                    theme.warning_style()
+                } else {
+                    theme.success_style()
+                };
+
+                let mut lines = vec![
+                    Line::from(vec![
+                        Span::styled(format!("{} ", index + 1), theme.muted_style()),
+                        Span::styled(status.name.clone(), theme.text_style()),
+                    ]),
+                    Line::from(Span::styled(format!("  {}", status.badge()), badge_style)),
+                ];
+
+                if let Some(result) = self.results.get(&status.path) {
+                    let line = match &result.outcome {
+                        Ok(message) => Span::styled(format!("  ✓ {}", message), theme.success_style()),
+                        Err(error) => Span::styled(format!("  ✗ {}", error), theme.error_style()),
+                    };
+                    lines.push(Line::from(line));
+                }
+                ListItem::new(lines)
+            })
+            .collect();
+
+        let title = format!(
+            "Workspace ({}/{}){}",
+            visible.len(),
+            self.statuses.len(),
+            if self.dirty_only { " [dirty]" } else { "" }
+        );
+        let is_empty = items.is_empty();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(theme.accent_border_style()),
+            )
+            .style(theme.text_style())
+            .highlight_style(theme.highlight_style())
+            .highlight_symbol("▶ ");
+
+        self.list_state.select(if is_empty { None } else { selected });
+        frame.render_stateful_widget(list, chunks[0], &mut self.list_state);
+
+        let activity = match (&self.bulk_task, &self.status_task) {
+            (Some((operation, _)), _) => format!("{} all running...", operation.name()),
+            (None, Some(_)) => "Refreshing...".to_string(),
+            (None, None) => "Alt+↑↓/1-9 switch".to_string(),
+        };
+        let footer = Paragraph::new(format!("{}\nAlt+F fetch · Alt+L pull · Alt+W dirty", activity))
+            .block(Block::default().borders(Borders::ALL).border_style(theme.border_style()))
+            .style(theme.muted_style())
+            .wrap(Wrap { trim: true });
+        frame.render_widget(footer, chunks[1]);
+    }
+
+    /// Handle workspace shortcuts; returns whether the key was consumed
+    ///
+    /// All workspace shortcuts use Alt so they never collide with text input
+    /// or tab-specific keys.
+    pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<bool> {
+        if !self.is_enabled() {
+            return Ok(false);
+        }
+
+        // Flush notifications produced by background work since the last key
+        for (is_error, message) in self.pending_messages.drain(..) {
+            if is_error {
+                state.add_error(message);
+            } else {
+                state.add_info(message);
+            }
+        }
+
+        if !key.modifiers.contains(KeyModifiers::ALT) {
+            return Ok(false);
+        }
+
+        match key.code {
+            KeyCode::Up | KeyCode::Down => {
+                let visible: Vec<PathBuf> = self.visible().iter().map(|s| s.path.clone()).collect();
+                if visible.is_empty() {
+                    return Ok(true);
+                }
+                let current = visible.iter().position(|path| Some(path) == self.active.as_ref());
+                let next = match (current, key.code) {
+                    (Some(index), KeyCode::Up) => (index + visible.len() - 1) % visible.len(),
+                    (Some(index), _) => (index + 1) % visible.len(),
+                    (None, _) => 0,
+                };
+                self.switch_to(visible[next].clone(), state);
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = (c as u8 - b'1') as usize;
+                if let Some(path) = self.visible().get(index).map(|s| s.path.clone()) {
+                    self.switch_to(path, state);
+                }
+            }
+            KeyCode::Char('f') => self.start_bulk(WorkspaceOperation::Fetch),
+            KeyCode::Char('l') => self.start_bulk(WorkspaceOperation::Pull),
+            KeyCode::Char('w') => {
+                self.dirty_only = !self.dirty_only;
+                if self.dirty_only {
+                    let dirty = self.visible().len();
+                    state.add_info(format!("{} repositories with uncommitted work", dirty));
+                }
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Make `path` the active repository
+    ///
+    /// The service is cached by the workspace, so only the status of the new
+    /// repository has to be read.
+    fn switch_to(&mut self, path: PathBuf, state: &mut AppState) {
+        if self.active.as_ref() == Some(&path) {
+            return;
+        }
+        let Some(workspace) = self.workspace.as_mut() else {
+            return;
+        };
+
+        let service = match workspace.service(&path) {
+            Ok(service) => service,
+            Err(e) => {
+                state.add_error(format!("Cannot open {}: {}", path.display(), e));
+                return;
+            }
+        };
+
+        let status = tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(service.get_status()))
+            .unwrap_or_else(|e| {
+                debug!("Failed to read status of {}: {}", path.display(), e);
+                Vec::new()
+            });
+        let branch = service.get_current_branch().ok().flatten();
+
+        debug!("Switched workspace repository to {}", path.display());
+        state.switch_git_service(service, status, branch);
+        self.active = Some(path);
+    }
+}
+
+impl Default for WorkspacePanelComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,12 +1,21 @@
 pub mod utils;
 pub mod inline_editor;
+pub mod algorithm;
+pub mod syntax;
+pub mod export;
+pub mod stream;
 
 use crate::{
     error::{AppError, AppResult},
+    git::{content, FuncnamePattern, PatchSource},
     ui::theme::Theme,
 };
 pub use utils::DiffUtils;
-pub use inline_editor::{InlineEditor, InlineEditorConfig, EditOperation, CursorPosition, Selection, EditorMode};
+pub use algorithm::{DiffOp, WhitespaceMode};
+pub use syntax::{HighlightedText, LineState, SyntaxPalette, TokenKind};
+pub use export::{DiffExport, ExportFile, ExportFormat, ReviewAnnotation};
+pub use stream::{StreamSettings, StreamedDiff, StreamedHunk};
+pub use inline_editor::{InlineEditor, InlineEditorConfig, EditOperation, CursorPosition, Selection, EditorMode, LineDiagnostic};
 use chrono::{DateTime, Utc};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -17,8 +26,8 @@ use ratatui::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
-    path::PathBuf,
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -38,6 +47,7 @@ pub enum DiffMode {
 
 /// 差异算法类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DiffAlgorithm {
     /// Myers算法
     Myers,
@@ -49,6 +59,37 @@ pub enum DiffAlgorithm {
     Minimal,
 }
 
+impl DiffAlgorithm {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DiffAlgorithm::Myers => "myers",
+            DiffAlgorithm::Patience => "patience",
+            DiffAlgorithm::Histogram => "histogram",
+            DiffAlgorithm::Minimal => "minimal",
+        }
+    }
+}
+
+/// 行内差异粒度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntralineGranularity {
+    /// 按单词（标识符、空白和单个符号）
+    #[default]
+    Word,
+    /// 按字符
+    Char,
+}
+
+impl IntralineGranularity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntralineGranularity::Word => "word",
+            IntralineGranularity::Char => "char",
+        }
+    }
+}
+
 /// 差异行类型
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DiffLineType {
@@ -146,7 +187,7 @@ pub struct SyntaxHighlighterConfig {
     pub enabled: bool,
     /// 默认语言
     pub default_language: String,
-    /// 主题名称
+    /// 配色名称：`theme` 跟随 UI 主题，或内置配色如 `base16-ocean.dark`
     pub theme_name: String,
     /// 最大文件大小（字节）
     pub max_file_size: usize,
@@ -157,136 +198,97 @@ impl Default for SyntaxHighlighterConfig {
         Self {
             enabled: true,
             default_language: "text".to_string(),
-            theme_name: "base16-ocean.dark".to_string(),
+            theme_name: syntax::THEME_PALETTE.to_string(),
             max_file_size: 1024 * 1024, // 1MB
         }
     }
 }
 
+/// 高亮结果缓存的最大条目数
+const HIGHLIGHT_CACHE_CAPACITY: usize = 32;
+
 /// 语法高亮器
+///
+/// Whole files are tokenized by the grammars in [`syntax`] so multi-line
+/// comments and strings keep their state; results are cached by file
+/// revision (language + content hash).
 pub struct SyntaxHighlighter {
     config: SyntaxHighlighterConfig,
-    language_cache: Arc<RwLock<HashMap<String, String>>>,
+    cache: HashMap<String, Arc<HighlightedText>>,
+    cache_order: VecDeque<String>,
 }
 
 impl SyntaxHighlighter {
     pub fn new(config: SyntaxHighlighterConfig) -> Self {
         Self {
             config,
-            language_cache: Arc::new(RwLock::new(HashMap::new())),
+            cache: HashMap::new(),
+            cache_order: VecDeque::new(),
         }
     }
 
-    /// 检测文件语言
-    pub async fn detect_language(&self, file_path: &PathBuf) -> String {
-        if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
-            let extension = extension.to_lowercase();
-            let language = match extension.as_str() {
-                "rs" => "rust",
-                "py" => "python",
-                "js" | "mjs" => "javascript",
-                "ts" => "typescript",
-                "html" | "htm" => "html",
-                "css" => "css",
-                "json" => "json",
-                "yaml" | "yml" => "yaml",
-                "toml" => "toml",
-                "md" => "markdown",
-                "sh" | "bash" => "bash",
-                "c" => "c",
-                "cpp" | "cc" | "cxx" => "cpp",
-                "go" => "go",
-                "java" => "java",
-                _ => &self.config.default_language,
-            };
+    pub fn config(&self) -> &SyntaxHighlighterConfig {
+        &self.config
+    }
 
-            language.to_string()
-        } else {
-            self.config.default_language.clone()
-        }
+    /// 替换配置并清空缓存
+    pub fn set_config(&mut self, config: SyntaxHighlighterConfig) {
+        self.config = config;
+        self.cache.clear();
+        self.cache_order.clear();
     }
 
-    /// 对代码行应用语法高亮
-    pub async fn highlight_line(&self, line: &str, language: &str) -> Vec<Span> {
-        if !self.config.enabled || line.len() > 1000 {
-            // 对于非常长的行，跳过语法高亮以提高性能
-            return vec![Span::raw(line.to_string())];
+    /// 检测文件语言
+    pub fn detect_language(&self, file_path: &Path) -> String {
+        let language = DiffUtils::detect_language_from_path(&file_path.to_path_buf());
+        if syntax::grammar_for(&language).is_some() {
+            return language;
+        }
+        // Other syntect grammars are found by extension (`hs`, `scala`, ...)
+        match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if syntax::grammar_for(ext).is_some() => ext.to_string(),
+            _ => self.config.default_language.clone(),
         }
-
-        // 简化的语法高亮实现
-        // 在实际项目中，这里会使用专门的语法高亮库如syntect
-        self.simple_highlight(line, language).await
     }
 
-    async fn simple_highlight(&self, line: &str, language: &str) -> Vec<Span> {
-        let mut spans = Vec::new();
-        let mut chars = line.chars().peekable();
-        let mut current_token = String::new();
-        let mut current_style = Style::default();
-
-        while let Some(ch) = chars.next() {
-            match language {
-                "rust" => {
-                    if ch.is_alphabetic() || ch == '_' {
-                        current_token.push(ch);
-                        while let Some(&next_ch) = chars.peek() {
-                            if next_ch.is_alphanumeric() || next_ch == '_' {
-                                current_token.push(chars.next().unwrap());
-                            } else {
-                                break;
-                            }
-                        }
+    /// 对单行应用语法高亮（不带跨行状态）
+    pub fn highlight_line(&self, line: &str, language: &str, palette: &SyntaxPalette) -> Vec<Span<'static>> {
+        match syntax::grammar_for(language).filter(|_| self.config.enabled) {
+            Some(grammar) => {
+                let tokens = grammar.tokenize_line(line, &mut grammar.start());
+                syntax::styled_spans(line, &tokens, palette, Style::default())
+            }
+            None => vec![Span::raw(line.to_string())],
+        }
+    }
 
-                        // Rust关键字高亮
-                        current_style = match current_token.as_str() {
-                            "fn" | "let" | "mut" | "const" | "static" | "struct" | "enum" | "impl" |
-                            "trait" | "pub" | "use" | "mod" | "crate" | "super" | "self" => {
-                                Style::default().fg(Color::Magenta)
-                            }
-                            "if" | "else" | "match" | "for" | "while" | "loop" | "break" | "continue" |
-                            "return" => Style::default().fg(Color::Blue),
-                            _ => Style::default(),
-                        };
+    /// 对整个文件分词；同一版本的内容只计算一次
+    pub fn highlight_file(&mut self, file_path: &Path, content: &str) -> Option<Arc<HighlightedText>> {
+        if !self.config.enabled || content.len() > self.config.max_file_size {
+            return None;
+        }
+        let language = self.detect_language(file_path);
+        let grammar = syntax::grammar_for(&language)?;
 
-                        spans.push(Span::styled(current_token.clone(), current_style));
-                        current_token.clear();
-                    } else if ch == '"' {
-                        // 字符串字面量
-                        current_token.push(ch);
-                        while let Some(next_ch) = chars.next() {
-                            current_token.push(next_ch);
-                            if next_ch == '"' && !current_token.ends_with("\\\"") {
-                                break;
-                            }
-                        }
-                        spans.push(Span::styled(current_token.clone(), Style::default().fg(Color::Green)));
-                        current_token.clear();
-                    } else if ch == '/' && chars.peek() == Some(&'/') {
-                        // 单行注释
-                        current_token.push(ch);
-                        current_token.push(chars.next().unwrap());
-                        for next_ch in chars.by_ref() {
-                            current_token.push(next_ch);
-                        }
-                        spans.push(Span::styled(current_token.clone(), Style::default().fg(Color::Gray)));
-                        current_token.clear();
-                        break;
-                    } else {
-                        spans.push(Span::raw(ch.to_string()));
-                    }
-                }
-                _ => {
-                    // 默认无高亮
-                    spans.push(Span::raw(ch.to_string()));
-                }
-            }
+        let key = format!("{}:{:x}", language, md5::compute(content));
+        if let Some(cached) = self.cache.get(&key) {
+            return Some(cached.clone());
         }
 
-        if !current_token.is_empty() {
-            spans.push(Span::styled(current_token, current_style));
+        let highlighted = Arc::new(syntax::highlight_text(grammar, content));
+        if self.cache_order.len() >= HIGHLIGHT_CACHE_CAPACITY {
+            if let Some(oldest) = self.cache_order.pop_front() {
+                self.cache.remove(&oldest);
+            }
         }
+        self.cache_order.push_back(key.clone());
+        self.cache.insert(key, highlighted.clone());
+        Some(highlighted)
+    }
 
-        spans
+    /// 当前配置的配色：内置配色名，否则跟随 UI 主题
+    pub fn palette(&self, theme: &Theme) -> SyntaxPalette {
+        SyntaxPalette::resolve(&self.config.theme_name, theme)
     }
 }
 
@@ -295,14 +297,26 @@ impl SyntaxHighlighter {
 pub struct DiffProcessorConfig {
     /// 差异算法
     pub algorithm: DiffAlgorithm,
+    /// 按文件类型选择算法：键为文件名（`Cargo.lock`）或扩展名（`rs`）
+    pub file_algorithms: HashMap<String, DiffAlgorithm>,
     /// 上下文行数
     pub context_lines: usize,
     /// 启用单词级差异
     pub word_level_diff: bool,
-    /// 忽略空白字符
-    pub ignore_whitespace: bool,
+    /// 行内差异粒度
+    pub intraline_granularity: IntralineGranularity,
+    /// 超过此长度（字节）的行不计算行内差异
+    pub max_intraline_length: usize,
+    /// 空白字符比较方式
+    pub whitespace: WhitespaceMode,
+    /// 忽略只增删空行的变更
+    pub ignore_blank_lines: bool,
     /// 最大文件大小
     pub max_file_size: usize,
+    /// 超过此大小（字节）的文件改用流式差异
+    pub stream_threshold: usize,
+    /// 流式差异中已解析差异块的缓存预算（字节）
+    pub stream_cache_bytes: usize,
     /// 启用缓存
     pub enable_cache: bool,
 }
@@ -311,18 +325,71 @@ impl Default for DiffProcessorConfig {
     fn default() -> Self {
         Self {
             algorithm: DiffAlgorithm::Myers,
+            file_algorithms: HashMap::new(),
             context_lines: 3,
             word_level_diff: true,
-            ignore_whitespace: false,
+            intraline_granularity: IntralineGranularity::Word,
+            max_intraline_length: 1000,
+            whitespace: WhitespaceMode::Exact,
+            ignore_blank_lines: false,
             max_file_size: 10 * 1024 * 1024, // 10MB
+            stream_threshold: 1024 * 1024,    // 1MB
+            stream_cache_bytes: 32 * 1024 * 1024,
             enable_cache: true,
         }
     }
 }
 
+impl DiffProcessorConfig {
+    /// 文件使用的算法：先按文件名，再按扩展名，最后用默认算法
+    pub fn algorithm_for(&self, file_path: &Path) -> DiffAlgorithm {
+        let by_key = |key: Option<&std::ffi::OsStr>| {
+            key.and_then(|key| key.to_str())
+                .and_then(|key| self.file_algorithms.get(key).or_else(|| self.file_algorithms.get(&key.to_lowercase())))
+                .copied()
+        };
+        by_key(file_path.file_name())
+            .or_else(|| by_key(file_path.extension()))
+            .unwrap_or(self.algorithm)
+    }
+}
+
+impl From<&crate::config::DiffConfig> for DiffProcessorConfig {
+    fn from(config: &crate::config::DiffConfig) -> Self {
+        Self {
+            algorithm: config.algorithm,
+            file_algorithms: config
+                .file_algorithms
+                .iter()
+                .map(|(key, algorithm)| (key.trim_start_matches("*.").trim_start_matches('.').to_string(), *algorithm))
+                .collect(),
+            context_lines: config.context_lines,
+            whitespace: config.whitespace,
+            ignore_blank_lines: config.ignore_blank_lines,
+            word_level_diff: config.intraline_highlight,
+            intraline_granularity: config.intraline_granularity,
+            stream_threshold: config.stream_threshold_kb.saturating_mul(1024),
+            stream_cache_bytes: config.stream_cache_mb.saturating_mul(1024 * 1024),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&crate::config::DiffConfig> for SyntaxHighlighterConfig {
+    fn from(config: &crate::config::DiffConfig) -> Self {
+        Self {
+            enabled: config.syntax_highlighting,
+            theme_name: config.syntax_theme.clone(),
+            ..Self::default()
+        }
+    }
+}
+
 /// 差异处理器
 pub struct DiffProcessor {
     config: DiffProcessorConfig,
+    /// 差异驱动的 xfuncname，用于块头后的上下文
+    funcname: Option<FuncnamePattern>,
     cache: Arc<RwLock<HashMap<String, FileDiff>>>,
     stats: Arc<RwLock<DiffStats>>,
 }
@@ -331,17 +398,44 @@ impl DiffProcessor {
     pub fn new(config: DiffProcessorConfig) -> Self {
         Self {
             config,
+            funcname: None,
             cache: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(DiffStats::default())),
         }
     }
 
+    /// 设置块头上下文的匹配规则（None 表示不显示）
+    pub fn set_funcname(&mut self, pattern: Option<FuncnamePattern>) {
+        self.funcname = pattern;
+    }
+
     /// 计算两个文件之间的差异
     pub async fn compute_diff(&self, old_content: &str, new_content: &str, file_path: &PathBuf) -> AppResult<FileDiff> {
+        self.compute_diff_with_context(old_content, new_content, file_path, self.config.context_lines).await
+    }
+
+    /// 按指定上下文行数计算差异（展开/折叠未修改的内容时使用）
+    pub async fn compute_diff_with_context(
+        &self,
+        old_content: &str,
+        new_content: &str,
+        file_path: &PathBuf,
+        context_lines: usize,
+    ) -> AppResult<FileDiff> {
         let start_time = Instant::now();
 
-        // 检查缓存
-        let cache_key = format!("{:x}", md5::compute(format!("{}{}{:?}", old_content, new_content, file_path)));
+        // 检查缓存（键包含影响结果的设置）
+        let cache_key = format!(
+            "{:x}",
+            md5::compute(format!(
+                "{}{}{:?}{}{:?}",
+                old_content,
+                new_content,
+                file_path,
+                context_lines,
+                self.funcname.as_ref().map(FuncnamePattern::source)
+            ))
+        );
         if self.config.enable_cache {
             let cache = self.cache.read().await;
             if let Some(cached_diff) = cache.get(&cache_key) {
@@ -379,7 +473,7 @@ impl DiffProcessor {
         }
 
         // 计算差异
-        let hunks = self.compute_hunks(old_content, new_content).await?;
+        let hunks = self.compute_hunks(old_content, new_content, file_path, context_lines);
 
         // 计算统计信息
         let mut stats = DiffStats::default();
@@ -427,141 +521,108 @@ impl DiffProcessor {
         Ok(file_diff)
     }
 
-    /// 计算差异块
-    async fn compute_hunks(&self, old_content: &str, new_content: &str) -> AppResult<Vec<DiffHunk>> {
+    /// 计算差异块：按配置的算法和空白模式得到编辑脚本，再按上下文行数分组
+    fn compute_hunks(&self, old_content: &str, new_content: &str, file_path: &Path, context: usize) -> Vec<DiffHunk> {
         let old_lines: Vec<&str> = old_content.lines().collect();
         let new_lines: Vec<&str> = new_content.lines().collect();
 
-        let mut hunks = Vec::new();
-        let diff_result = self.run_diff_algorithm(&old_lines, &new_lines).await?;
-
-        let mut current_hunk: Option<DiffHunk> = None;
-        let mut old_line_num = 1;
-        let mut new_line_num = 1;
-
-        for change in diff_result {
-            match change {
-                DiffChange::Equal(content) => {
-                    if let Some(ref mut hunk) = current_hunk {
-                        hunk.lines.push(DiffLine {
-                            line_type: DiffLineType::Context,
-                            old_line_number: Some(old_line_num),
-                            new_line_number: Some(new_line_num),
-                            content,
-                            highlights: vec![],
-                        });
-                    }
-                    old_line_num += 1;
-                    new_line_num += 1;
-                }
-                DiffChange::Delete(content) => {
-                    if current_hunk.is_none() {
-                        current_hunk = Some(DiffHunk {
-                            header: format!("@@ -{},{} +{},{} @@", old_line_num, 0, new_line_num, 0),
-                            old_start: old_line_num,
-                            old_lines: 0,
-                            new_start: new_line_num,
-                            new_lines: 0,
-                            lines: vec![],
-                        });
-                    }
-
-                    if let Some(ref mut hunk) = current_hunk {
-                        hunk.lines.push(DiffLine {
-                            line_type: DiffLineType::Deleted,
-                            old_line_number: Some(old_line_num),
-                            new_line_number: None,
-                            content,
-                            highlights: vec![],
-                        });
-                        hunk.old_lines += 1;
-                    }
-                    old_line_num += 1;
-                }
-                DiffChange::Insert(content) => {
-                    if current_hunk.is_none() {
-                        current_hunk = Some(DiffHunk {
-                            header: format!("@@ -{},{} +{},{} @@", old_line_num, 0, new_line_num, 0),
-                            old_start: old_line_num,
-                            old_lines: 0,
-                            new_start: new_line_num,
-                            new_lines: 0,
-                            lines: vec![],
-                        });
-                    }
-
-                    if let Some(ref mut hunk) = current_hunk {
-                        hunk.lines.push(DiffLine {
-                            line_type: DiffLineType::Added,
-                            old_line_number: None,
-                            new_line_number: Some(new_line_num),
-                            content,
-                            highlights: vec![],
-                        });
-                        hunk.new_lines += 1;
-                    }
-                    new_line_num += 1;
-                }
-            }
-        }
-
-        if let Some(hunk) = current_hunk {
-            hunks.push(hunk);
-        }
-
-        Ok(hunks)
-    }
+        let algorithm = self.config.algorithm_for(file_path);
+        debug!("Diffing {:?} with {:?}, whitespace {:?}", file_path, algorithm, self.config.whitespace);
+        let ops = algorithm::diff_lines(&old_lines, &new_lines, algorithm, self.config.whitespace);
+        let ignored = if self.config.ignore_blank_lines {
+            algorithm::blank_only_changes(&ops, &old_lines, &new_lines)
+        } else {
+            vec![false; ops.len()]
+        };
 
-    /// 运行差异算法
-    async fn run_diff_algorithm(&self, old_lines: &[&str], new_lines: &[&str]) -> AppResult<Vec<DiffChange>> {
-        match self.config.algorithm {
-            DiffAlgorithm::Myers => self.myers_diff(old_lines, new_lines).await,
-            _ => {
-                // 其他算法的简化实现
-                warn!("Algorithm {:?} not fully implemented, falling back to Myers", self.config.algorithm);
-                self.myers_diff(old_lines, new_lines).await
+        // 真正的变更（被忽略的空行变更显示为上下文）
+        let changes: Vec<usize> = (0..ops.len()).filter(|&i| ops[i].is_change() && !ignored[i]).collect();
+
+        // 相邻变更之间的上下文不超过 2 * context 时合并为一个块
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for &index in &changes {
+            let start = index.saturating_sub(context);
+            let end = index.saturating_add(context).saturating_add(1).min(ops.len());
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => ranges.push((start, end)),
             }
         }
-    }
 
-    /// Myers差异算法实现
-    async fn myers_diff(&self, old_lines: &[&str], new_lines: &[&str]) -> AppResult<Vec<DiffChange>> {
-        let mut result = Vec::new();
-        let mut old_idx = 0;
-        let mut new_idx = 0;
-
-        // 简化的Myers算法实现
-        while old_idx < old_lines.len() || new_idx < new_lines.len() {
-            if old_idx < old_lines.len() && new_idx < new_lines.len() {
-                if self.lines_equal(old_lines[old_idx], new_lines[new_idx]) {
-                    result.push(DiffChange::Equal(old_lines[old_idx].to_string()));
-                    old_idx += 1;
-                    new_idx += 1;
-                } else {
-                    // 简单的删除+插入策略
-                    result.push(DiffChange::Delete(old_lines[old_idx].to_string()));
-                    result.push(DiffChange::Insert(new_lines[new_idx].to_string()));
-                    old_idx += 1;
-                    new_idx += 1;
+        // 每个操作之前已消耗的旧/新行数
+        let mut positions = Vec::with_capacity(ops.len() + 1);
+        let (mut old_pos, mut new_pos) = (0, 0);
+        for op in &ops {
+            positions.push((old_pos, new_pos));
+            match op {
+                DiffOp::Equal { .. } => {
+                    old_pos += 1;
+                    new_pos += 1;
                 }
-            } else if old_idx < old_lines.len() {
-                result.push(DiffChange::Delete(old_lines[old_idx].to_string()));
-                old_idx += 1;
-            } else {
-                result.push(DiffChange::Insert(new_lines[new_idx].to_string()));
-                new_idx += 1;
+                DiffOp::Delete { .. } => old_pos += 1,
+                DiffOp::Insert { .. } => new_pos += 1,
             }
         }
+        positions.push((old_pos, new_pos));
+
+        ranges
+            .into_iter()
+            .map(|(start, end)| {
+                let lines: Vec<DiffLine> = (start..end)
+                    .map(|i| {
+                        let (line_type, old_line_number, new_line_number, content) = match ops[i] {
+                            DiffOp::Equal { old, new } => (DiffLineType::Context, Some(old + 1), Some(new + 1), new_lines[new]),
+                            DiffOp::Delete { old } if ignored[i] => (DiffLineType::Context, Some(old + 1), None, old_lines[old]),
+                            DiffOp::Delete { old } => (DiffLineType::Deleted, Some(old + 1), None, old_lines[old]),
+                            DiffOp::Insert { new } if ignored[i] => (DiffLineType::Context, None, Some(new + 1), new_lines[new]),
+                            DiffOp::Insert { new } => (DiffLineType::Added, None, Some(new + 1), new_lines[new]),
+                        };
+                        DiffLine {
+                            line_type,
+                            old_line_number,
+                            new_line_number,
+                            content: content.to_string(),
+                            highlights: vec![],
+                        }
+                    })
+                    .collect();
+
+                let (old_before, new_before) = positions[start];
+                let (old_after, new_after) = positions[end];
+                let (old_count, new_count) = (old_after - old_before, new_after - new_before);
+                // 与 git 相同：行数为 0 时起始行指向前一行
+                let old_start = if old_count > 0 { old_before + 1 } else { old_before };
+                let new_start = if new_count > 0 { new_before + 1 } else { new_before };
+
+                // 与 git 相同：块头后附上块之前最近的函数行
+                let mut header = format!("@@ -{},{} +{},{} @@", old_start, old_count, new_start, new_count);
+                if let Some(context) = self.funcname.as_ref().and_then(|pattern| pattern.find(&old_lines, old_before)) {
+                    header.push(' ');
+                    header.push_str(&context);
+                }
 
-        Ok(result)
+                let mut hunk = DiffHunk {
+                    header,
+                    old_start,
+                    old_lines: old_count,
+                    new_start,
+                    new_lines: new_count,
+                    lines,
+                };
+                self.apply_intraline(&mut hunk);
+                hunk
+            })
+            .collect()
     }
 
-    /// 比较两行是否相等
-    fn lines_equal(&self, old_line: &str, new_line: &str) -> bool {
-        if self.config.ignore_whitespace {
-            old_line.trim() == new_line.trim()
+    /// 按配置计算（或清除）块内的行内高亮
+    fn apply_intraline(&self, hunk: &mut DiffHunk) {
+        if self.config.word_level_diff {
+            DiffUtils::apply_intraline_highlights(hunk, self.config.intraline_granularity, self.config.max_intraline_length);
         } else {
-            old_line == new_line
+            for line in &mut hunk.lines {
+                line.highlights.clear();
+            }
         }
     }
 
@@ -583,17 +644,6 @@ impl DiffProcessor {
     }
 }
 
-/// 差异变更类型
-#[derive(Debug, Clone)]
-enum DiffChange {
-    /// 相等的内容
-    Equal(String),
-    /// 删除的内容
-    Delete(String),
-    /// 插入的内容
-    Insert(String),
-}
-
 /// 差异查看器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffViewerConfig {
@@ -630,6 +680,26 @@ pub struct DiffViewer {
     processor: DiffProcessor,
     highlighter: SyntaxHighlighter,
     current_diff: Option<FileDiff>,
+    /// 原文件分词结果（按行号索引）
+    old_highlight: Option<Arc<HighlightedText>>,
+    /// 新文件分词结果
+    new_highlight: Option<Arc<HighlightedText>>,
+    /// 当前差异的两侧内容与路径，展开上下文时重新计算
+    old_content: Arc<str>,
+    new_content: Arc<str>,
+    file_path: PathBuf,
+    /// 临时的上下文行数（None 表示使用配置值）
+    context_override: Option<usize>,
+    /// 最近一次渲染的可见行数
+    viewport_height: usize,
+    /// 行差异无法表达的信息（二进制、图片、编码、换行符），显示在差异上方
+    notices: Vec<String>,
+    /// 超大文件的流式差异（此时 current_diff 为 None）
+    streamed: Option<StreamedDiff>,
+    /// 流式差异目前为止的统计
+    stream_stats: DiffStats,
+    /// 流式差异中尚未索引到的滚动目标，索引到后再跳转
+    pending_scroll: Option<PendingScroll>,
     scroll_offset: usize,
     selected_hunk: usize,
     virtual_scroll_state: VirtualScrollState,
@@ -648,6 +718,23 @@ struct VirtualScrollState {
     line_height: usize,
 }
 
+/// 等待流式索引的滚动目标
+#[derive(Debug, Clone, Copy)]
+enum PendingScroll {
+    /// 恢复之前记录的位置（滚动偏移、差异块）
+    Position(usize, usize),
+    /// 改变上下文前第一个可见行的行号（旧、新）
+    Line(Option<usize>, Option<usize>),
+}
+
+/// 可见区域中的差异块；分词结果附带其第一项对应的行号
+struct VisibleHunk<'a> {
+    index: usize,
+    hunk: &'a DiffHunk,
+    old_tokens: Option<(&'a HighlightedText, usize)>,
+    new_tokens: Option<(&'a HighlightedText, usize)>,
+}
+
 impl DiffViewer {
     pub fn new(
         config: DiffViewerConfig,
@@ -659,6 +746,17 @@ impl DiffViewer {
             processor: DiffProcessor::new(processor_config),
             highlighter: SyntaxHighlighter::new(highlighter_config),
             current_diff: None,
+            old_highlight: None,
+            new_highlight: None,
+            old_content: Arc::from(""),
+            new_content: Arc::from(""),
+            file_path: PathBuf::new(),
+            context_override: None,
+            viewport_height: 0,
+            notices: Vec::new(),
+            streamed: None,
+            stream_stats: DiffStats::default(),
+            pending_scroll: None,
             scroll_offset: 0,
             selected_hunk: 0,
             virtual_scroll_state: VirtualScrollState::default(),
@@ -668,6 +766,15 @@ impl DiffViewer {
     /// 加载差异
     pub async fn load_diff(&mut self, old_content: &str, new_content: &str, file_path: &PathBuf) -> AppResult<()> {
         let diff = self.processor.compute_diff(old_content, new_content, file_path).await?;
+        self.old_highlight = self.highlighter.highlight_file(file_path, old_content);
+        self.new_highlight = self.highlighter.highlight_file(file_path, new_content);
+        self.old_content = Arc::from(old_content);
+        self.new_content = Arc::from(new_content);
+        self.file_path = file_path.clone();
+        self.context_override = None;
+        self.notices.clear();
+        self.streamed = None;
+        self.pending_scroll = None;
         self.current_diff = Some(diff);
         self.scroll_offset = 0;
         self.selected_hunk = 0;
@@ -675,10 +782,286 @@ impl DiffViewer {
         Ok(())
     }
 
+    /// 该大小（字节）的文件是否应使用流式差异
+    pub fn should_stream(&self, size: u64) -> bool {
+        size > self.processor.config.stream_threshold as u64
+    }
+
+    /// 加载流式差异：由 git 生成补丁并在后台建立索引，只解析显示到的差异块
+    pub fn load_stream(&mut self, source: PatchSource, file_path: &Path) -> AppResult<()> {
+        let language = self.highlighter.detect_language(file_path);
+        let settings = StreamSettings {
+            grammar: syntax::grammar_for(&language).filter(|_| self.highlighter.config.enabled),
+            max_highlight_size: self.highlighter.config.max_file_size,
+            intraline: self.intraline_settings(),
+            cache_bytes: self.processor.config.stream_cache_bytes,
+        };
+        let streamed = StreamedDiff::start(source, self.processor.config.context_lines, settings)?;
+
+        self.clear();
+        self.old_content = Arc::from("");
+        self.new_content = Arc::from("");
+        self.file_path = file_path.to_path_buf();
+        self.context_override = None;
+        self.streamed = Some(streamed);
+        Ok(())
+    }
+
+    /// 流式差异的统计及是否已全部索引；非流式差异返回 None
+    pub fn stream_state(&self) -> Option<(DiffStats, bool)> {
+        self.streamed.as_ref().map(|streamed| (streamed.stats(), streamed.stream().is_complete()))
+    }
+
+    fn has_diff(&self) -> bool {
+        self.current_diff.is_some() || self.streamed.is_some()
+    }
+
+    fn intraline_settings(&self) -> Option<(IntralineGranularity, usize)> {
+        let config = &self.processor.config;
+        config
+            .word_level_diff
+            .then_some((config.intraline_granularity, config.max_intraline_length))
+    }
+
+    /// 当前上下文行数
+    pub fn context_lines(&self) -> usize {
+        self.context_override.unwrap_or(self.processor.config.context_lines)
+    }
+
+    /// 在差异块周围多显示 `lines` 行未修改的内容
+    pub async fn expand_context(&mut self, lines: usize) -> AppResult<()> {
+        let context = self.context_lines().saturating_add(lines);
+        self.recompute_with_context(Some(context)).await
+    }
+
+    /// 折叠回配置的上下文行数
+    pub async fn collapse_context(&mut self) -> AppResult<()> {
+        self.recompute_with_context(None).await
+    }
+
+    /// 在显示整个文件与只显示差异块之间切换
+    pub async fn toggle_full_context(&mut self) -> AppResult<()> {
+        let context = if self.context_override == Some(usize::MAX) { None } else { Some(usize::MAX) };
+        self.recompute_with_context(context).await
+    }
+
+    /// 以新的上下文行数重新计算，并尽量保持当前可见的第一行不变
+    async fn recompute_with_context(&mut self, context: Option<usize>) -> AppResult<()> {
+        if !self.has_diff() {
+            return Ok(());
+        }
+        let anchor = self.line_at(self.scroll_offset).map(|line| (line.old_line_number, line.new_line_number));
+
+        self.context_override = context;
+        let context_lines = self.context_lines();
+        if let Some(streamed) = self.streamed.as_mut() {
+            // 新的补丁在后台生成，索引到锚点所在的差异块后再跳转
+            streamed.restart(context_lines)?;
+            self.selected_hunk = 0;
+            self.scroll_to(0);
+            self.pending_scroll = anchor.map(|(old, new)| PendingScroll::Line(old, new));
+            return Ok(());
+        }
+
+        let diff = self
+            .processor
+            .compute_diff_with_context(&self.old_content, &self.new_content, &self.file_path, context_lines)
+            .await?;
+        self.current_diff = Some(diff);
+
+        let offset = anchor
+            .and_then(|(old, new)| {
+                self.flat_lines().position(|line| {
+                    (new.is_some() && line.new_line_number == new) || (new.is_none() && line.old_line_number == old)
+                })
+            })
+            .unwrap_or(0);
+        // 差异块可能合并或拆分，选中锚点所在的块
+        self.selected_hunk = (1..self.hunk_count())
+            .take_while(|&index| self.hunk_offset(index) <= offset)
+            .last()
+            .unwrap_or(0);
+        self.scroll_to(offset);
+        Ok(())
+    }
+
+    /// 所有差异块的行，按显示顺序
+    fn flat_lines(&self) -> impl Iterator<Item = &DiffLine> {
+        self.current_diff.iter().flat_map(|diff| diff.hunks.iter().flat_map(|hunk| hunk.lines.iter()))
+    }
+
+    fn line_at(&mut self, index: usize) -> Option<DiffLine> {
+        let Some(streamed) = self.streamed.as_mut() else {
+            return self.flat_lines().nth(index).cloned();
+        };
+        let hunk_index = streamed.hunk_at_row(index)?;
+        let first_row = streamed.entry(hunk_index)?.first_row;
+        let hunk = streamed.hunk(hunk_index).ok()?;
+        hunk.hunk.lines.get(index - first_row).cloned()
+    }
+
+    /// 差异的总行数（流式差异为目前已索引的行数）
+    fn total_rows(&self) -> usize {
+        match &self.streamed {
+            Some(streamed) => streamed.rows(),
+            None => self.flat_lines().count(),
+        }
+    }
+
+    fn hunk_count(&self) -> usize {
+        match &self.streamed {
+            Some(streamed) => streamed.hunk_count(),
+            None => self.current_diff.as_ref().map_or(0, |diff| diff.hunks.len()),
+        }
+    }
+
+    /// 第 index 个差异块第一行的位置
+    fn hunk_offset(&self, index: usize) -> usize {
+        match &self.streamed {
+            Some(streamed) => streamed.entry(index).map_or(0, |entry| entry.first_row),
+            None => self
+                .current_diff
+                .as_ref()
+                .map_or(0, |diff| diff.hunks.iter().take(index).map(|hunk| hunk.lines.len()).sum()),
+        }
+    }
+
+    /// 差异块之前被折叠的未修改行数；`index == hunks.len()` 表示文件末尾
+    pub fn folded_lines_before(&self, index: usize) -> usize {
+        // 旧文件中的 [start, end) 行范围（从 1 开始）
+        let range = |old_start: usize, old_lines: usize| {
+            let start = if old_lines > 0 { old_start } else { old_start + 1 };
+            (start, start + old_lines)
+        };
+        let (previous, next, count) = match (&self.streamed, &self.current_diff) {
+            (Some(streamed), _) => streamed.stream().with_index(|patch| {
+                let at = |i: usize| patch.hunks.get(i).map(|hunk| range(hunk.old_start, hunk.old_lines));
+                (index.checked_sub(1).and_then(at), at(index), patch.hunks.len())
+            }),
+            (None, Some(diff)) => {
+                let at = |i: usize| diff.hunks.get(i).map(|hunk| range(hunk.old_start, hunk.old_lines));
+                (index.checked_sub(1).and_then(at), at(index), diff.hunks.len())
+            }
+            (None, None) => return 0,
+        };
+
+        let previous_end = previous.map_or(1, |(_, end)| end);
+        let next_start = match next {
+            Some((start, _)) => start,
+            None if count == 0 => return 0,
+            // 流式差异不读取整个文件，末尾折叠的行数未知
+            None if self.streamed.is_some() => return 0,
+            None => self.old_content.lines().count() + 1,
+        };
+        next_start.saturating_sub(previous_end)
+    }
+
+    /// 替换差异处理配置（算法、空白模式等）
+    pub fn set_processor_config(&mut self, config: DiffProcessorConfig) {
+        self.processor = DiffProcessor::new(config);
+    }
+
+    /// 当前差异处理配置
+    pub fn processor_config(&self) -> &DiffProcessorConfig {
+        &self.processor.config
+    }
+
+    /// 循环切换行内高亮：单词 → 字符 → 关闭，并刷新当前差异
+    pub fn cycle_intraline(&mut self) {
+        let mut config = self.processor.config.clone();
+        match (config.word_level_diff, config.intraline_granularity) {
+            (false, _) => {
+                config.word_level_diff = true;
+                config.intraline_granularity = IntralineGranularity::Word;
+            }
+            (true, IntralineGranularity::Word) => config.intraline_granularity = IntralineGranularity::Char,
+            (true, IntralineGranularity::Char) => config.word_level_diff = false,
+        }
+        self.set_processor_config(config);
+
+        let intraline = self.intraline_settings();
+        if let Some(streamed) = self.streamed.as_mut() {
+            streamed.set_intraline(intraline);
+        }
+        if let Some(diff) = self.current_diff.as_mut() {
+            for hunk in &mut diff.hunks {
+                self.processor.apply_intraline(hunk);
+            }
+        }
+    }
+
+    /// 替换语法高亮配置
+    pub fn set_highlighter_config(&mut self, config: SyntaxHighlighterConfig) {
+        self.highlighter.set_config(config);
+    }
+
+    /// 设置差异驱动的块头上下文规则，下次加载差异时生效
+    pub fn set_funcname(&mut self, pattern: Option<&str>) {
+        let pattern = pattern.and_then(|pattern| match FuncnamePattern::parse(pattern) {
+            Ok(pattern) => Some(pattern),
+            Err(e) => {
+                warn!("Ignoring funcname pattern: {}", e);
+                None
+            }
+        });
+        self.processor.set_funcname(pattern);
+    }
+
+    /// 当前显示的差异，按屏幕上的上下文行数导出；流式差异导出已索引的全部差异块
+    pub fn export_file(&self) -> Option<ExportFile> {
+        let path = self.file_path.to_string_lossy().to_string();
+        let diff = match &self.streamed {
+            Some(streamed) => {
+                let change = streamed.change();
+                FileDiff {
+                    old_path: change.old_path.as_ref().map(PathBuf::from),
+                    new_path: change.new_path.as_ref().map(PathBuf::from),
+                    status: export::file_status(&change.status),
+                    hunks: match streamed.load_all() {
+                        Ok(hunks) => hunks,
+                        Err(e) => {
+                            warn!("Failed to read the streamed diff for export: {}", e);
+                            return None;
+                        }
+                    },
+                    stats: streamed.stats(),
+                    is_binary: false,
+                }
+            }
+            None => self.current_diff.clone()?,
+        };
+        Some(ExportFile {
+            old_path: (diff.status != FileStatus::Added).then(|| path.clone()),
+            new_path: (diff.status != FileStatus::Deleted).then_some(path),
+            diff,
+            notices: self.notices.clone(),
+            patch: None,
+        })
+    }
+
+    /// 设置差异上方显示的文件信息
+    pub fn set_notices(&mut self, notices: Vec<String>) {
+        self.notices = notices;
+    }
+
+    /// 清除当前差异
+    pub fn clear(&mut self) {
+        self.current_diff = None;
+        self.streamed = None;
+        self.stream_stats = DiffStats::default();
+        self.pending_scroll = None;
+        self.notices.clear();
+        self.old_highlight = None;
+        self.new_highlight = None;
+        self.scroll_offset = 0;
+        self.selected_hunk = 0;
+        self.virtual_scroll_state = VirtualScrollState::default();
+    }
+
     /// 更新虚拟滚动状态
     fn update_virtual_scroll_state(&mut self) {
-        if let Some(ref diff) = self.current_diff {
-            let total_lines = diff.hunks.iter().map(|h| h.lines.len()).sum();
+        if self.has_diff() {
+            let total_lines = self.total_rows();
             self.virtual_scroll_state = VirtualScrollState {
                 viewport_start: self.scroll_offset,
                 viewport_end: (self.scroll_offset + self.config.lines_per_page).min(total_lines),
@@ -696,73 +1079,280 @@ impl DiffViewer {
 
     /// 向上滚动
     pub fn scroll_up(&mut self, lines: usize) {
+        self.pending_scroll = None;
         self.scroll_offset = self.scroll_offset.saturating_sub(lines);
         self.update_virtual_scroll_state();
     }
 
     /// 向下滚动
     pub fn scroll_down(&mut self, lines: usize) {
-        if let Some(ref diff) = self.current_diff {
-            let max_scroll = diff.hunks.iter().map(|h| h.lines.len()).sum::<usize>()
-                .saturating_sub(self.config.lines_per_page);
-            self.scroll_offset = (self.scroll_offset + lines).min(max_scroll);
+        if self.has_diff() {
+            self.pending_scroll = None;
+            let page = if self.viewport_height > 0 { self.viewport_height } else { self.config.lines_per_page };
+            let max_scroll = self.total_rows().saturating_sub(page);
+            self.scroll_offset = (self.scroll_offset + lines).min(max_scroll.max(self.scroll_offset));
             self.update_virtual_scroll_state();
         }
     }
 
-    /// 选择下一个差异块
-    pub fn next_hunk(&mut self) {
-        if let Some(ref diff) = self.current_diff {
-            if self.selected_hunk < diff.hunks.len().saturating_sub(1) {
-                self.selected_hunk += 1;
-            }
+    /// 跳到下一个差异块，返回是否移动
+    pub fn next_hunk(&mut self) -> bool {
+        if self.selected_hunk + 1 >= self.hunk_count() {
+            return false;
+        }
+        self.select_hunk(self.selected_hunk + 1);
+        true
+    }
+
+    /// 跳到上一个差异块，返回是否移动
+    pub fn previous_hunk(&mut self) -> bool {
+        if self.selected_hunk == 0 {
+            // 已滚离第一个块时先回到它的开头
+            let moved = self.scroll_offset > 0;
+            self.select_hunk(0);
+            return moved;
         }
+        self.select_hunk(self.selected_hunk - 1);
+        true
     }
 
-    /// 选择上一个差异块
-    pub fn previous_hunk(&mut self) {
-        self.selected_hunk = self.selected_hunk.saturating_sub(1);
+    /// 跳到最后一个差异块
+    pub fn last_hunk(&mut self) {
+        self.select_hunk(self.hunk_count().saturating_sub(1));
+    }
+
+    fn select_hunk(&mut self, index: usize) {
+        self.pending_scroll = None;
+        self.selected_hunk = index;
+        self.scroll_to(self.hunk_offset(index));
+    }
+
+    /// 当前滚动位置与选中的差异块
+    pub fn position(&self) -> (usize, usize) {
+        (self.scroll_offset, self.selected_hunk)
+    }
+
+    /// 恢复之前记录的位置（超出范围时截断）
+    pub fn restore_position(&mut self, (offset, hunk): (usize, usize)) {
+        if self.streamed.is_some() {
+            // 流式差异要等索引到该位置
+            self.pending_scroll = Some(PendingScroll::Position(offset, hunk));
+            return;
+        }
+        let hunks = self.hunk_count();
+        let lines = self.total_rows();
+        self.selected_hunk = hunk.min(hunks.saturating_sub(1));
+        self.scroll_to(offset.min(lines.saturating_sub(1)));
+    }
+
+    /// 流式索引追上后跳到等待中的滚动目标
+    fn resolve_pending_scroll(&mut self) -> AppResult<()> {
+        let (Some(pending), Some(streamed)) = (self.pending_scroll, self.streamed.as_mut()) else {
+            return Ok(());
+        };
+        let (rows, count, complete) = streamed.stream().with_index(|index| (index.rows, index.hunks.len(), index.complete));
+        match pending {
+            PendingScroll::Position(offset, hunk) => {
+                if (offset < rows && hunk < count) || complete {
+                    self.pending_scroll = None;
+                    self.selected_hunk = hunk.min(count.saturating_sub(1));
+                    self.scroll_to(offset.min(rows.saturating_sub(1)));
+                }
+            }
+            PendingScroll::Line(old, new) => {
+                let contains = |start: usize, lines: usize, line: Option<usize>| line.is_some_and(|line| start <= line && line < start + lines);
+                let target = streamed.stream().with_index(|index| {
+                    index.hunks.iter().position(|hunk| match new {
+                        Some(_) => contains(hunk.new_start, hunk.new_lines, new),
+                        None => contains(hunk.old_start, hunk.old_lines, old),
+                    })
+                });
+                match target {
+                    Some(index) => {
+                        let first_row = streamed.entry(index).map_or(0, |entry| entry.first_row);
+                        let within = streamed.hunk(index)?.hunk.lines.iter().position(|line| {
+                            (new.is_some() && line.new_line_number == new) || (new.is_none() && line.old_line_number == old)
+                        });
+                        self.pending_scroll = None;
+                        self.selected_hunk = index;
+                        self.scroll_to(first_row + within.unwrap_or(0));
+                    }
+                    None if complete => self.pending_scroll = None,
+                    None => {}
+                }
+            }
+        }
+        Ok(())
     }
 
     /// 渲染差异查看器
     pub async fn render(&mut self, frame: &mut Frame<'_>, area: Rect, theme: &Theme) -> AppResult<()> {
-        if let Some(diff) = self.current_diff.clone() {
-            let display_mode = self.config.display_mode.clone();
-            match display_mode {
-                DiffMode::SideBySide => self.render_side_by_side(frame, area, &diff, theme).await?,
-                DiffMode::Unified => self.render_unified(frame, area, &diff, theme).await?,
-                DiffMode::Inline => self.render_inline(frame, area, &diff, theme).await?,
-            }
-        } else {
+        if self.streamed.is_some() {
+            return self.render_streamed(frame, area, theme);
+        }
+        let Some(diff) = self.current_diff.as_ref() else {
             // 显示空状态
             let placeholder = Paragraph::new("No diff to display")
                 .block(Block::default().borders(Borders::ALL).title("Diff Viewer"))
                 .alignment(Alignment::Center)
                 .wrap(Wrap { trim: true });
             frame.render_widget(placeholder, area);
+            return Ok(());
+        };
+
+        // 没有行差异时（二进制、图片、仅换行符变化）只显示文件信息
+        if diff.hunks.is_empty() && !self.notices.is_empty() {
+            self.render_notices(frame, area, &self.notices, theme);
+            return Ok(());
+        }
+        let area = self.split_notices(frame, area, &self.notices, theme);
+        self.viewport_height = area.height.saturating_sub(2) as usize;
+
+        // 只取覆盖可见区域的差异块
+        let Some(diff) = self.current_diff.as_ref() else { return Ok(()) };
+        let (mut start, mut skip, mut shown) = (0, 0, 0);
+        let mut visible = Vec::new();
+        for (index, hunk) in diff.hunks.iter().enumerate() {
+            let end = start + hunk.lines.len();
+            if end > self.scroll_offset && shown <= self.viewport_height {
+                if visible.is_empty() {
+                    skip = self.scroll_offset.saturating_sub(start);
+                }
+                // 块正文加上折叠标记和块头
+                shown += end - start.max(self.scroll_offset) + 2;
+                visible.push(VisibleHunk {
+                    index,
+                    hunk,
+                    old_tokens: self.old_highlight.as_deref().map(|tokens| (tokens, 1)),
+                    new_tokens: self.new_highlight.as_deref().map(|tokens| (tokens, 1)),
+                });
+            }
+            start = end;
         }
+        self.render_hunks(frame, area, &visible, skip, diff.hunks.len(), theme);
+        Ok(())
+    }
 
+    /// 渲染流式差异：只读取、解析和高亮可见的差异块
+    fn render_streamed(&mut self, frame: &mut Frame<'_>, area: Rect, theme: &Theme) -> AppResult<()> {
+        self.resolve_pending_scroll()?;
+        let Some(streamed) = self.streamed.as_ref() else { return Ok(()) };
+        self.stream_stats = streamed.stats();
+        let (count, rows, complete, binary, bytes, error) = streamed.stream().with_index(|index| {
+            (index.hunks.len(), index.rows, index.complete, index.binary, index.bytes, index.error.clone())
+        });
+
+        let mut notices = self.notices.clone();
+        if let Some(error) = error {
+            notices.push(error);
+        } else if !complete {
+            notices.push(format!(
+                "Streaming diff: {} hunks indexed ({} read), hunks load as they scroll into view",
+                count,
+                content::format_size(bytes as usize)
+            ));
+        }
+        if count == 0 {
+            notices.push(match (complete, binary) {
+                (true, true) => "Binary files differ".to_string(),
+                (true, false) => "No differences".to_string(),
+                (false, _) => "Waiting for git diff…".to_string(),
+            });
+            self.render_notices(frame, area, &notices, theme);
+            return Ok(());
+        }
+        let area = self.split_notices(frame, area, &notices, theme);
+        self.viewport_height = area.height.saturating_sub(2) as usize;
+
+        if self.scroll_offset >= rows {
+            self.scroll_to(rows.saturating_sub(1));
+        }
+        let Some(streamed) = self.streamed.as_mut() else { return Ok(()) };
+        let first = streamed.hunk_at_row(self.scroll_offset).unwrap_or(0);
+        let skip = self.scroll_offset - streamed.entry(first).map_or(0, |entry| entry.first_row);
+
+        // 读取覆盖可见区域的差异块；其余的只在索引中
+        let mut loaded = Vec::new();
+        let mut shown = 0;
+        for index in first..count {
+            if shown > self.viewport_height {
+                break;
+            }
+            let hunk = streamed.hunk(index)?;
+            shown += hunk.hunk.lines.len() + 2;
+            loaded.push((index, hunk));
+        }
+        let visible: Vec<VisibleHunk> = loaded
+            .iter()
+            .map(|(index, streamed_hunk)| VisibleHunk {
+                index: *index,
+                hunk: &streamed_hunk.hunk,
+                old_tokens: streamed_hunk.old_tokens.as_ref().map(|tokens| (tokens, streamed_hunk.hunk.old_start)),
+                new_tokens: streamed_hunk.new_tokens.as_ref().map(|tokens| (tokens, streamed_hunk.hunk.new_start)),
+            })
+            .collect();
+        // 仍在索引时末尾还会有差异块，不显示文件末尾的折叠标记
+        let total = if complete { count } else { usize::MAX };
+        self.render_hunks(frame, area, &visible, skip, total, theme);
         Ok(())
     }
 
-    /// 渲染并排显示模式
-    async fn render_side_by_side(&mut self, frame: &mut Frame<'_>, area: Rect, diff: &FileDiff, theme: &Theme) -> AppResult<()> {
+    /// 在差异上方显示文件信息，返回剩余的区域
+    fn split_notices(&self, frame: &mut Frame<'_>, area: Rect, notices: &[String], theme: &Theme) -> Rect {
+        if notices.is_empty() {
+            return area;
+        }
+        let height = (notices.len() as u16 + 2).min(area.height / 3);
         let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(height), Constraint::Min(0)])
             .split(area);
+        self.render_notices(frame, chunks[0], notices, theme);
+        chunks[1]
+    }
 
-        // 左侧：原文件
-        self.render_side(frame, chunks[0], diff, true, theme).await?;
-
-        // 右侧：新文件
-        self.render_side(frame, chunks[1], diff, false, theme).await?;
+    /// 渲染文件信息（二进制摘要、图片尺寸、编码与换行符变化）
+    fn render_notices(&self, frame: &mut Frame<'_>, area: Rect, notices: &[String], theme: &Theme) {
+        let lines: Vec<Line> = notices
+            .iter()
+            .map(|notice| Line::from(Span::styled(notice.clone(), Style::default().fg(theme.colors.warning))))
+            .collect();
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(format!("File Info: {}", self.file_path.display())))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
 
-        Ok(())
+    /// 按显示模式渲染可见的差异块；skip 为第一个块中已滚过的行数，hunk_count 为块总数
+    fn render_hunks(&self, frame: &mut Frame<'_>, area: Rect, visible: &[VisibleHunk], skip: usize, hunk_count: usize, theme: &Theme) {
+        match self.config.display_mode {
+            DiffMode::SideBySide => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(area);
+
+                // 左侧：原文件；右侧：新文件
+                self.render_side(frame, chunks[0], visible, skip, hunk_count, true, theme);
+                self.render_side(frame, chunks[1], visible, skip, hunk_count, false, theme);
+            }
+            // 内联模式与统一模式类似，但可能有不同的样式
+            DiffMode::Unified | DiffMode::Inline => self.render_unified(frame, area, visible, skip, hunk_count, theme),
+        }
     }
 
     /// 渲染一侧（原文件或新文件）
-    async fn render_side(&mut self, frame: &mut Frame<'_>, area: Rect, diff: &FileDiff, is_old: bool, theme: &Theme) -> AppResult<()> {
+    #[allow(clippy::too_many_arguments)]
+    fn render_side(
+        &self,
+        frame: &mut Frame<'_>,
+        area: Rect,
+        visible: &[VisibleHunk],
+        skip: usize,
+        hunk_count: usize,
+        is_old: bool,
+        theme: &Theme,
+    ) {
         let title = if is_old { "Old File" } else { "New File" };
         let block = Block::default()
             .borders(Borders::ALL)
@@ -771,11 +1361,18 @@ impl DiffViewer {
         let inner_area = block.inner(area);
         frame.render_widget(block, area);
 
+        let palette = self.highlighter.palette(theme);
         let mut lines = Vec::new();
-        for hunk in &diff.hunks {
-            for line in &hunk.lines {
+        for (position, visible_hunk) in visible.iter().enumerate() {
+            let skipped = if position == 0 { skip } else { 0 };
+            if skipped == 0 {
+                lines.extend(self.fold_row(visible_hunk.index, theme));
+            }
+            for line in visible_hunk.hunk.lines.iter().skip(skipped) {
                 let should_show = match (&line.line_type, is_old) {
-                    (DiffLineType::Context, _) => true,
+                    // 被忽略的空行变更只在有该行的一侧显示
+                    (DiffLineType::Context, true) => line.old_line_number.is_some(),
+                    (DiffLineType::Context, false) => line.new_line_number.is_some(),
                     (DiffLineType::Deleted, true) => true,
                     (DiffLineType::Added, false) => true,
                     _ => false,
@@ -788,34 +1385,31 @@ impl DiffViewer {
                         line.new_line_number
                     };
 
-                    let style = match line.line_type {
-                        DiffLineType::Added => Style::default().bg(Color::Green).fg(Color::Black),
-                        DiffLineType::Deleted => Style::default().bg(Color::Red).fg(Color::White),
-                        DiffLineType::Context => Style::default(),
-                        DiffLineType::Modified => Style::default().bg(Color::Yellow).fg(Color::Black),
-                    };
-
-                    let line_content = if self.config.show_line_numbers {
-                        format!("{:4} {}", line_number.map_or("".to_string(), |n| n.to_string()), line.content)
-                    } else {
-                        line.content.clone()
-                    };
+                    let style = Self::line_background(&line.line_type);
+                    let mut spans = Vec::new();
+                    if self.config.show_line_numbers {
+                        let number = line_number.map_or("".to_string(), |n| n.to_string());
+                        spans.push(Span::styled(format!("{:4} ", number), style.fg(theme.colors.muted)));
+                    }
+                    let prefer_new = !is_old && line.line_type != DiffLineType::Deleted;
+                    spans.extend(self.content_spans(line, visible_hunk, prefer_new, &palette, style));
 
-                    lines.push(ListItem::new(line_content).style(style));
+                    lines.push(ListItem::new(Line::from(spans)).style(style));
                 }
             }
         }
+        if visible.last().is_none_or(|hunk| hunk.index + 1 == hunk_count) {
+            lines.extend(self.fold_row(hunk_count, theme));
+        }
 
         let list = List::new(lines)
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
         frame.render_widget(list, inner_area);
-
-        Ok(())
     }
 
     /// 渲染统一显示模式
-    async fn render_unified(&mut self, frame: &mut Frame<'_>, area: Rect, diff: &FileDiff, theme: &Theme) -> AppResult<()> {
+    fn render_unified(&self, frame: &mut Frame<'_>, area: Rect, visible: &[VisibleHunk], skip: usize, hunk_count: usize, theme: &Theme) {
         let block = Block::default()
             .borders(Borders::ALL)
             .title("Unified Diff");
@@ -823,14 +1417,23 @@ impl DiffViewer {
         let inner_area = block.inner(area);
         frame.render_widget(block, area);
 
+        let palette = self.highlighter.palette(theme);
         let mut lines = Vec::new();
 
-        for hunk in &diff.hunks {
-            // 添加块头
-            lines.push(ListItem::new(hunk.header.clone())
-                .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+        for (position, visible_hunk) in visible.iter().enumerate() {
+            let hunk = visible_hunk.hunk;
+            let skipped = if position == 0 { skip } else { 0 };
+            // 折叠标记和块头（选中的块反色显示）
+            if skipped == 0 {
+                lines.extend(self.fold_row(visible_hunk.index, theme));
+                let mut header_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+                if visible_hunk.index == self.selected_hunk {
+                    header_style = header_style.add_modifier(Modifier::REVERSED);
+                }
+                lines.push(ListItem::new(hunk.header.clone()).style(header_style));
+            }
 
-            for line in &hunk.lines {
+            for line in hunk.lines.iter().skip(skipped) {
                 let prefix = match line.line_type {
                     DiffLineType::Added => "+",
                     DiffLineType::Deleted => "-",
@@ -838,42 +1441,126 @@ impl DiffViewer {
                     DiffLineType::Modified => "~",
                 };
 
-                let style = match line.line_type {
-                    DiffLineType::Added => Style::default().fg(Color::Green),
-                    DiffLineType::Deleted => Style::default().fg(Color::Red),
-                    DiffLineType::Context => Style::default(),
-                    DiffLineType::Modified => Style::default().fg(Color::Yellow),
+                let marker_color = match line.line_type {
+                    DiffLineType::Added => theme.colors.success,
+                    DiffLineType::Deleted => theme.colors.error,
+                    DiffLineType::Context => theme.colors.muted,
+                    DiffLineType::Modified => theme.colors.warning,
                 };
+                let style = Self::line_background(&line.line_type);
 
-                let line_content = if self.config.show_line_numbers {
+                let mut spans = Vec::new();
+                if self.config.show_line_numbers {
                     let old_num = line.old_line_number.map_or("   ".to_string(), |n| format!("{:3}", n));
                     let new_num = line.new_line_number.map_or("   ".to_string(), |n| format!("{:3}", n));
-                    format!("{} {} {}{}", old_num, new_num, prefix, line.content)
-                } else {
-                    format!("{}{}", prefix, line.content)
-                };
+                    spans.push(Span::styled(format!("{} {} ", old_num, new_num), style.fg(theme.colors.muted)));
+                }
+                spans.push(Span::styled(prefix, style.fg(marker_color)));
+                let prefer_new = line.line_type != DiffLineType::Deleted;
+                spans.extend(self.content_spans(line, visible_hunk, prefer_new, &palette, style));
 
-                lines.push(ListItem::new(line_content).style(style));
+                lines.push(ListItem::new(Line::from(spans)).style(style));
             }
         }
+        if visible.last().is_none_or(|hunk| hunk.index + 1 == hunk_count) {
+            lines.extend(self.fold_row(hunk_count, theme));
+        }
 
         let list = List::new(lines)
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
         frame.render_widget(list, inner_area);
+    }
 
-        Ok(())
+    /// 差异块之前的折叠标记行
+    fn fold_row(&self, hunk_index: usize, theme: &Theme) -> Option<ListItem<'static>> {
+        let folded = self.folded_lines_before(hunk_index);
+        (folded > 0).then(|| {
+            let plural = if folded == 1 { "" } else { "s" };
+            ListItem::new(format!("⋯ {} unchanged line{} (+ to expand) ⋯", folded, plural))
+                .style(Style::default().fg(theme.colors.muted).add_modifier(Modifier::ITALIC))
+        })
     }
 
-    /// 渲染内联显示模式
-    async fn render_inline(&mut self, frame: &mut Frame<'_>, area: Rect, diff: &FileDiff, theme: &Theme) -> AppResult<()> {
-        // 内联模式与统一模式类似，但可能有不同的样式
-        self.render_unified(frame, area, diff, theme).await
+    /// 变更行的底色；前景色留给语法高亮
+    fn line_background(line_type: &DiffLineType) -> Style {
+        match line_type {
+            DiffLineType::Added => Style::default().bg(Color::Rgb(23, 58, 33)),
+            DiffLineType::Deleted => Style::default().bg(Color::Rgb(75, 25, 30)),
+            DiffLineType::Context => Style::default(),
+            DiffLineType::Modified => Style::default().bg(Color::Rgb(70, 60, 20)),
+        }
+    }
+
+    /// 按所在文件版本的分词结果为行内容着色
+    fn content_spans(
+        &self,
+        line: &DiffLine,
+        hunk: &VisibleHunk,
+        prefer_new: bool,
+        palette: &SyntaxPalette,
+        base: Style,
+    ) -> Vec<Span<'static>> {
+        fn side_tokens(tokens: Option<(&HighlightedText, usize)>, number: Option<usize>) -> Option<&[syntax::Token]> {
+            tokens.zip(number).map(|((tokens, first), number)| tokens.line(number.saturating_sub(first)))
+        }
+        let new_side = side_tokens(hunk.new_tokens, line.new_line_number);
+        let old_side = side_tokens(hunk.old_tokens, line.old_line_number);
+        let side = if prefer_new { new_side.or(old_side) } else { old_side.or(new_side) };
+
+        let spans = match side {
+            Some(tokens) => syntax::styled_spans(&line.content, tokens, palette, base),
+            None => vec![Span::styled(line.content.clone(), base)],
+        };
+
+        let emphasis = match line.line_type {
+            DiffLineType::Added => Style::default().bg(Color::Rgb(46, 120, 62)),
+            DiffLineType::Deleted => Style::default().bg(Color::Rgb(150, 45, 55)),
+            _ => return spans,
+        };
+        Self::emphasize(spans, &line.highlights, emphasis)
+    }
+
+    /// 在行内变化范围上叠加强调样式；按字节偏移拆分已有 span
+    fn emphasize(spans: Vec<Span<'static>>, ranges: &[(usize, usize)], emphasis: Style) -> Vec<Span<'static>> {
+        if ranges.is_empty() {
+            return spans;
+        }
+
+        let mut result = Vec::with_capacity(spans.len() + ranges.len() * 2);
+        let mut offset = 0;
+        for span in spans {
+            let text = span.content.as_ref();
+            let span_end = offset + text.len();
+            let mut cut = offset;
+            // 在范围边界处切开当前 span
+            let mut bounds: Vec<usize> = ranges
+                .iter()
+                .flat_map(|&(start, end)| [start, end])
+                .filter(|&bound| bound > offset && bound < span_end && text.is_char_boundary(bound - offset))
+                .collect();
+            bounds.push(span_end);
+            bounds.sort_unstable();
+            bounds.dedup();
+
+            for bound in bounds {
+                let piece = &text[cut - offset..bound - offset];
+                let inside = ranges.iter().any(|&(start, end)| start <= cut && cut < end);
+                let style = if inside { span.style.patch(emphasis) } else { span.style };
+                result.push(Span::styled(piece.to_string(), style));
+                cut = bound;
+            }
+            offset = span_end;
+        }
+        result
     }
 
     /// 获取差异统计信息
     pub fn get_stats(&self) -> Option<&DiffStats> {
-        self.current_diff.as_ref().map(|d| &d.stats)
+        match &self.streamed {
+            Some(_) => Some(&self.stream_stats),
+            None => self.current_diff.as_ref().map(|d| &d.stats),
+        }
     }
 
     /// 切换显示模式
@@ -894,4 +1581,83 @@ impl DiffViewer {
     pub fn toggle_whitespace(&mut self) {
         self.config.show_whitespace = !self.config.show_whitespace;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 30 numbered lines, with lines in `changed` rewritten on the new side
+    fn sides(changed: &[usize]) -> (String, String) {
+        let line = |n: usize, new: bool| {
+            if new && changed.contains(&n) {
+                format!("changed {}\n", n)
+            } else {
+                format!("line {}\n", n)
+            }
+        };
+        ((1..=30).map(|n| line(n, false)).collect(), (1..=30).map(|n| line(n, true)).collect())
+    }
+
+    async fn load(changed: &[usize]) -> DiffViewer {
+        let (old, new) = sides(changed);
+        let mut viewer = DiffViewer::new(DiffViewerConfig::default(), DiffProcessorConfig::default(), SyntaxHighlighterConfig::default());
+        viewer.load_diff(&old, &new, &PathBuf::from("numbers.txt")).await.unwrap();
+        viewer
+    }
+
+    #[tokio::test]
+    async fn test_folded_lines_at_start_middle_and_end() {
+        // Hunks cover old lines 2-8 and 22-28 with three lines of context
+        let viewer = load(&[5, 25]).await;
+        assert_eq!(viewer.hunk_count(), 2);
+        assert_eq!(viewer.folded_lines_before(0), 1);
+        assert_eq!(viewer.folded_lines_before(1), 13);
+        assert_eq!(viewer.folded_lines_before(2), 2);
+
+        // Changes at the file edges leave nothing folded there
+        let viewer = load(&[1, 30]).await;
+        assert_eq!(viewer.folded_lines_before(0), 0);
+        assert_eq!(viewer.folded_lines_before(2), 0);
+    }
+
+    #[tokio::test]
+    async fn test_context_change_keeps_scroll_anchor() {
+        let mut viewer = load(&[5, 25]).await;
+        viewer.next_hunk();
+        let anchor = viewer.line_at(viewer.position().0).unwrap().new_line_number;
+        assert_eq!(anchor, Some(22));
+
+        viewer.expand_context(10).await.unwrap();
+        assert_eq!(viewer.context_lines(), 13);
+        assert_eq!(viewer.hunk_count(), 1);
+        assert_eq!(viewer.line_at(viewer.position().0).unwrap().new_line_number, anchor);
+        assert!(viewer.position().1 < viewer.hunk_count());
+
+        viewer.collapse_context().await.unwrap();
+        assert_eq!(viewer.hunk_count(), 2);
+        assert_eq!(viewer.line_at(viewer.position().0).unwrap().new_line_number, anchor);
+        assert_eq!(viewer.position().1, 1);
+    }
+
+    #[tokio::test]
+    async fn test_hunk_jumps_stop_at_boundaries() {
+        let mut viewer = load(&[5, 15, 25]).await;
+        assert_eq!(viewer.hunk_count(), 3);
+        assert!(!viewer.previous_hunk());
+        assert!(viewer.next_hunk());
+        assert!(viewer.next_hunk());
+        assert!(!viewer.next_hunk());
+        assert_eq!(viewer.position(), (viewer.hunk_offset(2), 2));
+
+        // Scrolled into the first hunk, going back first returns to its start
+        viewer.select_hunk(0);
+        viewer.scroll_to(2);
+        assert!(viewer.previous_hunk());
+        assert_eq!(viewer.position(), (0, 0));
+
+        // Out-of-range positions are clamped
+        viewer.restore_position((999, 9));
+        assert_eq!(viewer.position(), (viewer.total_rows() - 1, 2));
+    }
+}
@@ -0,0 +1,545 @@
+//! 行级差异算法
+//!
+//! Myers, Minimal, Patience and Histogram over interned lines. Lines are
+//! normalized by the [`WhitespaceMode`] before they are compared, so every
+//! algorithm honours `-w`, `-b` and `--ignore-space-at-eol` the same way.
+//! The result is an edit script of [`DiffOp`]s indexing into the old and
+//! new line slices.
+
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::HashMap};
+
+use super::DiffAlgorithm;
+
+/// Myers gives up on an optimal split after this many edits (and at least
+/// `sqrt(n + m)`), like xdiff's cost heuristic; Minimal never does
+const MYERS_MIN_COST_LIMIT: usize = 256;
+/// Histogram falls back to Myers when every candidate line occurs more often
+const HISTOGRAM_MAX_CHAIN: usize = 64;
+
+/// 空白字符比较方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WhitespaceMode {
+    /// 逐字节比较
+    #[default]
+    Exact,
+    /// 忽略行尾空白（`--ignore-space-at-eol`）
+    IgnoreEol,
+    /// 忽略空白数量的变化（`-b`）
+    IgnoreChange,
+    /// 忽略所有空白（`-w`）
+    IgnoreAll,
+}
+
+impl WhitespaceMode {
+    /// Key a line is compared by
+    pub fn normalize<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        match self {
+            WhitespaceMode::Exact => Cow::Borrowed(line),
+            WhitespaceMode::IgnoreEol => Cow::Borrowed(line.trim_end()),
+            WhitespaceMode::IgnoreChange => {
+                let mut normalized = String::with_capacity(line.len());
+                let mut pending_space = false;
+                for c in line.trim_end().chars() {
+                    if c.is_whitespace() {
+                        pending_space = true;
+                    } else {
+                        if pending_space {
+                            normalized.push(' ');
+                            pending_space = false;
+                        }
+                        normalized.push(c);
+                    }
+                }
+                Cow::Owned(normalized)
+            }
+            WhitespaceMode::IgnoreAll => Cow::Owned(line.chars().filter(|c| !c.is_whitespace()).collect()),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WhitespaceMode::Exact => "exact",
+            WhitespaceMode::IgnoreEol => "ignore-eol",
+            WhitespaceMode::IgnoreChange => "ignore-change",
+            WhitespaceMode::IgnoreAll => "ignore-all",
+        }
+    }
+}
+
+/// 编辑脚本中的一步（行索引从 0 开始）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    Equal { old: usize, new: usize },
+    Delete { old: usize },
+    Insert { new: usize },
+}
+
+impl DiffOp {
+    pub fn is_change(&self) -> bool {
+        !matches!(self, DiffOp::Equal { .. })
+    }
+}
+
+/// 计算两组行之间的编辑脚本
+pub fn diff_lines<'a>(old: &[&'a str], new: &[&'a str], algorithm: DiffAlgorithm, whitespace: WhitespaceMode) -> Vec<DiffOp> {
+    // 先把行映射为整数，后续比较只需比较 id
+    let mut ids: HashMap<Cow<'a, str>, u32> = HashMap::new();
+    let a = intern(&mut ids, old, whitespace);
+    let b = intern(&mut ids, new, whitespace);
+
+    let mut script = Script { a: &a, b: &b, ops: Vec::with_capacity(a.len().max(b.len())) };
+    match algorithm {
+        DiffAlgorithm::Myers => script.myers(0, a.len(), 0, b.len(), false),
+        DiffAlgorithm::Minimal => script.myers(0, a.len(), 0, b.len(), true),
+        DiffAlgorithm::Patience => script.patience(0, a.len(), 0, b.len()),
+        DiffAlgorithm::Histogram => script.histogram(0, a.len(), 0, b.len()),
+    }
+    script.ops
+}
+
+fn intern<'a>(ids: &mut HashMap<Cow<'a, str>, u32>, lines: &[&'a str], whitespace: WhitespaceMode) -> Vec<u32> {
+    lines
+        .iter()
+        .map(|line| {
+            let next = ids.len() as u32;
+            *ids.entry(whitespace.normalize(line)).or_insert(next)
+        })
+        .collect()
+}
+
+/// 标记只由空行组成的变更组（`--ignore-blank-lines`）
+pub fn blank_only_changes(ops: &[DiffOp], old: &[&str], new: &[&str]) -> Vec<bool> {
+    let is_blank = |op: &DiffOp| match *op {
+        DiffOp::Delete { old: index } => old[index].trim().is_empty(),
+        DiffOp::Insert { new: index } => new[index].trim().is_empty(),
+        DiffOp::Equal { .. } => false,
+    };
+
+    let mut flags = vec![false; ops.len()];
+    let mut start = 0;
+    while start < ops.len() {
+        if !ops[start].is_change() {
+            start += 1;
+            continue;
+        }
+        let end = ops[start..].iter().position(|op| !op.is_change()).map_or(ops.len(), |offset| start + offset);
+        if ops[start..end].iter().all(is_blank) {
+            flags[start..end].iter_mut().for_each(|flag| *flag = true);
+        }
+        start = end;
+    }
+    flags
+}
+
+/// 编辑脚本构建器；各算法按顺序向 `ops` 追加
+struct Script<'a> {
+    a: &'a [u32],
+    b: &'a [u32],
+    ops: Vec<DiffOp>,
+}
+
+impl<'a> Script<'a> {
+    fn equal(&mut self, a_start: usize, b_start: usize, len: usize) {
+        self.ops.extend((0..len).map(|i| DiffOp::Equal { old: a_start + i, new: b_start + i }));
+    }
+
+    fn replace(&mut self, a_lo: usize, a_hi: usize, b_lo: usize, b_hi: usize) {
+        self.ops.extend((a_lo..a_hi).map(|old| DiffOp::Delete { old }));
+        self.ops.extend((b_lo..b_hi).map(|new| DiffOp::Insert { new }));
+    }
+
+    /// 去掉公共前后缀；返回剩余区间和后缀长度
+    fn trim(&mut self, a_lo: usize, a_hi: usize, b_lo: usize, b_hi: usize) -> (usize, usize, usize, usize, usize) {
+        let prefix = self.a[a_lo..a_hi]
+            .iter()
+            .zip(&self.b[b_lo..b_hi])
+            .take_while(|(x, y)| x == y)
+            .count();
+        self.equal(a_lo, b_lo, prefix);
+        let (a_lo, b_lo) = (a_lo + prefix, b_lo + prefix);
+
+        let suffix = self.a[a_lo..a_hi]
+            .iter()
+            .rev()
+            .zip(self.b[b_lo..b_hi].iter().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+        (a_lo, a_hi - suffix, b_lo, b_hi - suffix, suffix)
+    }
+
+    // ================== Myers / Minimal ==================
+
+    fn myers(&mut self, a_lo: usize, a_hi: usize, b_lo: usize, b_hi: usize, minimal: bool) {
+        let (a_lo, a_hi, b_lo, b_hi, suffix) = self.trim(a_lo, a_hi, b_lo, b_hi);
+
+        if a_lo == a_hi || b_lo == b_hi {
+            self.replace(a_lo, a_hi, b_lo, b_hi);
+        } else {
+            match self.bisect(a_lo, a_hi, b_lo, b_hi, minimal) {
+                Some((x, y)) => {
+                    self.myers(a_lo, x, b_lo, y, minimal);
+                    self.myers(x, a_hi, y, b_hi, minimal);
+                }
+                None => self.replace(a_lo, a_hi, b_lo, b_hi),
+            }
+        }
+
+        self.equal(a_hi, b_hi, suffix);
+    }
+
+    /// Linear-space middle snake search: returns a point on an optimal edit
+    /// path that splits the problem in two, or the furthest forward point once
+    /// the cost limit is hit (non-minimal only). `None` if no split exists.
+    fn bisect(&self, a_lo: usize, a_hi: usize, b_lo: usize, b_hi: usize, minimal: bool) -> Option<(usize, usize)> {
+        let a = &self.a[a_lo..a_hi];
+        let b = &self.b[b_lo..b_hi];
+        let (n, m) = (a.len() as isize, b.len() as isize);
+        let max_d = (n + m + 1) / 2;
+        let offset = max_d;
+        let len = 2 * max_d + 2;
+        let mut v1 = vec![-1isize; len as usize];
+        let mut v2 = vec![-1isize; len as usize];
+        v1[(offset + 1) as usize] = 0;
+        v2[(offset + 1) as usize] = 0;
+
+        let delta = n - m;
+        let front = delta % 2 != 0;
+        let cost_limit = ((n + m) as f64).sqrt() as isize;
+        let cost_limit = cost_limit.max(MYERS_MIN_COST_LIMIT as isize);
+        let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+        let split = |x: isize, y: isize| {
+            let point = (a_lo + x as usize, b_lo + y as usize);
+            (point != (a_lo, b_lo) && point != (a_hi, b_hi)).then_some(point)
+        };
+
+        for d in 0..max_d {
+            if !minimal && d > cost_limit {
+                // 取前向搜索走得最远的点作为分割点
+                let best = (0..len)
+                    .filter_map(|index| {
+                        let x = v1[index as usize];
+                        let y = x - (index - offset);
+                        (x >= 0 && x <= n && y >= 0 && y <= m).then_some((x, y))
+                    })
+                    .max_by_key(|(x, y)| x + y);
+                return best.and_then(|(x, y)| split(x, y));
+            }
+
+            let mut k1 = -d + k1_start;
+            while k1 <= d - k1_end {
+                let k1_offset = (offset + k1) as usize;
+                let mut x1 = if k1 == -d || (k1 != d && v1[k1_offset - 1] < v1[k1_offset + 1]) {
+                    v1[k1_offset + 1]
+                } else {
+                    v1[k1_offset - 1] + 1
+                };
+                let mut y1 = x1 - k1;
+                while x1 < n && y1 < m && a[x1 as usize] == b[y1 as usize] {
+                    x1 += 1;
+                    y1 += 1;
+                }
+                v1[k1_offset] = x1;
+                if x1 > n {
+                    k1_end += 2;
+                } else if y1 > m {
+                    k1_start += 2;
+                } else if front {
+                    let k2_offset = offset + delta - k1;
+                    if k2_offset >= 0 && k2_offset < len && v2[k2_offset as usize] != -1 {
+                        let x2 = n - v2[k2_offset as usize];
+                        if x1 >= x2 {
+                            return split(x1, y1);
+                        }
+                    }
+                }
+                k1 += 2;
+            }
+
+            let mut k2 = -d + k2_start;
+            while k2 <= d - k2_end {
+                let k2_offset = (offset + k2) as usize;
+                let mut x2 = if k2 == -d || (k2 != d && v2[k2_offset - 1] < v2[k2_offset + 1]) {
+                    v2[k2_offset + 1]
+                } else {
+                    v2[k2_offset - 1] + 1
+                };
+                let mut y2 = x2 - k2;
+                while x2 < n && y2 < m && a[(n - x2 - 1) as usize] == b[(m - y2 - 1) as usize] {
+                    x2 += 1;
+                    y2 += 1;
+                }
+                v2[k2_offset] = x2;
+                if x2 > n {
+                    k2_end += 2;
+                } else if y2 > m {
+                    k2_start += 2;
+                } else if !front {
+                    let k1_offset = offset + delta - k2;
+                    if k1_offset >= 0 && k1_offset < len && v1[k1_offset as usize] != -1 {
+                        let x1 = v1[k1_offset as usize];
+                        let y1 = offset + x1 - k1_offset;
+                        if x1 >= n - x2 {
+                            return split(x1, y1);
+                        }
+                    }
+                }
+                k2 += 2;
+            }
+        }
+
+        None
+    }
+
+    // ================== Patience ==================
+
+    /// Anchors the diff on lines that occur exactly once on both sides,
+    /// keeping the longest increasing run of them, and recurses between them
+    fn patience(&mut self, a_lo: usize, a_hi: usize, b_lo: usize, b_hi: usize) {
+        let (a_lo, a_hi, b_lo, b_hi, suffix) = self.trim(a_lo, a_hi, b_lo, b_hi);
+
+        if a_lo == a_hi || b_lo == b_hi {
+            self.replace(a_lo, a_hi, b_lo, b_hi);
+        } else {
+            let anchors = self.unique_anchors(a_lo, a_hi, b_lo, b_hi);
+            if anchors.is_empty() {
+                self.myers(a_lo, a_hi, b_lo, b_hi, false);
+            } else {
+                let (mut a_pos, mut b_pos) = (a_lo, b_lo);
+                for (a_index, b_index) in anchors {
+                    self.patience(a_pos, a_index, b_pos, b_index);
+                    self.equal(a_index, b_index, 1);
+                    a_pos = a_index + 1;
+                    b_pos = b_index + 1;
+                }
+                self.patience(a_pos, a_hi, b_pos, b_hi);
+            }
+        }
+
+        self.equal(a_hi, b_hi, suffix);
+    }
+
+    /// Longest increasing subsequence (patience sorting) of lines unique to both ranges
+    fn unique_anchors(&self, a_lo: usize, a_hi: usize, b_lo: usize, b_hi: usize) -> Vec<(usize, usize)> {
+        // line id -> (count in a, index in a, count in b, index in b)
+        let mut table: HashMap<u32, (usize, usize, usize, usize)> = HashMap::new();
+        for i in a_lo..a_hi {
+            let entry = table.entry(self.a[i]).or_insert((0, i, 0, 0));
+            entry.0 += 1;
+        }
+        for j in b_lo..b_hi {
+            if let Some(entry) = table.get_mut(&self.b[j]) {
+                entry.2 += 1;
+                entry.3 = j;
+            }
+        }
+        let mut pairs: Vec<(usize, usize)> = table
+            .into_values()
+            .filter(|&(a_count, _, b_count, _)| a_count == 1 && b_count == 1)
+            .map(|(_, a_index, _, b_index)| (a_index, b_index))
+            .collect();
+        pairs.sort_unstable();
+
+        // piles[i] = 长度为 i+1 的递增序列的最小结尾；back 记录前驱
+        let mut piles: Vec<usize> = Vec::new();
+        let mut back: Vec<Option<usize>> = vec![None; pairs.len()];
+        for (index, &(_, b_index)) in pairs.iter().enumerate() {
+            let pile = piles.partition_point(|&top| pairs[top].1 < b_index);
+            back[index] = pile.checked_sub(1).map(|previous| piles[previous]);
+            if pile == piles.len() {
+                piles.push(index);
+            } else {
+                piles[pile] = index;
+            }
+        }
+
+        let mut anchors = Vec::with_capacity(piles.len());
+        let mut cursor = piles.last().copied();
+        while let Some(index) = cursor {
+            anchors.push(pairs[index]);
+            cursor = back[index];
+        }
+        anchors.reverse();
+        anchors
+    }
+
+    // ================== Histogram ==================
+
+    /// git's histogram diff: split around the longest common run that
+    /// contains the rarest line, recursing to the left and looping right
+    fn histogram(&mut self, mut a_lo: usize, a_hi: usize, mut b_lo: usize, b_hi: usize) {
+        let (trim_a_lo, a_hi, trim_b_lo, b_hi, suffix) = self.trim(a_lo, a_hi, b_lo, b_hi);
+        a_lo = trim_a_lo;
+        b_lo = trim_b_lo;
+
+        loop {
+            if a_lo == a_hi || b_lo == b_hi {
+                self.replace(a_lo, a_hi, b_lo, b_hi);
+                break;
+            }
+            match self.histogram_region(a_lo, a_hi, b_lo, b_hi) {
+                Some((a_start, b_start, len)) => {
+                    self.histogram(a_lo, a_start, b_lo, b_start);
+                    self.equal(a_start, b_start, len);
+                    a_lo = a_start + len;
+                    b_lo = b_start + len;
+                }
+                None => {
+                    self.myers(a_lo, a_hi, b_lo, b_hi, false);
+                    break;
+                }
+            }
+        }
+
+        self.equal(a_hi, b_hi, suffix);
+    }
+
+    /// Common run `(a_start, b_start, len)` whose rarest line occurs least
+    /// often in `a`, the longest one on ties
+    fn histogram_region(&self, a_lo: usize, a_hi: usize, b_lo: usize, b_hi: usize) -> Option<(usize, usize, usize)> {
+        let mut occurrences: HashMap<u32, Vec<usize>> = HashMap::new();
+        for i in a_lo..a_hi {
+            occurrences.entry(self.a[i]).or_default().push(i);
+        }
+        let count = |line: u32| occurrences.get(&line).map_or(0, Vec::len);
+
+        let mut best: Option<(usize, usize, usize, usize)> = None; // (rarity, a, b, len)
+        let mut j = b_lo;
+        while j < b_hi {
+            let mut next_j = j + 1;
+            let Some(positions) = occurrences.get(&self.b[j]) else {
+                j = next_j;
+                continue;
+            };
+            if positions.len() > HISTOGRAM_MAX_CHAIN {
+                j = next_j;
+                continue;
+            }
+            for &i in positions {
+                let (mut a_start, mut b_start) = (i, j);
+                let mut rarity = positions.len();
+                while a_start > a_lo && b_start > b_lo && self.a[a_start - 1] == self.b[b_start - 1] {
+                    a_start -= 1;
+                    b_start -= 1;
+                    rarity = rarity.min(count(self.a[a_start]));
+                }
+                let (mut a_end, mut b_end) = (i + 1, j + 1);
+                while a_end < a_hi && b_end < b_hi && self.a[a_end] == self.b[b_end] {
+                    rarity = rarity.min(count(self.a[a_end]));
+                    a_end += 1;
+                    b_end += 1;
+                }
+
+                let len = a_end - a_start;
+                let better = match best {
+                    None => true,
+                    Some((best_rarity, _, _, best_len)) => rarity < best_rarity || (rarity == best_rarity && len > best_len),
+                };
+                if better {
+                    best = Some((rarity, a_start, b_start, len));
+                }
+                next_j = next_j.max(b_end);
+            }
+            j = next_j;
+        }
+
+        best.map(|(_, a_start, b_start, len)| (a_start, b_start, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders an edit script as ` a`, `-b`, `+c` lines
+    fn render(old: &[&str], new: &[&str], algorithm: DiffAlgorithm, whitespace: WhitespaceMode) -> Vec<String> {
+        diff_lines(old, new, algorithm, whitespace)
+            .into_iter()
+            .map(|op| match op {
+                DiffOp::Equal { new: index, .. } => format!(" {}", new[index]),
+                DiffOp::Delete { old: index } => format!("-{}", old[index]),
+                DiffOp::Insert { new: index } => format!("+{}", new[index]),
+            })
+            .collect()
+    }
+
+    /// Applying the script to `old` must give `new`
+    fn assert_valid(old: &[&str], new: &[&str], ops: &[DiffOp]) {
+        let (mut a, mut b) = (0, 0);
+        for op in ops {
+            match *op {
+                DiffOp::Equal { old: i, new: j } => {
+                    assert_eq!((i, j), (a, b));
+                    a += 1;
+                    b += 1;
+                }
+                DiffOp::Delete { old: i } => {
+                    assert_eq!(i, a);
+                    a += 1;
+                }
+                DiffOp::Insert { new: j } => {
+                    assert_eq!(j, b);
+                    b += 1;
+                }
+            }
+        }
+        assert_eq!((a, b), (old.len(), new.len()));
+    }
+
+    #[test]
+    fn test_algorithms_produce_valid_scripts() {
+        let old: Vec<String> = (0..300).map(|i| format!("line {}", (i * 7) % 31)).collect();
+        let new: Vec<String> = (0..280).map(|i| format!("line {}", (i * 5) % 29)).collect();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+
+        let minimal = diff_lines(&old, &new, DiffAlgorithm::Minimal, WhitespaceMode::Exact);
+        let minimal_cost = minimal.iter().filter(|op| op.is_change()).count();
+        for algorithm in [DiffAlgorithm::Myers, DiffAlgorithm::Minimal, DiffAlgorithm::Patience, DiffAlgorithm::Histogram] {
+            let ops = diff_lines(&old, &new, algorithm, WhitespaceMode::Exact);
+            assert_valid(&old, &new, &ops);
+            assert!(ops.iter().filter(|op| op.is_change()).count() >= minimal_cost);
+        }
+    }
+
+    #[test]
+    fn test_patience_and_histogram_keep_functions_together() {
+        // Myers matches the braces; patience/histogram anchor on the unique signatures
+        let old = ["fn a() {", "    one();", "}", "", "fn b() {", "    two();", "}"];
+        let new = ["fn b() {", "    two();", "}", "", "fn c() {", "    three();", "}", "", "fn a() {", "    one();", "}"];
+
+        for algorithm in [DiffAlgorithm::Patience, DiffAlgorithm::Histogram] {
+            let lines = render(&old, &new, algorithm, WhitespaceMode::Exact);
+            let kept: Vec<&String> = lines.iter().filter(|line| line.starts_with(' ')).collect();
+            assert!(kept.contains(&&" fn b() {".to_string()), "{:?}: {:?}", algorithm, lines);
+            assert!(kept.contains(&&"     two();".to_string()), "{:?}: {:?}", algorithm, lines);
+        }
+    }
+
+    #[test]
+    fn test_whitespace_modes() {
+        let old = ["let x = 1;", "if  a {", "\tcall();"];
+        let new = ["let x = 1;   ", "if a {", "call();"];
+        let changes = |mode| {
+            diff_lines(&old, &new, DiffAlgorithm::Myers, mode)
+                .iter()
+                .filter(|op| op.is_change())
+                .count()
+        };
+        assert_eq!(changes(WhitespaceMode::Exact), 6);
+        assert_eq!(changes(WhitespaceMode::IgnoreEol), 4);
+        assert_eq!(changes(WhitespaceMode::IgnoreChange), 2);
+        assert_eq!(changes(WhitespaceMode::IgnoreAll), 0);
+    }
+
+    #[test]
+    fn test_blank_only_changes() {
+        let old = ["a", "b", "c"];
+        let new = ["a", "", "b", "C"];
+        let ops = diff_lines(&old, &new, DiffAlgorithm::Myers, WhitespaceMode::Exact);
+        let flags = blank_only_changes(&ops, &old, &new);
+        let ignored: Vec<DiffOp> = ops.iter().zip(&flags).filter(|(_, flag)| **flag).map(|(op, _)| *op).collect();
+        assert_eq!(ignored, vec![DiffOp::Insert { new: 1 }]);
+    }
+}
@@ -0,0 +1,753 @@
+//! 差异导出：独立 HTML 页面（并排布局、主题配色）、可 `git apply` 的补丁和 Markdown
+//!
+//! HTML 和 Markdown 使用处理后的差异块（空白规则、折叠、函数名）；补丁直接
+//! 使用 git 打印的原始字节，文件模式、CRLF、`\ No newline at end of file`
+//! 和二进制内容都原样保留。只有不来自修订差异的单个文件才按差异行重建补丁。
+//!
+//! AI 审查结果（[`AnalysisFinding`]）作为注释写入导出：HTML 和 Markdown 中
+//! 显示在对应行之后，补丁中写在每个文件的 `diff --git` 之前，`git apply` 会忽略这些行。
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    stream::{self, StreamSettings, StreamedDiff},
+    DiffLine, DiffLineType, DiffProcessor, DiffProcessorConfig, FileDiff, FileStatus,
+};
+use crate::{
+    ai::agents::AnalysisFinding,
+    error::{AppError, AppResult},
+    git::{content::describe_change, ChangeStatus, FuncnamePattern, GitService, RevisionDiff, RevisionFileChange},
+    ui::theme::Theme,
+};
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Html,
+    Patch,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Html => "HTML",
+            ExportFormat::Patch => "patch",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Patch => "patch",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    /// 解析格式名或扩展名
+    pub fn parse(name: &str) -> AppResult<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "html" | "htm" => Ok(ExportFormat::Html),
+            "patch" | "diff" => Ok(ExportFormat::Patch),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            other => Err(AppError::InvalidOperation(format!(
+                "Unknown export format '{}' (expected html, patch or markdown)",
+                other
+            ))),
+        }
+    }
+}
+
+/// 一条审查注释
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewAnnotation {
+    /// 文件路径（仓库相对或绝对路径）
+    pub path: String,
+    /// 新文件中的行号
+    pub line: Option<usize>,
+    pub severity: String,
+    pub title: String,
+    pub body: String,
+    pub suggestion: Option<String>,
+}
+
+impl From<&AnalysisFinding> for ReviewAnnotation {
+    fn from(finding: &AnalysisFinding) -> Self {
+        Self {
+            path: finding.file.display().to_string(),
+            line: finding.line,
+            severity: format!("{:?}", finding.severity),
+            title: finding.title.clone(),
+            body: finding.description.clone(),
+            suggestion: finding.suggested_fix.clone(),
+        }
+    }
+}
+
+impl ReviewAnnotation {
+    /// 是否属于 `path`（按路径组件的后缀匹配，兼容绝对路径）
+    fn applies_to(&self, path: &str) -> bool {
+        !path.is_empty() && Path::new(&self.path).ends_with(path)
+    }
+
+    fn summary(&self) -> String {
+        match self.line {
+            Some(line) => format!("[{}] line {}: {}", self.severity, line, self.title),
+            None => format!("[{}] {}", self.severity, self.title),
+        }
+    }
+}
+
+/// 从代理结果（`CodeAnalysisResult`、发现列表或注释列表的 JSON）中提取审查注释
+pub fn annotations_from_json(value: &serde_json::Value) -> Vec<ReviewAnnotation> {
+    let findings = value.get("findings").unwrap_or(value);
+    if let Ok(findings) = serde_json::from_value::<Vec<AnalysisFinding>>(findings.clone()) {
+        return findings.iter().map(ReviewAnnotation::from).collect();
+    }
+    serde_json::from_value::<Vec<ReviewAnnotation>>(findings.clone()).unwrap_or_default()
+}
+
+/// 导出中的一个文件
+#[derive(Debug, Clone)]
+pub struct ExportFile {
+    /// 新增文件为 None
+    pub old_path: Option<String>,
+    /// 删除文件为 None
+    pub new_path: Option<String>,
+    pub diff: FileDiff,
+    /// 行差异之外的信息（二进制摘要、编码、换行符）
+    pub notices: Vec<String>,
+    /// git 打印的补丁；为 None 时按差异行重建
+    pub patch: Option<Vec<u8>>,
+}
+
+impl ExportFile {
+    pub fn path(&self) -> &str {
+        self.new_path.as_deref().or(self.old_path.as_deref()).unwrap_or_default()
+    }
+
+    fn display_path(&self) -> String {
+        match (&self.old_path, &self.new_path) {
+            (Some(old), Some(new)) if old != new => format!("{} → {}", old, new),
+            _ => self.path().to_string(),
+        }
+    }
+}
+
+/// 一次导出的全部内容
+#[derive(Debug, Clone, Default)]
+pub struct DiffExport {
+    pub title: String,
+    pub files: Vec<ExportFile>,
+    pub annotations: Vec<ReviewAnnotation>,
+}
+
+impl DiffExport {
+    pub fn render(&self, format: ExportFormat, theme: &Theme) -> Vec<u8> {
+        match format {
+            ExportFormat::Html => self.render_html(theme).into_bytes(),
+            ExportFormat::Patch => self.render_patch(),
+            ExportFormat::Markdown => self.render_markdown().into_bytes(),
+        }
+    }
+
+    fn totals(&self) -> (usize, usize) {
+        self.files.iter().fold((0, 0), |(added, deleted), file| {
+            (added + file.diff.stats.lines_added, deleted + file.diff.stats.lines_deleted)
+        })
+    }
+
+    fn annotations_for<'a>(&'a self, file: &ExportFile) -> Vec<&'a ReviewAnnotation> {
+        self.annotations.iter().filter(|a| a.applies_to(file.path())).collect()
+    }
+
+    /// 不属于任何导出文件的注释
+    fn general_annotations(&self) -> Vec<&ReviewAnnotation> {
+        self.annotations
+            .iter()
+            .filter(|a| !self.files.iter().any(|file| a.applies_to(file.path())))
+            .collect()
+    }
+
+    /// 注释是否落在差异中可见的某一行上
+    fn is_placed(annotation: &ReviewAnnotation, file: &ExportFile) -> bool {
+        annotation.line.is_some_and(|line| {
+            file.diff.hunks.iter().flat_map(|h| &h.lines).any(|l| l.new_line_number == Some(line))
+        })
+    }
+
+    fn render_patch(&self) -> Vec<u8> {
+        let mut out = String::new();
+        for annotation in self.general_annotations() {
+            let _ = writeln!(out, "# {}", annotation.summary());
+        }
+        let mut bytes = out.into_bytes();
+        for file in &self.files {
+            let mut out = String::new();
+            for annotation in self.annotations_for(file) {
+                let _ = writeln!(out, "# {}: {}", file.path(), annotation.summary());
+                for line in annotation.body.lines() {
+                    let _ = writeln!(out, "#   {}", line);
+                }
+            }
+            bytes.extend(out.into_bytes());
+            match &file.patch {
+                Some(patch) => bytes.extend_from_slice(patch),
+                None => bytes.extend(Self::rebuilt_patch(file).into_bytes()),
+            }
+        }
+        bytes
+    }
+
+    /// 按差异行重建一个文件的补丁（没有 git 补丁时）
+    fn rebuilt_patch(file: &ExportFile) -> String {
+        let mut out = String::new();
+        let old = file.old_path.as_deref().unwrap_or_else(|| file.path());
+        let new = file.new_path.as_deref().unwrap_or_else(|| file.path());
+        let _ = writeln!(out, "diff --git a/{} b/{}", old, new);
+        match (&file.old_path, &file.new_path) {
+            (None, _) => out.push_str("new file mode 100644\n"),
+            (_, None) => out.push_str("deleted file mode 100644\n"),
+            (Some(old), Some(new)) if old != new => {
+                let _ = writeln!(out, "rename from {}\nrename to {}", old, new);
+            }
+            _ => {}
+        }
+        let old_label = file.old_path.as_ref().map_or("/dev/null".to_string(), |p| format!("a/{}", p));
+        let new_label = file.new_path.as_ref().map_or("/dev/null".to_string(), |p| format!("b/{}", p));
+        if file.diff.is_binary {
+            let _ = writeln!(out, "Binary files {} and {} differ", old_label, new_label);
+            return out;
+        }
+        if file.diff.hunks.is_empty() {
+            return out;
+        }
+        let _ = writeln!(out, "--- {}\n+++ {}", old_label, new_label);
+        for hunk in &file.diff.hunks {
+            let _ = writeln!(out, "{}", hunk.header);
+            for line in &hunk.lines {
+                let _ = writeln!(out, "{}{}", line_prefix(line), line.content);
+            }
+        }
+        out
+    }
+
+    fn render_markdown(&self) -> String {
+        let (added, deleted) = self.totals();
+        let mut out = format!("# {}\n\n{} files changed, +{} −{}\n", self.title, self.files.len(), added, deleted);
+
+        let general = self.general_annotations();
+        if !general.is_empty() {
+            out.push_str("\n## Review notes\n\n");
+            for annotation in general {
+                markdown_annotation(&mut out, annotation);
+            }
+        }
+
+        for file in &self.files {
+            let _ = write!(out, "\n## `{}` (+{} −{})\n\n", file.display_path(), file.diff.stats.lines_added, file.diff.stats.lines_deleted);
+            for notice in &file.notices {
+                let _ = writeln!(out, "> {}", notice);
+            }
+            if !file.notices.is_empty() {
+                out.push('\n');
+            }
+
+            if !file.diff.hunks.is_empty() {
+                let body: String = file
+                    .diff
+                    .hunks
+                    .iter()
+                    .flat_map(|hunk| {
+                        std::iter::once(format!("{}\n", hunk.header))
+                            .chain(hunk.lines.iter().map(|line| format!("{}{}\n", line_prefix(line), line.content)))
+                    })
+                    .collect();
+                let fence = fence_for(&body);
+                let _ = write!(out, "{}diff\n{}{}\n", fence, body, fence);
+            }
+
+            let annotations = self.annotations_for(file);
+            if !annotations.is_empty() {
+                out.push_str("\n**Review notes**\n\n");
+                for annotation in annotations {
+                    markdown_annotation(&mut out, annotation);
+                }
+            }
+        }
+        out
+    }
+
+    fn render_html(&self, theme: &Theme) -> String {
+        let colors = &theme.colors;
+        let background = rgb(colors.background).unwrap_or((30, 30, 30));
+        let css = |color: Color, fallback: (u8, u8, u8)| hex(rgb(color).unwrap_or(fallback));
+        let tint = |color: Color, fallback: (u8, u8, u8), alpha: f32| hex(blend(background, rgb(color).unwrap_or(fallback), alpha));
+
+        let (added, deleted) = self.totals();
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+             body {{ background: {bg}; color: {fg}; font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 13px; margin: 1.5em; }}\n\
+             h1, h2 {{ color: {primary}; font-weight: 600; }}\n\
+             .summary, .num {{ color: {muted}; }}\n\
+             .file {{ border: 1px solid {muted}; margin: 1.5em 0; }}\n\
+             .file h2 {{ font-size: 14px; margin: 0; padding: .5em .8em; border-bottom: 1px solid {muted}; }}\n\
+             .stat-add {{ color: {success}; }} .stat-del {{ color: {error}; }}\n\
+             .notice {{ color: {warning}; margin: .3em .8em; }}\n\
+             table {{ width: 100%; border-collapse: collapse; table-layout: fixed; }}\n\
+             td {{ padding: 0 .5em; vertical-align: top; }}\n\
+             td.num {{ width: 4em; text-align: right; user-select: none; }}\n\
+             td.code {{ white-space: pre-wrap; word-break: break-all; }}\n\
+             tr.hunk td {{ color: {info}; background: {hunk_bg}; padding: .2em .5em; }}\n\
+             td.add {{ background: {add_bg}; }} td.del {{ background: {del_bg}; }}\n\
+             td.add .hl {{ background: {add_hl}; }} td.del .hl {{ background: {del_hl}; }}\n\
+             .annotation {{ border-left: 3px solid {accent}; background: {note_bg}; margin: .3em 0; padding: .3em .8em; white-space: normal; }}\n\
+             .annotation .severity {{ color: {accent}; font-weight: 600; }}\n\
+             .annotation pre {{ margin: .3em 0; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n\
+             <p class=\"summary\">{files} files changed, <span class=\"stat-add\">+{added}</span> <span class=\"stat-del\">−{deleted}</span></p>\n",
+            title = escape_html(&self.title),
+            bg = hex(background),
+            fg = css(colors.foreground, (220, 220, 220)),
+            primary = css(colors.primary, (97, 175, 239)),
+            muted = css(colors.muted, (128, 128, 128)),
+            success = css(colors.success, (80, 200, 120)),
+            error = css(colors.error, (224, 108, 117)),
+            warning = css(colors.warning, (229, 192, 123)),
+            info = css(colors.info, (86, 182, 194)),
+            accent = css(colors.accent, (198, 120, 221)),
+            hunk_bg = tint(colors.info, (86, 182, 194), 0.12),
+            add_bg = tint(colors.success, (80, 200, 120), 0.18),
+            del_bg = tint(colors.error, (224, 108, 117), 0.18),
+            add_hl = tint(colors.success, (80, 200, 120), 0.45),
+            del_hl = tint(colors.error, (224, 108, 117), 0.45),
+            note_bg = tint(colors.accent, (198, 120, 221), 0.10),
+            files = self.files.len(),
+            added = added,
+            deleted = deleted,
+        );
+
+        for annotation in self.general_annotations() {
+            html_annotation(&mut out, annotation);
+        }
+
+        for file in &self.files {
+            let _ = write!(
+                out,
+                "<section class=\"file\">\n<h2>{} <span class=\"stat-add\">+{}</span> <span class=\"stat-del\">−{}</span></h2>\n",
+                escape_html(&file.display_path()),
+                file.diff.stats.lines_added,
+                file.diff.stats.lines_deleted
+            );
+            for notice in &file.notices {
+                let _ = writeln!(out, "<div class=\"notice\">{}</div>", escape_html(notice));
+            }
+            let annotations = self.annotations_for(file);
+            for annotation in annotations.iter().filter(|a| !Self::is_placed(a, file)) {
+                html_annotation(&mut out, annotation);
+            }
+
+            if !file.diff.hunks.is_empty() {
+                out.push_str("<table>\n");
+                for hunk in &file.diff.hunks {
+                    let _ = writeln!(out, "<tr class=\"hunk\"><td colspan=\"4\">{}</td></tr>", escape_html(&hunk.header));
+                    for (old, new) in side_by_side_rows(&hunk.lines) {
+                        let _ = writeln!(out, "<tr>{}{}</tr>", html_cell(old, true), html_cell(new, false));
+                        // 注释显示在对应的新文件行之后
+                        let line = new.and_then(|l| l.new_line_number);
+                        for annotation in annotations.iter().filter(|a| line.is_some() && a.line == line) {
+                            out.push_str("<tr><td colspan=\"4\">");
+                            html_annotation(&mut out, annotation);
+                            out.push_str("</td></tr>\n");
+                        }
+                    }
+                }
+                out.push_str("</table>\n");
+            }
+            out.push_str("</section>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn line_prefix(line: &DiffLine) -> char {
+    match line.line_type {
+        DiffLineType::Added => '+',
+        DiffLineType::Deleted => '-',
+        DiffLineType::Context | DiffLineType::Modified => ' ',
+    }
+}
+
+/// 比内容中最长的反引号序列更长的代码围栏
+fn fence_for(body: &str) -> String {
+    let longest = body
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn markdown_annotation(out: &mut String, annotation: &ReviewAnnotation) {
+    let location = annotation.line.map(|line| format!(" · line {}", line)).unwrap_or_default();
+    let _ = writeln!(out, "- **{}**{} — {}", annotation.severity, location, annotation.title);
+    for line in annotation.body.lines() {
+        let _ = writeln!(out, "  {}", line);
+    }
+    if let Some(suggestion) = &annotation.suggestion {
+        let fence = fence_for(suggestion);
+        let _ = writeln!(out, "\n  Suggested fix:\n\n  {}", fence);
+        for line in suggestion.lines() {
+            let _ = writeln!(out, "  {}", line);
+        }
+        let _ = writeln!(out, "  {}", fence);
+    }
+}
+
+fn html_annotation(out: &mut String, annotation: &ReviewAnnotation) {
+    let location = annotation.line.map(|line| format!(" line {}", line)).unwrap_or_default();
+    let _ = write!(
+        out,
+        "<div class=\"annotation\"><span class=\"severity\">{}</span>{} — {}",
+        escape_html(&annotation.severity),
+        location,
+        escape_html(&annotation.title)
+    );
+    if !annotation.body.is_empty() {
+        let _ = write!(out, "<div>{}</div>", escape_html(&annotation.body));
+    }
+    if let Some(suggestion) = &annotation.suggestion {
+        let _ = write!(out, "<pre>{}</pre>", escape_html(suggestion));
+    }
+    out.push_str("</div>\n");
+}
+
+/// 将块内的行配成左右两列：上下文两侧都有，删除与紧随其后的添加逐行配对
+fn side_by_side_rows(lines: &[DiffLine]) -> Vec<(Option<&DiffLine>, Option<&DiffLine>)> {
+    let mut rows = Vec::new();
+    let mut deleted: Vec<&DiffLine> = Vec::new();
+    let mut added: Vec<&DiffLine> = Vec::new();
+    type Row<'a> = (Option<&'a DiffLine>, Option<&'a DiffLine>);
+    fn flush<'a>(rows: &mut Vec<Row<'a>>, deleted: &mut Vec<&'a DiffLine>, added: &mut Vec<&'a DiffLine>) {
+        for i in 0..deleted.len().max(added.len()) {
+            rows.push((deleted.get(i).copied(), added.get(i).copied()));
+        }
+        deleted.clear();
+        added.clear();
+    }
+
+    for line in lines {
+        match line.line_type {
+            DiffLineType::Deleted => {
+                if !added.is_empty() {
+                    flush(&mut rows, &mut deleted, &mut added);
+                }
+                deleted.push(line);
+            }
+            DiffLineType::Added => added.push(line),
+            _ => {
+                flush(&mut rows, &mut deleted, &mut added);
+                // 被忽略的空行变更只在有该行的一侧显示
+                rows.push((line.old_line_number.map(|_| line), line.new_line_number.map(|_| line)));
+            }
+        }
+    }
+    flush(&mut rows, &mut deleted, &mut added);
+    rows
+}
+
+fn html_cell(line: Option<&DiffLine>, is_old: bool) -> String {
+    let Some(line) = line else {
+        return "<td class=\"num\"></td><td class=\"code\"></td>".to_string();
+    };
+    let number = if is_old { line.old_line_number } else { line.new_line_number };
+    let class = match line.line_type {
+        DiffLineType::Added => "code add",
+        DiffLineType::Deleted => "code del",
+        _ => "code",
+    };
+    format!(
+        "<td class=\"num\">{}</td><td class=\"{}\">{}</td>",
+        number.map(|n| n.to_string()).unwrap_or_default(),
+        class,
+        highlighted_html(&line.content, &line.highlights)
+    )
+}
+
+/// 转义内容，并用 `<span class="hl">` 标出行内变化的字节范围
+fn highlighted_html(content: &str, ranges: &[(usize, usize)]) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut position = 0;
+    for &(start, end) in ranges {
+        let (start, end) = (start.max(position).min(content.len()), end.min(content.len()));
+        if start >= end || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+            continue;
+        }
+        out.push_str(&escape_html(&content[position..start]));
+        let _ = write!(out, "<span class=\"hl\">{}</span>", escape_html(&content[start..end]));
+        position = end;
+    }
+    out.push_str(&escape_html(&content[position..]));
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 终端颜色对应的 RGB 值（命名颜色按 xterm 默认值）
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    Some(match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 49, 49),
+        Color::Green => (13, 188, 121),
+        Color::Yellow => (229, 229, 16),
+        Color::Blue => (36, 114, 200),
+        Color::Magenta => (188, 63, 188),
+        Color::Cyan => (17, 168, 205),
+        Color::Gray => (204, 204, 204),
+        Color::DarkGray => (118, 118, 118),
+        Color::LightRed => (241, 76, 76),
+        Color::LightGreen => (35, 209, 139),
+        Color::LightYellow => (245, 245, 67),
+        Color::LightBlue => (59, 142, 234),
+        Color::LightMagenta => (214, 112, 214),
+        Color::LightCyan => (41, 184, 219),
+        Color::White => (229, 229, 229),
+        _ => return None,
+    })
+}
+
+fn blend(base: (u8, u8, u8), over: (u8, u8, u8), alpha: f32) -> (u8, u8, u8) {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * alpha).round() as u8;
+    (mix(base.0, over.0), mix(base.1, over.1), mix(base.2, over.2))
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+pub(crate) fn file_status(status: &ChangeStatus) -> FileStatus {
+    match status {
+        ChangeStatus::Added => FileStatus::Added,
+        ChangeStatus::Deleted => FileStatus::Deleted,
+        ChangeStatus::Renamed => FileStatus::Renamed,
+        ChangeStatus::Copied => FileStatus::Copied,
+        ChangeStatus::Modified | ChangeStatus::TypeChanged => FileStatus::Modified,
+    }
+}
+
+/// 超大文件与查看器一样由 `git diff` 生成，等补丁全部索引后读出
+async fn streamed_file(
+    git_service: &GitService,
+    diff: &RevisionDiff,
+    change: &RevisionFileChange,
+    config: &DiffProcessorConfig,
+    path: &Path,
+) -> AppResult<ExportFile> {
+    let source = git_service.patch_source(&diff.spec, change, stream::git_options(config, path))?;
+    let settings = StreamSettings {
+        grammar: None,
+        max_highlight_size: 0,
+        intraline: config
+            .word_level_diff
+            .then_some((config.intraline_granularity, config.max_intraline_length)),
+        cache_bytes: 0,
+    };
+    let streamed = StreamedDiff::start(source, config.context_lines, settings)?;
+    while !streamed.stream().is_complete() {
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let (binary, error) = streamed.stream().with_index(|index| (index.binary, index.error.clone()));
+    if let Some(error) = error {
+        return Err(AppError::application(error));
+    }
+
+    Ok(ExportFile {
+        old_path: change.old_path.clone(),
+        new_path: change.new_path.clone(),
+        diff: FileDiff {
+            old_path: change.old_path.as_ref().map(PathBuf::from),
+            new_path: change.new_path.as_ref().map(PathBuf::from),
+            status: file_status(&change.status),
+            hunks: streamed.load_all()?,
+            stats: streamed.stats(),
+            is_binary: binary,
+        },
+        notices: Vec::new(),
+        patch: None,
+    })
+}
+
+/// 计算修订差异中每个文件的差异，组成一次导出
+pub async fn revision_export(git_service: &GitService, diff: &RevisionDiff, config: DiffProcessorConfig) -> AppResult<DiffExport> {
+    let mut processor = DiffProcessor::new(config.clone());
+    let mut patches = git_service.revision_patches(&diff.spec).await?;
+    let mut files = Vec::with_capacity(diff.files.len());
+    for change in &diff.files {
+        let path = std::path::PathBuf::from(change.path());
+        let patch = patches
+            .iter()
+            .position(|patch| patch.old_path == change.old_path && patch.new_path == change.new_path)
+            .map(|index| patches.swap_remove(index).text);
+        if git_service.revision_file_size(&diff.spec, change).await? > config.stream_threshold as u64 {
+            let mut file = streamed_file(git_service, diff, change, &config, &path).await?;
+            file.patch = patch;
+            files.push(file);
+            continue;
+        }
+
+        let (old, new) = git_service.revision_file_sides(&diff.spec, change).await?;
+        let driver = new.driver.as_ref().or(old.driver.as_ref());
+        processor.set_funcname(
+            driver
+                .and_then(|driver| driver.funcname.as_deref())
+                .and_then(|pattern| FuncnamePattern::parse(pattern).ok()),
+        );
+        let mut file_diff = processor.compute_diff(&old.text, &new.text, &path).await?;
+        file_diff.is_binary = !old.is_text() || !new.is_text();
+        file_diff.status = file_status(&change.status);
+        files.push(ExportFile {
+            old_path: change.old_path.clone(),
+            new_path: change.new_path.clone(),
+            diff: file_diff,
+            notices: describe_change(&old, &new),
+            patch,
+        });
+    }
+    Ok(DiffExport {
+        title: diff.spec.label(),
+        files,
+        annotations: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::diff::{DiffHunk, DiffStats};
+
+    fn sample() -> DiffExport {
+        let line = |line_type, old: Option<usize>, new: Option<usize>, content: &str| DiffLine {
+            line_type,
+            old_line_number: old,
+            new_line_number: new,
+            content: content.to_string(),
+            highlights: vec![],
+        };
+        let mut added = line(DiffLineType::Added, None, Some(2), "let x = <b>;");
+        added.highlights = vec![(8, 11)];
+        let diff = FileDiff {
+            old_path: Some("src/a.rs".into()),
+            new_path: Some("src/a.rs".into()),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,2 +1,2 @@ fn main".to_string(),
+                old_start: 1,
+                old_lines: 2,
+                new_start: 1,
+                new_lines: 2,
+                lines: vec![
+                    line(DiffLineType::Context, Some(1), Some(1), "fn main() {"),
+                    line(DiffLineType::Deleted, Some(2), None, "let x = 1;"),
+                    added,
+                ],
+            }],
+            stats: DiffStats { lines_added: 1, lines_deleted: 1, files_changed: 1, ..Default::default() },
+            is_binary: false,
+        };
+        DiffExport {
+            title: "HEAD^..HEAD".to_string(),
+            files: vec![ExportFile {
+                old_path: Some("src/a.rs".to_string()),
+                new_path: Some("src/a.rs".to_string()),
+                diff,
+                notices: vec![],
+                patch: None,
+            }],
+            annotations: vec![ReviewAnnotation {
+                path: "/repo/src/a.rs".to_string(),
+                line: Some(2),
+                severity: "High".to_string(),
+                title: "Unescaped markup".to_string(),
+                body: "x is rendered".to_string(),
+                suggestion: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_patch_keeps_annotations_outside_hunks() {
+        let mut export = sample();
+        let patch = export.render(ExportFormat::Patch, &Theme::dark_theme());
+        assert_eq!(
+            String::from_utf8(patch).unwrap(),
+            "# src/a.rs: [High] line 2: Unescaped markup\n#   x is rendered\n\
+             diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+             @@ -1,2 +1,2 @@ fn main\n fn main() {\n-let x = 1;\n+let x = <b>;\n"
+        );
+
+        // git 打印的补丁原样写出，不再从差异行重建
+        let raw = b"diff --git a/src/a.rs b/src/a.rs\nold mode 100644\nnew mode 100755\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+                    @@ -2 +2 @@\n-let x = 1;\r\n\\ No newline at end of file\n+let x = \xff;\r\n";
+        export.files[0].patch = Some(raw.to_vec());
+        let patch = export.render(ExportFormat::Patch, &Theme::dark_theme());
+        assert!(patch.ends_with(raw));
+        assert!(patch.starts_with(b"# src/a.rs: [High] line 2"));
+    }
+
+    #[test]
+    fn test_markdown_and_html() {
+        let export = sample();
+        let markdown = String::from_utf8(export.render(ExportFormat::Markdown, &Theme::dark_theme())).unwrap();
+        assert!(markdown.contains("## `src/a.rs` (+1 −1)\n\n```diff\n@@ -1,2 +1,2 @@ fn main\n"));
+        assert!(markdown.contains("- **High** · line 2 — Unescaped markup\n"));
+
+        let html = String::from_utf8(export.render(ExportFormat::Html, &Theme::dark_theme())).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        // 删除和添加配成同一行，行内变化被标出并转义
+        assert!(html.contains(
+            "<tr><td class=\"num\">2</td><td class=\"code del\">let x = 1;</td>\
+             <td class=\"num\">2</td><td class=\"code add\">let x = <span class=\"hl\">&lt;b&gt;</span>;</td></tr>\n\
+             <tr><td colspan=\"4\"><div class=\"annotation\">"
+        ));
+    }
+
+    #[test]
+    fn test_annotations_from_json() {
+        let value = serde_json::json!({
+            "findings": [{
+                "id": "1", "title": "Leak", "description": "File handle leaks", "severity": "Medium",
+                "file": "src/a.rs", "line": 3, "column": null, "category": "Bug",
+                "suggested_fix": "Close it", "code_snippet": null
+            }]
+        });
+        let annotations = annotations_from_json(&value);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!((annotations[0].severity.as_str(), annotations[0].line), ("Medium", Some(3)));
+        assert!(annotations_from_json(&serde_json::json!({"other": 1})).is_empty());
+        assert_eq!(fence_for("a ```` b"), "`````");
+    }
+}
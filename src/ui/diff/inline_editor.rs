@@ -1,3 +1,4 @@
+use super::{syntax, SyntaxHighlighter, SyntaxHighlighterConfig};
 use crate::{
     error::{AppError, AppResult},
     ui::theme::Theme,
@@ -111,6 +112,8 @@ pub struct InlineEditorConfig {
     pub show_invisible: bool,
     /// 启用语法高亮
     pub syntax_highlighting: bool,
+    /// 语法配色：`theme` 跟随 UI 主题，或内置配色名
+    pub syntax_theme: String,
     /// 自动缩进
     pub auto_indent: bool,
     /// 最大撤销历史
@@ -127,6 +130,7 @@ impl Default for InlineEditorConfig {
             show_line_numbers: true,
             show_invisible: false,
             syntax_highlighting: true,
+            syntax_theme: syntax::THEME_PALETTE.to_string(),
             auto_indent: true,
             max_undo_history: 100,
         }
@@ -144,12 +148,24 @@ pub enum EditorMode {
     Select,
 }
 
+/// 行内诊断信息（例如提交信息 lint 结果），显示在对应行末尾
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineDiagnostic {
+    /// 从 0 开始的行号
+    pub line: usize,
+    pub message: String,
+    /// 错误（否则为警告）
+    pub is_error: bool,
+}
+
 /// 内联编辑器
 pub struct InlineEditor {
     /// 配置
     config: InlineEditorConfig,
     /// 文件路径
     file_path: Option<PathBuf>,
+    /// 未关联文件的文本所属路径（仅用于标题和语法检测）
+    source_path: Option<PathBuf>,
     /// 文件内容（行）
     lines: Vec<String>,
     /// 光标位置
@@ -176,13 +192,17 @@ pub struct InlineEditor {
     search_results: Vec<CursorPosition>,
     /// 当前搜索结果索引
     current_search_index: usize,
+    /// 行内诊断
+    diagnostics: Vec<LineDiagnostic>,
+    /// 语法高亮（按内容版本缓存）
+    highlighter: SyntaxHighlighter,
 }
 
 impl InlineEditor {
     pub fn new(config: InlineEditorConfig) -> Self {
         Self {
-            config,
             file_path: None,
+            source_path: None,
             lines: vec![String::new()],
             cursor: CursorPosition::default(),
             selection: None,
@@ -196,6 +216,13 @@ impl InlineEditor {
             search_query: None,
             search_results: Vec::new(),
             current_search_index: 0,
+            diagnostics: Vec::new(),
+            highlighter: SyntaxHighlighter::new(SyntaxHighlighterConfig {
+                enabled: config.syntax_highlighting,
+                theme_name: config.syntax_theme.clone(),
+                ..Default::default()
+            }),
+            config,
         }
     }
 
@@ -502,7 +529,7 @@ impl InlineEditor {
         let line = &mut self.lines[self.cursor.line];
         if self.cursor.column <= line.len() {
             line.insert(self.cursor.column, c);
-            self.cursor.column += 1;
+            self.cursor.column += c.len_utf8();
             self.is_modified = true;
         }
     }
@@ -554,8 +581,9 @@ impl InlineEditor {
         if self.cursor.column > 0 {
             self.save_state_for_undo();
             let line = &mut self.lines[self.cursor.line];
-            line.remove(self.cursor.column - 1);
-            self.cursor.column -= 1;
+            let previous = prev_char_boundary(line, self.cursor.column);
+            line.remove(previous);
+            self.cursor.column = previous;
             self.is_modified = true;
         } else if self.cursor.line > 0 {
             self.save_state_for_undo();
@@ -587,8 +615,7 @@ impl InlineEditor {
     fn move_cursor_up(&mut self) {
         if self.cursor.line > 0 {
             self.cursor.line -= 1;
-            let line_len = self.lines.get(self.cursor.line).map_or(0, |l| l.len());
-            self.cursor.column = min(self.cursor.column, line_len);
+            self.clamp_column();
             self.adjust_scroll();
         }
     }
@@ -596,15 +623,14 @@ impl InlineEditor {
     fn move_cursor_down(&mut self) {
         if self.cursor.line < self.lines.len().saturating_sub(1) {
             self.cursor.line += 1;
-            let line_len = self.lines.get(self.cursor.line).map_or(0, |l| l.len());
-            self.cursor.column = min(self.cursor.column, line_len);
+            self.clamp_column();
             self.adjust_scroll();
         }
     }
 
     fn move_cursor_left(&mut self) {
         if self.cursor.column > 0 {
-            self.cursor.column -= 1;
+            self.cursor.column = prev_char_boundary(&self.lines[self.cursor.line], self.cursor.column);
         } else if self.cursor.line > 0 {
             self.cursor.line -= 1;
             self.cursor.column = self.lines.get(self.cursor.line).map_or(0, |l| l.len());
@@ -614,9 +640,9 @@ impl InlineEditor {
 
     fn move_cursor_right(&mut self) {
         if self.cursor.line < self.lines.len() {
-            let line_len = self.lines[self.cursor.line].len();
-            if self.cursor.column < line_len {
-                self.cursor.column += 1;
+            let line = &self.lines[self.cursor.line];
+            if self.cursor.column < line.len() {
+                self.cursor.column += line[self.cursor.column..].chars().next().map_or(1, char::len_utf8);
             } else if self.cursor.line < self.lines.len() - 1 {
                 self.cursor.line += 1;
                 self.cursor.column = 0;
@@ -628,19 +654,27 @@ impl InlineEditor {
     fn page_up(&mut self) {
         let page_size = self.viewport_height.saturating_sub(1);
         self.cursor.line = self.cursor.line.saturating_sub(page_size);
-        let line_len = self.lines.get(self.cursor.line).map_or(0, |l| l.len());
-        self.cursor.column = min(self.cursor.column, line_len);
+        self.clamp_column();
         self.adjust_scroll();
     }
 
     fn page_down(&mut self) {
         let page_size = self.viewport_height.saturating_sub(1);
         self.cursor.line = min(self.cursor.line + page_size, self.lines.len().saturating_sub(1));
-        let line_len = self.lines.get(self.cursor.line).map_or(0, |l| l.len());
-        self.cursor.column = min(self.cursor.column, line_len);
+        self.clamp_column();
         self.adjust_scroll();
     }
 
+    /// 将光标列限制在当前行内并对齐到字符边界
+    fn clamp_column(&mut self) {
+        let line = self.lines.get(self.cursor.line).map_or("", |l| l.as_str());
+        let mut column = min(self.cursor.column, line.len());
+        while !line.is_char_boundary(column) {
+            column -= 1;
+        }
+        self.cursor.column = column;
+    }
+
     /// 调整滚动偏移
     fn adjust_scroll(&mut self) {
         if self.cursor.line < self.scroll_offset {
@@ -690,13 +724,12 @@ impl InlineEditor {
 
     /// 渲染编辑器
     pub fn render(&mut self, frame: &mut Frame<'_>, area: Rect, theme: &Theme) -> AppResult<()> {
-        self.viewport_height = area.height as usize;
-
         let block = Block::default()
             .borders(Borders::ALL)
             .title(format!(
                 "Inline Editor - {} {}",
                 self.file_path.as_ref()
+                    .or(self.source_path.as_ref())
                     .and_then(|p| p.file_name())
                     .and_then(|n| n.to_str())
                     .unwrap_or("Untitled"),
@@ -704,6 +737,8 @@ impl InlineEditor {
             ));
 
         let inner_area = block.inner(area);
+        self.viewport_height = (inner_area.height as usize).max(1);
+        self.adjust_scroll();
         frame.render_widget(block, area);
 
         // 渲染文本内容
@@ -721,6 +756,11 @@ impl InlineEditor {
     fn render_text_content(&mut self, frame: &mut Frame<'_>, area: Rect, theme: &Theme) -> AppResult<()> {
         let mut lines_to_render = Vec::new();
         let end_line = min(self.scroll_offset + area.height as usize, self.lines.len());
+        let highlighted = match self.file_path.clone().or_else(|| self.source_path.clone()) {
+            Some(path) if self.config.syntax_highlighting => self.highlighter.highlight_file(&path, &self.lines.join("\n")),
+            _ => None,
+        };
+        let palette = self.highlighter.palette(theme);
 
         for (i, line_idx) in (self.scroll_offset..end_line).enumerate() {
             let line_content = &self.lines[line_idx];
@@ -736,15 +776,26 @@ impl InlineEditor {
                 ));
             }
 
-            // 行内容
-            if line_idx == self.cursor.line {
-                // 当前行高亮
+            // 行内容（当前行加底色）
+            let base = if line_idx == self.cursor.line {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            match highlighted.as_ref() {
+                Some(highlighted) => {
+                    spans.extend(syntax::styled_spans(line_content, highlighted.line(line_idx), &palette, base));
+                }
+                None => spans.push(Span::styled(line_content.clone(), base)),
+            }
+
+            // 行内诊断
+            for diagnostic in self.diagnostics.iter().filter(|d| d.line == line_idx) {
+                let color = if diagnostic.is_error { Color::Red } else { Color::Yellow };
                 spans.push(Span::styled(
-                    line_content.clone(),
-                    Style::default().bg(Color::DarkGray)
+                    format!("  ◀ {}", diagnostic.message),
+                    Style::default().fg(color).add_modifier(Modifier::DIM),
                 ));
-            } else {
-                spans.push(Span::raw(line_content.clone()));
             }
 
             lines_to_render.push(ListItem::new(Line::from(spans)));
@@ -753,6 +804,18 @@ impl InlineEditor {
         let list = List::new(lines_to_render);
         frame.render_widget(list, area);
 
+        // 编辑模式下显示终端光标
+        if self.mode == EditorMode::Edit && self.cursor.line >= self.scroll_offset {
+            let gutter = if self.config.show_line_numbers { 5 } else { 0 };
+            let column = self.lines.get(self.cursor.line)
+                .map_or(0, |line| line[..self.cursor.column.min(line.len())].chars().count());
+            let x = area.x + min(gutter + column, area.width.saturating_sub(1) as usize) as u16;
+            let y = area.y + (self.cursor.line - self.scroll_offset) as u16;
+            if y < area.bottom() {
+                frame.set_cursor(x, y);
+            }
+        }
+
         Ok(())
     }
 
@@ -771,6 +834,47 @@ impl InlineEditor {
         frame.render_widget(status_text, status_area);
     }
 
+    /// 以给定文本进入编辑模式（不关联文件）
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = if text.is_empty() {
+            vec![String::new()]
+        } else {
+            text.lines().map(|s| s.to_string()).collect()
+        };
+        self.file_path = None;
+        self.source_path = None;
+        self.cursor = CursorPosition::default();
+        self.selection = None;
+        self.scroll_offset = 0;
+        self.is_modified = false;
+        self.undo_history.clear();
+        self.redo_history.clear();
+        self.status_message = None;
+        self.mode = EditorMode::Edit;
+    }
+
+    /// 以给定文本进入编辑模式，`path` 只用于标题和语法高亮（保存由调用者负责）
+    pub fn set_text_for_path(&mut self, text: &str, path: PathBuf) {
+        self.set_text(text);
+        self.source_path = Some(path);
+    }
+
+    /// 调用者保存成功后清除修改标记
+    pub fn mark_saved(&mut self, message: &str) {
+        self.is_modified = false;
+        self.status_message = Some(message.to_string());
+    }
+
+    /// 当前文本内容
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// 设置行内诊断，替换之前的诊断
+    pub fn set_diagnostics(&mut self, diagnostics: Vec<LineDiagnostic>) {
+        self.diagnostics = diagnostics;
+    }
+
     /// 获取当前模式
     pub fn get_mode(&self) -> EditorMode {
         self.mode.clone()
@@ -790,4 +894,50 @@ impl InlineEditor {
     pub fn clear_status_message(&mut self) {
         self.status_message = None;
     }
-}
\ No newline at end of file
+}
+
+/// 光标列之前一个字符的字节位置
+fn prev_char_boundary(line: &str, column: usize) -> usize {
+    line[..column].char_indices().next_back().map_or(0, |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(editor: &mut InlineEditor, code: KeyCode) {
+        editor.handle_key(KeyEvent::new(code, KeyModifiers::NONE)).unwrap();
+    }
+
+    #[test]
+    fn test_cursor_moves_by_characters() {
+        let mut editor = InlineEditor::new(InlineEditorConfig::default());
+        editor.set_text("aé€b");
+        for _ in 0..3 {
+            press(&mut editor, KeyCode::Right);
+        }
+        assert_eq!(editor.cursor.column, "aé€".len());
+
+        press(&mut editor, KeyCode::Backspace);
+        assert_eq!(editor.text(), "aéb");
+        assert_eq!(editor.cursor.column, "aé".len());
+
+        press(&mut editor, KeyCode::Char('ü'));
+        press(&mut editor, KeyCode::Left);
+        assert_eq!(editor.text(), "aéüb");
+        assert_eq!(editor.cursor.column, "aé".len());
+    }
+
+    #[test]
+    fn test_vertical_moves_land_on_char_boundary() {
+        let mut editor = InlineEditor::new(InlineEditorConfig::default());
+        editor.set_text("ab\néé");
+        press(&mut editor, KeyCode::Right);
+        press(&mut editor, KeyCode::Down);
+        assert_eq!(editor.cursor.line, 1);
+        assert_eq!(editor.cursor.column, 0);
+
+        press(&mut editor, KeyCode::Char('x'));
+        assert_eq!(editor.text(), "ab\nxéé");
+    }
+}
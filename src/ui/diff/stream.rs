@@ -0,0 +1,311 @@
+//! 流式差异
+//!
+//! 超过阈值的文件不再在内存中逐行比较：`git diff` 的输出写入临时文件并在
+//! 后台建立差异块索引（见 [`crate::git::patch_stream`]），查看器只读取、
+//! 解析和语法高亮进入可见区域的差异块。解析结果按内存预算缓存，最久未用的
+//! 先淘汰。
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::Path,
+    sync::Arc,
+};
+
+use super::{
+    syntax::{self, Grammar, HighlightedText},
+    DiffHunk, DiffLine, DiffLineType, DiffProcessorConfig, DiffStats, DiffUtils, IntralineGranularity, WhitespaceMode,
+};
+use crate::{
+    error::{AppError, AppResult},
+    git::{HunkEntry, PatchSource, PatchStream, RevisionFileChange},
+};
+
+/// 解析后的差异块及两侧的分词结果
+#[derive(Debug)]
+pub struct StreamedHunk {
+    pub hunk: DiffHunk,
+    /// 旧侧（上下文 + 删除行）的分词结果，第一项对应 `hunk.old_start`
+    pub old_tokens: Option<HighlightedText>,
+    /// 新侧（上下文 + 新增行）的分词结果，第一项对应 `hunk.new_start`
+    pub new_tokens: Option<HighlightedText>,
+}
+
+/// 解析与高亮设置
+#[derive(Debug, Clone, Copy)]
+pub struct StreamSettings {
+    /// 语法（None 表示不高亮）
+    pub grammar: Option<&'static Grammar>,
+    /// 块正文超过此大小（字节）时不做语法高亮
+    pub max_highlight_size: usize,
+    /// 行内高亮粒度与最大行长（None 表示关闭）
+    pub intraline: Option<(IntralineGranularity, usize)>,
+    /// 已解析差异块的缓存预算（字节）
+    pub cache_bytes: usize,
+}
+
+/// 正在流式加载的差异
+pub struct StreamedDiff {
+    source: PatchSource,
+    stream: PatchStream,
+    settings: StreamSettings,
+    cache: HashMap<usize, Arc<StreamedHunk>>,
+    order: VecDeque<usize>,
+    cached_bytes: usize,
+}
+
+impl StreamedDiff {
+    /// 以 `context` 行上下文启动 `git diff`
+    pub fn start(source: PatchSource, context: usize, settings: StreamSettings) -> AppResult<Self> {
+        let stream = source.spawn(context)?;
+        Ok(Self {
+            source,
+            stream,
+            settings,
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            cached_bytes: 0,
+        })
+    }
+
+    /// 以新的上下文行数重新生成补丁
+    pub fn restart(&mut self, context: usize) -> AppResult<()> {
+        self.stream = self.source.spawn(context)?;
+        self.clear_cache();
+        Ok(())
+    }
+
+    pub fn stream(&self) -> &PatchStream {
+        &self.stream
+    }
+
+    /// 正在显示的文件变更
+    pub fn change(&self) -> &RevisionFileChange {
+        self.source.change()
+    }
+
+    /// 已索引的差异块数
+    pub fn hunk_count(&self) -> usize {
+        self.stream.with_index(|index| index.hunks.len())
+    }
+
+    /// 已索引的正文行数
+    pub fn rows(&self) -> usize {
+        self.stream.with_index(|index| index.rows)
+    }
+
+    pub fn entry(&self, index: usize) -> Option<HunkEntry> {
+        self.stream.with_index(|patch| patch.hunks.get(index).cloned())
+    }
+
+    /// 包含第 row 行正文的差异块
+    pub fn hunk_at_row(&self, row: usize) -> Option<usize> {
+        self.stream.with_index(|index| index.hunk_at_row(row))
+    }
+
+    /// 目前为止的统计
+    pub fn stats(&self) -> DiffStats {
+        self.stream.with_index(|index| DiffStats {
+            lines_added: index.additions,
+            lines_deleted: index.deletions,
+            files_changed: 1,
+            ..DiffStats::default()
+        })
+    }
+
+    /// 更换行内高亮设置，已解析的差异块重新解析
+    pub fn set_intraline(&mut self, intraline: Option<(IntralineGranularity, usize)>) {
+        self.settings.intraline = intraline;
+        self.clear_cache();
+    }
+
+    fn clear_cache(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+        self.cached_bytes = 0;
+    }
+
+    /// 读取并解析第 index 个差异块（带缓存）
+    pub fn hunk(&mut self, index: usize) -> AppResult<Arc<StreamedHunk>> {
+        if let Some(hunk) = self.cache.get(&index).cloned() {
+            self.order.retain(|&cached| cached != index);
+            self.order.push_back(index);
+            return Ok(hunk);
+        }
+
+        let entry = self
+            .entry(index)
+            .ok_or_else(|| AppError::InvalidOperation(format!("Hunk {} is not indexed yet", index)))?;
+        let body = self.stream.read_body(&entry)?;
+        let hunk = Arc::new(parse_hunk(&entry, &body, &self.settings));
+
+        let size = Self::estimate(&hunk);
+        while self.cached_bytes + size > self.settings.cache_bytes {
+            let Some(oldest) = self.order.pop_front() else { break };
+            if let Some(evicted) = self.cache.remove(&oldest) {
+                self.cached_bytes -= Self::estimate(&evicted);
+            }
+        }
+        self.cached_bytes += size;
+        self.cache.insert(index, hunk.clone());
+        self.order.push_back(index);
+        Ok(hunk)
+    }
+
+    /// 行内容、行结构和 token 大约是内容的三倍
+    fn estimate(hunk: &StreamedHunk) -> usize {
+        hunk.hunk.lines.iter().map(|line| line.content.len() + 1).sum::<usize>() * 3
+    }
+
+    /// 解析全部差异块（导出时使用），不经过缓存
+    pub fn load_all(&self) -> AppResult<Vec<DiffHunk>> {
+        let entries = self.stream.with_index(|index| index.hunks.clone());
+        let settings = StreamSettings {
+            grammar: None,
+            ..self.settings
+        };
+        entries
+            .iter()
+            .map(|entry| Ok(parse_hunk(entry, &self.stream.read_body(entry)?, &settings).hunk))
+            .collect()
+    }
+}
+
+/// 把补丁中的一个差异块正文解析为差异行，并按设置计算行内差异和语法高亮
+pub fn parse_hunk(entry: &HunkEntry, body: &str, settings: &StreamSettings) -> StreamedHunk {
+    let (mut old_line, mut new_line) = (entry.old_start, entry.new_start);
+    let mut lines = Vec::with_capacity(entry.rows);
+    for raw in body.lines() {
+        let line_type = match raw.as_bytes().first() {
+            Some(b' ') => DiffLineType::Context,
+            Some(b'-') => DiffLineType::Deleted,
+            Some(b'+') => DiffLineType::Added,
+            // `\ No newline at end of file`
+            _ => continue,
+        };
+        let content = &raw[1..];
+        let old_line_number = (line_type != DiffLineType::Added).then(|| {
+            old_line += 1;
+            old_line - 1
+        });
+        let new_line_number = (line_type != DiffLineType::Deleted).then(|| {
+            new_line += 1;
+            new_line - 1
+        });
+        lines.push(DiffLine {
+            line_type,
+            old_line_number,
+            new_line_number,
+            content: content.to_string(),
+            highlights: vec![],
+        });
+    }
+
+    let mut hunk = DiffHunk {
+        header: entry.header.clone(),
+        old_start: entry.old_start,
+        old_lines: entry.old_lines,
+        new_start: entry.new_start,
+        new_lines: entry.new_lines,
+        lines,
+    };
+    if let Some((granularity, max_length)) = settings.intraline {
+        DiffUtils::apply_intraline_highlights(&mut hunk, granularity, max_length);
+    }
+
+    // 分词从块的第一行开始；块中途进入的多行注释或字符串无法得知
+    let grammar = settings.grammar.filter(|_| body.len() <= settings.max_highlight_size);
+    let tokens = |side: fn(&DiffLine) -> bool| {
+        grammar.map(|grammar| {
+            let text: Vec<&str> = hunk.lines.iter().filter(|line| side(line)).map(|line| line.content.as_str()).collect();
+            syntax::highlight_text(grammar, &text.join("\n"))
+        })
+    };
+    let old_tokens = tokens(|line| line.old_line_number.is_some());
+    let new_tokens = tokens(|line| line.new_line_number.is_some());
+
+    StreamedHunk {
+        hunk,
+        old_tokens,
+        new_tokens,
+    }
+}
+
+/// 与差异处理配置对应的 `git diff` 选项
+pub fn git_options(config: &DiffProcessorConfig, file_path: &Path) -> Vec<String> {
+    let mut options = vec![format!("--diff-algorithm={}", config.algorithm_for(file_path).label())];
+    match config.whitespace {
+        WhitespaceMode::Exact => {}
+        WhitespaceMode::IgnoreEol => options.push("--ignore-space-at-eol".to_string()),
+        WhitespaceMode::IgnoreChange => options.push("--ignore-space-change".to_string()),
+        WhitespaceMode::IgnoreAll => options.push("--ignore-all-space".to_string()),
+    }
+    if config.ignore_blank_lines {
+        options.push("--ignore-blank-lines".to_string());
+    }
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::diff::DiffAlgorithm;
+
+    fn entry(old_start: usize, new_start: usize) -> HunkEntry {
+        HunkEntry {
+            header: format!("@@ -{},3 +{},3 @@", old_start, new_start),
+            old_start,
+            old_lines: 3,
+            new_start,
+            new_lines: 3,
+            first_row: 0,
+            rows: 4,
+            offset: 0,
+            len: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_hunk_numbers_lines_and_highlights() {
+        let settings = StreamSettings {
+            grammar: syntax::grammar_for("rust"),
+            max_highlight_size: 1024,
+            intraline: Some((IntralineGranularity::Word, 1000)),
+            cache_bytes: 1024,
+        };
+        let body = " fn main() {\n-    let x = 1;\n+    let x = 2;\n }\n\\ No newline at end of file\n";
+        let parsed = parse_hunk(&entry(10, 20), body, &settings);
+
+        let numbers: Vec<_> = parsed.hunk.lines.iter().map(|line| (line.old_line_number, line.new_line_number)).collect();
+        assert_eq!(numbers, vec![(Some(10), Some(20)), (Some(11), None), (None, Some(21)), (Some(12), Some(22))]);
+        assert_eq!(parsed.hunk.lines[2].content, "    let x = 2;");
+        assert!(!parsed.hunk.lines[2].highlights.is_empty());
+
+        // 每侧的分词从块的起始行开始
+        let new_tokens = parsed.new_tokens.unwrap();
+        assert_eq!(new_tokens.lines.len(), 3);
+        assert!(!new_tokens.line(0).is_empty());
+
+        // 超过高亮上限时不分词
+        let small = StreamSettings {
+            max_highlight_size: 8,
+            ..settings
+        };
+        assert!(parse_hunk(&entry(1, 1), body, &small).old_tokens.is_none());
+    }
+
+    #[test]
+    fn test_git_options() {
+        let mut config = DiffProcessorConfig {
+            algorithm: DiffAlgorithm::Histogram,
+            whitespace: WhitespaceMode::IgnoreAll,
+            ignore_blank_lines: true,
+            ..DiffProcessorConfig::default()
+        };
+        config.file_algorithms.insert("lock".to_string(), DiffAlgorithm::Minimal);
+        assert_eq!(
+            git_options(&config, Path::new("src/main.rs")),
+            vec!["--diff-algorithm=histogram", "--ignore-all-space", "--ignore-blank-lines"]
+        );
+        assert_eq!(git_options(&config, Path::new("Cargo.lock"))[0], "--diff-algorithm=minimal");
+    }
+}
@@ -0,0 +1,495 @@
+//! 语法高亮
+//!
+//! Lines are tokenized with the Sublime Text grammars bundled with syntect,
+//! one after another with a [`LineState`] carried across line breaks, so
+//! block comments, multi-line strings and embedded code blocks stay
+//! highlighted on every line they span. The resulting scopes are folded into
+//! a handful of [`TokenKind`]s whose colors come from a [`SyntaxPalette`],
+//! derived from the UI [`Theme`] or picked by name.
+//!
+//! syntect's default set has no TypeScript, Kotlin, Swift, TOML, INI,
+//! Dockerfile, Terraform, ignore or dotenv grammar and loading extra
+//! `.sublime-syntax` files would mean shipping them with the binary, so those
+//! languages keep the small rule-based lexer in [`builtin`].
+
+mod builtin;
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+use std::ops::Range;
+use std::sync::OnceLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+
+use crate::ui::theme::Theme;
+
+/// Lines longer than this are not tokenized
+const MAX_HIGHLIGHT_LINE_LEN: usize = 2000;
+/// Palette name that follows the UI theme
+pub const THEME_PALETTE: &str = "theme";
+
+/// 词法单元类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Keyword,
+    Type,
+    Constant,
+    String,
+    Number,
+    Comment,
+    Function,
+    Attribute,
+    Operator,
+    /// Keys of JSON/YAML/TOML/CSS properties
+    Key,
+    /// Markup tags and TOML/INI sections
+    Tag,
+    /// `$var` in shells, PHP and Perl
+    Variable,
+    /// Markdown headings
+    Heading,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Byte range in the line
+    pub range: Range<usize>,
+    pub kind: TokenKind,
+}
+
+/// Tokenizer state at a line boundary, from [`Grammar::start`]
+#[derive(Debug, Clone)]
+pub struct LineState(State);
+
+#[derive(Debug, Clone)]
+enum State {
+    Syntect { parse: Box<ParseState>, scopes: ScopeStack },
+    Builtin(builtin::State),
+}
+
+#[derive(Debug)]
+enum Engine {
+    Syntect(&'static SyntaxReference),
+    Builtin(&'static builtin::Rules),
+}
+
+/// 语言语法
+#[derive(Debug)]
+pub struct Grammar {
+    pub name: &'static str,
+    engine: Engine,
+}
+
+/// `DiffUtils::detect_language_from_path` names of the syntect grammars
+const SYNTECT_LANGUAGES: &[(&str, &str)] = &[
+    ("rust", "Rust"),
+    ("python", "Python"),
+    ("javascript", "JavaScript"),
+    ("c", "C"),
+    ("cpp", "C++"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("ruby", "Ruby"),
+    ("php", "PHP"),
+    ("perl", "Perl"),
+    ("lua", "Lua"),
+    ("sql", "SQL"),
+    ("bash", "Bourne Again Shell (bash)"),
+    ("css", "CSS"),
+    ("html", "HTML"),
+    ("xml", "XML"),
+    ("json", "JSON"),
+    ("yaml", "YAML"),
+    ("markdown", "Markdown"),
+    ("makefile", "Makefile"),
+];
+
+/// Scope prefixes and their token kind. The first entry matching any scope
+/// on the stack wins, so containers (comments, keys, strings, headings,
+/// attributes) come before the tokens that may appear inside them.
+const SCOPE_KINDS: &[(&str, TokenKind)] = &[
+    ("comment", TokenKind::Comment),
+    ("punctuation.definition.comment", TokenKind::Comment),
+    ("meta.structure.dictionary.key", TokenKind::Key),
+    ("entity.name.tag.yaml", TokenKind::Key),
+    ("support.type.property-name", TokenKind::Key),
+    ("markup.heading", TokenKind::Heading),
+    ("meta.annotation", TokenKind::Attribute),
+    ("meta.attribute", TokenKind::Attribute),
+    // String prefixes such as `r#"` and `f"`
+    ("storage.type.string", TokenKind::String),
+    ("string", TokenKind::String),
+    ("markup.raw", TokenKind::String),
+    ("constant.numeric", TokenKind::Number),
+    ("constant", TokenKind::Constant),
+    ("support.constant", TokenKind::Constant),
+    ("keyword.operator", TokenKind::Operator),
+    ("keyword", TokenKind::Keyword),
+    // Most grammars scope `fn`, `let`, `var` and primitive types alike as storage
+    ("storage", TokenKind::Keyword),
+    ("entity.name.function", TokenKind::Function),
+    ("support.function", TokenKind::Function),
+    ("variable.function", TokenKind::Function),
+    ("entity.name.tag", TokenKind::Tag),
+    ("entity.name.section", TokenKind::Tag),
+    ("entity.other.attribute-name", TokenKind::Attribute),
+    ("entity.name", TokenKind::Type),
+    ("entity.other.inherited-class", TokenKind::Type),
+    ("support.type", TokenKind::Type),
+    ("support.class", TokenKind::Type),
+    ("variable.other.readwrite", TokenKind::Variable),
+    ("variable.other.php", TokenKind::Variable),
+    ("variable.other.normal", TokenKind::Variable),
+    ("punctuation.definition.variable", TokenKind::Variable),
+];
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn scope_kinds() -> &'static [(Scope, TokenKind)] {
+    static KINDS: OnceLock<Vec<(Scope, TokenKind)>> = OnceLock::new();
+    KINDS.get_or_init(|| {
+        SCOPE_KINDS
+            .iter()
+            .filter_map(|(prefix, kind)| Scope::new(prefix).ok().map(|scope| (scope, *kind)))
+            .collect()
+    })
+}
+
+/// All grammars: the named syntect ones, the built-in fallbacks, then every
+/// other visible syntect syntax under its own name
+fn grammars() -> &'static [Grammar] {
+    static GRAMMARS: OnceLock<Vec<Grammar>> = OnceLock::new();
+    GRAMMARS.get_or_init(|| {
+        let set = syntax_set();
+        let mut grammars: Vec<Grammar> = SYNTECT_LANGUAGES
+            .iter()
+            .filter_map(|(name, syntax)| {
+                set.find_syntax_by_name(syntax).map(|syntax| Grammar { name, engine: Engine::Syntect(syntax) })
+            })
+            .collect();
+        grammars.extend(builtin::RULES.iter().map(|rules| Grammar { name: rules.name, engine: Engine::Builtin(rules) }));
+        for syntax in set.syntaxes() {
+            let named = SYNTECT_LANGUAGES.iter().any(|(_, name)| *name == syntax.name);
+            if !syntax.hidden && !named && syntax.name != "Plain Text" {
+                grammars.push(Grammar { name: syntax.name.as_str(), engine: Engine::Syntect(syntax) });
+            }
+        }
+        grammars
+    })
+}
+
+/// 语言名（`DiffUtils::detect_language_from_path` 的结果）或文件扩展名对应的语法
+pub fn grammar_for(language: &str) -> Option<&'static Grammar> {
+    let name = match language {
+        "jsx" => "javascript",
+        "tsx" => "typescript",
+        "scss" => "css",
+        "shell" | "sh" | "zsh" => "bash",
+        other => other,
+    };
+    if let Some(grammar) = grammars().iter().find(|grammar| grammar.name == name) {
+        return Some(grammar);
+    }
+    // Extensions and names of the remaining syntect syntaxes (`hs`, `Scala`, ...)
+    let syntax = syntax_set().find_syntax_by_token(name)?;
+    grammars()
+        .iter()
+        .find(|grammar| matches!(grammar.engine, Engine::Syntect(other) if std::ptr::eq(other, syntax)))
+}
+
+/// Names of all grammars
+pub fn languages() -> impl Iterator<Item = &'static str> {
+    grammars().iter().map(|grammar| grammar.name)
+}
+
+/// Token kind of a scope stack
+fn scope_kind(scopes: &ScopeStack) -> Option<TokenKind> {
+    scope_kinds()
+        .iter()
+        .find(|(prefix, _)| scopes.as_slice().iter().any(|scope| prefix.is_prefix_of(*scope)))
+        .map(|(_, kind)| *kind)
+}
+
+impl Grammar {
+    /// 首行的分词状态
+    pub fn start(&self) -> LineState {
+        LineState(match self.engine {
+            Engine::Syntect(syntax) => State::Syntect { parse: Box::new(ParseState::new(syntax)), scopes: ScopeStack::new() },
+            Engine::Builtin(_) => State::Builtin(builtin::State::default()),
+        })
+    }
+
+    /// 对一行分词；`state` 为行首状态，返回时更新为下一行的行首状态
+    pub fn tokenize_line(&self, line: &str, state: &mut LineState) -> Vec<Token> {
+        if line.len() > MAX_HIGHLIGHT_LINE_LEN {
+            return Vec::new();
+        }
+        match (&self.engine, &mut state.0) {
+            (Engine::Builtin(rules), State::Builtin(state)) => rules.tokenize_line(line, state),
+            (Engine::Syntect(_), State::Syntect { parse, scopes }) => {
+                // The newline-aware grammars expect the line terminator
+                let text = format!("{}\n", line);
+                let ops = match parse.parse_line(&text, syntax_set()) {
+                    Ok(ops) => ops,
+                    Err(_) => return Vec::new(),
+                };
+                let mut tokens: Vec<Token> = Vec::new();
+                let mut pos = 0;
+                let mut push = |tokens: &mut Vec<Token>, end: usize, kind: Option<TokenKind>| {
+                    let end = end.min(line.len());
+                    if let Some(kind) = kind.filter(|_| end > pos) {
+                        match tokens.last_mut() {
+                            Some(last) if last.kind == kind && last.range.end == pos => last.range.end = end,
+                            _ => tokens.push(Token { range: pos..end, kind }),
+                        }
+                    }
+                    pos = pos.max(end);
+                };
+                for (offset, op) in ops {
+                    push(&mut tokens, offset, scope_kind(scopes));
+                    let _ = scopes.apply(&op);
+                }
+                push(&mut tokens, line.len(), scope_kind(scopes));
+                tokens
+            }
+            // A state from another grammar: start over
+            _ => {
+                *state = self.start();
+                self.tokenize_line(line, state)
+            }
+        }
+    }
+}
+
+/// 整个文件的分词结果（每行一组 token）
+#[derive(Debug, Clone, Default)]
+pub struct HighlightedText {
+    pub lines: Vec<Vec<Token>>,
+}
+
+impl HighlightedText {
+    pub fn line(&self, index: usize) -> &[Token] {
+        self.lines.get(index).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// 逐行分词，行间保留状态
+pub fn highlight_text(grammar: &Grammar, text: &str) -> HighlightedText {
+    let mut state = grammar.start();
+    HighlightedText {
+        lines: text.lines().map(|line| grammar.tokenize_line(line, &mut state)).collect(),
+    }
+}
+
+/// 词法单元配色
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxPalette {
+    pub keyword: Style,
+    pub type_name: Style,
+    pub constant: Style,
+    pub string: Style,
+    pub number: Style,
+    pub comment: Style,
+    pub function: Style,
+    pub attribute: Style,
+    pub operator: Style,
+    pub key: Style,
+    pub tag: Style,
+    pub variable: Style,
+    pub heading: Style,
+}
+
+impl SyntaxPalette {
+    /// Palette built from the UI theme colors
+    pub fn from_theme(theme: &Theme) -> Self {
+        let colors = &theme.colors;
+        let fg = |color: Color| Style::default().fg(color);
+        Self {
+            keyword: fg(colors.primary).add_modifier(Modifier::BOLD),
+            type_name: fg(colors.info),
+            constant: fg(colors.accent),
+            string: fg(colors.success),
+            number: fg(colors.accent),
+            comment: fg(colors.muted).add_modifier(Modifier::ITALIC),
+            function: fg(colors.foreground).add_modifier(Modifier::BOLD),
+            attribute: fg(colors.warning),
+            operator: fg(colors.foreground),
+            key: fg(colors.info),
+            tag: fg(colors.primary),
+            variable: fg(colors.warning),
+            heading: fg(colors.primary).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Built-in named palettes
+    pub fn named(name: &str) -> Option<Self> {
+        let (comment, foreground) = match name {
+            "base16-ocean.dark" => (Color::Rgb(0x65, 0x73, 0x7e), Color::Rgb(0xc0, 0xc5, 0xce)),
+            "base16-ocean.light" => (Color::Rgb(0xa7, 0xad, 0xba), Color::Rgb(0x4f, 0x5b, 0x66)),
+            _ => return None,
+        };
+        // base16 accents: 08 red, 09 orange, 0A yellow, 0B green, 0C cyan, 0D blue, 0E purple
+        let red = Color::Rgb(0xbf, 0x61, 0x6a);
+        let orange = Color::Rgb(0xd0, 0x87, 0x70);
+        let yellow = Color::Rgb(0xeb, 0xcb, 0x8b);
+        let green = Color::Rgb(0xa3, 0xbe, 0x8c);
+        let cyan = Color::Rgb(0x96, 0xb5, 0xb4);
+        let blue = Color::Rgb(0x8f, 0xa1, 0xb3);
+        let purple = Color::Rgb(0xb4, 0x8e, 0xad);
+        let fg = |color: Color| Style::default().fg(color);
+        Some(Self {
+            keyword: fg(purple),
+            type_name: fg(yellow),
+            constant: fg(orange),
+            string: fg(green),
+            number: fg(orange),
+            comment: fg(comment).add_modifier(Modifier::ITALIC),
+            function: fg(blue),
+            attribute: fg(cyan),
+            operator: fg(foreground),
+            key: fg(red),
+            tag: fg(red),
+            variable: fg(red),
+            heading: fg(blue).add_modifier(Modifier::BOLD),
+        })
+    }
+
+    /// `theme_name` from the config: a built-in palette, otherwise the UI theme
+    pub fn resolve(name: &str, theme: &Theme) -> Self {
+        Self::named(name).unwrap_or_else(|| Self::from_theme(theme))
+    }
+
+    pub fn style(&self, kind: TokenKind) -> Style {
+        match kind {
+            TokenKind::Keyword => self.keyword,
+            TokenKind::Type => self.type_name,
+            TokenKind::Constant => self.constant,
+            TokenKind::String => self.string,
+            TokenKind::Number => self.number,
+            TokenKind::Comment => self.comment,
+            TokenKind::Function => self.function,
+            TokenKind::Attribute => self.attribute,
+            TokenKind::Operator => self.operator,
+            TokenKind::Key => self.key,
+            TokenKind::Tag => self.tag,
+            TokenKind::Variable => self.variable,
+            TokenKind::Heading => self.heading,
+        }
+    }
+}
+
+/// 按 token 把一行切成带样式的 span；`base` 提供背景等公共样式
+pub fn styled_spans(line: &str, tokens: &[Token], palette: &SyntaxPalette, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::with_capacity(tokens.len() * 2 + 1);
+    let mut pos = 0;
+    for token in tokens {
+        // Tokens come from another revision of the line if the caller mixed them up
+        if token.range.start < pos || token.range.end > line.len() || !line.is_char_boundary(token.range.start) || !line.is_char_boundary(token.range.end) {
+            break;
+        }
+        if token.range.start > pos {
+            spans.push(Span::styled(line[pos..token.range.start].to_string(), base));
+        }
+        spans.push(Span::styled(line[token.range.clone()].to_string(), base.patch(palette.style(token.kind))));
+        pos = token.range.end;
+    }
+    if pos < line.len() {
+        spans.push(Span::styled(line[pos..].to_string(), base));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(language: &str, text: &str) -> Vec<Vec<(String, TokenKind)>> {
+        let grammar = grammar_for(language).unwrap();
+        let highlighted = highlight_text(grammar, text);
+        text.lines()
+            .zip(&highlighted.lines)
+            .map(|(line, tokens)| tokens.iter().map(|t| (line[t.range.clone()].to_string(), t.kind)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_tokens() {
+        let lines = kinds("rust", "#[derive(Debug)]\npub fn parse(x: u32) -> Option<String> { let s = r#\"raw \"q\"\"#; 0x1F }");
+        assert_eq!(lines[0], vec![("#[derive(Debug)]".to_string(), TokenKind::Attribute)]);
+        let line = &lines[1];
+        assert!(line.contains(&("pub".to_string(), TokenKind::Keyword)));
+        assert!(line.contains(&("fn".to_string(), TokenKind::Keyword)));
+        assert!(line.contains(&("parse".to_string(), TokenKind::Function)));
+        assert!(line.contains(&("r#\"raw \"q\"\"#".to_string(), TokenKind::String)));
+        assert!(line.contains(&("0x1F".to_string(), TokenKind::Number)));
+    }
+
+    #[test]
+    fn test_state_spans_lines() {
+        // Nested block comment across lines, then code again
+        let lines = kinds("rust", "a /* one\n/* two */ still\n*/ let b = 1;");
+        assert_eq!(lines[1], vec![("/* two */ still".to_string(), TokenKind::Comment)]);
+        assert_eq!(lines[2][0], ("*/".to_string(), TokenKind::Comment));
+        assert!(lines[2].contains(&("let".to_string(), TokenKind::Keyword)));
+
+        // Python triple-quoted strings
+        let lines = kinds("python", "s = '''\n    # not a comment\n    '''\nx = 1  # done");
+        assert_eq!(lines[1], vec![("    # not a comment".to_string(), TokenKind::String)]);
+        assert!(lines[3].contains(&("# done".to_string(), TokenKind::Comment)));
+
+        // Built-in lexer: block comments carry over, unterminated strings do not
+        let lines = kinds("typescript", "const x: number = 1; /* a\nb */ y\nconst s = \"open\nlet z;");
+        assert_eq!(lines[1], vec![("b */".to_string(), TokenKind::Comment)]);
+        assert!(lines[3].contains(&("let".to_string(), TokenKind::Keyword)));
+    }
+
+    #[test]
+    fn test_data_formats() {
+        let json = kinds("json", "{\"name\": \"ai\", \"ok\": true}");
+        assert_eq!(json[0][0], ("\"name\"".to_string(), TokenKind::Key));
+        assert!(json[0].contains(&("\"ai\"".to_string(), TokenKind::String)));
+
+        let yaml = kinds("yaml", "key: value");
+        assert_eq!(yaml[0][0], ("key".to_string(), TokenKind::Key));
+
+        let toml = kinds("toml", "[package]\nname = \"x\" # c");
+        assert_eq!(toml[0], vec![("[package]".to_string(), TokenKind::Tag)]);
+        assert_eq!(toml[1][0], ("name".to_string(), TokenKind::Key));
+
+        let markdown = kinds("markdown", "# Title\n```\nlet x\n```\ntext `code`");
+        assert_eq!(markdown[0], vec![("# Title".to_string(), TokenKind::Heading)]);
+        assert_eq!(markdown[2], vec![("let x".to_string(), TokenKind::String)]);
+        assert_eq!(markdown[4], vec![("`code`".to_string(), TokenKind::String)]);
+    }
+
+    #[test]
+    fn test_grammar_lookup() {
+        assert_eq!(grammar_for("jsx").map(|grammar| grammar.name), Some("javascript"));
+        assert_eq!(grammar_for("zsh").map(|grammar| grammar.name), Some("bash"));
+        // Syntect syntaxes without a language name of their own, by extension
+        assert_eq!(grammar_for("hs").map(|grammar| grammar.name), Some("Haskell"));
+        assert!(grammar_for("scala").is_some());
+        assert!(grammar_for("text").is_none());
+
+        // A state from another grammar is reset instead of mixed in
+        let mut state = grammar_for("toml").unwrap().start();
+        let tokens = grammar_for("rust").unwrap().tokenize_line("let x = 1;", &mut state);
+        assert_eq!(tokens[0].kind, TokenKind::Keyword);
+    }
+
+    #[test]
+    fn test_styled_spans_cover_line() {
+        let grammar = grammar_for("rust").unwrap();
+        let line = "let s = \"é\"; // ü";
+        let tokens = grammar.tokenize_line(line, &mut grammar.start());
+        let palette = SyntaxPalette::named("base16-ocean.dark").unwrap();
+        let spans = styled_spans(line, &tokens, &palette, Style::default());
+        let joined: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(joined, line);
+        assert!(languages().count() > 20);
+    }
+}
@@ -0,0 +1,440 @@
+//! 内置词法规则
+//!
+//! Fallback for languages the bundled syntect grammars do not cover
+//! (TypeScript, Kotlin, Swift, TOML, INI, Dockerfile, Terraform, ignore and
+//! dotenv files). Each language is a table of comment, string and keyword
+//! rules; lines are tokenized with a [`State`] carried across line breaks so
+//! block comments and multi-line strings stay highlighted on every line.
+
+use std::ops::Range;
+
+use super::{Token, TokenKind};
+
+/// Tokenizer state at a line boundary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum State {
+    #[default]
+    Normal,
+    /// Inside block comment `rule`, nested `depth` levels deep
+    BlockComment { rule: usize, depth: usize },
+    /// Inside multi-line string `rule`
+    Str { rule: usize },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct StringRule {
+    open: &'static str,
+    close: &'static str,
+    escape: bool,
+    multiline: bool,
+}
+
+const fn quoted(open: &'static str, close: &'static str) -> StringRule {
+    StringRule { open, close, escape: true, multiline: false }
+}
+
+const fn multiline(open: &'static str, close: &'static str, escape: bool) -> StringRule {
+    StringRule { open, close, escape, multiline: true }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttributeStyle {
+    None,
+    /// `@decorator`, `@Override`
+    At,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyStyle {
+    None,
+    /// `key = value` at the start of a line
+    Equals,
+}
+
+/// 语言语法描述
+#[derive(Debug)]
+pub struct Rules {
+    pub name: &'static str,
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    nested_comments: bool,
+    /// Longest openers first
+    strings: &'static [StringRule],
+    keywords: &'static [&'static str],
+    types: &'static [&'static str],
+    constants: &'static [&'static str],
+    case_insensitive: bool,
+    /// `Foo` is a type
+    capitalized_types: bool,
+    /// `-` may appear inside identifiers (CSS, YAML, Makefiles)
+    dash_in_identifiers: bool,
+    attributes: AttributeStyle,
+    keys: KeyStyle,
+    /// `[section]` lines
+    sections: bool,
+    variable_prefix: Option<char>,
+}
+
+const BASE: Rules = Rules {
+    name: "text",
+    line_comments: &[],
+    block_comments: &[],
+    nested_comments: false,
+    strings: &[],
+    keywords: &[],
+    types: &[],
+    constants: &[],
+    case_insensitive: false,
+    capitalized_types: false,
+    dash_in_identifiers: false,
+    attributes: AttributeStyle::None,
+    keys: KeyStyle::None,
+    sections: false,
+    variable_prefix: None,
+};
+
+const C_COMMENTS: &[(&str, &str)] = &[("/*", "*/")];
+
+pub(super) static RULES: &[Rules] = &[
+    Rules {
+        name: "typescript",
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &[quoted("\"", "\""), quoted("'", "'"), multiline("`", "`", true)],
+        keywords: &[
+            "abstract", "as", "async", "await", "break", "case", "catch", "class", "const", "continue", "declare",
+            "default", "delete", "do", "else", "enum", "export", "extends", "finally", "for", "from", "function", "if",
+            "implements", "import", "in", "instanceof", "interface", "is", "keyof", "let", "namespace", "new", "of",
+            "private", "protected", "public", "readonly", "return", "static", "super", "switch", "this", "throw",
+            "try", "type", "typeof", "var", "void", "while", "yield",
+        ],
+        types: &["any", "boolean", "never", "number", "object", "string", "symbol", "unknown", "bigint"],
+        constants: &["true", "false", "null", "undefined"],
+        capitalized_types: true,
+        attributes: AttributeStyle::At,
+        ..BASE
+    },
+    Rules {
+        name: "kotlin",
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        nested_comments: true,
+        strings: &[multiline("\"\"\"", "\"\"\"", false), quoted("\"", "\""), quoted("'", "'")],
+        keywords: &[
+            "as", "break", "class", "companion", "continue", "data", "do", "else", "enum", "for", "fun", "if",
+            "import", "in", "interface", "is", "object", "open", "override", "package", "private", "protected",
+            "public", "return", "sealed", "super", "suspend", "this", "throw", "try", "typealias", "val", "var",
+            "when", "while",
+        ],
+        constants: &["true", "false", "null"],
+        capitalized_types: true,
+        attributes: AttributeStyle::At,
+        ..BASE
+    },
+    Rules {
+        name: "swift",
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        nested_comments: true,
+        strings: &[multiline("\"\"\"", "\"\"\"", true), quoted("\"", "\"")],
+        keywords: &[
+            "actor", "associatedtype", "async", "await", "break", "case", "catch", "class", "continue", "default",
+            "defer", "do", "else", "enum", "extension", "fileprivate", "for", "func", "guard", "if", "import", "in",
+            "init", "inout", "internal", "let", "mutating", "open", "private", "protocol", "public", "repeat",
+            "return", "self", "static", "struct", "subscript", "super", "switch", "throw", "throws", "try", "var",
+            "where", "while",
+        ],
+        constants: &["true", "false", "nil"],
+        capitalized_types: true,
+        attributes: AttributeStyle::At,
+        ..BASE
+    },
+    Rules {
+        name: "toml",
+        line_comments: &["#"],
+        strings: &[
+            multiline("\"\"\"", "\"\"\"", true),
+            multiline("'''", "'''", false),
+            quoted("\"", "\""),
+            quoted("'", "'"),
+        ],
+        constants: &["true", "false"],
+        dash_in_identifiers: true,
+        keys: KeyStyle::Equals,
+        sections: true,
+        ..BASE
+    },
+    Rules {
+        name: "ini",
+        line_comments: &["#", ";"],
+        strings: &[quoted("\"", "\"")],
+        constants: &["true", "false"],
+        dash_in_identifiers: true,
+        keys: KeyStyle::Equals,
+        sections: true,
+        ..BASE
+    },
+    Rules {
+        name: "dockerfile",
+        line_comments: &["#"],
+        strings: &[quoted("\"", "\""), quoted("'", "'")],
+        keywords: &[
+            "FROM", "RUN", "CMD", "LABEL", "EXPOSE", "ENV", "ADD", "COPY", "ENTRYPOINT", "VOLUME", "USER", "WORKDIR",
+            "ARG", "ONBUILD", "STOPSIGNAL", "HEALTHCHECK", "SHELL", "AS",
+        ],
+        case_insensitive: true,
+        variable_prefix: Some('$'),
+        ..BASE
+    },
+    Rules {
+        name: "terraform",
+        line_comments: &["#", "//"],
+        block_comments: C_COMMENTS,
+        strings: &[quoted("\"", "\"")],
+        keywords: &[
+            "resource", "data", "variable", "output", "module", "provider", "locals", "terraform", "for", "in", "if",
+            "dynamic", "content",
+        ],
+        constants: &["true", "false", "null"],
+        keys: KeyStyle::Equals,
+        ..BASE
+    },
+    Rules {
+        name: "gitignore",
+        line_comments: &["#"],
+        ..BASE
+    },
+    Rules {
+        name: "dotenv",
+        line_comments: &["#"],
+        strings: &[quoted("\"", "\""), quoted("'", "'")],
+        keys: KeyStyle::Equals,
+        variable_prefix: Some('$'),
+        ..BASE
+    },
+];
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl Rules {
+    fn is_ident_continue(&self, c: char) -> bool {
+        is_ident_char(c) || (self.dash_in_identifiers && c == '-')
+    }
+
+    fn word_in(&self, list: &[&str], word: &str) -> bool {
+        if self.case_insensitive {
+            list.iter().any(|entry| entry.eq_ignore_ascii_case(word))
+        } else {
+            list.contains(&word)
+        }
+    }
+
+    /// String rule starting at `pos`, honouring a preceding identifier boundary
+    fn string_at(&self, line: &str, pos: usize) -> Option<usize> {
+        let rest = &line[pos..];
+        self.strings.iter().position(|rule| {
+            rest.starts_with(rule.open)
+                && (!rule.open.starts_with(is_ident_start)
+                    || !line[..pos].chars().next_back().is_some_and(is_ident_char))
+        })
+    }
+
+    /// 对一行分词；`state` 为行首状态，返回时更新为下一行的行首状态
+    pub fn tokenize_line(&self, line: &str, state: &mut State) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let push = |tokens: &mut Vec<Token>, range: Range<usize>, kind| {
+            if !range.is_empty() {
+                tokens.push(Token { range, kind });
+            }
+        };
+
+        let mut pos = 0;
+        while pos < line.len() {
+            match *state {
+                State::BlockComment { rule, depth } => {
+                    let end = self.scan_block_comment(line, pos, rule, depth, state);
+                    push(&mut tokens, pos..end, TokenKind::Comment);
+                    pos = end;
+                    continue;
+                }
+                State::Str { rule } => {
+                    let end = self.scan_string(line, pos, rule, state);
+                    push(&mut tokens, pos..end, TokenKind::String);
+                    pos = end;
+                    continue;
+                }
+                State::Normal => {}
+            }
+
+            let rest = &line[pos..];
+            let c = rest.chars().next().unwrap_or(' ');
+            let at_line_start = line[..pos].trim().is_empty();
+
+            if c.is_whitespace() {
+                pos += c.len_utf8();
+            } else if self.sections && at_line_start && c == '[' {
+                let end = rest.rfind(']').map_or(line.len(), |index| pos + index + 1);
+                push(&mut tokens, pos..end, TokenKind::Tag);
+                pos = end;
+            } else if self.line_comments.iter().any(|prefix| rest.starts_with(prefix)) {
+                push(&mut tokens, pos..line.len(), TokenKind::Comment);
+                pos = line.len();
+            } else if let Some(rule) = self.block_comments.iter().position(|(open, _)| rest.starts_with(open)) {
+                let start = pos;
+                pos += self.block_comments[rule].0.len();
+                *state = State::BlockComment { rule, depth: 1 };
+                let end = self.scan_block_comment(line, pos, rule, 1, state);
+                push(&mut tokens, start..end, TokenKind::Comment);
+                pos = end;
+            } else if let Some(rule) = self.string_at(line, pos) {
+                let start = pos;
+                pos += self.strings[rule].open.len();
+                *state = State::Str { rule };
+                let end = self.scan_string(line, pos, rule, state);
+                push(&mut tokens, start..end, TokenKind::String);
+                pos = end;
+            } else if self.attributes == AttributeStyle::At && c == '@' && rest[1..].starts_with(is_ident_start) {
+                let end = pos + 1 + self.ident_len(&rest[1..]);
+                push(&mut tokens, pos..end, TokenKind::Attribute);
+                pos = end;
+            } else if self.variable_prefix == Some(c) && rest.len() > 1 {
+                let end = pos + variable_len(rest);
+                push(&mut tokens, pos..end, TokenKind::Variable);
+                pos = end;
+            } else if c.is_ascii_digit() && !line[..pos].chars().next_back().is_some_and(is_ident_char) {
+                let end = pos + number_len(rest);
+                push(&mut tokens, pos..end, TokenKind::Number);
+                pos = end;
+            } else if is_ident_start(c) || (c == '#' && self.keywords.iter().any(|k| k.starts_with('#'))) {
+                let len = c.len_utf8() + self.ident_len(&rest[c.len_utf8()..]);
+                let word = &rest[..len];
+                let after = &line[pos + len..];
+
+                let kind = self.classify(word, after, at_line_start);
+                if let Some(kind) = kind {
+                    push(&mut tokens, pos..pos + len, kind);
+                }
+                pos += len;
+            } else {
+                if "+-*/%=<>!&|^~?:".contains(c) {
+                    push(&mut tokens, pos..pos + c.len_utf8(), TokenKind::Operator);
+                }
+                pos += c.len_utf8();
+            }
+        }
+
+        // Single-line strings end with the line
+        if let State::Str { rule } = *state {
+            if !self.strings[rule].multiline {
+                *state = State::Normal;
+            }
+        }
+        tokens
+    }
+
+    fn ident_len(&self, text: &str) -> usize {
+        text.char_indices()
+            .find(|&(_, c)| !self.is_ident_continue(c))
+            .map_or(text.len(), |(index, _)| index)
+    }
+
+    fn classify(&self, word: &str, after: &str, at_line_start: bool) -> Option<TokenKind> {
+        let next = after.trim_start();
+        if self.keys == KeyStyle::Equals && at_line_start && next.starts_with('=') {
+            return Some(TokenKind::Key);
+        }
+        if self.word_in(self.keywords, word) {
+            Some(TokenKind::Keyword)
+        } else if self.word_in(self.constants, word) {
+            Some(TokenKind::Constant)
+        } else if self.word_in(self.types, word) {
+            Some(TokenKind::Type)
+        } else if after.starts_with('(') || after.starts_with("!(") {
+            Some(TokenKind::Function)
+        } else if self.capitalized_types && word.starts_with(|c: char| c.is_uppercase()) {
+            Some(TokenKind::Type)
+        } else {
+            None
+        }
+    }
+
+    /// Scan to the end of a block comment; updates `state`, returns the end offset
+    fn scan_block_comment(&self, line: &str, mut pos: usize, rule: usize, mut depth: usize, state: &mut State) -> usize {
+        let (open, close) = self.block_comments[rule];
+        while pos < line.len() {
+            let rest = &line[pos..];
+            if rest.starts_with(close) {
+                pos += close.len();
+                depth -= 1;
+                if depth == 0 {
+                    *state = State::Normal;
+                    return pos;
+                }
+            } else if self.nested_comments && rest.starts_with(open) {
+                pos += open.len();
+                depth += 1;
+            } else {
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        *state = State::BlockComment { rule, depth };
+        line.len()
+    }
+
+    /// Scan to the closing delimiter of a string; updates `state`, returns the end offset
+    fn scan_string(&self, line: &str, mut pos: usize, rule: usize, state: &mut State) -> usize {
+        let string = self.strings[rule];
+        while pos < line.len() {
+            let rest = &line[pos..];
+            if string.escape && rest.starts_with('\\') {
+                pos += 1;
+                pos += line[pos..].chars().next().map_or(0, char::len_utf8);
+            } else if rest.starts_with(string.close) {
+                *state = State::Normal;
+                return pos + string.close.len();
+            } else {
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        *state = State::Str { rule };
+        line.len()
+    }
+}
+
+fn number_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut len = 0;
+    while len < bytes.len() {
+        let c = bytes[len];
+        let decimal_point = c == b'.' && bytes.get(len + 1).is_some_and(u8::is_ascii_digit);
+        if c.is_ascii_alphanumeric() || c == b'_' || decimal_point {
+            len += 1;
+        } else {
+            break;
+        }
+    }
+    len
+}
+
+/// `$name`, `${...}`, `$(...)`, `$1`, `$@`
+fn variable_len(text: &str) -> usize {
+    let rest = &text[1..];
+    if rest.starts_with('{') || rest.starts_with('(') {
+        let close = if rest.starts_with('{') { '}' } else { ')' };
+        return rest.find(close).map_or(text.len(), |index| index + 2);
+    }
+    let ident = rest.char_indices().find(|&(_, c)| !is_ident_char(c)).map_or(rest.len(), |(index, _)| index);
+    if ident > 0 {
+        1 + ident
+    } else if rest.starts_with(|c: char| "@*#?$!-".contains(c)) {
+        2
+    } else {
+        1
+    }
+}
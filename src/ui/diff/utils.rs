@@ -1,10 +1,13 @@
 use crate::{
     error::{AppError, AppResult},
-    ui::diff::{DiffLine, DiffLineType, FileDiff, DiffHunk},
+    ui::diff::{algorithm, DiffAlgorithm, DiffLine, DiffLineType, DiffOp, FileDiff, DiffHunk, IntralineGranularity, WhitespaceMode},
 };
 use std::path::PathBuf;
 use tracing::{debug, warn};
 
+/// 行内高亮所需的最小相似度（未变化的非空白内容占较长一行的百分比）
+const MIN_INTRALINE_SIMILARITY_PERCENT: usize = 30;
+
 /// 差异工具函数集合
 pub struct DiffUtils;
 
@@ -141,59 +144,115 @@ impl DiffUtils {
 
     /// 计算单词级差异高亮
     pub fn compute_word_highlights(old_line: &str, new_line: &str) -> (Vec<(usize, usize)>, Vec<(usize, usize)>) {
-        let old_words: Vec<&str> = old_line.split_whitespace().collect();
-        let new_words: Vec<&str> = new_line.split_whitespace().collect();
-
-        let mut old_highlights = Vec::new();
-        let mut new_highlights = Vec::new();
-
-        // 简化的单词级差异算法
-        let mut old_pos = 0;
-        let mut new_pos = 0;
-
-        for (i, old_word) in old_words.iter().enumerate() {
-            let old_start = old_pos;
-            let old_end = old_pos + old_word.len();
-
-            let mut found_match = false;
-            let mut temp_new_pos = 0;
+        Self::compute_intraline_highlights(old_line, new_line, IntralineGranularity::Word)
+    }
 
-            for (j, new_word) in new_words.iter().enumerate() {
-                if i == j && old_word == new_word {
-                    found_match = true;
-                    break;
+    /// 计算一对修改行的行内差异，返回两侧变化部分的字节范围
+    ///
+    /// Lines that share too little are left without highlights: marking
+    /// almost every token of a rewritten line only adds noise.
+    pub fn compute_intraline_highlights(
+        old_line: &str,
+        new_line: &str,
+        granularity: IntralineGranularity,
+    ) -> (Vec<(usize, usize)>, Vec<(usize, usize)>) {
+        let old_tokens = Self::intraline_tokens(old_line, granularity);
+        let new_tokens = Self::intraline_tokens(new_line, granularity);
+        let old_words: Vec<&str> = old_tokens.iter().map(|&(start, end)| &old_line[start..end]).collect();
+        let new_words: Vec<&str> = new_tokens.iter().map(|&(start, end)| &new_line[start..end]).collect();
+
+        let ops = algorithm::diff_lines(&old_words, &new_words, DiffAlgorithm::Myers, WhitespaceMode::Exact);
+
+        let mut old_highlights: Vec<(usize, usize)> = Vec::new();
+        let mut new_highlights: Vec<(usize, usize)> = Vec::new();
+        let mut common = 0;
+        for op in ops {
+            match op {
+                DiffOp::Equal { old, .. } => {
+                    if !old_words[old].trim().is_empty() {
+                        common += old_words[old].len();
+                    }
                 }
-                temp_new_pos += new_word.len() + 1; // +1 for space
-            }
-
-            if !found_match {
-                old_highlights.push((old_start, old_end));
+                DiffOp::Delete { old } => Self::push_range(&mut old_highlights, old_tokens[old]),
+                DiffOp::Insert { new } => Self::push_range(&mut new_highlights, new_tokens[new]),
             }
+        }
 
-            old_pos = old_end + 1; // +1 for space
+        let longest = old_line.trim().len().max(new_line.trim().len());
+        if common * 100 < longest * MIN_INTRALINE_SIMILARITY_PERCENT {
+            return (Vec::new(), Vec::new());
         }
+        (old_highlights, new_highlights)
+    }
 
-        for (i, new_word) in new_words.iter().enumerate() {
-            let new_start = new_pos;
-            let new_end = new_pos + new_word.len();
+    /// 为块内成对的删除/添加行填充行内高亮
+    ///
+    /// A run of deleted lines directly followed by a run of added lines is
+    /// treated as a modification; the lines are paired in order. Lines longer
+    /// than `max_line_len` are skipped.
+    pub fn apply_intraline_highlights(hunk: &mut DiffHunk, granularity: IntralineGranularity, max_line_len: usize) {
+        for line in &mut hunk.lines {
+            line.highlights.clear();
+        }
 
-            let mut found_match = false;
+        let lines = &mut hunk.lines;
+        let mut index = 0;
+        while index < lines.len() {
+            let deleted_start = index;
+            while index < lines.len() && lines[index].line_type == DiffLineType::Deleted {
+                index += 1;
+            }
+            let added_start = index;
+            while index < lines.len() && lines[index].line_type == DiffLineType::Added {
+                index += 1;
+            }
+            if deleted_start == added_start || added_start == index {
+                index = index.max(deleted_start + 1);
+                continue;
+            }
 
-            for (j, old_word) in old_words.iter().enumerate() {
-                if i == j && new_word == old_word {
-                    found_match = true;
-                    break;
+            for offset in 0..(added_start - deleted_start).min(index - added_start) {
+                let (old_index, new_index) = (deleted_start + offset, added_start + offset);
+                if lines[old_index].content.len() > max_line_len || lines[new_index].content.len() > max_line_len {
+                    continue;
                 }
+                let (old_highlights, new_highlights) =
+                    Self::compute_intraline_highlights(&lines[old_index].content, &lines[new_index].content, granularity);
+                lines[old_index].highlights = old_highlights;
+                lines[new_index].highlights = new_highlights;
             }
+        }
+    }
 
-            if !found_match {
-                new_highlights.push((new_start, new_end));
+    /// 按粒度切分：单词粒度下为标识符、空白串和单个符号；字符粒度下为每个字符
+    fn intraline_tokens(line: &str, granularity: IntralineGranularity) -> Vec<(usize, usize)> {
+        let mut tokens = Vec::new();
+        let mut chars = line.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let mut end = start + c.len_utf8();
+            if granularity == IntralineGranularity::Word {
+                let class = |c: char| if c.is_alphanumeric() || c == '_' { 1 } else if c.is_whitespace() { 2 } else { 0 };
+                if class(c) != 0 {
+                    while let Some(&(index, next)) = chars.peek() {
+                        if class(next) != class(c) {
+                            break;
+                        }
+                        end = index + next.len_utf8();
+                        chars.next();
+                    }
+                }
             }
-
-            new_pos = new_end + 1; // +1 for space
+            tokens.push((start, end));
         }
+        tokens
+    }
 
-        (old_highlights, new_highlights)
+    /// 追加范围，与前一个相邻范围合并
+    fn push_range(ranges: &mut Vec<(usize, usize)>, (start, end): (usize, usize)) {
+        match ranges.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
     }
 
     /// 检测文件的编程语言
@@ -230,7 +289,10 @@ impl DiffUtils {
                 "xml" => "xml",
                 "sql" => "sql",
                 "dockerfile" => "dockerfile",
-                "tf" => "terraform",
+                "tf" | "hcl" => "terraform",
+                "mk" => "makefile",
+                "ini" | "cfg" | "conf" => "ini",
+                "svg" | "xhtml" | "vue" => "xml",
                 _ => "text",
             }.to_string()
         } else {
@@ -327,4 +389,71 @@ impl DiffUtils {
 
         score as u32
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(line_type: DiffLineType, content: &str) -> DiffLine {
+        DiffLine {
+            line_type,
+            old_line_number: None,
+            new_line_number: None,
+            content: content.to_string(),
+            highlights: vec![],
+        }
+    }
+
+    #[test]
+    fn test_intraline_granularity() {
+        let (old, new) = DiffUtils::compute_intraline_highlights(
+            "let total = count + 1;",
+            "let total = counter + 1;",
+            IntralineGranularity::Word,
+        );
+        assert_eq!(old, vec![(12, 17)]);
+        assert_eq!(new, vec![(12, 19)]);
+
+        let (old, new) = DiffUtils::compute_intraline_highlights(
+            "let total = count + 1;",
+            "let total = counter + 1;",
+            IntralineGranularity::Char,
+        );
+        assert!(old.is_empty());
+        assert_eq!(new, vec![(17, 19)]);
+
+        // Completely rewritten lines get no highlights
+        let (old, new) = DiffUtils::compute_intraline_highlights("fn a() {}", "struct Zed;", IntralineGranularity::Word);
+        assert!(old.is_empty() && new.is_empty());
+    }
+
+    #[test]
+    fn test_apply_intraline_pairs_lines() {
+        let mut hunk = DiffHunk {
+            header: "@@ -1,3 +1,3 @@".to_string(),
+            old_start: 1,
+            old_lines: 3,
+            new_start: 1,
+            new_lines: 3,
+            lines: vec![
+                line(DiffLineType::Context, "fn main() {"),
+                line(DiffLineType::Deleted, "    let x = 1;"),
+                line(DiffLineType::Deleted, "    let y = 2;"),
+                line(DiffLineType::Added, "    let x = 10;"),
+                line(DiffLineType::Added, "    let y = 2; // same"),
+                line(DiffLineType::Added, "    println!();"),
+            ],
+        };
+        DiffUtils::apply_intraline_highlights(&mut hunk, IntralineGranularity::Word, 1000);
+        assert_eq!(hunk.lines[1].highlights, vec![(12, 13)]);
+        assert_eq!(hunk.lines[3].highlights, vec![(12, 14)]);
+        assert!(hunk.lines[2].highlights.is_empty());
+        assert_eq!(hunk.lines[4].highlights, vec![(14, 22)]);
+        assert!(hunk.lines[5].highlights.is_empty());
+
+        // Over-long lines fall back to whole-line highlighting
+        DiffUtils::apply_intraline_highlights(&mut hunk, IntralineGranularity::Word, 10);
+        assert!(hunk.lines.iter().all(|line| line.highlights.is_empty()));
+    }
+}
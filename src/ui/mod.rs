@@ -10,7 +10,7 @@ pub mod theme;
 pub mod diff;
 pub mod selection;
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::Style,
@@ -23,6 +23,7 @@ use crate::{
     app::state::{AppState, TabType, FocusArea},
     config::UIConfig,
     error::AppResult,
+    git::Workspace,
 };
 use selection::{TextPosition, SelectionMode};
 use components::*;
@@ -36,6 +37,17 @@ pub struct UI {
     config: UIConfig,
     /// Component instances
     components: UIComponents,
+    /// Registered keyboard shortcuts, also listed by the command palette
+    shortcuts: ShortcutsManager,
+    /// Push, pull or branch deletion waiting for confirmation
+    pending_confirmation: Option<PendingConfirmation>,
+}
+
+/// Action the confirmation modal runs once confirmed
+enum PendingConfirmation {
+    /// Push or pull of the current branch
+    Remote(ShortcutAction),
+    DeleteBranch(String),
 }
 
 impl UI {
@@ -44,15 +56,27 @@ impl UI {
         debug!("Initializing UI with theme: {}", config.theme);
 
         let theme = Theme::load(&config.theme)?;
-        let components = UIComponents::new(&theme);
+        let mut components = UIComponents::new(&theme);
+        let mut diff_config = config.diff.clone();
+        diff_config.syntax_highlighting &= config.enable_syntax_highlighting;
+        components.status_tab.set_diff_config(&diff_config);
+        components.branches_tab.set_diff_config(&diff_config);
+        components.history_tab.set_diff_config(&diff_config);
 
         Ok(Self {
             theme,
             config: config.clone(),
             components,
+            shortcuts: ShortcutsManager::new(),
+            pending_confirmation: None,
         })
     }
 
+    /// Enable workspace mode with the given repositories
+    pub fn set_workspace(&mut self, workspace: Workspace) {
+        self.components.workspace.set_workspace(workspace);
+    }
+
     /// Render the entire UI with four-layer layout: Tabs + Toolbar + Content + Help
     pub fn render(&mut self, frame: &mut Frame, state: &AppState) {
         let size = frame.size();
@@ -82,15 +106,74 @@ impl UI {
 
         // Render help overlay if visible (rendered last to appear on top)
         self.components.help.render(frame, size, state, &self.theme);
+
+        // The command palette sits above everything else
+        self.components.palette.render(frame, size, &self.theme);
+        if self.components.confirm.is_open() {
+            self.components.confirm.render(frame, size, &self.theme);
+        }
+    }
+
+    /// Whether the active tab is editing text, so global shortcuts must not fire
+    pub fn captures_text_input(&self, state: &AppState) -> bool {
+        self.components.palette.is_open()
+            || self.components.confirm.is_open()
+            || (!self.components.help.is_visible() && self.components.captures_text_input(state))
     }
 
     /// Handle key events
     pub fn handle_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        // A pending push, pull or branch deletion waits for its confirmation
+        if self.components.confirm.is_open() {
+            match self.components.confirm.handle_key_event(key)? {
+                ModalResult::Confirmed => match self.pending_confirmation.take() {
+                    Some(PendingConfirmation::Remote(action)) => self.run_remote_action(action, state),
+                    Some(PendingConfirmation::DeleteBranch(branch)) => {
+                        self.run_picked(BranchPicker::Delete, &branch, state)
+                    }
+                    None => {}
+                },
+                ModalResult::Cancelled => self.pending_confirmation = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // The command palette takes every key while open
+        if self.components.palette.is_open() {
+            if let Some(action) = self.components.palette.handle_key(key) {
+                self.run_palette_action(action, state)?;
+            }
+            return Ok(());
+        }
+
+        self.shortcuts.set_context(self.components.shortcut_context(state));
+        let shortcut = self.shortcuts.handle_key_event(key);
+
+        // The palette also opens over a diff, which otherwise receives every key
+        if shortcut == Some(ShortcutAction::CommandPalette)
+            && !self.components.help.is_visible()
+            && (!self.components.captures_text_input(state) || self.components.shows_diff(state))
+        {
+            self.open_palette(state);
+            return Ok(());
+        }
+
+        // An open editor receives every key, including the global shortcuts
+        if self.captures_text_input(state) {
+            return self.components.handle_active_tab_key(key, state);
+        }
+
         // Handle help system first (highest priority)
         if self.components.help.handle_key_event(key, state)? {
             return Ok(());
         }
 
+        // Workspace shortcuts (Alt+key) work in every tab
+        if self.components.workspace.handle_key_event(key, state)? {
+            return Ok(());
+        }
+
         // Handle global help toggle
         if matches!(key.code, KeyCode::Char('?')) {
             self.components.help.toggle();
@@ -122,9 +205,18 @@ impl UI {
             }
         }
 
+        // Registered Ctrl/Alt/function-key shortcuts; plain keys stay with the views
+        let registry_key = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            || matches!(key.code, KeyCode::F(_));
+        if let Some(action) = shortcut.filter(|_| registry_key) {
+            if self.run_shortcut(action, Shortcut::from_key_event(key), state)? {
+                return Ok(());
+            }
+        }
+
         match key.code {
             // Tab navigation with number keys
-            KeyCode::Char(c @ '1'..='7') if !key.modifiers.contains(KeyModifiers::ALT) => {
+            KeyCode::Char(c @ '1'..='8') if !key.modifiers.contains(KeyModifiers::ALT) => {
                 let tab_index = (c as u8 - b'1') as usize;
                 if tab_index < TabType::all().len() {
                     state.set_current_tab(TabType::all()[tab_index]);
@@ -153,31 +245,6 @@ impl UI {
                 };
                 state.set_current_tab(TabType::all()[prev_index]);
             }
-            // Modern Git client keyboard shortcuts
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // Alt+C: Checkout branch
-                debug!("Alt+C: Checkout branch requested");
-            }
-            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // Alt+N: Create new branch
-                debug!("Alt+N: Create new branch requested");
-            }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // Alt+D: Delete branch
-                debug!("Alt+D: Delete branch requested");
-            }
-            KeyCode::Char('m') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // Alt+M: Merge branch
-                debug!("Alt+M: Merge branch requested");
-            }
-            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // Alt+P: Push changes
-                debug!("Alt+P: Push changes requested");
-            }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::ALT) => {
-                // Alt+U: Pull changes
-                debug!("Alt+U: Pull changes requested");
-            }
             KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Ctrl+R: Refresh
                 debug!("Refresh requested");
@@ -202,6 +269,7 @@ impl UI {
                     TabType::Remotes => self.components.remotes_tab.handle_key_event(key, state)?,
                     TabType::History => self.components.history_tab.handle_key_event(key, state)?,
                     TabType::GitFlow => self.components.gitflow_tab.handle_key_event(key, state)?,
+                    TabType::Insights => self.components.insights_tab.handle_key_event(key, state)?,
                 }
             }
             // Forward other keys to the active component
@@ -213,6 +281,256 @@ impl UI {
         Ok(())
     }
 
+    /// Open the command palette over the actions of the current view
+    ///
+    /// Lists the registered shortcuts of the current context, the keys the
+    /// view handles itself and, in the Status file list, the file menu.
+    fn open_palette(&mut self, state: &AppState) {
+        let context = self.components.shortcut_context(state);
+        self.shortcuts.set_context(context.clone());
+
+        let mut definitions: Vec<_> = self
+            .shortcuts
+            .get_shortcuts_for_context(&context)
+            .into_iter()
+            .filter(|definition| Self::runs_shortcut(&definition.action))
+            .collect();
+        definitions.sort_by_key(|definition| definition.shortcut.to_string());
+
+        let mut commands: Vec<PaletteCommand> = definitions
+            .into_iter()
+            .map(|definition| {
+                let key = definition.shortcut.to_string();
+                let group = if definition.context == ShortcutContext::Global { "Global" } else { "Shortcut" };
+                PaletteCommand::new(
+                    format!("shortcut:{}", key),
+                    definition.description.clone(),
+                    group,
+                    PaletteAction::Shortcut(definition.action.clone(), definition.shortcut.clone()),
+                )
+                .with_keybinding(key)
+            })
+            .collect();
+
+        commands.extend(command_palette::view_commands(state.current_tab(), &context));
+
+        if context == ShortcutContext::FileList {
+            commands.extend(self.components.status_tab.file_menu_items(state).into_iter().map(|item| {
+                let command = PaletteCommand::new(format!("menu:{}", item.id), item.label, "File menu", PaletteAction::Menu(item.action));
+                match item.hotkey {
+                    Some(hotkey) => command.with_keybinding(hotkey),
+                    None => command,
+                }
+            }));
+        }
+
+        self.components.palette.open(commands);
+    }
+
+    /// Registered actions the UI knows how to run
+    fn runs_shortcut(action: &ShortcutAction) -> bool {
+        matches!(
+            action,
+            ShortcutAction::Quit
+                | ShortcutAction::Refresh
+                | ShortcutAction::Help
+                | ShortcutAction::GitStatus
+                | ShortcutAction::GitDiff
+                | ShortcutAction::GitCommit
+                | ShortcutAction::GitPush
+                | ShortcutAction::GitPull
+                | ShortcutAction::CheckoutBranch
+                | ShortcutAction::CreateBranch
+                | ShortcutAction::DeleteBranch
+                | ShortcutAction::MergeBranch
+                | ShortcutAction::OpenFile
+                | ShortcutAction::SwitchTab
+        )
+    }
+
+    /// Run a command chosen in the palette
+    fn run_palette_action(&mut self, action: PaletteAction, state: &mut AppState) -> AppResult<()> {
+        match action {
+            PaletteAction::Shortcut(action, shortcut) => {
+                self.run_shortcut(action, shortcut, state)?;
+            }
+            PaletteAction::Key(key) => self.handle_key_event(key, state)?,
+            PaletteAction::Menu(action) => self.components.status_tab.run_file_menu_action(action, state),
+            PaletteAction::Picked(BranchPicker::Delete, branch) => {
+                let message = format!("Delete local branch '{}'?", branch);
+                self.components.confirm.open_with_buttons("Delete Branch", &message, "Delete", "Cancel");
+                self.pending_confirmation = Some(PendingConfirmation::DeleteBranch(branch));
+            }
+            PaletteAction::Picked(kind, value) => self.run_picked(kind, &value, state),
+        }
+        Ok(())
+    }
+
+    /// Run a registered shortcut; returns false when the UI does not handle it
+    fn run_shortcut(&mut self, action: ShortcutAction, shortcut: Shortcut, state: &mut AppState) -> AppResult<bool> {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        match action {
+            ShortcutAction::Quit => state.set_should_quit(true),
+            ShortcutAction::Refresh => {
+                if let Some(git_service) = state.git_service.clone() {
+                    match tokio::task::block_in_place(|| {
+                        tokio::runtime::Handle::current().block_on(git_service.get_status())
+                    }) {
+                        Ok(status) => state.update_git_status(status),
+                        Err(e) => state.add_error(format!("Failed to refresh status: {}", e)),
+                    }
+                }
+            }
+            ShortcutAction::Help => self.components.help.show(),
+            ShortcutAction::CommandPalette => self.open_palette(state),
+            ShortcutAction::GitStatus => state.set_current_tab(TabType::Status),
+            ShortcutAction::GitDiff | ShortcutAction::OpenFile => self.handle_key_event(key(KeyCode::Enter), state)?,
+            ShortcutAction::GitCommit => {
+                state.set_current_tab(TabType::Status);
+                self.handle_key_event(key(KeyCode::Char('c')), state)?;
+            }
+            ShortcutAction::SwitchTab => {
+                let tabs = TabType::all();
+                let current = tabs.iter().position(|&tab| tab == state.current_tab()).unwrap_or(0);
+                let next = if shortcut.modifiers.contains(KeyModifiers::SHIFT) {
+                    (current + tabs.len() - 1) % tabs.len()
+                } else {
+                    (current + 1) % tabs.len()
+                };
+                state.set_current_tab(tabs[next]);
+            }
+            ShortcutAction::GitPush | ShortcutAction::GitPull => {
+                let Some(branch) = state.git_state.current_branch.as_ref().filter(|_| state.git_service.is_some()) else {
+                    return Ok(true);
+                };
+                let (title, message, confirm) = if action == ShortcutAction::GitPush {
+                    ("Push", format!("Push branch '{}' to its upstream?", branch.name), "Push")
+                } else {
+                    ("Pull", format!("Pull into branch '{}' from its upstream?", branch.name), "Pull")
+                };
+                self.components.confirm.open_with_buttons(title, &message, confirm, "Cancel");
+                self.pending_confirmation = Some(PendingConfirmation::Remote(action));
+            }
+            ShortcutAction::CheckoutBranch => self.open_branch_picker(BranchPicker::Checkout, state),
+            ShortcutAction::CreateBranch => self.components.palette.open_picker(BranchPicker::Create, Vec::new()),
+            ShortcutAction::DeleteBranch => self.open_branch_picker(BranchPicker::Delete, state),
+            ShortcutAction::MergeBranch => self.open_branch_picker(BranchPicker::Merge, state),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Run a confirmed push or pull of the current branch
+    fn run_remote_action(&mut self, action: ShortcutAction, state: &mut AppState) {
+        let (Some(git_service), Some(branch)) = (state.git_service.clone(), state.git_state.current_branch.clone()) else {
+            return;
+        };
+        if action == ShortcutAction::GitPush {
+            match tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(git_service.push_branch(&branch.name))
+            }) {
+                Ok(()) => state.add_info(format!("Pushed branch '{}'", branch.name)),
+                Err(e) => state.add_error(format!("Failed to push branch '{}': {}", branch.name, e)),
+            }
+        } else {
+            match tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(git_service.pull())) {
+                Ok(()) => state.add_info("Pulled current branch".to_string()),
+                Err(e) => state.add_error(format!("Failed to pull: {}", e)),
+            }
+        }
+    }
+
+    /// Chain a branch action into a picker over the branches it applies to
+    fn open_branch_picker(&mut self, kind: BranchPicker, state: &mut AppState) {
+        let Some(git_service) = &state.git_service else {
+            return;
+        };
+        let branches = match git_service.list_branches() {
+            Ok(branches) => branches,
+            Err(e) => {
+                state.add_error(format!("Failed to load branches: {}", e));
+                return;
+            }
+        };
+
+        let choices: Vec<PaletteCommand> = branches
+            .into_iter()
+            .filter(|branch| !branch.is_current)
+            .filter(|branch| kind == BranchPicker::Merge || !branch.is_remote)
+            .map(|branch| {
+                let group = if branch.is_remote { "Remote" } else { "Local" };
+                PaletteCommand::new(
+                    format!("branch:{}", branch.name),
+                    branch.name.clone(),
+                    group,
+                    PaletteAction::Picked(kind, branch.name),
+                )
+            })
+            .collect();
+
+        if choices.is_empty() {
+            state.add_info("No other branches".to_string());
+            return;
+        }
+        self.components.palette.open_picker(kind, choices);
+    }
+
+    /// Run a branch action with the value chosen in its picker
+    fn run_picked(&mut self, kind: BranchPicker, branch: &str, state: &mut AppState) {
+        let Some(git_service) = state.git_service.clone() else {
+            return;
+        };
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                match kind {
+                    BranchPicker::Checkout => git_service.switch_branch(branch).await,
+                    BranchPicker::Create => git_service.create_branch(branch, None).await.map(|_| ()),
+                    BranchPicker::Delete => git_service.delete_branch(branch).await,
+                    BranchPicker::Merge => git_service.merge_branch(branch).await,
+                }
+            })
+        });
+
+        let (done, failed) = match kind {
+            BranchPicker::Checkout => ("Switched to branch", "Failed to checkout branch"),
+            BranchPicker::Create => ("Created branch", "Failed to create branch"),
+            BranchPicker::Delete => ("Deleted branch", "Failed to delete branch"),
+            BranchPicker::Merge => ("Merged branch", "Failed to merge branch"),
+        };
+        match result {
+            Ok(()) => state.add_info(format!("{} '{}'", done, branch)),
+            Err(e) => {
+                state.add_error(format!("{} '{}': {}", failed, branch, e));
+                return;
+            }
+        }
+
+        if kind == BranchPicker::Checkout {
+            if let Some(current) = git_service
+                .list_branches()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|candidate| candidate.is_current)
+            {
+                state.update_current_branch(current, Vec::new());
+            }
+        }
+    }
+
+    /// Handle mouse events
+    ///
+    /// Only the Status tab reacts to the mouse (file selection and context menu).
+    pub fn handle_mouse_event(&mut self, mouse: MouseEvent, state: &mut AppState) -> AppResult<()> {
+        if self.components.help.is_visible() {
+            return Ok(());
+        }
+
+        if state.current_tab() == TabType::Status {
+            self.components.status_tab.handle_mouse_event(mouse, state)?;
+        }
+        Ok(())
+    }
+
     /// Handle terminal resize
     pub fn handle_resize(&mut self, width: u16, height: u16) {
         debug!("Terminal resized to {}x{}", width, height);
@@ -274,6 +592,17 @@ impl UI {
                     "• hotfix/".to_string(),
                 ]
             }
+            TabType::Insights => {
+                // Repository insights sections
+                vec![
+                    "Repository insights".to_string(),
+                    "".to_string(),
+                    "• Authors".to_string(),
+                    "• Churn".to_string(),
+                    "• Hotspots".to_string(),
+                    "• Bus Factor".to_string(),
+                ]
+            }
         }
     }
 
@@ -365,15 +694,7 @@ impl UI {
 
     /// Render bottom help/shortcuts bar
     fn render_help_bar(&self, frame: &mut Frame, area: Rect, state: &AppState) {
-        let help_text = match state.current_tab() {
-            TabType::Branches => "↑/↓: Select branch | Space: Switch panel | Enter: Checkout | ?: Help | 1-7: Switch tabs",
-            TabType::Tags => "↑/↓: Select tag | Space: Switch panel | Enter: View tag | ?: Help | 1-7: Switch tabs",
-            TabType::Stash => "↑/↓: Select stash | Space: Switch panel | Enter: Apply | ?: Help | 1-7: Switch tabs",
-            TabType::Status => "↑/↓: Select file | Space: Switch panel | Enter: Stage | ?: Help | 1-7: Switch tabs",
-            TabType::Remotes => "↑/↓: Select remote | Space: Switch panel | Enter: Fetch | ?: Help | 1-7: Switch tabs",
-            TabType::History => "↑/↓: Select commit | Space: Switch panel | Enter: View | ?: Help | 1-7: Switch tabs",
-            TabType::GitFlow => "↑/↓: Navigate | Space: Switch panel | Enter: Execute | ?: Help | 1-7: Switch tabs",
-        };
+        let help_text = view_keys::help_bar(state.current_tab());
 
         let help_para = Paragraph::new(help_text)
             .block(
@@ -459,6 +780,11 @@ impl UI {
                     .gitflow_tab
                     .render(frame, content_area, state, &self.theme);
             }
+            TabType::Insights => {
+                self.components
+                    .insights_tab
+                    .render(frame, content_area, state, &self.theme);
+            }
         }
     }
 
@@ -652,6 +978,24 @@ impl UI {
 
     /// Render tab content based on current active tab
     fn render_tab_content(&mut self, frame: &mut Frame, area: Rect, state: &AppState) {
+        // In workspace mode the repository list stays visible next to every tab
+        let area = if self.components.workspace.is_enabled() {
+            let workspace_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(32), // Workspace repositories
+                    Constraint::Min(0),     // Tab content
+                ])
+                .split(area);
+
+            self.components
+                .workspace
+                .render(frame, workspace_layout[0], state, &self.theme);
+            workspace_layout[1]
+        } else {
+            area
+        };
+
         match state.current_tab() {
             TabType::Branches => {
                 // For Branches tab, show enhanced branch management interface
@@ -707,6 +1051,11 @@ impl UI {
                     .gitflow_tab
                     .render(frame, area, state, &self.theme);
             }
+            TabType::Insights => {
+                self.components
+                    .insights_tab
+                    .render(frame, area, state, &self.theme);
+            }
         }
     }
 }
@@ -721,7 +1070,11 @@ struct UIComponents {
     pub remotes_tab: RemotesTabComponent,
     pub history_tab: CommitHistoryComponent,
     pub gitflow_tab: GitFlowTabComponent,
+    pub insights_tab: InsightsTabComponent,
     pub help: HelpComponent,
+    pub workspace: WorkspacePanelComponent,
+    pub palette: CommandPaletteComponent,
+    pub confirm: ConfirmationModal,
 }
 
 impl UIComponents {
@@ -735,7 +1088,11 @@ impl UIComponents {
             remotes_tab: RemotesTabComponent::new(),
             history_tab: CommitHistoryComponent::new(),
             gitflow_tab: GitFlowTabComponent::new(),
+            insights_tab: InsightsTabComponent::new(),
             help: HelpComponent::new(),
+            workspace: WorkspacePanelComponent::new(),
+            palette: CommandPaletteComponent::new(),
+            confirm: ConfirmationModal::new(),
         }
     }
 
@@ -757,19 +1114,71 @@ impl UIComponents {
             }
             FocusArea::MainContent => {
                 // Forward to the active tab component for main content area
-                match state.current_tab() {
-                    TabType::Status => self.status_tab.handle_key_event(key, state),
-                    TabType::Branches => self.branches_tab.handle_key_event(key, state),
-                    TabType::Tags => self.tags_tab.handle_key_event(key, state),
-                    TabType::Stash => self.stash_tab.handle_key_event(key, state),
-                    TabType::Remotes => self.remotes_tab.handle_key_event(key, state),
-                    TabType::History => self.history_tab.handle_key_event(key, state),
-                    TabType::GitFlow => self.gitflow_tab.handle_key_event(key, state),
-                }
+                self.handle_active_tab_key(key, state)
             }
         }
     }
 
+    fn handle_active_tab_key(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
+        match state.current_tab() {
+            TabType::Status => self.status_tab.handle_key_event(key, state),
+            TabType::Branches => self.branches_tab.handle_key_event(key, state),
+            TabType::Tags => self.tags_tab.handle_key_event(key, state),
+            TabType::Stash => self.stash_tab.handle_key_event(key, state),
+            TabType::Remotes => self.remotes_tab.handle_key_event(key, state),
+            TabType::History => self.history_tab.handle_key_event(key, state),
+            TabType::GitFlow => self.gitflow_tab.handle_key_event(key, state),
+            TabType::Insights => self.insights_tab.handle_key_event(key, state),
+        }
+    }
+
+    /// Whether the active tab has an editor open that takes every key
+    ///
+    /// This is the text input routing: while it holds, `App` skips its q/Esc/r
+    /// handling and `UI` skips help, workspace and global shortcuts, so every
+    /// key reaches the tab. A tab that opens a text field, modal or panel
+    /// handling Esc itself must report it from its own `captures_text_input`.
+    fn captures_text_input(&self, state: &AppState) -> bool {
+        match state.current_tab() {
+            TabType::Status => self.status_tab.captures_text_input(),
+            TabType::Branches => self.branches_tab.captures_text_input(),
+            TabType::History => self.history_tab.captures_text_input(),
+            TabType::Tags => self.tags_tab.captures_text_input(),
+            TabType::Remotes => self.remotes_tab.captures_text_input(),
+            TabType::GitFlow => self.gitflow_tab.captures_text_input(),
+            TabType::Insights => self.insights_tab.captures_text_input(),
+            // The stash list has no text input
+            TabType::Stash => false,
+        }
+    }
+
+    /// Whether the active tab shows a diff that takes the diff viewer keys
+    fn shows_diff(&self, state: &AppState) -> bool {
+        match state.current_tab() {
+            TabType::Status => self.status_tab.shows_diff(),
+            TabType::Branches => self.branches_tab.shows_diff(),
+            TabType::History => self.history_tab.shows_diff(),
+            _ => false,
+        }
+    }
+
+    /// Shortcut context of the active tab
+    fn shortcut_context(&self, state: &AppState) -> ShortcutContext {
+        if self.shows_diff(state) {
+            return ShortcutContext::DiffViewer;
+        }
+        match state.current_tab() {
+            TabType::Status => ShortcutContext::FileList,
+            TabType::Branches => ShortcutContext::BranchList,
+            TabType::History => ShortcutContext::CommitList,
+            TabType::Tags => ShortcutContext::TagList,
+            TabType::Stash => ShortcutContext::StashList,
+            TabType::Remotes => ShortcutContext::RemoteList,
+            TabType::GitFlow => ShortcutContext::GitFlowView,
+            TabType::Insights => ShortcutContext::InsightsView,
+        }
+    }
+
     fn handle_sidebar_key_event(&mut self, key: KeyEvent, state: &mut AppState) -> AppResult<()> {
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
@@ -809,6 +1218,7 @@ impl UIComponents {
             TabType::Remotes => 3, // Mock data for now
             TabType::History => 3, // Mock data for now
             TabType::GitFlow => 3, // Mock data for now
+            TabType::Insights => 4, // Insight sections
         }
     }
 
@@ -11,7 +11,7 @@ use ai_c::{
         },
         Agent, // Import the Agent trait
     },
-    git::{FileStatus, GitStatusFlags},
+    git::{FileStatus, GitStatusFlags, LintRules},
 };
 use chrono::Utc;
 
@@ -195,6 +195,35 @@ async fn test_detailed_commit_generation() {
     );
 }
 
+#[tokio::test]
+async fn test_linted_commit_generation() {
+    let mut agent = create_test_agent();
+    let files = create_test_files();
+
+    let rules = LintRules {
+        types: vec!["chore".to_string()],
+        header_max_length: 40,
+        body_max_line_length: 30,
+        ..LintRules::default()
+    };
+    let request = CommitGenerationRequest {
+        staged_files: files,
+        context: None,
+        style: CommitMessageStyle::Simple,
+        max_length: None,
+        include_description: true,
+    };
+
+    let (message, report) = agent
+        .generate_linted_commit_message(request, &rules, 3)
+        .await
+        .expect("Linted commit generation should succeed");
+
+    assert!(!report.has_errors(), "Message should pass lint: {}", report.summary());
+    assert!(message.starts_with("chore"), "Disallowed type should be replaced: {}", message);
+    assert!(message.lines().next().unwrap().chars().count() <= 40);
+}
+
 #[tokio::test]
 async fn test_custom_commit_generation() {
     let mut agent = create_test_agent();